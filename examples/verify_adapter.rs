@@ -9,7 +9,7 @@ async fn main() -> Result<()> {
     println!("Adapter initialized.");
 
     println!("Fetching operation log...");
-    let log = adapter.get_operation_log(None, 100, None).await?;
+    let log = adapter.get_operation_log(0, 100, None).await?;
     println!("Operation ID: {}", log.operation_id);
     println!("Working Copy ID: {}", log.working_copy_id);
     println!("Graph has {} entries.", log.graph.len());
@@ -19,7 +19,7 @@ async fn main() -> Result<()> {
             "Checking diff for {} - {}",
             entry.commit_id, entry.description
         );
-        let diff = adapter.get_commit_diff(&entry.commit_id).await?;
+        let diff = adapter.get_commit_diff(&entry.commit_id).await?.to_string();
         if diff != "(No changes or diff not implemented)" && !diff.contains("Root commit") {
             println!("SUCCESS: Found non-empty diff!");
             println!("Diff length: {}", diff.len());
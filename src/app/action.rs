@@ -1,5 +1,17 @@
-use crate::domain::models::{CommitId, RepoStatus};
+use crate::domain::bookmark::Bookmark;
+use crate::domain::error::RevsetError;
+use crate::domain::conflict::{FileConflict, Resolution};
+use crate::domain::diff::Diff;
+use crate::domain::models::{
+    CommitId, RebaseLocation, RebaseMode, RepoStatus, Rewrite, RewritePreview, SquashDestination,
+};
+use crate::domain::operation::{Operation, OperationChanges};
+use crate::domain::push::{PushPlan, PushedBookmark};
+use crate::domain::remote::Remote;
+use crate::domain::workspace::Workspace;
 use crate::app::command::Command;
+use crate::app::state::PushMode;
+use crate::app::syntax::DiffHighlights;
 
 #[derive(Debug, Clone)]
 pub enum UpdateResult {
@@ -28,7 +40,9 @@ pub enum Action {
     NextHunk,
     PrevHunk,
     ToggleDiffs,
+    ToggleDiffLayout,
     ToggleSelection(Option<CommitId>),
+    TogglePickFile,
     ClearSelection,
 
     // --- JJ Domain Intents ---
@@ -46,21 +60,30 @@ pub enum Action {
     Absorb,                                // `jj absorb`
     DuplicateRevision,                     // `jj duplicate` (uses selection)
     ParallelizeRevision,                   // `jj parallelize` (uses selection)
-    RebaseRevisionIntent,                  // Start rebase (open destination input)
-    RebaseRevision(Vec<CommitId>, String), // `jj rebase -r <revs> -d <dest>`
+    RebaseRevisionIntent,                  // Start rebase (pick the target in the graph)
+    SetRebaseMode(RebaseMode),             // Move `-r` revisions, `-s` descendants or `-b` branches
+    SetRebaseLocation(RebaseLocation),     // Place them `-d` onto, `-A` after or `-B` before it
+    RebaseRevision(Vec<CommitId>, String), // `jj rebase <mode> <revs> <location> <target>`
     SetBookmarkIntent,                     // Start setting a bookmark
     SetBookmark(CommitId, String),         // `jj bookmark set <name> -r <rev>`
     DeleteBookmarkIntent,                  // Start deleting a bookmark (may prompt)
     DeleteBookmark(String),                // `jj bookmark delete <name>`
-    SplitRevision(Option<CommitId>),       // `jj split -r <rev>`
+    Bookmarks,                             // `jj bookmark list --all`
+    SplitRevision(Option<CommitId>),       // Start picking changes to split off
+    SplitSelected(String),                 // `jj split -r <rev>` with the picked changes
+    PickSquashTarget,                      // Pick the revision to move changes into
+    SquashSelected(SquashDestination),     // `jj squash --from <rev> --into <dest> <paths>`
     EvologRevision(Option<CommitId>),      // `jj evolog <rev>`
     OperationLog,                          // `jj operation log`
+    Workspaces,                            // `jj workspace list`
     Undo,                                  // `jj undo`
     Redo,                                  // `jj redo`
     Fetch,                                 // `jj git fetch`
-    PushIntent,                            // Trigger push (may prompt)
-    Push(Option<String>),                  // `jj git push [-b <bookmark>]`
-    ResolveConflict(String),               // `jj resolve --tool ... <path>`
+    Remotes,                               // `jj git remote list`
+    PushIntent,                            // Open the push dialog
+    ResolveConflict(String),               // Open the conflict editor for <path>
+    ResolveConflictExternally(String),     // `jj resolve <path>` in the merge tool
+    RunCustomCommand(String, Option<CommitId>), // Run a `[commands]` entry on <rev>
     LoadMoreGraph,                         // Trigger pagination
     InitRepo,                              // `jj git init --colocate`
 
@@ -86,7 +109,7 @@ pub enum Action {
     FilterNext,                                    // Next recent filter
     FilterPrev,                                    // Previous recent filter
     ToggleFilterSource,                            // Toggle between recent and preset filters
-    EnterSquashMode,                               // Open squash selection modal
+    EnterSquashMode(Option<CommitId>),             // Start picking changes to move (S)
     FocusDiff,                                     // Switch focus to diff window
     FocusGraph,                                    // Switch focus to revision graph
     CancelMode,                                    // ESC key (close modal/mode)
@@ -96,6 +119,7 @@ pub enum Action {
     SelectThemePrev,                               // Previous theme in selection
     SwitchTheme(crate::theme::PaletteType),        // Apply a new theme
     TextAreaInput(crossterm::event::KeyEvent),     // Handle text area input
+    PendingKeys(Vec<crossterm::event::KeyEvent>),  // Keys typed so far of a key sequence
     OpenContextMenu(Option<CommitId>, (u16, u16)), // Open menu at position
     SelectContextMenuAction(usize),                // Select action by index
     SelectContextMenuNext,                         // Next item in menu
@@ -104,27 +128,117 @@ pub enum Action {
     CommandPaletteNext,                            // Next item in palette
     CommandPalettePrev,                            // Prev item in palette
     CommandPaletteSelect,                          // Execute selected command
+    HunkSelectNext,                                // Next file, hunk or line to pick
+    HunkSelectPrev,                                // Previous file, hunk or line to pick
+    HunkSelectToggle,                              // Pick or unpick the item under the cursor
+    HunkSelectToggleAll,                           // Pick or unpick every change
+    HunkSelectConfirm,                             // Use the picked changes
 
     // --- Async Results (The "Callback") ---
     // These are dispatched by your async workers back to the main thread
     RepoLoaded(Box<RepoStatus>),             // Fresh graph data arrived
     RepoReloadedBackground(Box<RepoStatus>), // Background refresh data arrived
     GraphBatchLoaded(Box<RepoStatus>),       // Additional graph data arrived
-    DiffLoaded(CommitId, String),            // Diff content for the selected commit
+    DiffLoaded(CommitId, Result<Diff, String>), // Diff for the selected commit, or why it failed
+    DiffHighlighted(CommitId, DiffHighlights),  // Syntax highlighting for a loaded diff
     OperationStarted(String),                // "Squashing..." (sets loading state)
     OperationCompleted(Result<String, String>), // Success/Failure message
     ErrorOccurred(String),                   // General error reporting
+    RevsetFailed(RevsetError),               // Revset filter failed to parse/resolve
     ExternalChangeDetected,                  // External change to the repo (jj op heads)
 
     // --- Evolog ---
     OpenEvolog(String),    // Open evolog modal with content
+    OpenCommandOutput(String, String), // Show what a custom command printed
     CloseEvolog,           // Close evolog modal
     ScrollEvologUp(u16),   // Scroll evolog up
     ScrollEvologDown(u16), // Scroll evolog down
 
+    // --- Conflict Editor ---
+    OpenConflictEditor(Box<FileConflict>), // Conflict read; start resolving it
+    ConflictNext,                          // Next conflicting region
+    ConflictPrev,                          // Previous conflicting region
+    ConflictPick(Resolution),              // Resolve the region with a side
+    ConflictClear,                         // Mark the region unresolved again
+    ConflictEditStart,                     // Edit the region's result by hand
+    ConflictEditDone(String),              // Keep the hand-edited text
+    ConflictSave,                          // Write the resolved file
+
     // --- Operation Log ---
-    OpenOperationLog(String),    // Open operation log modal with content
-    CloseOperationLog,           // Close operation log modal
-    ScrollOperationLogUp(u16),   // Scroll operation log up
-    ScrollOperationLogDown(u16), // Scroll operation log down
+    OpenOperationLog(Vec<Operation>),                      // Open operation log modal
+    CloseOperationLog,                                     // Close operation log modal
+    SelectOperationNext(usize),                            // Select an older operation
+    SelectOperationPrev(usize),                            // Select a newer operation
+    OperationChangesLoaded(String, Box<OperationChanges>), // What an operation changed
+    RestoreOperation,                                      // `jj op restore <selected>`
+    RevertOperation,                                       // `jj op revert <selected>`
+
+    // --- Workspaces ---
+    OpenWorkspaces(Vec<Workspace>), // Open the workspace panel
+    CloseWorkspaces,                // Close the workspace panel
+    SelectWorkspaceNext,            // Select the next workspace
+    SelectWorkspacePrev,            // Select the previous workspace
+    AddWorkspaceIntent,             // Start adding a workspace (path input)
+    AddWorkspace(String),           // `jj workspace add <path>`
+    ForgetWorkspace,                // `jj workspace forget <selected>`
+    UpdateStaleWorkspace,           // `jj workspace update-stale` for <selected>
+
+    // --- Bookmarks ---
+    OpenBookmarks(Vec<Bookmark>),                 // Open the bookmark panel
+    CloseBookmarks,                               // Close the bookmark panel
+    SelectBookmarkNext,                           // Select the next bookmark
+    SelectBookmarkPrev,                           // Select the previous bookmark
+    JumpToBookmark,                               // Select <selected>'s target in the graph
+    RenameBookmarkIntent,                         // Start renaming (name input)
+    RenameBookmark(String),                       // `jj bookmark rename <selected> <name>`
+    MoveBookmarkIntent,                           // Start moving (revision input)
+    MoveBookmark(String),                         // `jj bookmark set <selected> -r <rev>`
+    ConfirmMoveBookmarkBackwards(String, String), // Ask before moving <name> back to <rev>
+    MoveBookmarkBackwards,                        // `jj bookmark set --allow-backwards`
+    ToggleTrackBookmark,                          // `jj bookmark track/untrack <selected>`
+    ForgetBookmark,                               // `jj bookmark forget <selected>`
+
+    // --- Push ---
+    PushPreviewLoaded(Box<PushPlan>),    // Show what the push would do
+    ClosePush,                           // Close the push dialog
+    PushNextRemote,                      // Preview pushing to the next remote
+    SetPushMode(PushMode),               // `-b <picked>`, `--change`, `--all` or `--tracked`
+    PushSelectNext,                      // Move to the next bookmark
+    PushSelectPrev,                      // Move to the previous bookmark
+    PushToggleBookmark,                  // Pick or drop the bookmark under the cursor
+    ConfirmPush,                         // `jj git push` as previewed
+    PushCompleted(Vec<PushedBookmark>),  // Show how each bookmark went
+
+    // --- Remotes ---
+    OpenRemotes(Vec<Remote>),  // Open the remote panel
+    CloseRemotes,              // Close the remote panel
+    SelectRemoteNext,          // Select the next remote
+    SelectRemotePrev,          // Select the previous remote
+    AddRemoteIntent,           // Start adding a remote (name and URL input)
+    AddRemote(String),         // `jj git remote add <name> <url>`
+    RenameRemoteIntent,        // Start renaming (name input)
+    RenameRemote(String),      // `jj git remote rename <selected> <name>`
+    SetRemoteUrlIntent,        // Start changing the URL (URL input)
+    SetRemoteUrl(String),      // `jj git remote set-url <selected> <url>`
+    RemoveRemote,              // `jj git remote remove <selected>`
+    FetchRemote,               // `jj git fetch --remote <selected>`
+    FetchAllRemotes,           // `jj git fetch --all-remotes`
+    FetchBranchesIntent,       // Start fetching some branches (pattern input)
+    FetchBranches(String),     // `jj git fetch --remote <selected> -b <patterns>`
+
+    // --- Tags ---
+    TagMenu,                        // Open the tag menu for the selected revision
+    CreateTagIntent,                // Start tagging the selected revision (name input)
+    MoveTagIntent,                  // Start moving a tag to it (name input)
+    SetTag(CommitId, String, bool), // `jj tag set <name> -r <rev> [--allow-move]`
+    DeleteTag(String),              // `jj tag delete <name>`
+    PushTag(String),                // `git push <remote> refs/tags/<name>`
+
+    // --- Rewrite Preview ---
+    TogglePreviewRewrites,                              // Preview rewrites before running them
+    RewritePreviewLoaded(Rewrite, Box<RewritePreview>), // Show the graph a rewrite would leave
+    SelectPreviewNext,                                  // Select the next previewed revision
+    SelectPreviewPrev,                                  // Select the previous previewed revision
+    ConfirmRewrite,                                     // Run the previewed rewrite
+    CloseRewritePreview,                                // Drop the preview without running it
 }
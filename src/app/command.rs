@@ -1,31 +1,74 @@
-use crate::domain::models::CommitId;
+use crate::domain::conflict::FileConflict;
+use crate::domain::diff::{Diff, FileSelection};
+use crate::domain::models::{CommitId, RebaseLocation, RebaseMode, Rewrite, SquashDestination};
+use crate::domain::push::PushScope;
 
 #[derive(Debug, Clone)]
 pub enum Command {
-    LoadRepo(Option<Vec<CommitId>>, usize, Option<String>),
+    LoadRepo(usize, Option<String>),
+    LoadGraphPage(usize, usize, Option<String>),
     LoadRepoBackground(usize, Option<String>),
     LoadDiff(CommitId),
+    HighlightDiff(CommitId, Box<Diff>),
     DescribeRevision(CommitId, String),
     Commit(String),
     Snapshot,
     Edit(CommitId),
     Squash(Vec<CommitId>),
+    SquashChanges(CommitId, Vec<FileSelection>, SquashDestination),
     New(CommitId),
     Abandon(Vec<CommitId>),
     Revert(Vec<CommitId>),
     Absorb,
     Duplicate(Vec<CommitId>),
     Parallelize(Vec<CommitId>),
-    Rebase(Vec<CommitId>, String),
+    /// Revisions, what to move along with them, where to put them and the
+    /// target revision.
+    Rebase(Vec<CommitId>, RebaseMode, RebaseLocation, String),
+    /// Rewrite to preview, how many rows of the graph and the revset filter.
+    PreviewRewrite(Rewrite, usize, Option<String>),
     SetBookmark(CommitId, String),
     DeleteBookmark(String),
-    Split(CommitId),
+    Bookmarks,
+    RenameBookmark(String, String),
+    /// Bookmark name, destination revision and whether it may go backwards.
+    MoveBookmark(String, String, bool),
+    TrackBookmark(String, String),
+    UntrackBookmark(String, String),
+    ForgetBookmark(String),
+    /// Revision, tag name and whether an existing tag may be moved.
+    SetTag(CommitId, String, bool),
+    DeleteTag(String),
+    PushTag(String),
+    Split(CommitId, Vec<FileSelection>, String),
     Undo,
     Redo,
-    Fetch,
-    Push(Option<String>),
+    /// Remotes (the default one if empty) and branch patterns (all if empty).
+    Fetch(Vec<String>, Vec<String>),
+    Remotes,
+    /// Name and URL.
+    AddRemote(String, String),
+    RenameRemote(String, String),
+    RemoveRemote(String),
+    SetRemoteUrl(String, String),
+    /// Remote, if not the one `jj` would pick, and what to push.
+    PreviewPush(Option<String>, PushScope),
+    Push(String, PushScope),
     ResolveConflict(String),
+    /// Name and command line of a custom command to run in the background.
+    RunCustomCommand(String, String),
+    /// Same, but with the terminal handed over to it.
+    RunInteractiveCommand(String, String),
+    LoadConflict(CommitId, String),
+    SaveResolution(FileConflict, String),
     InitRepo,
     Evolog(CommitId),
     OperationLog,
+    LoadOperationChanges(String),
+    RestoreOperation(String),
+    RevertOperation(String),
+    Workspaces,
+    AddWorkspace(String),
+    ForgetWorkspace(String),
+    UpdateStaleWorkspace(String),
 }
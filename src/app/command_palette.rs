@@ -1,15 +1,17 @@
 use super::action::Action;
+use super::keymap::CustomCommand;
 
 #[derive(Debug, Clone)]
-pub struct CommandDefinition {
-    pub name: &'static str,
-    pub description: &'static str,
+pub struct CommandDefinition<'a> {
+    pub name: &'a str,
+    pub description: &'a str,
     pub action: Action,
 }
 
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 
-static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
+static COMMANDS: LazyLock<Vec<CommandDefinition<'static>>> = LazyLock::new(|| {
     vec![
         CommandDefinition {
             name: "Snapshot",
@@ -48,9 +50,14 @@ static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
         },
         CommandDefinition {
             name: "Split",
-            description: "Split the revision into two",
+            description: "Pick hunks or lines to split into a new parent",
             action: Action::SplitRevision(None),
         },
+        CommandDefinition {
+            name: "Move Changes",
+            description: "Squash picked changes into another revision",
+            action: Action::EnterSquashMode(None),
+        },
         CommandDefinition {
             name: "Absorb",
             description: "Absorb changes into matching parents",
@@ -71,6 +78,11 @@ static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
             description: "Rebase revision(s) onto a new destination",
             action: Action::RebaseRevisionIntent,
         },
+        CommandDefinition {
+            name: "Toggle Rewrite Preview",
+            description: "Preview rebase, abandon, squash and parallelize before they run",
+            action: Action::TogglePreviewRewrites,
+        },
         CommandDefinition {
             name: "Evolution Log",
             description: "Show the evolution log of the selected revision",
@@ -78,9 +90,29 @@ static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
         },
         CommandDefinition {
             name: "Operation Log",
-            description: "Show the jujutsu operation log",
+            description: "Browse, restore or revert operations",
             action: Action::OperationLog,
         },
+        CommandDefinition {
+            name: "Bookmarks",
+            description: "Rename, move, track or forget local and remote bookmarks",
+            action: Action::Bookmarks,
+        },
+        CommandDefinition {
+            name: "Tags",
+            description: "Create, move, delete or push tags on the selected revision",
+            action: Action::TagMenu,
+        },
+        CommandDefinition {
+            name: "Workspaces",
+            description: "List, add, forget or update stale workspaces",
+            action: Action::Workspaces,
+        },
+        CommandDefinition {
+            name: "Remotes",
+            description: "Add, rename, remove or fetch from Git remotes",
+            action: Action::Remotes,
+        },
         CommandDefinition {
             name: "Set Bookmark",
             description: "Set a bookmark on the selected revision",
@@ -108,7 +140,7 @@ static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
         },
         CommandDefinition {
             name: "Push",
-            description: "Push to remote",
+            description: "Preview and push bookmarks to a remote",
             action: Action::PushIntent,
         },
         CommandDefinition {
@@ -201,6 +233,11 @@ static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
             description: "Toggle the diff panel",
             action: Action::ToggleDiffs,
         },
+        CommandDefinition {
+            name: "Toggle Split Diff",
+            description: "Switch the diff panel between unified and side-by-side",
+            action: Action::ToggleDiffLayout,
+        },
         CommandDefinition {
             name: "Help",
             description: "Show the help overlay",
@@ -214,17 +251,24 @@ static COMMANDS: LazyLock<Vec<CommandDefinition>> = LazyLock::new(|| {
     ]
 });
 
+/// The built-in commands followed by the `[commands]` of `config.toml`.
 #[must_use]
-pub fn get_commands() -> &'static [CommandDefinition] {
-    &COMMANDS
+pub fn get_commands(custom: &BTreeMap<String, CustomCommand>) -> Vec<CommandDefinition<'_>> {
+    let mut commands = COMMANDS.clone();
+    commands.extend(custom.iter().map(|(name, command)| CommandDefinition {
+        name,
+        description: command.description.as_deref().unwrap_or(&command.run),
+        action: Action::RunCustomCommand(name.clone(), None),
+    }));
+    commands
 }
-pub fn search_commands(query: &str) -> Vec<usize> {
+pub fn search_commands(query: &str, custom: &BTreeMap<String, CustomCommand>) -> Vec<usize> {
+    let commands = get_commands(custom);
     if query.is_empty() {
-        return (0..get_commands().len()).collect();
+        return (0..commands.len()).collect();
     }
 
     let query_lower = query.to_lowercase();
-    let commands = get_commands();
     let mut results = Vec::new();
 
     // First pass: exact substring match in name (higher priority)
@@ -1,11 +1,13 @@
 use anyhow::Result;
 use ratatui::{backend::Backend, Terminal};
+use std::path::Path;
 use std::process::Command;
 
 pub fn run_external_command<B: Backend>(
     terminal: &mut Terminal<B>,
     program: &str,
     args: &[&str],
+    dir: Option<&Path>,
 ) -> Result<bool> {
     // 1. Suspend TUI
     crossterm::terminal::disable_raw_mode()?;
@@ -16,7 +18,12 @@ pub fn run_external_command<B: Backend>(
     )?;
 
     // 2. Run external tool
-    let mut child = Command::new(program).args(args).spawn()?;
+    let mut command = Command::new(program);
+    command.args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let mut child = command.spawn()?;
     let status = child.wait()?;
 
     // 3. Resume TUI
@@ -0,0 +1,275 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    features::navigation,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{AppMode, AppState, AppTextArea, BookmarkMove, BookmarksState, InputState},
+};
+use crate::domain::bookmark::Bookmark;
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::Bookmarks => UpdateResult::Handled(Some(Command::Bookmarks)),
+        Action::OpenBookmarks(bookmarks) => {
+            state.bookmarks_state = Some(BookmarksState {
+                bookmarks: bookmarks.clone(),
+                selected: 0,
+            });
+            state.mode = AppMode::Bookmarks;
+            UpdateResult::Handled(None)
+        }
+        Action::CloseBookmarks => {
+            close(state);
+            UpdateResult::Handled(None)
+        }
+        Action::SelectBookmarkNext => select(state, |selected| selected + 1),
+        Action::SelectBookmarkPrev => select(state, |selected| selected.saturating_sub(1)),
+        Action::JumpToBookmark => {
+            let Some(bookmark) = selected_bookmark(state) else {
+                return UpdateResult::Handled(None);
+            };
+            let Some(target) = bookmark.targets.first() else {
+                return UpdateResult::Handled(None);
+            };
+            let row = state.repo.as_ref().and_then(|repo| {
+                repo.graph
+                    .iter()
+                    .position(|row| row.commit_id == target.commit_id)
+            });
+            let Some(row) = row else {
+                set_status(
+                    state,
+                    format!(
+                        "{} points to {}, which isn't in the graph",
+                        bookmark.label(),
+                        target.change_id_short
+                    ),
+                );
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            state.log.list_state.select(Some(row));
+            UpdateResult::Handled(navigation::handle_selection(state))
+        }
+        Action::RenameBookmarkIntent => {
+            let Some(bookmark) = selected_local_bookmark(state, "renamed") else {
+                return UpdateResult::Handled(None);
+            };
+            let mut text_area = AppTextArea::default();
+            text_area.insert_str(&bookmark.name);
+            state.input = Some(InputState { text_area });
+            state.mode = AppMode::BookmarkRename;
+            UpdateResult::Handled(None)
+        }
+        Action::RenameBookmark(new_name) => {
+            let bookmark = selected_bookmark(state);
+            state.input = None;
+            close(state);
+            let new_name = new_name.trim();
+            match bookmark {
+                Some(bookmark) if !new_name.is_empty() && new_name != bookmark.name => {
+                    UpdateResult::Handled(Some(Command::RenameBookmark(
+                        bookmark.name,
+                        new_name.to_string(),
+                    )))
+                }
+                _ => UpdateResult::Handled(None),
+            }
+        }
+        Action::MoveBookmarkIntent => {
+            if selected_local_bookmark(state, "moved").is_none() {
+                return UpdateResult::Handled(None);
+            }
+            // Default to the revision selected in the graph.
+            let mut text_area = AppTextArea::default();
+            if let (Some(repo), Some(idx)) = (&state.repo, state.log.list_state.selected()) {
+                if let Some(row) = repo.graph.get(idx) {
+                    text_area.insert_str(&row.change_id_short);
+                }
+            }
+            state.input = Some(InputState { text_area });
+            state.mode = AppMode::BookmarkMove;
+            UpdateResult::Handled(None)
+        }
+        Action::MoveBookmark(revision) => {
+            let bookmark = selected_bookmark(state);
+            state.input = None;
+            close(state);
+            let revision = revision.trim();
+            match bookmark {
+                Some(bookmark) if !revision.is_empty() => UpdateResult::Handled(Some(
+                    Command::MoveBookmark(bookmark.name, revision.to_string(), false),
+                )),
+                _ => UpdateResult::Handled(None),
+            }
+        }
+        Action::ConfirmMoveBookmarkBackwards(name, revision) => {
+            // The move that was refused is no longer running.
+            if !state.active_tasks.is_empty() {
+                state.active_tasks.remove(0);
+            }
+            state.bookmark_move = Some(BookmarkMove {
+                name: name.clone(),
+                revision: revision.clone(),
+            });
+            state.mode = AppMode::ConfirmMove;
+            UpdateResult::Handled(None)
+        }
+        Action::MoveBookmarkBackwards => {
+            state.mode = AppMode::Normal;
+            match state.bookmark_move.take() {
+                Some(BookmarkMove { name, revision }) => {
+                    UpdateResult::Handled(Some(Command::MoveBookmark(name, revision, true)))
+                }
+                None => UpdateResult::Handled(None),
+            }
+        }
+        Action::ToggleTrackBookmark => {
+            let Some(bookmark) = selected_bookmark(state) else {
+                return UpdateResult::Handled(None);
+            };
+            let Some(remote) = bookmark.remote else {
+                set_status(
+                    state,
+                    "Select a remote bookmark to track or untrack it".into(),
+                );
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            UpdateResult::Handled(Some(if bookmark.is_tracked {
+                Command::UntrackBookmark(bookmark.name, remote)
+            } else {
+                Command::TrackBookmark(bookmark.name, remote)
+            }))
+        }
+        Action::ForgetBookmark => {
+            let Some(bookmark) = selected_bookmark(state) else {
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            UpdateResult::Handled(Some(Command::ForgetBookmark(bookmark.name)))
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+fn selected_bookmark(state: &AppState) -> Option<Bookmark> {
+    state.bookmarks_state.as_ref()?.selected_bookmark().cloned()
+}
+
+/// The selected bookmark if it's a local one; otherwise explains that only
+/// local bookmarks can be `verb`.
+fn selected_local_bookmark(state: &mut AppState, verb: &str) -> Option<Bookmark> {
+    let bookmark = selected_bookmark(state)?;
+    if bookmark.remote.is_some() {
+        set_status(state, format!("Only local bookmarks can be {verb}"));
+        return None;
+    }
+    Some(bookmark)
+}
+
+fn select(state: &mut AppState, f: impl FnOnce(usize) -> usize) -> UpdateResult {
+    if let Some(panel) = &mut state.bookmarks_state {
+        panel.selected = f(panel.selected).min(panel.bookmarks.len().saturating_sub(1));
+    }
+    UpdateResult::Handled(None)
+}
+
+fn close(state: &mut AppState) {
+    state.mode = AppMode::Normal;
+    state.bookmarks_state = None;
+}
+
+fn set_status(state: &mut AppState, message: String) {
+    state.status_message = Some(message);
+    state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{keymap::KeyConfig, reducer};
+    use crate::domain::bookmark::BookmarkTarget;
+    use crate::domain::models::{CommitId, GraphRow, RepoStatus};
+
+    #[test]
+    fn test_bookmark_panel() {
+        let mut state = AppState::new(KeyConfig::default());
+        let main = CommitId("abc".to_string());
+        state.repo = Some(RepoStatus {
+            repo_name: "test".to_string(),
+            operation_id: "op".to_string(),
+            workspace_id: "ws".to_string(),
+            working_copy_id: CommitId("def".to_string()),
+            graph: vec![
+                GraphRow {
+                    commit_id: CommitId("def".to_string()),
+                    change_id_short: "zzzzzzzz".to_string(),
+                    ..Default::default()
+                },
+                GraphRow {
+                    commit_id: main.clone(),
+                    ..Default::default()
+                },
+            ],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+        let bookmarks = vec![
+            Bookmark {
+                name: "main".to_string(),
+                targets: vec![BookmarkTarget {
+                    commit_id: main,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            Bookmark {
+                name: "main".to_string(),
+                remote: Some("origin".to_string()),
+                is_tracked: true,
+                ..Default::default()
+            },
+        ];
+        reducer::update(&mut state, Action::OpenBookmarks(bookmarks.clone()));
+        assert_eq!(state.mode, AppMode::Bookmarks);
+
+        reducer::update(&mut state, Action::JumpToBookmark);
+        assert_eq!(state.mode, AppMode::Normal);
+        assert_eq!(state.log.list_state.selected(), Some(1));
+
+        // Moving starts from the revision selected in the graph; a refused
+        // backwards move asks before going ahead.
+        state.log.list_state.select(Some(0));
+        reducer::update(&mut state, Action::OpenBookmarks(bookmarks.clone()));
+        reducer::update(&mut state, Action::MoveBookmarkIntent);
+        assert_eq!(state.mode, AppMode::BookmarkMove);
+        let input = state.input.as_ref().expect("revision input");
+        assert_eq!(input.text_area.lines(), ["zzzzzzzz"]);
+        let command = reducer::update(&mut state, Action::MoveBookmark("zzzzzzzz".into()));
+        assert!(matches!(
+            command,
+            Some(Command::MoveBookmark(name, rev, false)) if name == "main" && rev == "zzzzzzzz"
+        ));
+        reducer::update(
+            &mut state,
+            Action::ConfirmMoveBookmarkBackwards("main".into(), "zzzzzzzz".into()),
+        );
+        assert_eq!(state.mode, AppMode::ConfirmMove);
+        let command = reducer::update(&mut state, Action::MoveBookmarkBackwards);
+        assert!(matches!(command, Some(Command::MoveBookmark(_, _, true))));
+
+        // Remote bookmarks can be untracked but not renamed.
+        reducer::update(&mut state, Action::OpenBookmarks(bookmarks));
+        reducer::update(&mut state, Action::SelectBookmarkNext);
+        reducer::update(&mut state, Action::RenameBookmarkIntent);
+        assert_eq!(state.mode, AppMode::Bookmarks);
+        assert!(state.status_message.is_some());
+        let command = reducer::update(&mut state, Action::ToggleTrackBookmark);
+        assert!(matches!(
+            command,
+            Some(Command::UntrackBookmark(name, remote)) if name == "main" && remote == "origin"
+        ));
+    }
+}
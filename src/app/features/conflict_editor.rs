@@ -0,0 +1,157 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{AppMode, AppState, AppTextArea, ConflictEditorState, InputState},
+};
+use crate::domain::conflict::{ConflictRegion, Resolution};
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::OpenConflictEditor(conflict) => {
+            state.conflict_editor = Some(ConflictEditorState::new((**conflict).clone()));
+            state.mode = AppMode::ConflictEditor;
+            UpdateResult::Handled(None)
+        }
+        Action::ConflictNext => with_editor(state, |editor| editor.move_current(1)),
+        Action::ConflictPrev => with_editor(state, |editor| editor.move_current(-1)),
+        Action::ConflictPick(resolution) => with_editor(state, |editor| {
+            editor.resolve_current(Some(resolution.clone()));
+            editor.move_current(1);
+        }),
+        Action::ConflictClear => with_editor(state, |editor| editor.resolve_current(None)),
+        Action::ConflictEditStart => {
+            let Some(editor) = &state.conflict_editor else {
+                return UpdateResult::Handled(None);
+            };
+            let text = editor.current_text();
+            let mut text_area = AppTextArea::default();
+            text_area.insert_str(text.strip_suffix('\n').unwrap_or(&text));
+            state.input = Some(InputState { text_area });
+            state.mode = AppMode::ConflictInput;
+            UpdateResult::Handled(None)
+        }
+        Action::ConflictEditDone(text) => {
+            state.input = None;
+            state.mode = AppMode::ConflictEditor;
+            with_editor(state, |editor| {
+                // The text area drops the region's final newline, if it had
+                // one; at the end of a file it may not.
+                let newline = editor
+                    .current_region()
+                    .is_some_and(ConflictRegion::ends_with_newline);
+                let text = if text.is_empty() || !newline {
+                    text.clone()
+                } else {
+                    format!("{text}\n")
+                };
+                editor.resolve_current(Some(Resolution::Edited(text)));
+            })
+        }
+        Action::ConflictSave => {
+            let Some(editor) = &state.conflict_editor else {
+                return UpdateResult::Handled(None);
+            };
+            let Some(content) = editor.conflict.resolve(&editor.resolutions) else {
+                let left = editor.unresolved_count();
+                state.status_message = Some(format!(
+                    "{left} conflict{} left to resolve",
+                    if left == 1 { "" } else { "s" }
+                ));
+                state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+                return UpdateResult::Handled(None);
+            };
+            let conflict = editor.conflict.clone();
+            state.conflict_editor = None;
+            state.mode = AppMode::Normal;
+            UpdateResult::Handled(Some(Command::SaveResolution(conflict, content)))
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+fn with_editor(state: &mut AppState, f: impl FnOnce(&mut ConflictEditorState)) -> UpdateResult {
+    if let Some(editor) = &mut state.conflict_editor {
+        f(editor);
+    }
+    UpdateResult::Handled(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{keymap::KeyConfig, reducer};
+    use crate::domain::conflict::FileConflict;
+    use crate::domain::models::CommitId;
+
+    #[test]
+    fn test_resolve_regions_then_save() {
+        let mut state = AppState::new(KeyConfig::default());
+        let region = |ours: &str, theirs: &str| ConflictRegion::Conflict {
+            base: "base\n".to_string(),
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+        };
+        let conflict = FileConflict {
+            commit_id: CommitId("abc".to_string()),
+            path: "a.txt".to_string(),
+            regions: vec![
+                region("a\n", "b\n"),
+                ConflictRegion::Resolved("same\n".to_string()),
+                region("c\n", "d\n"),
+            ],
+        };
+        reducer::update(
+            &mut state,
+            Action::OpenConflictEditor(Box::new(conflict.clone())),
+        );
+        assert_eq!(state.mode, AppMode::ConflictEditor);
+
+        // Picking a side moves on to the next region.
+        reducer::update(&mut state, Action::ConflictPick(Resolution::Theirs));
+        assert!(reducer::update(&mut state, Action::ConflictSave).is_none());
+        assert_eq!(state.mode, AppMode::ConflictEditor);
+
+        // Editing starts from our side.
+        reducer::update(&mut state, Action::ConflictEditStart);
+        assert_eq!(state.mode, AppMode::ConflictInput);
+        let input = state.input.as_ref().unwrap();
+        assert_eq!(input.text_area.lines(), ["c"]);
+        reducer::update(&mut state, Action::ConflictEditDone("c\nd".to_string()));
+        assert_eq!(state.mode, AppMode::ConflictEditor);
+
+        let command = reducer::update(&mut state, Action::ConflictSave);
+        let Some(Command::SaveResolution(saved, content)) = command else {
+            panic!("expected a save command, got {command:?}");
+        };
+        assert_eq!(saved, conflict);
+        assert_eq!(content, "b\nsame\nc\nd\n");
+        assert!(state.conflict_editor.is_none());
+    }
+
+    #[test]
+    fn test_edit_keeps_missing_final_newline() {
+        let mut state = AppState::new(KeyConfig::default());
+        let conflict = FileConflict {
+            commit_id: CommitId("abc".to_string()),
+            path: "a.txt".to_string(),
+            regions: vec![
+                ConflictRegion::Resolved("start\n".to_string()),
+                ConflictRegion::Conflict {
+                    base: "base".to_string(),
+                    ours: "a".to_string(),
+                    theirs: "b".to_string(),
+                },
+            ],
+        };
+        reducer::update(&mut state, Action::OpenConflictEditor(Box::new(conflict)));
+        reducer::update(&mut state, Action::ConflictEditStart);
+        reducer::update(&mut state, Action::ConflictEditDone("a\nb".to_string()));
+        let command = reducer::update(&mut state, Action::ConflictSave);
+        let Some(Command::SaveResolution(_, content)) = command else {
+            panic!("expected a save command, got {command:?}");
+        };
+        assert_eq!(content, "start\na\nb");
+    }
+}
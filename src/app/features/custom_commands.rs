@@ -0,0 +1,201 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{AppMode, AppState, EvologState},
+};
+use crate::domain::models::{CommitId, GraphRow};
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::RunCustomCommand(name, commit_id) => {
+            let Some(command) = state.custom_commands.get(name).cloned() else {
+                set_status(state, format!("No command named {name} in [commands]"));
+                return UpdateResult::Handled(None);
+            };
+            match render(&command.run, state, commit_id.as_ref()) {
+                Ok(line) if command.interactive => {
+                    UpdateResult::Handled(Some(Command::RunInteractiveCommand(name.clone(), line)))
+                }
+                Ok(line) => {
+                    UpdateResult::Handled(Some(Command::RunCustomCommand(name.clone(), line)))
+                }
+                Err(message) => {
+                    set_status(state, message);
+                    UpdateResult::Handled(None)
+                }
+            }
+        }
+        Action::OpenCommandOutput(name, output) => {
+            state.mode = AppMode::Evolog;
+            state.evolog_state = Some(EvologState {
+                title: name.clone(),
+                content: output.lines().map(ToString::to_string).collect(),
+                scroll: 0,
+            });
+            UpdateResult::Handled(None)
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+/// `template` with its placeholders filled in for the revision `commit_id`,
+/// or the selected one. Values are quoted for the shell.
+fn render(
+    template: &str,
+    state: &AppState,
+    commit_id: Option<&CommitId>,
+) -> Result<String, String> {
+    let graph = state
+        .repo
+        .as_ref()
+        .map(|repo| &repo.graph[..])
+        .unwrap_or_default();
+    let highlighted = state
+        .log
+        .list_state
+        .selected()
+        .and_then(|idx| graph.get(idx));
+    let row: &GraphRow = match commit_id {
+        Some(id) => graph.iter().find(|row| row.commit_id == *id),
+        None => highlighted,
+    }
+    .ok_or("No revision selected")?;
+    let selected: Vec<&CommitId> = if state.log.selected_ids.is_empty() {
+        vec![&row.commit_id]
+    } else {
+        // Graph order, so the command sees them the same way every time.
+        graph
+            .iter()
+            .map(|row| &row.commit_id)
+            .filter(|id| state.log.selected_ids.contains(id))
+            .collect()
+    };
+
+    let mut line = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let name = rest.find('}').map_or("", |end| &rest[1..end]);
+        let value = match name {
+            "commit_id" => quote(&row.commit_id.0),
+            "change_id" => quote(&row.change_id),
+            "bookmarks" => words(row.bookmarks.iter().map(String::as_str)),
+            "selected" => words(selected.iter().map(|id| id.0.as_str())),
+            "file" => {
+                // Files are only picked on the highlighted revision.
+                let file = highlighted
+                    .filter(|highlighted| highlighted.commit_id == row.commit_id)
+                    .and_then(|_| state.get_selected_file())
+                    .ok_or("Select a file in the diff panel to fill in {file}")?;
+                quote(&file.path)
+            }
+            // Anything else is the shell's, like `${HOME}`.
+            _ => {
+                line.push('{');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        line.push_str(&value);
+        rest = &rest[name.len() + 2..];
+    }
+    line.push_str(rest);
+    Ok(line)
+}
+
+fn words<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    values.map(quote).collect::<Vec<_>>().join(" ")
+}
+
+/// `value` as a single shell word.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn set_status(state: &mut AppState, message: String) {
+    state.status_message = Some(message);
+    state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::keymap::{CustomCommand, KeyConfig};
+    use crate::app::reducer;
+    use crate::domain::models::{FileChange, FileStatus, RepoStatus};
+
+    #[test]
+    fn test_run_custom_command() {
+        let mut config = KeyConfig::default();
+        config.commands.insert(
+            "review".to_string(),
+            CustomCommand {
+                run: "review {change_id} {bookmarks} -- {file}".to_string(),
+                key: Some("z".to_string()),
+                ..Default::default()
+            },
+        );
+        config.commands.insert(
+            "land".to_string(),
+            CustomCommand {
+                run: "jj rebase -r {selected} -d {commit_id}".to_string(),
+                interactive: true,
+                ..Default::default()
+            },
+        );
+        let mut state = AppState::new(config);
+        let row = |id: &str| GraphRow {
+            commit_id: CommitId(id.to_string()),
+            change_id: format!("change-{id}"),
+            ..Default::default()
+        };
+        state.repo = Some(RepoStatus {
+            repo_name: "repo".to_string(),
+            operation_id: String::new(),
+            workspace_id: "default".to_string(),
+            working_copy_id: CommitId("a".to_string()),
+            graph: vec![
+                GraphRow {
+                    bookmarks: vec!["main".to_string(), "it's".to_string()],
+                    changed_files: vec![FileChange {
+                        path: "src/a b.rs".to_string(),
+                        status: FileStatus::Modified,
+                    }],
+                    ..row("a")
+                },
+                row("b"),
+            ],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+
+        // {file} needs a file picked in the diff panel.
+        let run = Action::RunCustomCommand("review".to_string(), None);
+        assert!(reducer::update(&mut state, run.clone()).is_none());
+        assert!(state.status_message.is_some());
+
+        state.log.selected_file_index = Some(0);
+        let key = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Char('z'));
+        assert_eq!(state.keymap.get_action(key, &state), Some(run.clone()));
+        let command = reducer::update(&mut state, run);
+        assert!(matches!(
+            command,
+            Some(Command::RunCustomCommand(name, line))
+                if name == "review"
+                    && line == r"review 'change-a' 'main' 'it'\''s' -- 'src/a b.rs'"
+        ));
+
+        // From the context menu it runs on the revision clicked.
+        state.log.selected_ids.insert(CommitId("a".to_string()));
+        state.log.selected_ids.insert(CommitId("b".to_string()));
+        let run = Action::RunCustomCommand("land".to_string(), Some(CommitId("b".to_string())));
+        let command = reducer::update(&mut state, run);
+        assert!(matches!(
+            command,
+            Some(Command::RunInteractiveCommand(_, line)) if line == "jj rebase -r 'a' 'b' -d 'b'"
+        ));
+    }
+}
@@ -1,8 +1,11 @@
 use crate::app::{
     action::{Action, UpdateResult},
     command::Command,
+    reducer::{GRAPH_PAGE_SIZE, STATUS_CLEAR_DURATION},
     state::{AppMode, AppState, AppTextArea},
 };
+use std::time::Instant;
+use tui_textarea::CursorMove;
 
 pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
     match action {
@@ -18,12 +21,14 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                 }
             }
             state.selected_filter_index = None;
+            state.revset_error = None;
             UpdateResult::Handled(None)
         }
         Action::ApplyFilter(filter) => {
             state.mode = AppMode::Normal;
             state.input = None;
             state.selected_filter_index = None;
+            state.revset_error = None;
 
             let filter_str = filter.trim().to_string();
             if filter_str.is_empty() {
@@ -36,14 +41,39 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                 }
                 state.recent_filters.insert(0, filter_str);
                 state.recent_filters.truncate(10);
-                super::super::persistence::save_recent_filters(&state.recent_filters);
             }
-            UpdateResult::Handled(Some(Command::LoadRepo(None, 100, state.revset.clone())))
+            // Saved right away too, so a crash doesn't lose the history.
+            if let Err(e) = state.save_session() {
+                state.status_message = Some(format!("Filter applied, but not saved: {e}"));
+                state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+            }
+            UpdateResult::Handled(Some(Command::LoadRepo(
+                GRAPH_PAGE_SIZE,
+                state.revset.clone(),
+            )))
         }
         Action::ClearFilter => {
             state.revset = None;
+            state.revset_error = None;
             state.selected_filter_index = None;
-            UpdateResult::Handled(Some(Command::LoadRepo(None, 100, None)))
+            UpdateResult::Handled(Some(Command::LoadRepo(GRAPH_PAGE_SIZE, None)))
+        }
+        Action::RevsetFailed(err) => {
+            // Drop the broken filter and reopen the filter bar on it, with the
+            // cursor on the offending part so it can be fixed in place.
+            state.revset = None;
+            state.mode = AppMode::FilterInput;
+            state.is_selecting_presets = false;
+            state.selected_filter_index = None;
+            let mut text_area = AppTextArea::default();
+            text_area.insert_str(&err.revset);
+            if let Some(span) = &err.span {
+                let (row, col) = char_offset_to_cursor(&err.revset, span.start);
+                text_area.move_cursor(CursorMove::Jump(row, col));
+            }
+            state.input = Some(crate::app::state::InputState { text_area });
+            state.revset_error = Some(err.clone());
+            UpdateResult::Handled(Some(Command::LoadRepo(GRAPH_PAGE_SIZE, None)))
         }
         Action::FilterMine => UpdateResult::Handled(apply_quick_filter(state, "mine()")),
         Action::FilterTrunk => UpdateResult::Handled(apply_quick_filter(state, "trunk()")),
@@ -73,6 +103,7 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                         None => 0,
                     };
                     state.selected_filter_index = Some(next);
+                    state.revset_error = None;
                     if let Some(input) = &mut state.input {
                         input.text_area = AppTextArea::default();
                         input.text_area.insert_str(&filters[next]);
@@ -101,6 +132,7 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                         None => filters.len() - 1,
                     };
                     state.selected_filter_index = Some(next);
+                    state.revset_error = None;
                     if let Some(input) = &mut state.input {
                         input.text_area = AppTextArea::default();
                         input.text_area.insert_str(&filters[next]);
@@ -119,6 +151,7 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                     &state.recent_filters
                 };
                 if !filters.is_empty() {
+                    state.revset_error = None;
                     if let Some(input) = &mut state.input {
                         input.text_area = AppTextArea::default();
                         input.text_area.insert_str(&filters[0]);
@@ -134,5 +167,70 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
 fn apply_quick_filter(state: &mut AppState, filter: &str) -> Option<Command> {
     state.revset = Some(filter.to_string());
     state.mode = AppMode::Normal;
-    Some(Command::LoadRepo(None, 100, state.revset.clone()))
+    Some(Command::LoadRepo(GRAPH_PAGE_SIZE, state.revset.clone()))
+}
+
+/// Converts a character offset into a (row, column) text-area cursor.
+fn char_offset_to_cursor(text: &str, offset: usize) -> (u16, u16) {
+    let mut remaining = offset;
+    for (row, line) in text.split('\n').enumerate() {
+        let len = line.chars().count();
+        if remaining <= len {
+            return (row as u16, remaining as u16);
+        }
+        remaining -= len + 1;
+    }
+    (0, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::error::RevsetError;
+
+    #[test]
+    fn test_revset_failure_reopens_filter_bar() {
+        let mut state = AppState {
+            revset: Some("all() | foo(".to_string()),
+            ..Default::default()
+        };
+        let err = RevsetError {
+            revset: "all() | foo(".to_string(),
+            message: "Syntax error".to_string(),
+            span: Some(12..13),
+        };
+
+        let result = update(&mut state, &Action::RevsetFailed(err.clone()));
+
+        assert!(matches!(
+            result,
+            UpdateResult::Handled(Some(Command::LoadRepo(_, None)))
+        ));
+        assert_eq!(state.revset, None);
+        assert_eq!(state.mode, AppMode::FilterInput);
+        assert_eq!(state.revset_error, Some(err));
+        let input = state.input.as_ref().expect("filter input");
+        assert_eq!(input.text_area.lines(), ["all() | foo("]);
+        assert_eq!(input.text_area.cursor(), (0, 12));
+
+        update(&mut state, &Action::EnterFilterMode);
+        assert_eq!(state.revset_error, None);
+    }
+
+    #[test]
+    fn test_applied_filter_is_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.toml");
+        let mut state = AppState {
+            session_path: Some(path.clone()),
+            ..Default::default()
+        };
+
+        update(&mut state, &Action::ApplyFilter(" mine() ".to_string()));
+
+        let session = crate::app::persistence::load_session(&path).expect("saved session");
+        assert_eq!(session.revset.as_deref(), Some("mine()"));
+        assert_eq!(session.recent_filters, ["mine()"]);
+        assert_eq!(state.status_message, None);
+    }
 }
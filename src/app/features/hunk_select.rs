@@ -0,0 +1,130 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    reducer::STATUS_CLEAR_DURATION,
+    state::{
+        AppMode, AppState, AppTextArea, ContextMenuState, ErrorSeverity, ErrorState,
+        HunkSelectPurpose, HunkSelectState, InputState,
+    },
+};
+use crate::domain::models::SquashDestination;
+use chrono::Local;
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::HunkSelectNext => move_cursor(state, 1),
+        Action::HunkSelectPrev => move_cursor(state, -1),
+        Action::HunkSelectToggle => {
+            if let Some(hunk_select) = &mut state.hunk_select {
+                hunk_select.toggle_at_cursor();
+            }
+            UpdateResult::Handled(None)
+        }
+        Action::HunkSelectToggleAll => {
+            if let Some(hunk_select) = &mut state.hunk_select {
+                hunk_select.selection.toggle_everything(&hunk_select.diff);
+            }
+            UpdateResult::Handled(None)
+        }
+        Action::HunkSelectConfirm => {
+            let Some(hunk_select) = &state.hunk_select else {
+                return UpdateResult::Handled(None);
+            };
+            if hunk_select.selection.is_empty() {
+                state.status_message =
+                    Some("Nothing picked: press Space to pick changes".to_string());
+                state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+                return UpdateResult::Handled(None);
+            }
+            match hunk_select.purpose {
+                HunkSelectPurpose::Split => {
+                    // Like `jj split`, the first commit starts out with the
+                    // original description.
+                    let mut text_area = AppTextArea::default();
+                    text_area.insert_str(&hunk_select.diff.header.description);
+                    state.input = Some(InputState { text_area });
+                    state.mode = AppMode::SplitInput;
+                }
+                HunkSelectPurpose::Squash => {
+                    state.context_menu = Some(ContextMenuState {
+                        commit_id: hunk_select.commit_id.clone(),
+                        x: 20,
+                        y: 8,
+                        selected_index: 0,
+                        actions: vec![
+                            (
+                                "Into parent".to_string(),
+                                Action::SquashSelected(SquashDestination::Parent),
+                            ),
+                            (
+                                "Into a revision picked in the graph".to_string(),
+                                Action::PickSquashTarget,
+                            ),
+                            (
+                                "Into a new child".to_string(),
+                                Action::SquashSelected(SquashDestination::NewChild),
+                            ),
+                        ],
+                    });
+                    state.mode = AppMode::ContextMenu;
+                }
+            }
+            UpdateResult::Handled(None)
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+/// Starts picking changes from the diff of `commit_id`, if it is loaded.
+/// Files picked in the graph's file list start out selected.
+pub fn enter(
+    state: &mut AppState,
+    purpose: HunkSelectPurpose,
+    commit_id: &crate::domain::models::CommitId,
+) {
+    let diff = state.log.diff_cache.get(commit_id).cloned().or_else(|| {
+        state
+            .log
+            .current_diff
+            .clone()
+            .filter(|diff| diff.header.commit_id == commit_id.0)
+    });
+    let message = match diff {
+        Some(diff) if !diff.files.is_empty() => {
+            let mut hunk_select = HunkSelectState::new(purpose, commit_id.clone(), diff);
+            for path in state.log.picked_files.drain() {
+                if let Some(idx) = hunk_select.diff.files.iter().position(|f| f.path == path) {
+                    hunk_select.selection.toggle_file(&hunk_select.diff, idx);
+                }
+            }
+            state.show_diffs = true;
+            state.log.diff_scroll = 0;
+            state.hunk_select = Some(hunk_select);
+            state.mode = AppMode::HunkSelect;
+            return;
+        }
+        Some(_) => "The revision has no changes to select from.",
+        None => "The revision's diff is still loading.",
+    };
+    state.last_error = Some(ErrorState {
+        suggestions: Vec::new(),
+        message: message.to_string(),
+        timestamp: Local::now(),
+        severity: ErrorSeverity::Warning,
+    });
+}
+
+fn move_cursor(state: &mut AppState, delta: isize) -> UpdateResult {
+    if let Some(hunk_select) = &mut state.hunk_select {
+        hunk_select.move_cursor(delta);
+        // Keep the cursor on screen.
+        let cursor = hunk_select.cursor as u16;
+        let height = state.log.diff_height.max(1);
+        if cursor < state.log.diff_scroll {
+            state.log.diff_scroll = cursor;
+        } else if cursor >= state.log.diff_scroll + height {
+            state.log.diff_scroll = cursor + 1 - height;
+        }
+    }
+    UpdateResult::Handled(None)
+}
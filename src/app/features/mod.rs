@@ -1,4 +1,13 @@
+pub mod bookmarks;
+pub mod conflict_editor;
+pub mod custom_commands;
 pub mod filter;
+pub mod hunk_select;
 pub mod navigation;
+pub mod push;
+pub mod remotes;
+pub mod rewrite_preview;
+pub mod tags;
 pub mod ui;
 pub mod vcs;
+pub mod workspaces;
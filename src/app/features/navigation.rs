@@ -3,6 +3,7 @@ use crate::app::{
     command::Command,
     state::{AppMode, AppState, Panel},
 };
+use crate::domain::diff::DiffLayout;
 
 pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
     match action {
@@ -63,13 +64,10 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         Action::NextHunk => {
             if let Some(diff) = &state.log.current_diff {
                 let current = state.log.diff_scroll as usize;
-                let lines: Vec<&str> = diff.lines().collect();
-                for (idx, line) in lines.iter().enumerate().skip(current + 1) {
-                    if line.starts_with("@@") {
-                        state.log.diff_scroll = idx as u16;
-                        state.hunk_highlight_time = Some(std::time::Instant::now());
-                        break;
-                    }
+                let hunk_rows = diff.hunk_rows(state.diff_layout());
+                if let Some(row) = hunk_rows.into_iter().find(|&row| row > current) {
+                    state.log.diff_scroll = row as u16;
+                    state.hunk_highlight_time = Some(std::time::Instant::now());
                 }
             }
             UpdateResult::Handled(None)
@@ -77,13 +75,10 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         Action::PrevHunk => {
             if let Some(diff) = &state.log.current_diff {
                 let current = state.log.diff_scroll as usize;
-                let lines: Vec<&str> = diff.lines().collect();
-                for (idx, line) in lines.iter().enumerate().take(current).rev() {
-                    if line.starts_with("@@") {
-                        state.log.diff_scroll = idx as u16;
-                        state.hunk_highlight_time = Some(std::time::Instant::now());
-                        break;
-                    }
+                let hunk_rows = diff.hunk_rows(state.diff_layout());
+                if let Some(row) = hunk_rows.into_iter().rev().find(|&row| row < current) {
+                    state.log.diff_scroll = row as u16;
+                    state.hunk_highlight_time = Some(std::time::Instant::now());
                 }
             }
             UpdateResult::Handled(None)
@@ -105,6 +100,16 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             }
             UpdateResult::Handled(None)
         }
+        Action::ToggleDiffLayout => {
+            state.log.diff_layout = match state.log.diff_layout {
+                DiffLayout::Unified => DiffLayout::Split,
+                DiffLayout::Split => DiffLayout::Unified,
+            };
+            // Row positions differ between layouts; keep the selected file in view.
+            state.log.diff_scroll = 0;
+            scroll_to_selected_file(state);
+            UpdateResult::Handled(None)
+        }
         Action::FocusDiff => {
             state.show_diffs = true;
             state.mode = AppMode::Diff;
@@ -143,6 +148,24 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             state.log.selected_ids.clear();
             UpdateResult::Handled(None)
         }
+        Action::TogglePickFile => {
+            if let (Some(repo), Some(idx), Some(file_idx)) = (
+                &state.repo,
+                state.log.list_state.selected(),
+                state.log.selected_file_index,
+            ) {
+                if let Some(file) = repo
+                    .graph
+                    .get(idx)
+                    .and_then(|r| r.changed_files.get(file_idx))
+                {
+                    if !state.log.picked_files.remove(&file.path) {
+                        state.log.picked_files.insert(file.path.clone());
+                    }
+                }
+            }
+            UpdateResult::Handled(None)
+        }
         _ => UpdateResult::NotHandled,
     }
 }
@@ -172,6 +195,9 @@ pub fn handle_selection(state: &mut AppState) -> Option<Command> {
             let commit_id = row.commit_id.clone();
             state.log.diff_scroll = 0; // Reset scroll on selection change
             state.log.selected_file_index = None;
+            state.log.picked_files.clear();
+            state.log.diff_error = None;
+            state.log.current_highlights = state.log.highlight_cache.get(&commit_id).cloned();
             if let Some(cached_diff) = state.log.diff_cache.get(&commit_id) {
                 state.log.current_diff = Some(cached_diff.clone());
                 state.log.is_loading_diff = false;
@@ -194,9 +220,8 @@ fn scroll_to_selected_file(state: &mut AppState) {
     ) {
         if let Some(row) = repo.graph.get(idx) {
             if let Some(file) = row.changed_files.get(file_idx) {
-                let target = format!("File: {}", file.path);
-                if let Some(line_idx) = diff.lines().position(|l| l.starts_with(&target)) {
-                    state.log.diff_scroll = line_idx as u16;
+                if let Some(row_idx) = diff.file_row(&file.path, state.diff_layout()) {
+                    state.log.diff_scroll = row_idx as u16;
                 }
             }
         }
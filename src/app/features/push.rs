@@ -0,0 +1,259 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{AppMode, AppState, PushMode, PushState},
+};
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::PushIntent => {
+            let Some(row) = state
+                .repo
+                .as_ref()
+                .zip(state.log.list_state.selected())
+                .and_then(|(repo, idx)| repo.graph.get(idx))
+            else {
+                return UpdateResult::Handled(None);
+            };
+            // Like `jj git push`, which pushes tracked bookmarks by default.
+            let mode = if row.bookmarks.is_empty() {
+                PushMode::Tracked
+            } else {
+                PushMode::Bookmarks
+            };
+            state.push_state = Some(PushState {
+                remote: None,
+                mode,
+                revision: row.commit_id.clone(),
+                picked: row.bookmarks.clone(),
+                cursor: 0,
+                plan: None,
+                results: None,
+            });
+            state.mode = AppMode::Push;
+            UpdateResult::Handled(preview(state))
+        }
+        Action::PushPreviewLoaded(plan) => {
+            if let Some(push) = &mut state.push_state {
+                push.remote = Some(plan.remote.clone());
+                push.cursor = push.cursor.min(plan.bookmarks.len().saturating_sub(1));
+                push.plan = Some(*plan.clone());
+            }
+            UpdateResult::Handled(None)
+        }
+        Action::ClosePush => {
+            close(state);
+            UpdateResult::Handled(None)
+        }
+        Action::PushNextRemote => {
+            let Some(push) = &mut state.push_state else {
+                return UpdateResult::Handled(None);
+            };
+            let Some(plan) = &push.plan else {
+                return UpdateResult::Handled(None);
+            };
+            if plan.remotes.len() < 2 {
+                set_status(state, "There is no other remote to push to".into());
+                return UpdateResult::Handled(None);
+            }
+            let next = plan
+                .remotes
+                .iter()
+                .position(|remote| *remote == plan.remote)
+                .map_or(0, |idx| (idx + 1) % plan.remotes.len());
+            push.remote = Some(plan.remotes[next].clone());
+            UpdateResult::Handled(preview(state))
+        }
+        Action::SetPushMode(mode) => {
+            let Some(push) = &mut state.push_state else {
+                return UpdateResult::Handled(None);
+            };
+            if push.mode == *mode {
+                return UpdateResult::Handled(None);
+            }
+            push.mode = *mode;
+            UpdateResult::Handled(preview(state))
+        }
+        Action::PushSelectNext => select(state, |cursor| cursor + 1),
+        Action::PushSelectPrev => select(state, |cursor| cursor.saturating_sub(1)),
+        Action::PushToggleBookmark => {
+            let Some(push) = &mut state.push_state else {
+                return UpdateResult::Handled(None);
+            };
+            let Some(name) = push
+                .plan
+                .as_ref()
+                .and_then(|plan| plan.bookmarks.get(push.cursor))
+                .cloned()
+            else {
+                return UpdateResult::Handled(None);
+            };
+            // Picking a bookmark means pushing just the picked ones.
+            if push.mode != PushMode::Bookmarks {
+                push.mode = PushMode::Bookmarks;
+                push.picked.clear();
+            }
+            match push.picked.iter().position(|picked| *picked == name) {
+                Some(idx) => {
+                    push.picked.remove(idx);
+                }
+                None => push.picked.push(name),
+            }
+            UpdateResult::Handled(preview(state))
+        }
+        Action::ConfirmPush => {
+            let Some(push) = &state.push_state else {
+                return UpdateResult::Handled(None);
+            };
+            let (Some(remote), Some(plan)) = (&push.remote, &push.plan) else {
+                return UpdateResult::Handled(None);
+            };
+            if push.results.is_some() {
+                close(state);
+                return UpdateResult::Handled(None);
+            }
+            if plan.updates.is_empty() {
+                set_status(state, "Nothing to push".into());
+                return UpdateResult::Handled(None);
+            }
+            UpdateResult::Handled(Some(Command::Push(remote.clone(), push.scope())))
+        }
+        Action::PushCompleted(results) => {
+            if let Some(push) = &mut state.push_state {
+                push.results = Some(results.clone());
+                state.mode = AppMode::Push;
+            }
+            UpdateResult::Handled(None)
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+/// Asks for a new preview of the dialog's scope, dropping the old one.
+fn preview(state: &mut AppState) -> Option<Command> {
+    let push = state.push_state.as_mut()?;
+    push.plan = None;
+    Some(Command::PreviewPush(push.remote.clone(), push.scope()))
+}
+
+fn select(state: &mut AppState, f: impl FnOnce(usize) -> usize) -> UpdateResult {
+    if let Some(push) = &mut state.push_state {
+        let len = push.plan.as_ref().map_or(0, |plan| plan.bookmarks.len());
+        push.cursor = f(push.cursor).min(len.saturating_sub(1));
+    }
+    UpdateResult::Handled(None)
+}
+
+fn close(state: &mut AppState) {
+    state.mode = AppMode::Normal;
+    state.push_state = None;
+}
+
+fn set_status(state: &mut AppState, message: String) {
+    state.status_message = Some(message);
+    state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{keymap::KeyConfig, reducer};
+    use crate::domain::{
+        models::{CommitId, GraphRow, RepoStatus},
+        push::{BookmarkUpdate, PushPlan, PushScope, PushedBookmark, UpdateKind},
+    };
+
+    #[test]
+    fn test_push_dialog() {
+        let mut state = AppState::new(KeyConfig::default());
+        state.repo = Some(RepoStatus {
+            repo_name: "test".to_string(),
+            operation_id: "op".to_string(),
+            workspace_id: "ws".to_string(),
+            working_copy_id: CommitId("abc".to_string()),
+            graph: vec![GraphRow {
+                commit_id: CommitId("abc".to_string()),
+                bookmarks: vec!["main".to_string()],
+                ..Default::default()
+            }],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+
+        // The selected revision's bookmarks, to the remote `jj` would pick.
+        let command = reducer::update(&mut state, Action::PushIntent);
+        assert!(matches!(
+            command,
+            Some(Command::PreviewPush(None, PushScope::Bookmarks(names))) if names == ["main"]
+        ));
+        assert_eq!(state.mode, AppMode::Push);
+
+        let plan = PushPlan {
+            remote: "origin".into(),
+            remotes: vec!["origin".into(), "upstream".into()],
+            bookmarks: vec!["feature".into(), "main".into()],
+            updates: vec![BookmarkUpdate {
+                name: "main".into(),
+                kind: UpdateKind::Add,
+                old: None,
+                new: Some("abc".into()),
+            }],
+            skipped: Vec::new(),
+        };
+        reducer::update(
+            &mut state,
+            Action::PushPreviewLoaded(Box::new(plan.clone())),
+        );
+        let command = reducer::update(&mut state, Action::PushNextRemote);
+        assert!(matches!(
+            command,
+            Some(Command::PreviewPush(Some(remote), _)) if remote == "upstream"
+        ));
+
+        let command = reducer::update(&mut state, Action::SetPushMode(PushMode::Change));
+        assert!(matches!(
+            command,
+            Some(Command::PreviewPush(_, PushScope::Change(id))) if id.0 == "abc"
+        ));
+
+        // Picking a bookmark switches back to pushing picked ones.
+        reducer::update(&mut state, Action::PushPreviewLoaded(Box::new(plan)));
+        let command = reducer::update(&mut state, Action::PushToggleBookmark);
+        assert!(matches!(
+            command,
+            Some(Command::PreviewPush(_, PushScope::Bookmarks(names))) if names == ["feature"]
+        ));
+        assert!(reducer::update(&mut state, Action::ConfirmPush).is_none());
+
+        let plan = PushPlan {
+            remote: "origin".into(),
+            updates: vec![BookmarkUpdate {
+                name: "feature".into(),
+                kind: UpdateKind::MoveForward,
+                old: Some("abc".into()),
+                new: Some("def".into()),
+            }],
+            ..Default::default()
+        };
+        reducer::update(&mut state, Action::PushPreviewLoaded(Box::new(plan)));
+        let command = reducer::update(&mut state, Action::ConfirmPush);
+        assert!(matches!(
+            command,
+            Some(Command::Push(remote, PushScope::Bookmarks(_))) if remote == "origin"
+        ));
+
+        reducer::update(
+            &mut state,
+            Action::PushCompleted(vec![PushedBookmark {
+                name: "feature".into(),
+                error: None,
+            }]),
+        );
+        assert_eq!(state.mode, AppMode::Push);
+        reducer::update(&mut state, Action::ConfirmPush);
+        assert_eq!(state.mode, AppMode::Normal);
+        assert!(state.push_state.is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{AppMode, AppState, AppTextArea, InputState, RemotesState},
+};
+use crate::domain::remote::Remote;
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::Remotes => UpdateResult::Handled(Some(Command::Remotes)),
+        Action::OpenRemotes(remotes) => {
+            state.remotes_state = Some(RemotesState {
+                remotes: remotes.clone(),
+                selected: 0,
+            });
+            state.mode = AppMode::Remotes;
+            UpdateResult::Handled(None)
+        }
+        Action::CloseRemotes => {
+            close(state);
+            UpdateResult::Handled(None)
+        }
+        Action::SelectRemoteNext => select(state, |selected| selected + 1),
+        Action::SelectRemotePrev => select(state, |selected| selected.saturating_sub(1)),
+        Action::AddRemoteIntent => {
+            start_input(state, "", AppMode::RemoteAdd);
+            UpdateResult::Handled(None)
+        }
+        Action::AddRemote(text) => {
+            state.input = None;
+            let mut parts = text.split_whitespace();
+            let (Some(name), Some(url), None) = (parts.next(), parts.next(), parts.next()) else {
+                state.mode = AppMode::Remotes;
+                set_status(
+                    state,
+                    "Enter the remote's name and URL, like: upstream <url>".into(),
+                );
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            UpdateResult::Handled(Some(Command::AddRemote(name.to_string(), url.to_string())))
+        }
+        Action::RenameRemoteIntent => {
+            let Some(remote) = selected_remote(state) else {
+                return UpdateResult::Handled(None);
+            };
+            start_input(state, &remote.name, AppMode::RemoteRename);
+            UpdateResult::Handled(None)
+        }
+        Action::RenameRemote(new_name) => {
+            let remote = selected_remote(state);
+            state.input = None;
+            close(state);
+            let new_name = new_name.trim();
+            match remote {
+                Some(remote) if !new_name.is_empty() && new_name != remote.name => {
+                    UpdateResult::Handled(Some(Command::RenameRemote(
+                        remote.name,
+                        new_name.to_string(),
+                    )))
+                }
+                _ => UpdateResult::Handled(None),
+            }
+        }
+        Action::SetRemoteUrlIntent => {
+            let Some(remote) = selected_remote(state) else {
+                return UpdateResult::Handled(None);
+            };
+            start_input(state, &remote.url, AppMode::RemoteUrl);
+            UpdateResult::Handled(None)
+        }
+        Action::SetRemoteUrl(url) => {
+            let remote = selected_remote(state);
+            state.input = None;
+            close(state);
+            let url = url.trim();
+            match remote {
+                Some(remote) if !url.is_empty() && url != remote.url => {
+                    UpdateResult::Handled(Some(Command::SetRemoteUrl(remote.name, url.to_string())))
+                }
+                _ => UpdateResult::Handled(None),
+            }
+        }
+        Action::RemoveRemote => {
+            let Some(remote) = selected_remote(state) else {
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            UpdateResult::Handled(Some(Command::RemoveRemote(remote.name)))
+        }
+        Action::FetchRemote => {
+            let Some(remote) = selected_remote(state) else {
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            UpdateResult::Handled(Some(Command::Fetch(vec![remote.name], Vec::new())))
+        }
+        Action::FetchAllRemotes => {
+            let Some(panel) = state.remotes_state.take() else {
+                return UpdateResult::Handled(None);
+            };
+            close(state);
+            if panel.remotes.is_empty() {
+                return UpdateResult::Handled(None);
+            }
+            let names = panel
+                .remotes
+                .into_iter()
+                .map(|remote| remote.name)
+                .collect();
+            UpdateResult::Handled(Some(Command::Fetch(names, Vec::new())))
+        }
+        Action::FetchBranchesIntent => {
+            if selected_remote(state).is_none() {
+                return UpdateResult::Handled(None);
+            }
+            start_input(state, "", AppMode::RemoteFetch);
+            UpdateResult::Handled(None)
+        }
+        Action::FetchBranches(text) => {
+            let remote = selected_remote(state);
+            state.input = None;
+            close(state);
+            let branches: Vec<String> = text
+                .split([',', ' '])
+                .filter(|branch| !branch.is_empty())
+                .map(str::to_string)
+                .collect();
+            match remote {
+                Some(remote) if !branches.is_empty() => {
+                    UpdateResult::Handled(Some(Command::Fetch(vec![remote.name], branches)))
+                }
+                _ => UpdateResult::Handled(None),
+            }
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+fn selected_remote(state: &AppState) -> Option<Remote> {
+    state.remotes_state.as_ref()?.selected_remote().cloned()
+}
+
+fn start_input(state: &mut AppState, text: &str, mode: AppMode) {
+    let mut text_area = AppTextArea::default();
+    text_area.insert_str(text);
+    state.input = Some(InputState { text_area });
+    state.mode = mode;
+}
+
+fn select(state: &mut AppState, f: impl FnOnce(usize) -> usize) -> UpdateResult {
+    if let Some(panel) = &mut state.remotes_state {
+        panel.selected = f(panel.selected).min(panel.remotes.len().saturating_sub(1));
+    }
+    UpdateResult::Handled(None)
+}
+
+fn close(state: &mut AppState) {
+    state.mode = AppMode::Normal;
+    state.remotes_state = None;
+}
+
+fn set_status(state: &mut AppState, message: String) {
+    state.status_message = Some(message);
+    state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{keymap::KeyConfig, reducer};
+
+    #[test]
+    fn test_remote_panel() {
+        let mut state = AppState::new(KeyConfig::default());
+        let remote = |name: &str| Remote {
+            name: name.to_string(),
+            url: format!("https://example.com/{name}.git"),
+            push_url: None,
+        };
+        let remotes = vec![remote("origin"), remote("upstream")];
+        reducer::update(&mut state, Action::OpenRemotes(remotes.clone()));
+        assert_eq!(state.mode, AppMode::Remotes);
+
+        // Adding takes both a name and a URL.
+        reducer::update(&mut state, Action::AddRemoteIntent);
+        assert_eq!(state.mode, AppMode::RemoteAdd);
+        assert!(reducer::update(&mut state, Action::AddRemote("fork".into())).is_none());
+        assert_eq!(state.mode, AppMode::Remotes);
+        assert!(state.status_message.is_some());
+        let command = reducer::update(&mut state, Action::AddRemote(" fork /tmp/fork ".into()));
+        assert!(matches!(
+            command,
+            Some(Command::AddRemote(name, url)) if name == "fork" && url == "/tmp/fork"
+        ));
+        assert_eq!(state.mode, AppMode::Normal);
+
+        reducer::update(&mut state, Action::OpenRemotes(remotes.clone()));
+        reducer::update(&mut state, Action::SelectRemoteNext);
+        reducer::update(&mut state, Action::FetchBranchesIntent);
+        assert_eq!(state.mode, AppMode::RemoteFetch);
+        let command = reducer::update(&mut state, Action::FetchBranches("main, release-*".into()));
+        assert!(matches!(
+            command,
+            Some(Command::Fetch(remotes, branches))
+                if remotes == ["upstream"] && branches == ["main", "release-*"]
+        ));
+
+        reducer::update(&mut state, Action::OpenRemotes(remotes));
+        let command = reducer::update(&mut state, Action::FetchAllRemotes);
+        assert!(matches!(
+            command,
+            Some(Command::Fetch(remotes, branches))
+                if remotes == ["origin", "upstream"] && branches.is_empty()
+        ));
+        assert!(state.remotes_state.is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    reducer::{reload_limit, STATUS_CLEAR_DURATION},
+    state::{AppMode, AppState, RewritePreviewState},
+};
+use crate::domain::graph_layout;
+use crate::domain::models::Rewrite;
+use ratatui::widgets::TableState;
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::TogglePreviewRewrites => {
+            state.preview_rewrites = !state.preview_rewrites;
+            let message = if state.preview_rewrites {
+                "Rewrites are previewed before they run"
+            } else {
+                "Rewrites run straight away"
+            };
+            state.status_message = Some(message.to_string());
+            state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+            UpdateResult::Handled(None)
+        }
+        Action::RewritePreviewLoaded(rewrite, preview) => {
+            let mut preview = *preview.clone();
+            graph_layout::calculate_graph_layout(&mut preview.status.graph);
+            // Start on the first revision the rewrite was started on, found
+            // by its change ID since rewriting gives it a new commit ID.
+            let change_ids: Vec<&str> = state
+                .repo
+                .iter()
+                .flat_map(|repo| &repo.graph)
+                .filter(|row| rewrite.commit_ids().contains(&row.commit_id))
+                .map(|row| row.change_id.as_str())
+                .collect();
+            let selected = preview
+                .status
+                .graph
+                .iter()
+                .position(|row| change_ids.contains(&row.change_id.as_str()))
+                .unwrap_or(0);
+            let mut list_state = TableState::default();
+            list_state.select(Some(selected));
+            state.rewrite_preview = Some(RewritePreviewState {
+                rewrite: rewrite.clone(),
+                preview,
+                list_state,
+            });
+            state.mode = AppMode::RewritePreview;
+            UpdateResult::Handled(None)
+        }
+        Action::SelectPreviewNext => select(state, |selected| selected + 1),
+        Action::SelectPreviewPrev => select(state, |selected| selected.saturating_sub(1)),
+        Action::ConfirmRewrite => {
+            let Some(preview) = state.rewrite_preview.take() else {
+                return UpdateResult::Handled(None);
+            };
+            state.mode = AppMode::Normal;
+            UpdateResult::Handled(Some(run(preview.rewrite)))
+        }
+        Action::CloseRewritePreview => {
+            state.rewrite_preview = None;
+            state.mode = AppMode::Normal;
+            UpdateResult::Handled(None)
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+/// What to do for `rewrite`: preview it if previews are on, else run it.
+pub fn command(state: &AppState, rewrite: Rewrite) -> Command {
+    if state.preview_rewrites {
+        Command::PreviewRewrite(rewrite, reload_limit(state), state.revset.clone())
+    } else {
+        run(rewrite)
+    }
+}
+
+fn run(rewrite: Rewrite) -> Command {
+    match rewrite {
+        Rewrite::Abandon(ids) => Command::Abandon(ids),
+        Rewrite::Squash(ids) => Command::Squash(ids),
+        Rewrite::Parallelize(ids) => Command::Parallelize(ids),
+        Rewrite::Rebase(ids, mode, location, destination) => {
+            Command::Rebase(ids, mode, location, destination)
+        }
+    }
+}
+
+fn select(state: &mut AppState, f: impl FnOnce(usize) -> usize) -> UpdateResult {
+    if let Some(panel) = &mut state.rewrite_preview {
+        let last = panel.preview.status.graph.len().saturating_sub(1);
+        let selected = f(panel.list_state.selected().unwrap_or(0)).min(last);
+        panel.list_state.select(Some(selected));
+    }
+    UpdateResult::Handled(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::keymap::KeyConfig;
+    use crate::app::reducer;
+    use crate::domain::models::{CommitId, GraphRow, RepoStatus, RewritePreview};
+
+    #[test]
+    fn test_preview_then_confirm() {
+        let mut state = AppState::new(KeyConfig::default());
+        let row = |commit_id: &str, change_id: &str| GraphRow {
+            commit_id: CommitId(commit_id.to_string()),
+            change_id: change_id.to_string(),
+            ..Default::default()
+        };
+        state.repo = Some(RepoStatus {
+            repo_name: "repo".to_string(),
+            operation_id: String::new(),
+            workspace_id: "default".to_string(),
+            working_copy_id: CommitId("abc".to_string()),
+            graph: vec![row("abc", "kkk"), row("def", "lll")],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+
+        reducer::update(&mut state, Action::TogglePreviewRewrites);
+        let command = reducer::update(&mut state, Action::ParallelizeRevision);
+        let Some(Command::PreviewRewrite(rewrite, _, _)) = command else {
+            panic!("expected a preview, got {command:?}");
+        };
+        assert_eq!(state.mode, AppMode::Normal);
+
+        // The rewritten revision keeps its change ID under a new commit ID.
+        let preview = RewritePreview {
+            status: RepoStatus {
+                graph: vec![row("def", "lll"), row("new", "kkk")],
+                ..state.repo.clone().expect("repo is loaded")
+            },
+            new_conflicts: Default::default(),
+        };
+        reducer::update(
+            &mut state,
+            Action::RewritePreviewLoaded(rewrite, Box::new(preview)),
+        );
+        assert_eq!(state.mode, AppMode::RewritePreview);
+        let panel = state.rewrite_preview.as_ref().expect("preview is open");
+        assert_eq!(panel.list_state.selected(), Some(1));
+
+        let command = reducer::update(&mut state, Action::ConfirmRewrite);
+        assert!(matches!(
+            command,
+            Some(Command::Parallelize(ids)) if ids == [CommitId("abc".to_string())]
+        ));
+        assert_eq!(state.mode, AppMode::Normal);
+        assert!(state.rewrite_preview.is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    state::{AppMode, AppState, AppTextArea, ContextMenuState, InputState},
+};
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::TagMenu => {
+            let Some(row) = state
+                .repo
+                .as_ref()
+                .zip(state.log.list_state.selected())
+                .and_then(|(repo, idx)| repo.graph.get(idx))
+            else {
+                return UpdateResult::Handled(None);
+            };
+            let mut actions = vec![
+                ("Create tag here...".to_string(), Action::CreateTagIntent),
+                ("Move a tag here...".to_string(), Action::MoveTagIntent),
+            ];
+            for tag in &row.tags {
+                actions.push((format!("Delete tag: {tag}"), Action::DeleteTag(tag.clone())));
+                actions.push((format!("Push tag: {tag}"), Action::PushTag(tag.clone())));
+            }
+            state.context_menu = Some(ContextMenuState {
+                commit_id: row.commit_id.clone(),
+                x: 20,
+                y: 8,
+                selected_index: 0,
+                actions,
+            });
+            state.mode = AppMode::ContextMenu;
+            UpdateResult::Handled(None)
+        }
+        Action::CreateTagIntent => start_input(state, AppMode::TagInput),
+        Action::MoveTagIntent => start_input(state, AppMode::TagMove),
+        Action::SetTag(commit_id, name, allow_move) => {
+            state.input = None;
+            state.mode = AppMode::Normal;
+            UpdateResult::Handled(Some(Command::SetTag(
+                commit_id.clone(),
+                name.clone(),
+                *allow_move,
+            )))
+        }
+        Action::DeleteTag(name) => {
+            state.mode = AppMode::Normal;
+            UpdateResult::Handled(Some(Command::DeleteTag(name.clone())))
+        }
+        Action::PushTag(name) => {
+            state.mode = AppMode::Normal;
+            UpdateResult::Handled(Some(Command::PushTag(name.clone())))
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+fn start_input(state: &mut AppState, mode: AppMode) -> UpdateResult {
+    state.input = Some(InputState {
+        text_area: AppTextArea::default(),
+    });
+    state.mode = mode;
+    UpdateResult::Handled(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::keymap::KeyConfig;
+    use crate::app::reducer;
+    use crate::domain::models::{CommitId, GraphRow, RepoStatus};
+
+    #[test]
+    fn test_tag_menu() {
+        let mut state = AppState::new(KeyConfig::default());
+        let commit_id = CommitId("abc".to_string());
+        state.repo = Some(RepoStatus {
+            repo_name: "repo".to_string(),
+            operation_id: String::new(),
+            workspace_id: "default".to_string(),
+            working_copy_id: commit_id.clone(),
+            graph: vec![GraphRow {
+                commit_id: commit_id.clone(),
+                tags: vec!["v1.0".to_string()],
+                ..Default::default()
+            }],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+
+        reducer::update(&mut state, Action::TagMenu);
+        assert_eq!(state.mode, AppMode::ContextMenu);
+        let labels: Vec<String> = state
+            .context_menu
+            .as_ref()
+            .expect("menu is open")
+            .actions
+            .iter()
+            .map(|(label, _)| label.clone())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "Create tag here...",
+                "Move a tag here...",
+                "Delete tag: v1.0",
+                "Push tag: v1.0"
+            ]
+        );
+        let command = reducer::update(&mut state, Action::SelectContextMenuAction(3));
+        assert!(matches!(command, Some(Command::PushTag(name)) if name == "v1.0"));
+
+        reducer::update(&mut state, Action::TagMenu);
+        reducer::update(&mut state, Action::SelectContextMenuAction(1));
+        assert_eq!(state.mode, AppMode::TagMove);
+        let enter = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Enter);
+        if let Some(input) = &mut state.input {
+            input.text_area.insert_str("v1.0");
+        }
+        let action = crate::app::input::map_event_to_action(
+            crossterm::event::Event::Key(enter),
+            &state,
+            ratatui::layout::Size::new(80, 24),
+        )
+        .expect("enter submits");
+        let command = reducer::update(&mut state, action);
+        assert!(matches!(
+            command,
+            Some(Command::SetTag(id, name, true)) if id == commit_id && name == "v1.0"
+        ));
+        assert_eq!(state.mode, AppMode::Normal);
+    }
+}
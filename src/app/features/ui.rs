@@ -1,15 +1,21 @@
 use crate::app::{
     action::{Action, UpdateResult},
-    state::{AppMode, AppState, CommandPaletteState, EvologState, OperationLogState},
+    command::Command,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{
+        AppMode, AppState, CommandPaletteState, ErrorSeverity, ErrorState, EvologState,
+        OperationLogState,
+    },
 };
 use crate::theme::Theme;
+use std::time::Instant;
 
 pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
     match action {
         Action::EnterCommandMode => {
             state.mode = AppMode::CommandPalette;
             state.command_palette = Some(CommandPaletteState {
-                matches: crate::app::command_palette::search_commands(""),
+                matches: crate::app::command_palette::search_commands("", &state.custom_commands),
                 ..Default::default()
             });
             UpdateResult::Handled(None)
@@ -22,8 +28,19 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             state.context_menu = None;
             state.evolog_state = None;
             state.operation_log_state = None;
-            state.theme_selection = None;
+            state.workspaces_state = None;
+            state.bookmarks_state = None;
+            state.bookmark_move = None;
+            state.push_state = None;
+            state.remotes_state = None;
+            if let Some(ts) = state.theme_selection.take() {
+                // Put back the theme from before the preview.
+                state.theme = ts.original;
+            }
             state.rebase_sources.clear();
+            state.rewrite_preview = None;
+            state.hunk_select = None;
+            state.conflict_editor = None;
             UpdateResult::Handled(None)
         }
         Action::ToggleHelp => {
@@ -35,31 +52,62 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             UpdateResult::Handled(None)
         }
         Action::EnterThemeSelection => {
+            // Read again each time, so edits to theme files show up.
+            let (themes, problems) =
+                crate::theme::load_themes(crate::theme::themes_dir().as_deref());
+            if !problems.is_empty() {
+                state.last_error = Some(ErrorState {
+                    message: "Some theme files could not be used".to_string(),
+                    timestamp: chrono::Local::now(),
+                    severity: ErrorSeverity::Warning,
+                    suggestions: problems,
+                });
+            }
             state.mode = AppMode::ThemeSelection;
-            state.theme_selection = Some(crate::app::state::ThemeSelectionState::default());
+            state.theme_selection = Some(crate::app::state::ThemeSelectionState {
+                selected_index: themes
+                    .iter()
+                    .position(|entry| entry.name == state.theme_name)
+                    .unwrap_or(0),
+                themes,
+                original: state.theme.clone(),
+            });
             UpdateResult::Handled(None)
         }
         Action::SwitchTheme(palette) => {
-            state.palette_type = *palette;
+            state.theme_name = palette.name().to_string();
             state.theme = Theme::from_palette_type(*palette);
             state.mode = AppMode::Normal;
             UpdateResult::Handled(None)
         }
+        Action::PendingKeys(keys) => {
+            state.pending_keys = keys.clone();
+            UpdateResult::Handled(None)
+        }
         Action::TextAreaInput(key) => {
             if let Some(input) = &mut state.input {
-                input.text_area.input(*key);
+                if input.text_area.input(*key) && state.mode == AppMode::FilterInput {
+                    // Any edit invalidates the highlighted error span.
+                    state.revset_error = None;
+                }
             } else if state.mode == AppMode::CommandPalette {
                 if let Some(cp) = &mut state.command_palette {
                     use crossterm::event::KeyCode;
                     match key.code {
                         KeyCode::Char(c) => {
                             cp.query.push(c);
-                            cp.matches = crate::app::command_palette::search_commands(&cp.query);
+                            cp.matches = crate::app::command_palette::search_commands(
+                                &cp.query,
+                                &state.custom_commands,
+                            );
                             cp.selected_index = 0;
                         }
                         KeyCode::Backspace => {
                             cp.query.pop();
-                            cp.matches = crate::app::command_palette::search_commands(&cp.query);
+                            cp.matches = crate::app::command_palette::search_commands(
+                                &cp.query,
+                                &state.custom_commands,
+                            );
                             cp.selected_index = 0;
                         }
                         _ => {}
@@ -91,6 +139,8 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         Action::SelectThemeNext => {
             if let Some(ts) = &mut state.theme_selection {
                 ts.selected_index = (ts.selected_index + 1) % ts.themes.len();
+                // Preview it; cancelling puts the old one back.
+                state.theme = ts.themes[ts.selected_index].theme.clone();
             }
             UpdateResult::Handled(None)
         }
@@ -101,18 +151,25 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                 } else {
                     ts.selected_index -= 1;
                 }
+                state.theme = ts.themes[ts.selected_index].theme.clone();
             }
             UpdateResult::Handled(None)
         }
         Action::CommandPaletteSelect => {
             if state.mode == AppMode::ThemeSelection {
-                if let Some(ts) = &state.theme_selection {
-                    if let Some(palette) = ts.themes.get(ts.selected_index) {
-                        state.palette_type = *palette;
-                        state.theme = Theme::from_palette_type(*palette);
-                        state.mode = AppMode::Normal;
-                        state.theme_selection = None;
+                if let Some(mut ts) = state.theme_selection.take() {
+                    if ts.selected_index < ts.themes.len() {
+                        let entry = ts.themes.swap_remove(ts.selected_index);
+                        state.theme = entry.theme;
+                        state.status_message =
+                            Some(match super::super::persistence::save_theme(&entry.name) {
+                                Ok(()) => format!("Theme set to {}", entry.label),
+                                Err(e) => format!("Theme set, but not saved: {e}"),
+                            });
+                        state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+                        state.theme_name = entry.name;
                     }
+                    state.mode = AppMode::Normal;
                 }
                 return UpdateResult::Handled(None);
             }
@@ -121,6 +178,7 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         Action::OpenEvolog(content) => {
             state.mode = AppMode::Evolog;
             state.evolog_state = Some(EvologState {
+                title: "EVOLUTION LOG".to_string(),
                 content: content.lines().map(|s| s.to_string()).collect(),
                 scroll: 0,
             });
@@ -144,32 +202,52 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             }
             UpdateResult::Handled(None)
         }
-        Action::OpenOperationLog(content) => {
+        Action::OpenOperationLog(operations) => {
             state.mode = AppMode::OperationLog;
-            state.operation_log_state = Some(OperationLogState {
-                content: content.lines().map(|s| s.to_string()).collect(),
-                scroll: 0,
-            });
-            UpdateResult::Handled(None)
+            let log = OperationLogState {
+                operations: operations.clone(),
+                ..Default::default()
+            };
+            let command = log
+                .selected_operation()
+                .map(|op| Command::LoadOperationChanges(op.id.clone()));
+            state.operation_log_state = Some(log);
+            UpdateResult::Handled(command)
         }
         Action::CloseOperationLog => {
             state.mode = AppMode::Normal;
             state.operation_log_state = None;
             UpdateResult::Handled(None)
         }
-        Action::ScrollOperationLogUp(n) => {
-            if let Some(op) = &mut state.operation_log_state {
-                op.scroll = op.scroll.saturating_sub(*n);
-            }
-            UpdateResult::Handled(None)
+        Action::SelectOperationNext(n) => select_operation(state, |selected| selected + n),
+        Action::SelectOperationPrev(n) => {
+            select_operation(state, |selected| selected.saturating_sub(*n))
         }
-        Action::ScrollOperationLogDown(n) => {
-            if let Some(op) = &mut state.operation_log_state {
-                let max_scroll = op.content.len().saturating_sub(1) as u16;
-                op.scroll = op.scroll.saturating_add(*n).min(max_scroll);
+        Action::OperationChangesLoaded(op_id, changes) => {
+            if let Some(log) = &mut state.operation_log_state {
+                if log.selected_operation().is_some_and(|op| op.id == *op_id) {
+                    log.changes = Some((**changes).clone());
+                }
             }
             UpdateResult::Handled(None)
         }
+        Action::RestoreOperation | Action::RevertOperation => {
+            let Some(op) = state
+                .operation_log_state
+                .as_ref()
+                .and_then(OperationLogState::selected_operation)
+            else {
+                return UpdateResult::Handled(None);
+            };
+            let command = if matches!(action, Action::RestoreOperation) {
+                Command::RestoreOperation(op.id.clone())
+            } else {
+                Command::RevertOperation(op.id.clone())
+            };
+            state.mode = AppMode::Normal;
+            state.operation_log_state = None;
+            UpdateResult::Handled(Some(command))
+        }
         Action::SelectContextMenuNext => {
             if let Some(menu) = &mut state.context_menu {
                 menu.selected_index = (menu.selected_index + 1) % menu.actions.len();
@@ -188,24 +266,36 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         }
         Action::CloseContextMenu => {
             state.context_menu = None;
-            state.mode = AppMode::Normal;
+            // Back out of choosing where picked changes go.
+            state.mode = if state.hunk_select.is_some() {
+                AppMode::HunkSelect
+            } else {
+                AppMode::Normal
+            };
             UpdateResult::Handled(None)
         }
         Action::OpenContextMenu(commit_id_opt, (x, y)) => {
             if let Some(commit_id) = commit_id_opt {
+                let mut actions = vec![
+                    ("Edit".to_string(), Action::EditRevision(Some(commit_id.clone()))),
+                    ("New Child".to_string(), Action::NewRevision(Some(commit_id.clone()))),
+                    ("Abandon".to_string(), Action::AbandonRevision(Some(commit_id.clone()))),
+                    ("Duplicate".to_string(), Action::DuplicateRevision),
+                    (
+                        "Move Changes".to_string(),
+                        Action::EnterSquashMode(Some(commit_id.clone())),
+                    ),
+                    ("Evolog".to_string(), Action::EvologRevision(Some(commit_id.clone()))),
+                ];
+                actions.extend(state.custom_commands.keys().map(|name| {
+                    (name.clone(), Action::RunCustomCommand(name.clone(), Some(commit_id.clone())))
+                }));
                 state.context_menu = Some(crate::app::state::ContextMenuState {
                     commit_id: commit_id.clone(),
                     x: *x,
                     y: *y,
                     selected_index: 0,
-                    actions: vec![
-                        ("Edit".to_string(), Action::EditRevision(Some(commit_id.clone()))),
-                        ("New Child".to_string(), Action::NewRevision(Some(commit_id.clone()))),
-                        ("Abandon".to_string(), Action::AbandonRevision(Some(commit_id.clone()))),
-                        ("Duplicate".to_string(), Action::DuplicateRevision),
-                        ("Squash".to_string(), Action::EnterSquashMode),
-                        ("Evolog".to_string(), Action::EvologRevision(Some(commit_id.clone()))),
-                    ],
+                    actions,
                 });
                 state.mode = AppMode::ContextMenu;
             }
@@ -214,3 +304,21 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         _ => UpdateResult::NotHandled,
     }
 }
+
+/// Moves the operation log selection, loading what the newly selected
+/// operation changed.
+fn select_operation(state: &mut AppState, f: impl FnOnce(usize) -> usize) -> UpdateResult {
+    let Some(log) = &mut state.operation_log_state else {
+        return UpdateResult::Handled(None);
+    };
+    let selected = f(log.selected).min(log.operations.len().saturating_sub(1));
+    if selected == log.selected {
+        return UpdateResult::Handled(None);
+    }
+    log.selected = selected;
+    log.changes = None;
+    let command = log
+        .selected_operation()
+        .map(|op| Command::LoadOperationChanges(op.id.clone()));
+    UpdateResult::Handled(command)
+}
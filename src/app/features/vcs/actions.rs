@@ -1,9 +1,10 @@
 use crate::app::{
     action::{Action, UpdateResult},
     command::Command,
-    state::{AppMode, AppState, AppTextArea, ErrorSeverity, ErrorState},
+    features::{hunk_select, rewrite_preview},
+    state::{AppMode, AppState, AppTextArea, ErrorSeverity, ErrorState, HunkSelectPurpose},
 };
-use crate::domain::models::FileStatus;
+use crate::domain::models::{CommitId, FileStatus, RebaseLocation, Rewrite, SquashDestination};
 use chrono::Local;
 
 pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
@@ -15,15 +16,24 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                 let idx = state.log.list_state.selected()?;
                 repo.graph.get(idx).map(|r| r.commit_id.clone())
             });
+            if let Some(id) = &id {
+                if rejects_immutable(state, std::slice::from_ref(id)) {
+                    return UpdateResult::Handled(None);
+                }
+            }
             UpdateResult::Handled(id.map(Command::Edit))
         }
         Action::SquashRevision => {
             let ids = state.get_selected_commit_ids();
-            if ids.is_empty() {
+            // The parent the changes land in is rewritten too.
+            let mut rewritten = ids.clone();
+            rewritten.extend(parents_of(state, &ids));
+            if ids.is_empty() || rejects_immutable(state, &rewritten) {
                 UpdateResult::Handled(None)
             } else {
                 state.log.selected_ids.clear();
-                UpdateResult::Handled(Some(Command::Squash(ids)))
+                let command = rewrite_preview::command(state, Rewrite::Squash(ids));
+                UpdateResult::Handled(Some(command))
             }
         }
         Action::NewRevision(commit_id_opt) => {
@@ -35,6 +45,11 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             UpdateResult::Handled(id.map(Command::New))
         }
         Action::DescribeRevisionIntent => {
+            if let Some(id) = selected_commit_id(state) {
+                if rejects_immutable(state, &[id]) {
+                    return UpdateResult::Handled(None);
+                }
+            }
             state.mode = AppMode::Input;
             let mut text_area = AppTextArea::default();
             if let (Some(repo), Some(idx)) = (&state.repo, state.log.list_state.selected()) {
@@ -96,11 +111,12 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
             } else {
                 state.get_selected_commit_ids()
             };
-            if ids.is_empty() {
+            if ids.is_empty() || rejects_immutable(state, &ids) {
                 UpdateResult::Handled(None)
             } else {
                 state.log.selected_ids.clear();
-                UpdateResult::Handled(Some(Command::Abandon(ids)))
+                let command = rewrite_preview::command(state, Rewrite::Abandon(ids));
+                UpdateResult::Handled(Some(command))
             }
         }
         Action::RevertRevision(ids) => {
@@ -119,16 +135,17 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         }
         Action::ParallelizeRevision => {
             let ids = state.get_selected_commit_ids();
-            if ids.is_empty() {
+            if ids.is_empty() || rejects_immutable(state, &ids) {
                 UpdateResult::Handled(None)
             } else {
                 state.log.selected_ids.clear();
-                UpdateResult::Handled(Some(Command::Parallelize(ids)))
+                let command = rewrite_preview::command(state, Rewrite::Parallelize(ids));
+                UpdateResult::Handled(Some(command))
             }
         }
         Action::RebaseRevisionIntent => {
             let ids = state.get_selected_commit_ids();
-            if ids.is_empty() {
+            if ids.is_empty() || rejects_immutable(state, &ids) {
                 UpdateResult::Handled(None)
             } else {
                 state.rebase_sources = ids;
@@ -137,13 +154,40 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                 UpdateResult::Handled(None)
             }
         }
+        Action::SetRebaseMode(mode) => {
+            state.rebase_mode = *mode;
+            UpdateResult::Handled(None)
+        }
+        Action::SetRebaseLocation(location) => {
+            state.rebase_location = *location;
+            UpdateResult::Handled(None)
+        }
         Action::RebaseRevision(sources, destination) => {
+            // Inserting rewrites the target (`-B`) or its children (`-A`).
+            let target = CommitId(destination.trim().to_string());
+            let rewritten: Vec<CommitId> = match state.rebase_location {
+                RebaseLocation::Destination => Vec::new(),
+                RebaseLocation::InsertBefore => vec![target],
+                RebaseLocation::InsertAfter => state
+                    .repo
+                    .iter()
+                    .flat_map(|repo| &repo.graph)
+                    .filter(|row| row.parents.contains(&target))
+                    .map(|row| row.commit_id.clone())
+                    .collect(),
+            };
+            if rejects_immutable(state, &rewritten) {
+                return UpdateResult::Handled(None);
+            }
             state.mode = AppMode::Normal;
             state.rebase_sources.clear();
-            UpdateResult::Handled(Some(Command::Rebase(
+            let rewrite = Rewrite::Rebase(
                 sources.clone(),
+                state.rebase_mode,
+                state.rebase_location,
                 destination.clone(),
-            )))
+            );
+            UpdateResult::Handled(Some(rewrite_preview::command(state, rewrite)))
         }
         Action::SetBookmarkIntent => {
             state.mode = AppMode::BookmarkInput;
@@ -199,6 +243,7 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
         }
         Action::DeleteBookmark(name) => {
             state.mode = AppMode::Normal;
+            state.bookmarks_state = None;
             UpdateResult::Handled(Some(Command::DeleteBookmark(name.clone())))
         }
         Action::SplitRevision(commit_id_opt) => {
@@ -207,45 +252,79 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
                 let idx = state.log.list_state.selected()?;
                 repo.graph.get(idx).map(|r| r.commit_id.clone())
             });
-            UpdateResult::Handled(id.map(Command::Split))
+            if let Some(id) = id {
+                if !rejects_immutable(state, std::slice::from_ref(&id)) {
+                    hunk_select::enter(state, HunkSelectPurpose::Split, &id);
+                }
+            }
+            UpdateResult::Handled(None)
         }
-        Action::Undo => UpdateResult::Handled(Some(Command::Undo)),
-        Action::Redo => UpdateResult::Handled(Some(Command::Redo)),
-        Action::Fetch => UpdateResult::Handled(Some(Command::Fetch)),
-        Action::PushIntent => {
-            if let (Some(repo), Some(idx)) = (&state.repo, state.log.list_state.selected()) {
-                if let Some(row) = repo.graph.get(idx) {
-                    if row.bookmarks.is_empty() {
-                        return UpdateResult::Handled(Some(Command::Push(None)));
-                    } else if row.bookmarks.len() == 1 {
-                        return UpdateResult::Handled(Some(Command::Push(Some(
-                            row.bookmarks[0].clone(),
-                        ))));
-                    }
-                    // Multiple bookmarks: open context menu for selection
-                    let mut actions = Vec::new();
-                    for bookmark in &row.bookmarks {
-                        actions.push((
-                            format!("Push bookmark: {bookmark}"),
-                            Action::Push(Some(bookmark.clone())),
-                        ));
-                    }
-                    actions.push(("Push All".to_string(), Action::Push(None)));
-
-                    state.mode = AppMode::ContextMenu;
-                    state.context_menu = Some(crate::app::state::ContextMenuState {
-                        commit_id: row.commit_id.clone(),
-                        x: 20,
-                        y: 8,
-                        selected_index: 0,
-                        actions,
-                    });
+        Action::SplitSelected(message) => {
+            state.mode = AppMode::Normal;
+            state.input = None;
+            let Some(hunk_select) = state.hunk_select.take() else {
+                return UpdateResult::Handled(None);
+            };
+            let files = hunk_select.selection.file_selections(&hunk_select.diff);
+            UpdateResult::Handled(Some(Command::Split(
+                hunk_select.commit_id,
+                files,
+                message.clone(),
+            )))
+        }
+        Action::EnterSquashMode(commit_id_opt) => {
+            let id = commit_id_opt.clone().or_else(|| {
+                let repo = state.repo.as_ref()?;
+                let idx = state.log.list_state.selected()?;
+                repo.graph.get(idx).map(|r| r.commit_id.clone())
+            });
+            if let Some(id) = id {
+                if !rejects_immutable(state, std::slice::from_ref(&id)) {
+                    hunk_select::enter(state, HunkSelectPurpose::Squash, &id);
                 }
             }
             UpdateResult::Handled(None)
         }
-        Action::Push(bookmark) => UpdateResult::Handled(Some(Command::Push(bookmark.clone()))),
+        Action::PickSquashTarget => {
+            state.mode = AppMode::SquashSelect;
+            UpdateResult::Handled(None)
+        }
+        Action::SquashSelected(destination) => {
+            let targets = match (destination, &state.hunk_select) {
+                (SquashDestination::Revision(id), _) => vec![id.clone()],
+                (SquashDestination::Parent, Some(hunk_select)) => {
+                    parents_of(state, std::slice::from_ref(&hunk_select.commit_id))
+                }
+                _ => Vec::new(),
+            };
+            // Stay put so that another destination can be picked.
+            if rejects_immutable(state, &targets) {
+                return UpdateResult::Handled(None);
+            }
+            state.mode = AppMode::Normal;
+            let Some(hunk_select) = state.hunk_select.take() else {
+                return UpdateResult::Handled(None);
+            };
+            let files = hunk_select.selection.file_selections(&hunk_select.diff);
+            UpdateResult::Handled(Some(Command::SquashChanges(
+                hunk_select.commit_id,
+                files,
+                destination.clone(),
+            )))
+        }
+        Action::Undo => UpdateResult::Handled(Some(Command::Undo)),
+        Action::Redo => UpdateResult::Handled(Some(Command::Redo)),
+        Action::Fetch => UpdateResult::Handled(Some(Command::Fetch(Vec::new(), Vec::new()))),
         Action::ResolveConflict(path) => {
+            let id = selected_commit_id(state);
+            if let Some(id) = &id {
+                if rejects_immutable(state, std::slice::from_ref(id)) {
+                    return UpdateResult::Handled(None);
+                }
+            }
+            UpdateResult::Handled(id.map(|id| Command::LoadConflict(id, path.clone())))
+        }
+        Action::ResolveConflictExternally(path) => {
             UpdateResult::Handled(Some(Command::ResolveConflict(path.clone())))
         }
         Action::InitRepo => UpdateResult::Handled(Some(Command::InitRepo)),
@@ -262,6 +341,45 @@ pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
     }
 }
 
+fn selected_commit_id(state: &AppState) -> Option<CommitId> {
+    let repo = state.repo.as_ref()?;
+    let idx = state.log.list_state.selected()?;
+    repo.graph.get(idx).map(|r| r.commit_id.clone())
+}
+
+fn parents_of(state: &AppState, ids: &[CommitId]) -> Vec<CommitId> {
+    let Some(repo) = &state.repo else {
+        return Vec::new();
+    };
+    repo.graph
+        .iter()
+        .filter(|row| ids.contains(&row.commit_id))
+        .flat_map(|row| row.parents.iter().cloned())
+        .collect()
+}
+
+/// Reports the first loaded revision among `ids` that `jj` would refuse to
+/// rewrite, so the operation isn't started only to fail.
+fn rejects_immutable(state: &mut AppState, ids: &[CommitId]) -> bool {
+    let Some(row) = state.repo.as_ref().and_then(|repo| {
+        repo.graph
+            .iter()
+            .find(|row| row.is_immutable && ids.contains(&row.commit_id))
+    }) else {
+        return false;
+    };
+    state.last_error = Some(ErrorState {
+        message: format!("Revision {} is immutable", row.change_id_short),
+        suggestions: vec![
+            "Immutable revisions are set by `revset-aliases.\"immutable_heads()\"` in the jj config."
+                .to_string(),
+        ],
+        timestamp: Local::now(),
+        severity: ErrorSeverity::Error,
+    });
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,13 +399,16 @@ mod tests {
             workspace_id: "ws".to_string(),
             working_copy_id: CommitId("abc".to_string()),
             graph: vec![],
+            has_more: false,
+        };
+        let row = GraphRow {
+            is_working_copy: true,
+            changed_files: vec![FileChange {
+                path: "conflict.txt".to_string(),
+                status: FileStatus::Conflicted,
+            }],
+            ..Default::default()
         };
-        let mut row = GraphRow::default();
-        row.is_working_copy = true;
-        row.changed_files.push(FileChange {
-            path: "conflict.txt".to_string(),
-            status: FileStatus::Conflicted,
-        });
         repo.graph.push(row);
         state.repo = Some(repo);
 
@@ -303,4 +424,284 @@ mod tests {
             .message
             .contains("conflicts"));
     }
+
+    #[test]
+    fn test_immutable_revisions_are_not_rewritten() {
+        let mut state = AppState::new(KeyConfig::default());
+        let trunk = CommitId("abc".to_string());
+        let wc = CommitId("def".to_string());
+        state.repo = Some(RepoStatus {
+            repo_name: "test".to_string(),
+            operation_id: "op".to_string(),
+            workspace_id: "ws".to_string(),
+            working_copy_id: wc.clone(),
+            graph: vec![
+                GraphRow {
+                    commit_id: wc.clone(),
+                    parents: vec![trunk.clone()],
+                    is_working_copy: true,
+                    ..Default::default()
+                },
+                GraphRow {
+                    commit_id: trunk.clone(),
+                    change_id_short: "qpvuntsm".to_string(),
+                    is_immutable: true,
+                    ..Default::default()
+                },
+            ],
+            has_more: false,
+        });
+
+        // Squashing @ would rewrite its immutable parent.
+        state.log.list_state.select(Some(0));
+        let result = update(&mut state, &Action::SquashRevision);
+        assert!(matches!(result, UpdateResult::Handled(None)));
+        let error = state.last_error.take().expect("squash is refused");
+        assert_eq!(error.message, "Revision qpvuntsm is immutable");
+
+        state.log.list_state.select(Some(1));
+        update(&mut state, &Action::DescribeRevisionIntent);
+        assert_eq!(state.mode, AppMode::Normal);
+        assert!(state.last_error.take().is_some());
+        let result = update(&mut state, &Action::AbandonRevision(Some(trunk)));
+        assert!(matches!(result, UpdateResult::Handled(None)));
+        assert!(state.last_error.take().is_some());
+
+        // Revisions on top of it are still fine to rewrite.
+        let result = update(&mut state, &Action::AbandonRevision(Some(wc.clone())));
+        assert!(matches!(
+            result,
+            UpdateResult::Handled(Some(Command::Abandon(ids))) if ids == [wc]
+        ));
+        assert!(state.last_error.is_none());
+    }
+
+    #[test]
+    fn test_rebase_picks_mode_and_target() {
+        use crate::app::reducer;
+        use crate::domain::models::RebaseMode;
+
+        let mut state = AppState::new(KeyConfig::default());
+        let trunk = CommitId("abc".to_string());
+        let wc = CommitId("def".to_string());
+        state.repo = Some(RepoStatus {
+            repo_name: "test".to_string(),
+            operation_id: "op".to_string(),
+            workspace_id: "ws".to_string(),
+            working_copy_id: wc.clone(),
+            graph: vec![
+                GraphRow {
+                    commit_id: wc.clone(),
+                    parents: vec![trunk.clone()],
+                    is_working_copy: true,
+                    ..Default::default()
+                },
+                GraphRow {
+                    commit_id: trunk.clone(),
+                    is_immutable: true,
+                    ..Default::default()
+                },
+            ],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+        let press = |state: &mut AppState, c: char| {
+            let key = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Char(c));
+            let action = state.keymap.get_action(key, state).expect("key is bound");
+            reducer::update(state, action)
+        };
+
+        press(&mut state, 'r');
+        assert_eq!(state.mode, AppMode::RebaseSelect);
+        press(&mut state, 's');
+        press(&mut state, 'B');
+        press(&mut state, 'j');
+        assert_eq!(state.log.list_state.selected(), Some(1));
+
+        // Inserting before trunk would rewrite it.
+        let enter = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Enter);
+        let action = state
+            .keymap
+            .get_action(enter, &state)
+            .expect("enter is bound");
+        assert!(reducer::update(&mut state, action).is_none());
+        assert!(state.last_error.take().is_some());
+        assert_eq!(state.mode, AppMode::RebaseSelect);
+
+        press(&mut state, 'A');
+        let action = state
+            .keymap
+            .get_action(enter, &state)
+            .expect("enter is bound");
+        let command = reducer::update(&mut state, action);
+        assert!(matches!(
+            command,
+            Some(Command::Rebase(ids, RebaseMode::Source, RebaseLocation::InsertAfter, target))
+                if ids == [wc] && target == trunk.0
+        ));
+        assert_eq!(state.mode, AppMode::Normal);
+        // The choice sticks for the next rebase.
+        assert_eq!(state.rebase_mode, RebaseMode::Source);
+    }
+
+    #[test]
+    fn test_split_picks_changes_before_describing() {
+        use crate::app::reducer;
+        use crate::domain::diff::{Diff, DiffHeader, DiffLine, DiffLineKind, FileDiff, Hunk};
+
+        let mut state = AppState::new(KeyConfig::default());
+        let id = CommitId("abc".to_string());
+        state.repo = Some(RepoStatus {
+            repo_name: "test".to_string(),
+            operation_id: "op".to_string(),
+            workspace_id: "ws".to_string(),
+            working_copy_id: id.clone(),
+            graph: vec![GraphRow {
+                commit_id: id.clone(),
+                ..Default::default()
+            }],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+        let line = |kind, content: &str| DiffLine {
+            kind,
+            content: content.to_string(),
+            old_lineno: Some(1),
+            new_lineno: Some(1),
+            emphasis: Vec::new(),
+        };
+        let diff = Diff {
+            header: DiffHeader {
+                commit_id: "abc".to_string(),
+                description: "both changes".to_string(),
+                ..Default::default()
+            },
+            files: vec![FileDiff {
+                path: "a.txt".to_string(),
+                status: FileStatus::Modified,
+                is_binary: false,
+                hunks: vec![Hunk {
+                    old_start: 1,
+                    old_len: 1,
+                    new_start: 1,
+                    new_len: 1,
+                    lines: vec![
+                        line(DiffLineKind::Removed, "old"),
+                        line(DiffLineKind::Added, "new"),
+                    ],
+                }],
+            }],
+        };
+        state.log.current_diff = Some(diff);
+
+        assert!(reducer::update(&mut state, Action::SplitRevision(None)).is_none());
+        assert_eq!(state.mode, AppMode::HunkSelect);
+
+        // Nothing picked yet, so confirming stays in the selection.
+        reducer::update(&mut state, Action::HunkSelectConfirm);
+        assert_eq!(state.mode, AppMode::HunkSelect);
+
+        // The cursor starts on the file header, then stops at the hunk
+        // header and the removed line.
+        reducer::update(&mut state, Action::HunkSelectNext);
+        reducer::update(&mut state, Action::HunkSelectNext);
+        reducer::update(&mut state, Action::HunkSelectToggle);
+        reducer::update(&mut state, Action::HunkSelectConfirm);
+        assert_eq!(state.mode, AppMode::SplitInput);
+
+        let command = reducer::update(&mut state, Action::SplitSelected("first".to_string()));
+        let Some(Command::Split(commit_id, files, message)) = command else {
+            panic!("expected a split command, got {command:?}");
+        };
+        assert_eq!(commit_id, id);
+        assert_eq!(message, "first");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].lines, Some(vec![(0, 0)]));
+        assert!(state.hunk_select.is_none());
+    }
+
+    #[test]
+    fn test_move_picked_files_into_revision_picked_in_graph() {
+        use crate::app::reducer;
+        use crate::domain::diff::{Diff, DiffHeader, FileDiff};
+        use crate::domain::models::{FileChange, SquashDestination};
+
+        let mut state = AppState::new(KeyConfig::default());
+        let source = CommitId("abc".to_string());
+        let target = CommitId("def".to_string());
+        let files = ["a.txt", "b.txt"];
+        state.repo = Some(RepoStatus {
+            repo_name: "test".to_string(),
+            operation_id: "op".to_string(),
+            workspace_id: "ws".to_string(),
+            working_copy_id: source.clone(),
+            graph: vec![
+                GraphRow {
+                    commit_id: source.clone(),
+                    changed_files: files
+                        .iter()
+                        .map(|path| FileChange {
+                            path: (*path).to_string(),
+                            status: FileStatus::Added,
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+                GraphRow {
+                    commit_id: target.clone(),
+                    ..Default::default()
+                },
+            ],
+            has_more: false,
+        });
+        state.log.list_state.select(Some(0));
+        state.log.current_diff = Some(Diff {
+            header: DiffHeader {
+                commit_id: "abc".to_string(),
+                ..Default::default()
+            },
+            files: files
+                .iter()
+                .map(|path| FileDiff {
+                    path: (*path).to_string(),
+                    status: FileStatus::Added,
+                    is_binary: true,
+                    hunks: Vec::new(),
+                })
+                .collect(),
+        });
+
+        // Pick b.txt in the file list; it starts out selected.
+        state.log.selected_file_index = Some(1);
+        reducer::update(&mut state, Action::TogglePickFile);
+        reducer::update(&mut state, Action::EnterSquashMode(None));
+        assert_eq!(state.mode, AppMode::HunkSelect);
+        assert!(state.log.picked_files.is_empty());
+
+        // Closing the destination menu goes back to picking.
+        reducer::update(&mut state, Action::HunkSelectConfirm);
+        assert_eq!(state.mode, AppMode::ContextMenu);
+        reducer::update(&mut state, Action::CloseContextMenu);
+        assert_eq!(state.mode, AppMode::HunkSelect);
+
+        reducer::update(&mut state, Action::HunkSelectConfirm);
+        reducer::update(&mut state, Action::SelectContextMenuAction(1));
+        assert_eq!(state.mode, AppMode::SquashSelect);
+        reducer::update(&mut state, Action::SelectNext);
+        let enter = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Enter);
+        let action = state
+            .keymap
+            .get_action(enter, &state)
+            .expect("enter is bound");
+        let command = reducer::update(&mut state, action);
+        let Some(Command::SquashChanges(commit_id, picked, destination)) = command else {
+            panic!("expected a squash command, got {command:?}");
+        };
+        assert_eq!(commit_id, source);
+        assert_eq!(destination, SquashDestination::Revision(target));
+        assert_eq!(picked.len(), 1);
+        assert_eq!(picked[0].path, "b.txt");
+        assert_eq!(picked[0].lines, None);
+        assert_eq!(state.mode, AppMode::Normal);
+    }
 }
@@ -1,10 +1,13 @@
-use crate::app::{action::Action, command::Command};
-use crate::domain::vcs::VcsFacade;
+use crate::app::{action::Action, command::Command, syntax};
+use crate::domain::{error::VcsError, models::RebaseLocation, vcs::VcsFacade};
 use anyhow::Result;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+/// How many operations the operation log loads.
+const OPERATION_LOG_LIMIT: usize = 500;
+
 pub fn handle_command(
     command: Command,
     adapter: Arc<dyn VcsFacade>,
@@ -13,53 +16,44 @@ pub fn handle_command(
     match command {
         Command::LoadRepoBackground(limit, revset) => {
             tokio::spawn(async move {
-                match adapter.get_operation_log(None, limit, revset).await {
+                match adapter.get_operation_log(0, limit, revset).await {
                     Ok(repo) => {
                         let _ = tx
                             .send(Action::RepoReloadedBackground(Box::new(repo)))
                             .await;
                     }
                     Err(e) => {
-                        let _ = tx
-                            .send(Action::ErrorOccurred(format!(
-                                "Background sync failed: {e}"
-                            )))
-                            .await;
+                        let action = match e.downcast_ref::<VcsError>() {
+                            Some(VcsError::InvalidRevset(err)) => Action::RevsetFailed(err.clone()),
+                            _ => Action::ErrorOccurred(format!("Background sync failed: {e}")),
+                        };
+                        let _ = tx.send(action).await;
                     }
                 }
             });
         }
-        Command::LoadRepo(heads, limit, revset) => {
-            let is_batch = heads.is_some();
-            tokio::spawn(async move {
-                match adapter.get_operation_log(heads, limit, revset).await {
-                    Ok(repo) => {
-                        if is_batch {
-                            let _ = tx.send(Action::GraphBatchLoaded(Box::new(repo))).await;
-                        } else {
-                            let _ = tx.send(Action::RepoLoaded(Box::new(repo))).await;
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx
-                            .send(Action::ErrorOccurred(format!("Failed to load repo: {e}")))
-                            .await;
-                    }
-                }
-            });
+        Command::LoadRepo(limit, revset) => load_graph(adapter, tx, 0, limit, revset),
+        Command::LoadGraphPage(offset, limit, revset) => {
+            load_graph(adapter, tx, offset, limit, revset)
         }
         Command::LoadDiff(commit_id) => {
             let commit_id_clone = commit_id.clone();
             tokio::spawn(async move {
-                match adapter.get_commit_diff(&commit_id).await {
-                    Ok(diff) => {
-                        let _ = tx.send(Action::DiffLoaded(commit_id_clone, diff)).await;
-                    }
-                    Err(e) => {
-                        let _ = tx
-                            .send(Action::DiffLoaded(commit_id_clone, format!("Error: {e}")))
-                            .await;
-                    }
+                let diff = adapter
+                    .get_commit_diff(&commit_id)
+                    .await
+                    .map_err(|e| format!("Error: {e}"));
+                let _ = tx.send(Action::DiffLoaded(commit_id_clone, diff)).await;
+            });
+        }
+        Command::HighlightDiff(commit_id, diff) => {
+            tokio::spawn(async move {
+                if let Ok(highlights) =
+                    tokio::task::spawn_blocking(move || syntax::highlight_diff(&diff)).await
+                {
+                    let _ = tx
+                        .send(Action::DiffHighlighted(commit_id, highlights))
+                        .await;
                 }
             });
         }
@@ -126,6 +120,21 @@ pub fn handle_command(
                 .await;
             });
         }
+        Command::SquashChanges(commit_id, files, destination) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Moving changes from {commit_id}..."),
+                    "Changes moved",
+                    move || async move {
+                        adapter
+                            .squash_changes(&commit_id, &files, &destination)
+                            .await
+                    },
+                )
+                .await;
+            });
+        }
         Command::New(commit_id) => {
             tokio::spawn(async move {
                 run_operation(
@@ -161,19 +170,25 @@ pub fn handle_command(
                 .await;
             });
         }
-        Command::Rebase(commit_ids, destination) => {
+        Command::Rebase(commit_ids, mode, location, destination) => {
             tokio::spawn(async move {
+                let place = match location {
+                    RebaseLocation::Destination => "onto",
+                    RebaseLocation::InsertAfter => "after",
+                    RebaseLocation::InsertBefore => "before",
+                };
                 let msg = if commit_ids.len() == 1 {
-                    format!("Rebasing {} onto {}...", commit_ids[0], destination)
+                    format!("Rebasing {} {place} {destination}...", commit_ids[0])
                 } else {
                     format!(
-                        "Rebasing {} revisions onto {}...",
-                        commit_ids.len(),
-                        destination
+                        "Rebasing {} revisions {place} {destination}...",
+                        commit_ids.len()
                     )
                 };
                 run_operation(tx, msg, "Rebase successful", move || async move {
-                    adapter.rebase(&commit_ids, &destination).await
+                    adapter
+                        .rebase(&commit_ids, mode, location, &destination)
+                        .await
                 })
                 .await;
             });
@@ -217,8 +232,16 @@ pub fn handle_command(
                 .await;
             });
         }
-        Command::Split(_commit_id) => {
-            // Handled directly in run_loop because it requires suspending TUI
+        Command::Split(commit_id, files, message) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Splitting {commit_id}..."),
+                    "Revision split",
+                    move || async move { adapter.split(&commit_id, &files, &message).await },
+                )
+                .await;
+            });
         }
         Command::SetBookmark(commit_id, name) => {
             let name_clone = name.clone();
@@ -244,6 +267,123 @@ pub fn handle_command(
                 .await;
             });
         }
+        Command::SetTag(commit_id, name, allow_move) => {
+            let success = if allow_move {
+                "Tag moved"
+            } else {
+                "Tag created"
+            };
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Setting tag {name}..."),
+                    success,
+                    move || async move { adapter.set_tag(&commit_id, &name, allow_move).await },
+                )
+                .await;
+            });
+        }
+        Command::DeleteTag(name) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Deleting tag {name}..."),
+                    "Tag deleted",
+                    move || async move { adapter.delete_tag(&name).await },
+                )
+                .await;
+            });
+        }
+        Command::PushTag(name) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Pushing tag {name}..."),
+                    "Tag pushed",
+                    move || async move { adapter.push_tag(&name).await },
+                )
+                .await;
+            });
+        }
+        Command::Bookmarks => {
+            tokio::spawn(async move {
+                let action = match adapter.bookmarks().await {
+                    Ok(bookmarks) => Action::OpenBookmarks(bookmarks),
+                    Err(e) => Action::ErrorOccurred(format!("Error: {e}")),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::RenameBookmark(old_name, new_name) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Renaming bookmark {old_name} to {new_name}..."),
+                    "Bookmark renamed",
+                    move || async move { adapter.rename_bookmark(&old_name, &new_name).await },
+                )
+                .await;
+            });
+        }
+        Command::MoveBookmark(name, revision, allow_backwards) => {
+            tokio::spawn(async move {
+                let _ = tx
+                    .send(Action::OperationStarted(format!(
+                        "Moving bookmark {name} to {revision}..."
+                    )))
+                    .await;
+                let action = match adapter
+                    .move_bookmark(&name, &revision, allow_backwards)
+                    .await
+                {
+                    Ok(()) => Action::OperationCompleted(Ok("Bookmark moved".to_string())),
+                    // Like `jj bookmark set --allow-backwards`, but asked for.
+                    Err(e)
+                        if matches!(
+                            e.downcast_ref::<VcsError>(),
+                            Some(VcsError::BookmarkMovedBackwards(_))
+                        ) =>
+                    {
+                        Action::ConfirmMoveBookmarkBackwards(name, revision)
+                    }
+                    Err(e) => Action::OperationCompleted(Err(format!("Error: {e}"))),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::TrackBookmark(name, remote) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Tracking {name}@{remote}..."),
+                    "Bookmark tracked",
+                    move || async move { adapter.track_bookmark(&name, &remote).await },
+                )
+                .await;
+            });
+        }
+        Command::UntrackBookmark(name, remote) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Untracking {name}@{remote}..."),
+                    "Bookmark untracked",
+                    move || async move { adapter.untrack_bookmark(&name, &remote).await },
+                )
+                .await;
+            });
+        }
+        Command::ForgetBookmark(name) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Forgetting bookmark {name}..."),
+                    "Bookmark forgotten",
+                    move || async move { adapter.forget_bookmark(&name).await },
+                )
+                .await;
+            });
+        }
         Command::Undo => {
             tokio::spawn(async move {
                 run_operation(
@@ -266,34 +406,191 @@ pub fn handle_command(
                 .await;
             });
         }
-        Command::Fetch => {
+        Command::Fetch(remotes, branches) => {
+            tokio::spawn(async move {
+                let msg = match remotes.as_slice() {
+                    [] => "Fetching...".to_string(),
+                    [remote] => format!("Fetching from {remote}..."),
+                    _ => format!("Fetching from {} remotes...", remotes.len()),
+                };
+                run_operation(tx, msg, "Fetch successful", move || async move {
+                    adapter.fetch(&remotes, &branches).await
+                })
+                .await;
+            });
+        }
+        Command::Remotes => {
+            tokio::spawn(async move {
+                let action = match adapter.remotes().await {
+                    Ok(remotes) => Action::OpenRemotes(remotes),
+                    Err(e) => Action::ErrorOccurred(format!("Error: {e}")),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::AddRemote(name, url) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Adding remote {name}..."),
+                    "Remote added",
+                    move || async move { adapter.add_remote(&name, &url).await },
+                )
+                .await;
+            });
+        }
+        Command::RenameRemote(old_name, new_name) => {
             tokio::spawn(async move {
                 run_operation(
                     tx,
-                    "Fetching...".to_string(),
-                    "Fetch successful",
-                    move || async move { adapter.fetch().await },
+                    format!("Renaming remote {old_name} to {new_name}..."),
+                    "Remote renamed",
+                    move || async move { adapter.rename_remote(&old_name, &new_name).await },
                 )
                 .await;
             });
         }
-        Command::Push(bookmark_opt) => {
-            let bookmark_clone = bookmark_opt.clone();
+        Command::RemoveRemote(name) => {
             tokio::spawn(async move {
-                let msg = if let Some(ref b) = bookmark_clone {
-                    format!("Pushing {b}...")
-                } else {
-                    "Pushing...".to_string()
-                };
-                run_operation(tx, msg, "Push successful", move || async move {
-                    adapter.push(bookmark_clone).await
-                })
+                run_operation(
+                    tx,
+                    format!("Removing remote {name}..."),
+                    "Remote removed",
+                    move || async move { adapter.remove_remote(&name).await },
+                )
+                .await;
+            });
+        }
+        Command::SetRemoteUrl(name, url) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Setting the URL of {name}..."),
+                    "Remote URL set",
+                    move || async move { adapter.set_remote_url(&name, &url).await },
+                )
                 .await;
             });
         }
-        Command::ResolveConflict(_) => {
+        Command::PreviewRewrite(rewrite, limit, revset) => {
+            tokio::spawn(async move {
+                match adapter.preview_rewrite(&rewrite, limit, revset).await {
+                    Ok(preview) => {
+                        let _ = tx
+                            .send(Action::RewritePreviewLoaded(rewrite, Box::new(preview)))
+                            .await;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Action::ErrorOccurred(format!("Error: {e}"))).await;
+                    }
+                }
+            });
+        }
+        Command::PreviewPush(remote, scope) => {
+            tokio::spawn(async move {
+                match adapter.push_preview(remote, &scope).await {
+                    Ok(plan) => {
+                        let _ = tx.send(Action::PushPreviewLoaded(Box::new(plan))).await;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Action::ClosePush).await;
+                        let _ = tx.send(Action::ErrorOccurred(format!("Error: {e}"))).await;
+                    }
+                }
+            });
+        }
+        Command::Push(remote, scope) => {
+            tokio::spawn(async move {
+                let _ = tx
+                    .send(Action::OperationStarted(format!("Pushing to {remote}...")))
+                    .await;
+                match adapter.push(&remote, &scope).await {
+                    Ok(results) => {
+                        let failed = results.iter().filter(|r| r.error.is_some()).count();
+                        let message = if failed == 0 {
+                            format!("Pushed to {remote}")
+                        } else {
+                            format!("{failed} of {} bookmarks not pushed", results.len())
+                        };
+                        let _ = tx.send(Action::OperationCompleted(Ok(message))).await;
+                        let _ = tx.send(Action::PushCompleted(results)).await;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Action::ClosePush).await;
+                        let _ = tx
+                            .send(Action::OperationCompleted(Err(format!("Error: {e}"))))
+                            .await;
+                    }
+                }
+            });
+        }
+        Command::ResolveConflict(_) | Command::RunInteractiveCommand(..) => {
             // Handled specially in run_loop to allow TUI suspension
         }
+        Command::RunCustomCommand(name, line) => {
+            tokio::spawn(async move {
+                let _ = tx
+                    .send(Action::OperationStarted(format!("Running {name}...")))
+                    .await;
+                let output = tokio::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&line)
+                    .current_dir(adapter.workspace_root())
+                    .output()
+                    .await;
+                let output = match output {
+                    Ok(output) => output,
+                    Err(e) => {
+                        let _ = tx
+                            .send(Action::OperationCompleted(Err(format!(
+                                "Failed to run {name}: {e}"
+                            ))))
+                            .await;
+                        return;
+                    }
+                };
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if !output.status.success() {
+                    let _ = tx
+                        .send(Action::OperationCompleted(Err(format!(
+                            "{name} failed ({}): {}",
+                            output.status,
+                            stderr.trim()
+                        ))))
+                        .await;
+                    return;
+                }
+                let _ = tx
+                    .send(Action::OperationCompleted(Ok(format!("{name} finished"))))
+                    .await;
+                let printed = String::from_utf8_lossy(&output.stdout) + stderr;
+                if !printed.trim().is_empty() {
+                    let _ = tx
+                        .send(Action::OpenCommandOutput(name, printed.into_owned()))
+                        .await;
+                }
+            });
+        }
+        Command::LoadConflict(commit_id, path) => {
+            tokio::spawn(async move {
+                let action = match adapter.get_file_conflict(&commit_id, &path).await {
+                    Ok(conflict) => Action::OpenConflictEditor(Box::new(conflict)),
+                    Err(e) => Action::ErrorOccurred(format!("Error: {e}")),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::SaveResolution(conflict, content) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Resolving {}...", conflict.path),
+                    "Conflict resolved",
+                    move || async move { adapter.resolve_conflict(&conflict, &content).await },
+                )
+                .await;
+            });
+        }
         Command::InitRepo => {
             tokio::spawn(async move {
                 run_operation(
@@ -327,27 +624,120 @@ pub fn handle_command(
         }
         Command::OperationLog => {
             tokio::spawn(async move {
-                let _ = tx
-                    .send(Action::OperationStarted(
-                        "Fetching operation log...".to_string(),
-                    ))
-                    .await;
-                match adapter.operation_log().await {
-                    Ok(content) => {
-                        let _ = tx.send(Action::OpenOperationLog(content)).await;
-                    }
-                    Err(e) => {
-                        let _ = tx
-                            .send(Action::OperationCompleted(Err(format!("Error: {e}"))))
-                            .await;
-                    }
-                }
+                let action = match adapter.operation_log(OPERATION_LOG_LIMIT).await {
+                    Ok(operations) => Action::OpenOperationLog(operations),
+                    Err(e) => Action::ErrorOccurred(format!("Error: {e}")),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::LoadOperationChanges(op_id) => {
+            tokio::spawn(async move {
+                let action = match adapter.operation_changes(&op_id).await {
+                    Ok(changes) => Action::OperationChangesLoaded(op_id, Box::new(changes)),
+                    Err(e) => Action::ErrorOccurred(format!("Error: {e}")),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::RestoreOperation(op_id) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    "Restoring operation...".to_string(),
+                    "Operation restored",
+                    move || async move { adapter.restore_operation(&op_id).await },
+                )
+                .await;
+            });
+        }
+        Command::RevertOperation(op_id) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    "Reverting operation...".to_string(),
+                    "Operation reverted",
+                    move || async move { adapter.revert_operation(&op_id).await },
+                )
+                .await;
+            });
+        }
+        Command::Workspaces => {
+            tokio::spawn(async move {
+                let action = match adapter.workspaces().await {
+                    Ok(workspaces) => Action::OpenWorkspaces(workspaces),
+                    Err(e) => Action::ErrorOccurred(format!("Error: {e}")),
+                };
+                let _ = tx.send(action).await;
+            });
+        }
+        Command::AddWorkspace(path) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Adding workspace at {path}..."),
+                    "Workspace added",
+                    move || async move { adapter.add_workspace(&path).await },
+                )
+                .await;
+            });
+        }
+        Command::ForgetWorkspace(name) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Forgetting workspace {name}..."),
+                    "Workspace forgotten",
+                    move || async move { adapter.forget_workspace(&name).await },
+                )
+                .await;
+            });
+        }
+        Command::UpdateStaleWorkspace(name) => {
+            tokio::spawn(async move {
+                run_operation(
+                    tx,
+                    format!("Updating workspace {name}..."),
+                    "Workspace updated",
+                    move || async move { adapter.update_stale_workspace(&name).await },
+                )
+                .await;
             });
         }
     }
     Ok(())
 }
 
+/// Loads the graph from scratch, or one more page of it after `offset`
+/// rows.
+fn load_graph(
+    adapter: Arc<dyn VcsFacade>,
+    tx: mpsc::Sender<Action>,
+    offset: usize,
+    limit: usize,
+    revset: Option<String>,
+) {
+    let is_batch = offset > 0;
+    tokio::spawn(async move {
+        match adapter.get_operation_log(offset, limit, revset).await {
+            Ok(repo) => {
+                if is_batch {
+                    let _ = tx.send(Action::GraphBatchLoaded(Box::new(repo))).await;
+                } else {
+                    let _ = tx.send(Action::RepoLoaded(Box::new(repo))).await;
+                }
+            }
+            Err(e) => {
+                let action = match e.downcast_ref::<VcsError>() {
+                    Some(VcsError::InvalidRevset(err)) => Action::RevsetFailed(err.clone()),
+                    _ => Action::ErrorOccurred(format!("Failed to load repo: {e}")),
+                };
+                let _ = tx.send(action).await;
+            }
+        }
+    });
+}
+
 async fn run_operation<F, Fut>(
     tx: mpsc::Sender<Action>,
     start_msg: String,
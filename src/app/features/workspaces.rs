@@ -0,0 +1,143 @@
+use crate::app::{
+    action::{Action, UpdateResult},
+    command::Command,
+    reducer::STATUS_CLEAR_DURATION,
+    state::{AppMode, AppState, AppTextArea, InputState, WorkspacesState},
+};
+use std::time::Instant;
+
+pub fn update(state: &mut AppState, action: &Action) -> UpdateResult {
+    match action {
+        Action::Workspaces => UpdateResult::Handled(Some(Command::Workspaces)),
+        Action::OpenWorkspaces(workspaces) => {
+            state.workspaces_state = Some(WorkspacesState {
+                workspaces: workspaces.clone(),
+                selected: 0,
+            });
+            state.mode = AppMode::Workspaces;
+            UpdateResult::Handled(None)
+        }
+        Action::CloseWorkspaces => {
+            close(state);
+            UpdateResult::Handled(None)
+        }
+        Action::SelectWorkspaceNext => select(state, |selected| selected + 1),
+        Action::SelectWorkspacePrev => select(state, |selected| selected.saturating_sub(1)),
+        Action::AddWorkspaceIntent => {
+            state.input = Some(InputState {
+                text_area: AppTextArea::default(),
+            });
+            state.mode = AppMode::WorkspaceInput;
+            UpdateResult::Handled(None)
+        }
+        Action::AddWorkspace(path) => {
+            state.input = None;
+            close(state);
+            let path = path.trim();
+            if path.is_empty() {
+                return UpdateResult::Handled(None);
+            }
+            UpdateResult::Handled(Some(Command::AddWorkspace(path.to_string())))
+        }
+        Action::ForgetWorkspace => {
+            let Some(workspace) = selected_workspace(state) else {
+                return UpdateResult::Handled(None);
+            };
+            if workspace.is_current {
+                set_status(
+                    state,
+                    "Cannot forget the workspace Judo is running in".into(),
+                );
+                return UpdateResult::Handled(None);
+            }
+            close(state);
+            UpdateResult::Handled(Some(Command::ForgetWorkspace(workspace.name)))
+        }
+        Action::UpdateStaleWorkspace => {
+            let Some(workspace) = selected_workspace(state) else {
+                return UpdateResult::Handled(None);
+            };
+            if !workspace.is_stale {
+                set_status(state, format!("Workspace {} is up to date", workspace.name));
+                return UpdateResult::Handled(None);
+            }
+            close(state);
+            UpdateResult::Handled(Some(Command::UpdateStaleWorkspace(workspace.name)))
+        }
+        _ => UpdateResult::NotHandled,
+    }
+}
+
+fn selected_workspace(state: &AppState) -> Option<crate::domain::workspace::Workspace> {
+    state
+        .workspaces_state
+        .as_ref()?
+        .selected_workspace()
+        .cloned()
+}
+
+fn select(state: &mut AppState, f: impl FnOnce(usize) -> usize) -> UpdateResult {
+    if let Some(panel) = &mut state.workspaces_state {
+        panel.selected = f(panel.selected).min(panel.workspaces.len().saturating_sub(1));
+    }
+    UpdateResult::Handled(None)
+}
+
+fn close(state: &mut AppState) {
+    state.mode = AppMode::Normal;
+    state.workspaces_state = None;
+}
+
+fn set_status(state: &mut AppState, message: String) {
+    state.status_message = Some(message);
+    state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{keymap::KeyConfig, reducer};
+    use crate::domain::workspace::Workspace;
+
+    #[test]
+    fn test_workspace_panel() {
+        let mut state = AppState::new(KeyConfig::default());
+        let workspace = |name: &str, is_current, is_stale| Workspace {
+            name: name.to_string(),
+            is_current,
+            is_stale,
+            ..Default::default()
+        };
+        let workspaces = vec![
+            workspace("default", true, false),
+            workspace("review", false, true),
+        ];
+        reducer::update(&mut state, Action::OpenWorkspaces(workspaces.clone()));
+        assert_eq!(state.mode, AppMode::Workspaces);
+
+        // The workspace Judo runs in stays.
+        assert!(reducer::update(&mut state, Action::ForgetWorkspace).is_none());
+        assert_eq!(state.mode, AppMode::Workspaces);
+        assert!(state.status_message.is_some());
+
+        reducer::update(&mut state, Action::SelectWorkspaceNext);
+        reducer::update(&mut state, Action::SelectWorkspaceNext);
+        let command = reducer::update(&mut state, Action::UpdateStaleWorkspace);
+        assert!(matches!(
+            command,
+            Some(Command::UpdateStaleWorkspace(name)) if name == "review"
+        ));
+        assert_eq!(state.mode, AppMode::Normal);
+        assert!(state.workspaces_state.is_none());
+
+        reducer::update(&mut state, Action::OpenWorkspaces(workspaces));
+        reducer::update(&mut state, Action::AddWorkspaceIntent);
+        assert_eq!(state.mode, AppMode::WorkspaceInput);
+        let command = reducer::update(&mut state, Action::AddWorkspace(" ../review ".into()));
+        assert!(matches!(
+            command,
+            Some(Command::AddWorkspace(path)) if path == "../review"
+        ));
+        assert_eq!(state.mode, AppMode::Normal);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::app::{action::Action, state::AppState, ui};
-use crate::components::revision_graph::calculate_row_height;
+use crate::components::revision_graph::{calculate_row_height, details_height};
 use crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
 use ratatui::layout::Size;
 use std::time::Instant;
@@ -15,11 +15,14 @@ pub fn resolve_clicked_row(
     for i in offset..repo.graph.len() {
         let row = &repo.graph[i];
         let is_selected = app_state.log.list_state.selected() == Some(i);
-        let row_height = calculate_row_height(row, is_selected, app_state.show_diffs) as usize;
+        let template = &app_state.row_template;
+        let row_height =
+            calculate_row_height(row, template, is_selected, app_state.show_diffs) as usize;
+        let files_y = current_y + details_height(row, template) as usize;
 
         if clicked_row >= current_y && clicked_row < current_y + row_height {
-            let file_idx = if is_selected && app_state.show_diffs && clicked_row >= current_y + 2 {
-                Some(clicked_row - (current_y + 2))
+            let file_idx = if is_selected && app_state.show_diffs && clicked_row >= files_y {
+                Some(clicked_row - files_y)
             } else {
                 None
             };
@@ -44,10 +47,50 @@ pub fn map_event_to_action(
     match app_state.mode {
         crate::app::state::AppMode::Input
         | crate::app::state::AppMode::BookmarkInput
-        | crate::app::state::AppMode::CommitInput => match event {
+        | crate::app::state::AppMode::CommitInput
+        | crate::app::state::AppMode::SplitInput
+        | crate::app::state::AppMode::WorkspaceInput
+        | crate::app::state::AppMode::BookmarkRename
+        | crate::app::state::AppMode::BookmarkMove
+        | crate::app::state::AppMode::RemoteAdd
+        | crate::app::state::AppMode::RemoteRename
+        | crate::app::state::AppMode::RemoteUrl
+        | crate::app::state::AppMode::RemoteFetch
+        | crate::app::state::AppMode::TagInput
+        | crate::app::state::AppMode::TagMove => match event {
             Event::Key(key) => match key.code {
                 KeyCode::Esc => Some(Action::CancelMode),
                 KeyCode::Enter => {
+                    if app_state.mode == crate::app::state::AppMode::WorkspaceInput {
+                        return app_state
+                            .input
+                            .as_ref()
+                            .map(|input| Action::AddWorkspace(input.text_area.lines().join("")));
+                    }
+                    if let Some(input) = &app_state.input {
+                        let text = input.text_area.lines().join("");
+                        match app_state.mode {
+                            crate::app::state::AppMode::BookmarkRename => {
+                                return Some(Action::RenameBookmark(text));
+                            }
+                            crate::app::state::AppMode::BookmarkMove => {
+                                return Some(Action::MoveBookmark(text));
+                            }
+                            crate::app::state::AppMode::RemoteAdd => {
+                                return Some(Action::AddRemote(text));
+                            }
+                            crate::app::state::AppMode::RemoteRename => {
+                                return Some(Action::RenameRemote(text));
+                            }
+                            crate::app::state::AppMode::RemoteUrl => {
+                                return Some(Action::SetRemoteUrl(text));
+                            }
+                            crate::app::state::AppMode::RemoteFetch => {
+                                return Some(Action::FetchBranches(text));
+                            }
+                            _ => {}
+                        }
+                    }
                     if app_state.mode == crate::app::state::AppMode::CommitInput {
                         if let Some(input) = &app_state.input {
                             return Some(Action::CommitWorkingCopy(
@@ -56,6 +99,11 @@ pub fn map_event_to_action(
                         }
                         return None;
                     }
+                    if app_state.mode == crate::app::state::AppMode::SplitInput {
+                        return app_state.input.as_ref().map(|input| {
+                            Action::SplitSelected(input.text_area.lines().join("\n"))
+                        });
+                    }
 
                     if let (Some(repo), Some(idx), Some(input)) = (
                         &app_state.repo,
@@ -69,6 +117,18 @@ pub fn map_event_to_action(
                                     return None;
                                 }
                                 Some(Action::SetBookmark(row.commit_id.clone(), name))
+                            } else if matches!(
+                                app_state.mode,
+                                crate::app::state::AppMode::TagInput
+                                    | crate::app::state::AppMode::TagMove
+                            ) {
+                                let name = input.text_area.lines().join("").trim().to_string();
+                                if name.is_empty() {
+                                    return None;
+                                }
+                                let allow_move =
+                                    app_state.mode == crate::app::state::AppMode::TagMove;
+                                Some(Action::SetTag(row.commit_id.clone(), name, allow_move))
                             } else {
                                 Some(Action::DescribeRevision(
                                     row.commit_id.clone(),
@@ -86,6 +146,16 @@ pub fn map_event_to_action(
             },
             _ => None,
         },
+        crate::app::state::AppMode::ConflictInput => match event {
+            Event::Key(key) => match key.code {
+                KeyCode::Esc => app_state
+                    .input
+                    .as_ref()
+                    .map(|input| Action::ConflictEditDone(input.text_area.lines().join("\n"))),
+                _ => Some(Action::TextAreaInput(key)),
+            },
+            _ => None,
+        },
         crate::app::state::AppMode::FilterInput => match event {
             Event::Key(key) => {
                 if let Some(action) = app_state.keymap.get_action(key, app_state) {
@@ -97,16 +167,7 @@ pub fn map_event_to_action(
         },
         crate::app::state::AppMode::ContextMenu => {
             match event {
-                Event::Key(key) => match key.code {
-                    KeyCode::Esc => Some(Action::CloseContextMenu),
-                    KeyCode::Char('j') | KeyCode::Down => Some(Action::SelectContextMenuNext),
-                    KeyCode::Char('k') | KeyCode::Up => Some(Action::SelectContextMenuPrev),
-                    KeyCode::Enter => app_state
-                        .context_menu
-                        .as_ref()
-                        .map(|menu| Action::SelectContextMenuAction(menu.selected_index)),
-                    _ => None,
-                },
+                Event::Key(key) => app_state.keymap.get_action(key, app_state),
                 Event::Mouse(mouse) => match mouse.kind {
                     MouseEventKind::Down(MouseButton::Left) => {
                         if let Some(menu) = &app_state.context_menu {
@@ -148,13 +209,23 @@ pub fn map_event_to_action(
             _ => None,
         },
         crate::app::state::AppMode::Loading => None,
-        crate::app::state::AppMode::Help => match event {
-            Event::Key(key) => match key.code {
-                KeyCode::Esc | KeyCode::Char('q' | '?') => Some(Action::ToggleHelp),
+        crate::app::state::AppMode::HunkSelect => match event {
+            Event::Key(key) => app_state.keymap.get_action(key, app_state),
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollUp => Some(Action::ScrollDiffUp(1)),
+                MouseEventKind::ScrollDown => Some(Action::ScrollDiffDown(1)),
                 _ => None,
             },
             _ => None,
         },
+        crate::app::state::AppMode::ConflictEditor => match event {
+            Event::Key(key) => app_state.keymap.get_action(key, app_state),
+            _ => None,
+        },
+        crate::app::state::AppMode::Help => match event {
+            Event::Key(key) => app_state.keymap.get_action(key, app_state),
+            _ => None,
+        },
         crate::app::state::AppMode::Diff => {
             match event {
                 Event::Key(key) => {
@@ -162,7 +233,7 @@ pub fn map_event_to_action(
                         return Some(action);
                     }
                     match key.code {
-                        KeyCode::Char('m') | KeyCode::Enter => {
+                        KeyCode::Char('m' | 'M') | KeyCode::Enter => {
                             if let (Some(repo), Some(idx)) =
                                 (&app_state.repo, app_state.log.list_state.selected())
                             {
@@ -172,9 +243,12 @@ pub fn map_event_to_action(
                                             if file.status
                                                 == crate::domain::models::FileStatus::Conflicted
                                             {
-                                                return Some(Action::ResolveConflict(
-                                                    file.path.clone(),
-                                                ));
+                                                let path = file.path.clone();
+                                                return Some(if key.code == KeyCode::Char('M') {
+                                                    Action::ResolveConflictExternally(path)
+                                                } else {
+                                                    Action::ResolveConflict(path)
+                                                });
                                             }
                                         }
                                     }
@@ -235,6 +309,16 @@ pub fn map_event_to_action(
                 _ => None,
             }
         }
+        crate::app::state::AppMode::RewritePreview => match event {
+            Event::Resize(w, h) => Some(Action::Resize(w, h)),
+            Event::Key(key) => app_state.keymap.get_action(key, app_state),
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollUp => Some(Action::SelectPreviewPrev),
+                MouseEventKind::ScrollDown => Some(Action::SelectPreviewNext),
+                _ => None,
+            },
+            _ => None,
+        },
         _ => match event {
             Event::Resize(w, h) => Some(Action::Resize(w, h)),
             Event::Key(key) => {
@@ -1,12 +1,84 @@
 use super::action::Action;
+use super::state::{AppMode, AppState, PushMode};
+use crate::domain::{
+    conflict::Resolution,
+    models::{RebaseLocation, RebaseMode, SquashDestination},
+    row_template::DEFAULT_ROW,
+};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct KeyConfig {
     pub profile: String,
     pub custom: Option<HashMap<String, String>>,
+    pub diff: DiffConfig,
+    pub graph: GraphConfig,
+    pub commands: BTreeMap<String, CustomCommand>,
+    /// The `[keys.<mode>]` tables: key sequences bound to actions in one
+    /// mode, like `"ctrl-d" = "scrolldiffdown 20"`.
+    pub keys: BTreeMap<String, BTreeMap<String, String>>,
+    /// Name of the theme last picked in the theme selection.
+    pub theme: Option<String>,
+    /// Why the file was ignored, if it couldn't be read.
+    #[serde(skip)]
+    pub problems: Vec<String>,
+}
+
+/// The `[diff]` table of `config.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// Narrowest diff panel, in columns, that shows the side-by-side
+    /// layout. Below this the panel falls back to the unified layout.
+    pub split_min_width: u16,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            split_min_width: 100,
+        }
+    }
+}
+
+/// The `[graph]` table of `config.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GraphConfig {
+    /// One template per line of each row in the revision graph, like
+    /// `"{change_id} {author}"`.
+    pub row: Vec<String>,
+}
+
+impl Default for GraphConfig {
+    fn default() -> Self {
+        Self {
+            row: DEFAULT_ROW.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// A `[commands.<name>]` table of `config.toml`: a shell command line run on
+/// the selected revision.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CustomCommand {
+    /// Run by `sh -c` from the workspace root, after filling in
+    /// `{commit_id}`, `{change_id}`, `{bookmarks}`, `{selected}` and
+    /// `{file}`.
+    pub run: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Keys that run it, written as in `[keys.<mode>]`.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Hands the terminal to the command instead of running it in the
+    /// background and showing what it printed.
+    #[serde(default)]
+    pub interactive: bool,
 }
 
 impl KeyConfig {
@@ -18,15 +90,38 @@ impl KeyConfig {
             config_path.push("config.toml");
 
             if config_path.exists() {
-                if let Ok(content) = std::fs::read_to_string(config_path) {
-                    if let Ok(config) = toml::from_str(&content) {
-                        return config;
-                    }
-                }
+                return Self::load_from(&config_path);
             }
         }
         Self::default()
     }
+
+    /// Reads the config at `path`. A file that can't be read or parsed
+    /// leaves the defaults, with the reason in `problems`.
+    #[must_use]
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Self::parse(&content))
+            .unwrap_or_else(|e| Self {
+                problems: vec![format!("config.toml: {e}")],
+                ..Self::default()
+            })
+    }
+
+    /// Parses a `config.toml`, pointing at where it went wrong.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|e: toml::de::Error| {
+            let Some(span) = e.span() else {
+                return e.message().to_string();
+            };
+            let before = &content[..span.start];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            let line = before.matches('\n').count() + 1;
+            let column = before[line_start..].chars().count() + 1;
+            format!("line {line}, column {column}: {}", e.message())
+        })
+    }
 }
 
 impl Default for KeyConfig {
@@ -34,35 +129,187 @@ impl Default for KeyConfig {
         Self {
             profile: "vim".to_string(),
             custom: None,
+            diff: DiffConfig::default(),
+            graph: GraphConfig::default(),
+            commands: BTreeMap::new(),
+            keys: BTreeMap::new(),
+            theme: None,
+            problems: Vec::new(),
+        }
+    }
+}
+
+/// What a key sequence is bound to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    Action(Box<Action>),
+    /// Finishes the mode with what was typed or highlighted: applies the
+    /// filter, picks the rebase or squash target, or runs the menu item.
+    Confirm,
+    /// Deletes the highlighted bookmark, if it is a local one.
+    DeleteSelectedBookmark,
+}
+
+impl From<Action> for Binding {
+    fn from(action: Action) -> Self {
+        Self::Action(Box::new(action))
+    }
+}
+
+/// The key sequences of one mode and what they are bound to.
+#[derive(Debug, Default, PartialEq)]
+pub struct Bindings(HashMap<Vec<KeyEvent>, Binding>);
+
+enum Lookup<'a> {
+    Bound(&'a Binding),
+    /// The keys start one or more longer sequences.
+    Prefix,
+    Unbound,
+}
+
+impl Bindings {
+    fn insert(&mut self, key: KeyEvent, binding: impl Into<Binding>) {
+        self.0.insert(vec![key], binding.into());
+    }
+
+    fn lookup(&self, keys: &[KeyEvent]) -> Lookup<'_> {
+        if let Some(binding) = self.0.get(keys) {
+            Lookup::Bound(binding)
+        } else if self.0.keys().any(|bound| bound.starts_with(keys)) {
+            Lookup::Prefix
+        } else {
+            Lookup::Unbound
+        }
+    }
+
+    /// Binds `keys`, dropping the sequences that would keep it from being
+    /// typed or that it would keep from being typed.
+    fn replace(&mut self, keys: Vec<KeyEvent>, binding: Option<Binding>) {
+        self.0
+            .retain(|bound, _| !bound.starts_with(&keys) && !keys.starts_with(bound));
+        if let Some(binding) = binding {
+            self.0.insert(keys, binding);
         }
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct KeyMap {
-    pub global: HashMap<KeyEvent, Action>,
-    pub diff_mode: HashMap<KeyEvent, Action>,
+    /// Bindings of the revision graph, which the diff panel falls back to.
+    pub global: Bindings,
+    /// Bindings of the modes with keys of their own.
+    pub modes: HashMap<AppMode, Bindings>,
+    /// Bindings in `config.toml` that were left out, and why.
+    pub problems: Vec<String>,
 }
 
+/// Names of the modes `[keys.<mode>]` can bind keys in.
+const MODES: &[(&str, AppMode)] = &[
+    ("normal", AppMode::Normal),
+    ("diff", AppMode::Diff),
+    ("command_palette", AppMode::CommandPalette),
+    ("theme_selection", AppMode::ThemeSelection),
+    ("filter", AppMode::FilterInput),
+    ("rebase_input", AppMode::RebaseInput),
+    ("rebase", AppMode::RebaseSelect),
+    ("squash", AppMode::SquashSelect),
+    ("hunk_select", AppMode::HunkSelect),
+    ("conflict_editor", AppMode::ConflictEditor),
+    ("evolog", AppMode::Evolog),
+    ("operation_log", AppMode::OperationLog),
+    ("workspaces", AppMode::Workspaces),
+    ("bookmarks", AppMode::Bookmarks),
+    ("confirm_move", AppMode::ConfirmMove),
+    ("push", AppMode::Push),
+    ("remotes", AppMode::Remotes),
+    ("rewrite_preview", AppMode::RewritePreview),
+    ("context_menu", AppMode::ContextMenu),
+    ("help", AppMode::Help),
+];
+
 impl KeyMap {
+    /// The built-in bindings with those of `[custom]`, `[keys.<mode>]` and
+    /// `[commands]` on top. A binding replaces the built-in ones it overlaps
+    /// with; bindings in the config that overlap each other, or that name
+    /// an unknown mode, key or action, end up in `problems`.
     #[must_use]
     pub fn from_config(config: &KeyConfig) -> Self {
         let mut map = Self::vim_profile();
 
+        // Where each binding comes from, for `problems`.
+        let mut entries: Vec<(String, AppMode, &str, String)> = Vec::new();
         if let Some(custom) = &config.custom {
-            for (key_str, action_str) in custom {
-                if let (Some(key), Some(action)) = (parse_key(key_str), parse_action(action_str)) {
-                    map.global.insert(key, action);
-                }
+            let mut custom: Vec<_> = custom.iter().collect();
+            custom.sort();
+            for (keys, action) in custom {
+                entries.push(("[custom]".into(), AppMode::Normal, keys, action.clone()));
+            }
+        }
+        for (mode_name, bindings) in &config.keys {
+            let Some(&(_, mode)) = MODES.iter().find(|(name, _)| name == mode_name) else {
+                map.problems
+                    .push(format!("[keys.{mode_name}]: no such mode"));
+                continue;
+            };
+            for (keys, action) in bindings {
+                let source = format!("[keys.{mode_name}]");
+                entries.push((source, mode, keys, action.clone()));
+            }
+        }
+        for (name, command) in &config.commands {
+            if let Some(keys) = &command.key {
+                let source = format!("[commands.{name}]");
+                entries.push((source, AppMode::Normal, keys, format!("run {name}")));
+            }
+        }
+
+        let mut accepted: Vec<(String, AppMode, Vec<KeyEvent>, Option<Binding>)> = Vec::new();
+        for (source, mode, keys_str, action_str) in entries {
+            let Some(keys) = parse_keys(keys_str) else {
+                map.problems
+                    .push(format!("{source} \"{keys_str}\": unknown key"));
+                continue;
+            };
+            // `none` leaves the keys unbound.
+            let binding = match action_str.as_str() {
+                "none" => None,
+                action_str => match parse_binding(action_str, config) {
+                    Some(binding) => Some(binding),
+                    None => {
+                        map.problems.push(format!(
+                            "{source} \"{keys_str}\" = \"{action_str}\": unknown action"
+                        ));
+                        continue;
+                    }
+                },
+            };
+            let overlapping = accepted.iter().find(|(_, other_mode, other, _)| {
+                *other_mode == mode && (other.starts_with(&keys) || keys.starts_with(other))
+            });
+            if let Some((other_source, _, other, _)) = overlapping {
+                map.problems.push(format!(
+                    "{source} \"{keys_str}\" overlaps {other_source} \"{}\"",
+                    format_keys(other)
+                ));
+                continue;
             }
+            accepted.push((source, mode, keys, binding));
+        }
+
+        for (_, mode, keys, binding) in accepted {
+            let bindings = match mode {
+                AppMode::Normal => &mut map.global,
+                mode => map.modes.entry(mode).or_default(),
+            };
+            bindings.replace(keys, binding);
         }
 
         map
     }
 
     fn vim_profile() -> Self {
-        let mut global = HashMap::new();
-        let mut diff_mode = HashMap::new();
+        let mut global = Bindings::default();
+        let mut modes = HashMap::new();
 
         global.insert(key_char('q'), Action::Quit);
         global.insert(key_code(KeyCode::Enter), Action::ToggleDiffs);
@@ -73,12 +320,14 @@ impl KeyMap {
         global.insert(key_char('k'), Action::SelectPrev);
         global.insert(key_code(KeyCode::Up), Action::SelectPrev);
         global.insert(key_char('s'), Action::SnapshotWorkingCopy);
-        global.insert(key_char('S'), Action::EnterSquashMode);
+        global.insert(key_char('S'), Action::EnterSquashMode(None));
         global.insert(key_char('e'), Action::EditRevision(None));
         global.insert(key_char('n'), Action::NewRevision(None));
         global.insert(key_char('a'), Action::AbandonRevision(None));
         global.insert(key_char('b'), Action::SetBookmarkIntent);
         global.insert(key_char('B'), Action::DeleteBookmarkIntent);
+        global.insert(key_char('M'), Action::Bookmarks);
+        global.insert(key_char('g'), Action::TagMenu);
         global.insert(key_char('d'), Action::DescribeRevisionIntent);
         global.insert(key_char('m'), Action::FilterMine);
         global.insert(key_char('x'), Action::ToggleSelection(None));
@@ -87,20 +336,25 @@ impl KeyMap {
         global.insert(key_char('u'), Action::Undo);
         global.insert(key_char('U'), Action::Redo);
         global.insert(key_char('f'), Action::Fetch);
+        global.insert(key_char('F'), Action::Remotes);
         global.insert(key_char('/'), Action::EnterFilterMode);
         global.insert(key_char('p'), Action::PushIntent);
         global.insert(key_char('?'), Action::ToggleHelp);
         global.insert(key_char('T'), Action::EnterThemeSelection);
         global.insert(key_char('r'), Action::RebaseRevisionIntent);
+        global.insert(key_char('P'), Action::TogglePreviewRewrites);
         global.insert(key_char('v'), Action::EvologRevision(None));
+        global.insert(key_char('W'), Action::Workspaces);
         global.insert(key_code(KeyCode::PageDown), Action::ScrollDiffDown(10));
         global.insert(key_code(KeyCode::PageUp), Action::ScrollDiffUp(10));
         global.insert(key_char('['), Action::PrevHunk);
         global.insert(key_char(']'), Action::NextHunk);
+        global.insert(key_char('|'), Action::ToggleDiffLayout);
         global.insert(key_char(':'), Action::EnterCommandMode);
         global.insert(key_char('C'), Action::ClearFilter);
         global.insert(key_code(KeyCode::Esc), Action::CancelMode);
 
+        let diff_mode: &mut Bindings = modes.entry(AppMode::Diff).or_default();
         diff_mode.insert(key_char('h'), Action::FocusGraph);
         diff_mode.insert(key_code(KeyCode::Tab), Action::FocusGraph);
         diff_mode.insert(key_char('j'), Action::SelectNextFile);
@@ -111,130 +365,294 @@ impl KeyMap {
         diff_mode.insert(key_code(KeyCode::PageUp), Action::ScrollDiffUp(10));
         diff_mode.insert(key_char('['), Action::PrevHunk);
         diff_mode.insert(key_char(']'), Action::NextHunk);
+        diff_mode.insert(key_char('|'), Action::ToggleDiffLayout);
+        diff_mode.insert(key_char('x'), Action::TogglePickFile);
+        diff_mode.insert(key_char('S'), Action::EnterSquashMode(None));
         diff_mode.insert(key_code(KeyCode::Esc), Action::CancelMode);
 
-        Self { global, diff_mode }
+        let palette = modes.entry(AppMode::CommandPalette).or_default();
+        palette.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        palette.insert(key_code(KeyCode::Enter), Action::CommandPaletteSelect);
+        palette.insert(key_code(KeyCode::Down), Action::CommandPaletteNext);
+        palette.insert(key_code(KeyCode::Up), Action::CommandPalettePrev);
+        palette.insert(key_ctrl('n'), Action::CommandPaletteNext);
+        palette.insert(key_ctrl('p'), Action::CommandPalettePrev);
+
+        let themes = modes.entry(AppMode::ThemeSelection).or_default();
+        themes.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        themes.insert(key_char('j'), Action::SelectThemeNext);
+        themes.insert(key_code(KeyCode::Down), Action::SelectThemeNext);
+        themes.insert(key_char('k'), Action::SelectThemePrev);
+        themes.insert(key_code(KeyCode::Up), Action::SelectThemePrev);
+        themes.insert(key_code(KeyCode::Enter), Action::CommandPaletteSelect);
+
+        let filter = modes.entry(AppMode::FilterInput).or_default();
+        filter.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        filter.insert(key_code(KeyCode::Enter), Binding::Confirm);
+        filter.insert(key_code(KeyCode::Tab), Action::ToggleFilterSource);
+        filter.insert(key_code(KeyCode::Down), Action::FilterNext);
+        filter.insert(key_code(KeyCode::Up), Action::FilterPrev);
+        filter.insert(key_ctrl('n'), Action::FilterNext);
+        filter.insert(key_ctrl('p'), Action::FilterPrev);
+
+        let rebase_input = modes.entry(AppMode::RebaseInput).or_default();
+        rebase_input.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        rebase_input.insert(key_code(KeyCode::Enter), Binding::Confirm);
+
+        let rebase = modes.entry(AppMode::RebaseSelect).or_default();
+        rebase.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        rebase.insert(key_char('j'), Action::SelectNext);
+        rebase.insert(key_code(KeyCode::Down), Action::SelectNext);
+        rebase.insert(key_char('k'), Action::SelectPrev);
+        rebase.insert(key_code(KeyCode::Up), Action::SelectPrev);
+        rebase.insert(key_char('r'), Action::SetRebaseMode(RebaseMode::Revisions));
+        rebase.insert(key_char('s'), Action::SetRebaseMode(RebaseMode::Source));
+        rebase.insert(key_char('b'), Action::SetRebaseMode(RebaseMode::Branch));
+        rebase.insert(
+            key_char('d'),
+            Action::SetRebaseLocation(RebaseLocation::Destination),
+        );
+        rebase.insert(
+            key_char('A'),
+            Action::SetRebaseLocation(RebaseLocation::InsertAfter),
+        );
+        rebase.insert(
+            key_char('B'),
+            Action::SetRebaseLocation(RebaseLocation::InsertBefore),
+        );
+        rebase.insert(key_code(KeyCode::Enter), Binding::Confirm);
+
+        let squash = modes.entry(AppMode::SquashSelect).or_default();
+        squash.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        squash.insert(key_char('j'), Action::SelectNext);
+        squash.insert(key_code(KeyCode::Down), Action::SelectNext);
+        squash.insert(key_char('k'), Action::SelectPrev);
+        squash.insert(key_code(KeyCode::Up), Action::SelectPrev);
+        squash.insert(key_code(KeyCode::Enter), Binding::Confirm);
+
+        let hunks = modes.entry(AppMode::HunkSelect).or_default();
+        hunks.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        hunks.insert(key_char('q'), Action::CancelMode);
+        hunks.insert(key_char('j'), Action::HunkSelectNext);
+        hunks.insert(key_code(KeyCode::Down), Action::HunkSelectNext);
+        hunks.insert(key_char('k'), Action::HunkSelectPrev);
+        hunks.insert(key_code(KeyCode::Up), Action::HunkSelectPrev);
+        hunks.insert(key_char(' '), Action::HunkSelectToggle);
+        hunks.insert(key_char('a'), Action::HunkSelectToggleAll);
+        hunks.insert(key_code(KeyCode::Enter), Action::HunkSelectConfirm);
+        hunks.insert(key_code(KeyCode::PageDown), Action::ScrollDiffDown(10));
+        hunks.insert(key_code(KeyCode::PageUp), Action::ScrollDiffUp(10));
+
+        let conflicts = modes.entry(AppMode::ConflictEditor).or_default();
+        conflicts.insert(key_code(KeyCode::Esc), Action::CancelMode);
+        conflicts.insert(key_char('q'), Action::CancelMode);
+        conflicts.insert(key_char('j'), Action::ConflictNext);
+        conflicts.insert(key_char('n'), Action::ConflictNext);
+        conflicts.insert(key_code(KeyCode::Down), Action::ConflictNext);
+        conflicts.insert(key_char('k'), Action::ConflictPrev);
+        conflicts.insert(key_char('p'), Action::ConflictPrev);
+        conflicts.insert(key_code(KeyCode::Up), Action::ConflictPrev);
+        conflicts.insert(key_char('o'), Action::ConflictPick(Resolution::Ours));
+        conflicts.insert(key_char('t'), Action::ConflictPick(Resolution::Theirs));
+        conflicts.insert(key_char('b'), Action::ConflictPick(Resolution::Base));
+        conflicts.insert(key_char('a'), Action::ConflictPick(Resolution::Both));
+        conflicts.insert(key_char('x'), Action::ConflictClear);
+        conflicts.insert(key_code(KeyCode::Backspace), Action::ConflictClear);
+        conflicts.insert(key_char('e'), Action::ConflictEditStart);
+        conflicts.insert(key_code(KeyCode::Enter), Action::ConflictSave);
+
+        let evolog = modes.entry(AppMode::Evolog).or_default();
+        evolog.insert(key_code(KeyCode::Esc), Action::CloseEvolog);
+        evolog.insert(key_char('q'), Action::CloseEvolog);
+        evolog.insert(key_char('j'), Action::ScrollEvologDown(1));
+        evolog.insert(key_code(KeyCode::Down), Action::ScrollEvologDown(1));
+        evolog.insert(key_char('k'), Action::ScrollEvologUp(1));
+        evolog.insert(key_code(KeyCode::Up), Action::ScrollEvologUp(1));
+        evolog.insert(key_code(KeyCode::PageDown), Action::ScrollEvologDown(10));
+        evolog.insert(key_code(KeyCode::PageUp), Action::ScrollEvologUp(10));
+
+        let op_log = modes.entry(AppMode::OperationLog).or_default();
+        op_log.insert(key_code(KeyCode::Esc), Action::CloseOperationLog);
+        op_log.insert(key_char('q'), Action::CloseOperationLog);
+        op_log.insert(key_char('j'), Action::SelectOperationNext(1));
+        op_log.insert(key_code(KeyCode::Down), Action::SelectOperationNext(1));
+        op_log.insert(key_char('k'), Action::SelectOperationPrev(1));
+        op_log.insert(key_code(KeyCode::Up), Action::SelectOperationPrev(1));
+        op_log.insert(key_code(KeyCode::PageDown), Action::SelectOperationNext(10));
+        op_log.insert(key_code(KeyCode::PageUp), Action::SelectOperationPrev(10));
+        op_log.insert(key_char('r'), Action::RestoreOperation);
+        op_log.insert(key_char('R'), Action::RevertOperation);
+
+        let workspaces = modes.entry(AppMode::Workspaces).or_default();
+        workspaces.insert(key_code(KeyCode::Esc), Action::CloseWorkspaces);
+        workspaces.insert(key_char('q'), Action::CloseWorkspaces);
+        workspaces.insert(key_char('j'), Action::SelectWorkspaceNext);
+        workspaces.insert(key_code(KeyCode::Down), Action::SelectWorkspaceNext);
+        workspaces.insert(key_char('k'), Action::SelectWorkspacePrev);
+        workspaces.insert(key_code(KeyCode::Up), Action::SelectWorkspacePrev);
+        workspaces.insert(key_char('a'), Action::AddWorkspaceIntent);
+        workspaces.insert(key_char('d'), Action::ForgetWorkspace);
+        workspaces.insert(key_char('u'), Action::UpdateStaleWorkspace);
+
+        let bookmarks = modes.entry(AppMode::Bookmarks).or_default();
+        bookmarks.insert(key_code(KeyCode::Esc), Action::CloseBookmarks);
+        bookmarks.insert(key_char('q'), Action::CloseBookmarks);
+        bookmarks.insert(key_char('j'), Action::SelectBookmarkNext);
+        bookmarks.insert(key_code(KeyCode::Down), Action::SelectBookmarkNext);
+        bookmarks.insert(key_char('k'), Action::SelectBookmarkPrev);
+        bookmarks.insert(key_code(KeyCode::Up), Action::SelectBookmarkPrev);
+        bookmarks.insert(key_code(KeyCode::Enter), Action::JumpToBookmark);
+        bookmarks.insert(key_char('r'), Action::RenameBookmarkIntent);
+        bookmarks.insert(key_char('m'), Action::MoveBookmarkIntent);
+        bookmarks.insert(key_char('t'), Action::ToggleTrackBookmark);
+        bookmarks.insert(key_char('f'), Action::ForgetBookmark);
+        bookmarks.insert(key_char('d'), Binding::DeleteSelectedBookmark);
+
+        let confirm_move = modes.entry(AppMode::ConfirmMove).or_default();
+        confirm_move.insert(key_char('y'), Action::MoveBookmarkBackwards);
+        confirm_move.insert(key_code(KeyCode::Enter), Action::MoveBookmarkBackwards);
+        confirm_move.insert(key_char('n'), Action::CancelMode);
+        confirm_move.insert(key_code(KeyCode::Esc), Action::CancelMode);
+
+        let push = modes.entry(AppMode::Push).or_default();
+        push.insert(key_code(KeyCode::Esc), Action::ClosePush);
+        push.insert(key_char('q'), Action::ClosePush);
+        push.insert(key_code(KeyCode::Tab), Action::PushNextRemote);
+        push.insert(key_char('b'), Action::SetPushMode(PushMode::Bookmarks));
+        push.insert(key_char('c'), Action::SetPushMode(PushMode::Change));
+        push.insert(key_char('a'), Action::SetPushMode(PushMode::All));
+        push.insert(key_char('t'), Action::SetPushMode(PushMode::Tracked));
+        push.insert(key_char('j'), Action::PushSelectNext);
+        push.insert(key_code(KeyCode::Down), Action::PushSelectNext);
+        push.insert(key_char('k'), Action::PushSelectPrev);
+        push.insert(key_code(KeyCode::Up), Action::PushSelectPrev);
+        push.insert(key_char(' '), Action::PushToggleBookmark);
+        push.insert(key_code(KeyCode::Enter), Action::ConfirmPush);
+
+        let remotes = modes.entry(AppMode::Remotes).or_default();
+        remotes.insert(key_code(KeyCode::Esc), Action::CloseRemotes);
+        remotes.insert(key_char('q'), Action::CloseRemotes);
+        remotes.insert(key_char('j'), Action::SelectRemoteNext);
+        remotes.insert(key_code(KeyCode::Down), Action::SelectRemoteNext);
+        remotes.insert(key_char('k'), Action::SelectRemotePrev);
+        remotes.insert(key_code(KeyCode::Up), Action::SelectRemotePrev);
+        remotes.insert(key_char('a'), Action::AddRemoteIntent);
+        remotes.insert(key_char('r'), Action::RenameRemoteIntent);
+        remotes.insert(key_char('u'), Action::SetRemoteUrlIntent);
+        remotes.insert(key_char('d'), Action::RemoveRemote);
+        remotes.insert(key_char('f'), Action::FetchRemote);
+        remotes.insert(key_code(KeyCode::Enter), Action::FetchRemote);
+        remotes.insert(key_char('F'), Action::FetchAllRemotes);
+        remotes.insert(key_char('b'), Action::FetchBranchesIntent);
+
+        let preview = modes.entry(AppMode::RewritePreview).or_default();
+        preview.insert(key_code(KeyCode::Esc), Action::CloseRewritePreview);
+        preview.insert(key_char('q'), Action::CloseRewritePreview);
+        preview.insert(key_char('j'), Action::SelectPreviewNext);
+        preview.insert(key_code(KeyCode::Down), Action::SelectPreviewNext);
+        preview.insert(key_char('k'), Action::SelectPreviewPrev);
+        preview.insert(key_code(KeyCode::Up), Action::SelectPreviewPrev);
+        preview.insert(key_code(KeyCode::Enter), Action::ConfirmRewrite);
+        preview.insert(key_char('y'), Action::ConfirmRewrite);
+
+        let menu = modes.entry(AppMode::ContextMenu).or_default();
+        menu.insert(key_code(KeyCode::Esc), Action::CloseContextMenu);
+        menu.insert(key_char('j'), Action::SelectContextMenuNext);
+        menu.insert(key_code(KeyCode::Down), Action::SelectContextMenuNext);
+        menu.insert(key_char('k'), Action::SelectContextMenuPrev);
+        menu.insert(key_code(KeyCode::Up), Action::SelectContextMenuPrev);
+        menu.insert(key_code(KeyCode::Enter), Binding::Confirm);
+
+        let help = modes.entry(AppMode::Help).or_default();
+        help.insert(key_code(KeyCode::Esc), Action::ToggleHelp);
+        help.insert(key_char('q'), Action::ToggleHelp);
+        help.insert(key_char('?'), Action::ToggleHelp);
+
+        Self {
+            global,
+            modes,
+            problems: Vec::new(),
+        }
     }
 
-    pub fn get_action(
-        &self,
-        event: KeyEvent,
-        state: &super::state::AppState<'_>,
-    ) -> Option<Action> {
-        let mode = state.mode;
-        if mode == super::state::AppMode::Diff {
-            if let Some(action) = self.diff_mode.get(&event) {
-                return Some(action.clone());
+    /// What `event` does, following on from the keys already typed in
+    /// `state.pending_keys`. Typing the start of a longer sequence gives
+    /// [`Action::PendingKeys`].
+    pub fn get_action(&self, event: KeyEvent, state: &AppState<'_>) -> Option<Action> {
+        let mut keys = state.pending_keys.clone();
+        keys.push(normalize(event));
+
+        let mode_bindings = self.modes.get(&state.mode);
+        // The diff panel and modes without keys of their own use the graph's.
+        let fallback =
+            (state.mode == AppMode::Diff || mode_bindings.is_none()).then_some(&self.global);
+        for bindings in mode_bindings.into_iter().chain(fallback) {
+            match bindings.lookup(&keys) {
+                Lookup::Bound(binding) => return resolve(binding, state),
+                Lookup::Prefix => return Some(Action::PendingKeys(keys)),
+                Lookup::Unbound => {}
             }
-        } else if mode == super::state::AppMode::CommandPalette {
-            return match event.code {
-                KeyCode::Esc => Some(Action::CancelMode),
-                KeyCode::Enter => Some(Action::CommandPaletteSelect),
-                KeyCode::Down => Some(Action::CommandPaletteNext),
-                KeyCode::Up => Some(Action::CommandPalettePrev),
-                KeyCode::Char('n') if event.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(Action::CommandPaletteNext)
-                }
-                KeyCode::Char('p') if event.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(Action::CommandPalettePrev)
-                }
-                _ => Some(Action::TextAreaInput(event)),
-            };
-        } else if mode == super::state::AppMode::ThemeSelection {
-            return match event.code {
-                KeyCode::Esc => Some(Action::CancelMode),
-                KeyCode::Char('j') | KeyCode::Down => Some(Action::SelectThemeNext),
-                KeyCode::Char('k') | KeyCode::Up => Some(Action::SelectThemePrev),
-                KeyCode::Enter => Some(Action::CommandPaletteSelect),
-                _ => None,
-            };
-        } else if mode == super::state::AppMode::FilterInput {
-            return match event.code {
-                KeyCode::Esc => Some(Action::CancelMode),
-                KeyCode::Enter => {
-                    let text = if let Some(input) = &state.input {
-                        input.text_area.lines().join("\n")
-                    } else {
-                        String::new()
-                    };
-                    Some(Action::ApplyFilter(text))
-                }
-                KeyCode::Tab => Some(Action::ToggleFilterSource),
-                KeyCode::Down => Some(Action::FilterNext),
-                KeyCode::Up => Some(Action::FilterPrev),
-                KeyCode::Char('n') if event.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(Action::FilterNext)
-                }
-                KeyCode::Char('p') if event.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(Action::FilterPrev)
-                }
-                _ => Some(Action::TextAreaInput(event)),
-            };
-        } else if mode == super::state::AppMode::RebaseInput {
-            return match event.code {
-                KeyCode::Esc => Some(Action::CancelMode),
-                KeyCode::Enter => {
-                    let text = if let Some(input) = &state.input {
-                        input.text_area.lines().join("\n")
-                    } else {
-                        String::new()
-                    };
-                    if text.trim().is_empty() {
-                        return Some(Action::CancelMode);
-                    }
-                    let ids = state.get_selected_commit_ids();
-                    Some(Action::RebaseRevision(ids, text))
-                }
-                _ => Some(Action::TextAreaInput(event)),
-            };
-        } else if mode == super::state::AppMode::RebaseSelect {
-            return match event.code {
-                KeyCode::Esc => Some(Action::CancelMode),
-                KeyCode::Enter => {
-                    let text = if let Some(input) = &state.input {
-                        input.text_area.lines().join("\n")
-                    } else {
-                        String::new()
-                    };
-                    if text.trim().is_empty() {
-                        return Some(Action::CancelMode);
-                    }
-                    let ids = state.rebase_sources.clone();
-                    Some(Action::RebaseRevision(ids, text))
-                }
-                _ => Some(Action::TextAreaInput(event)),
-            };
-        } else if mode == super::state::AppMode::Evolog {
-            return match event.code {
-                KeyCode::Esc | KeyCode::Char('q') => Some(Action::CloseEvolog),
-                KeyCode::Char('j') | KeyCode::Down => Some(Action::ScrollEvologDown(1)),
-                KeyCode::Char('k') | KeyCode::Up => Some(Action::ScrollEvologUp(1)),
-                KeyCode::PageDown => Some(Action::ScrollEvologDown(10)),
-                KeyCode::PageUp => Some(Action::ScrollEvologUp(10)),
-                _ => None,
-            };
-        } else if mode == super::state::AppMode::OperationLog {
-            return match event.code {
-                KeyCode::Esc | KeyCode::Char('q') => Some(Action::CloseOperationLog),
-                KeyCode::Char('j') | KeyCode::Down => Some(Action::ScrollOperationLogDown(1)),
-                KeyCode::Char('k') | KeyCode::Up => Some(Action::ScrollOperationLogUp(1)),
-                KeyCode::PageDown => Some(Action::ScrollOperationLogDown(10)),
-                KeyCode::PageUp => Some(Action::ScrollOperationLogUp(10)),
-                _ => None,
-            };
-        } else if mode == super::state::AppMode::ContextMenu {
-            return match event.code {
-                KeyCode::Esc => Some(Action::CloseContextMenu),
-                KeyCode::Char('j') | KeyCode::Down => Some(Action::SelectContextMenuNext),
-                KeyCode::Char('k') | KeyCode::Up => Some(Action::SelectContextMenuPrev),
-                KeyCode::Enter => {
-                    let idx = state.context_menu.as_ref()?.selected_index;
-                    Some(Action::SelectContextMenuAction(idx))
+        }
+
+        if !state.pending_keys.is_empty() {
+            // Nothing starts this way; forget the keys typed so far.
+            return Some(Action::PendingKeys(Vec::new()));
+        }
+        matches!(
+            state.mode,
+            AppMode::CommandPalette | AppMode::FilterInput | AppMode::RebaseInput
+        )
+        .then_some(Action::TextAreaInput(event))
+    }
+}
+
+/// The action `binding` stands for right now.
+fn resolve(binding: &Binding, state: &AppState<'_>) -> Option<Action> {
+    let typed = || {
+        state
+            .input
+            .as_ref()
+            .map(|input| input.text_area.lines().join("\n"))
+            .unwrap_or_default()
+    };
+    let highlighted = || {
+        let repo = state.repo.as_ref()?;
+        repo.graph.get(state.log.list_state.selected()?)
+    };
+    match binding {
+        Binding::Action(action) => Some(Action::clone(action)),
+        Binding::Confirm => match state.mode {
+            AppMode::FilterInput => Some(Action::ApplyFilter(typed())),
+            AppMode::RebaseInput => {
+                let text = typed();
+                if text.trim().is_empty() {
+                    return Some(Action::CancelMode);
                 }
-                _ => None,
-            };
+                let ids = state.get_selected_commit_ids();
+                Some(Action::RebaseRevision(ids, text))
+            }
+            AppMode::RebaseSelect => {
+                let row = highlighted()?;
+                let ids = state.rebase_sources.clone();
+                Some(Action::RebaseRevision(ids, row.commit_id.0.clone()))
+            }
+            AppMode::SquashSelect => Some(Action::SquashSelected(SquashDestination::Revision(
+                highlighted()?.commit_id.clone(),
+            ))),
+            AppMode::ContextMenu => {
+                let idx = state.context_menu.as_ref()?.selected_index;
+                Some(Action::SelectContextMenuAction(idx))
+            }
+            _ => None,
+        },
+        Binding::DeleteSelectedBookmark => {
+            let bookmark = state.bookmarks_state.as_ref()?.selected_bookmark()?;
+            // Deleting a remote bookmark takes a push; see `jj git push`.
+            bookmark
+                .remote
+                .is_none()
+                .then(|| Action::DeleteBookmark(bookmark.name.clone()))
         }
-        self.global.get(&event).cloned()
     }
 }
 
@@ -246,23 +664,141 @@ fn key_char(c: char) -> KeyEvent {
     KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty())
 }
 
+fn key_ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+/// `event` as bindings are written: a press, and without Shift on keys
+/// like `?` that are typed with it on some keyboards.
+fn normalize(event: KeyEvent) -> KeyEvent {
+    let mut modifiers = event.modifiers;
+    if matches!(event.code, KeyCode::Char(c) if !c.is_alphabetic()) {
+        modifiers.remove(KeyModifiers::SHIFT);
+    }
+    KeyEvent::new(event.code, modifiers)
+}
+
+/// A sequence of keys separated by spaces, like `g g` or `<space> b s`.
+fn parse_keys(s: &str) -> Option<Vec<KeyEvent>> {
+    let keys = s
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Option<Vec<_>>>()?;
+    (!keys.is_empty()).then_some(keys)
+}
+
+/// One key, like `x`, `X`, `enter`, `f5`, `ctrl-r`, `alt-shift-tab` or
+/// `<C-r>`.
 fn parse_key(s: &str) -> Option<KeyEvent> {
+    let mut rest = match s.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+        Some(inner) if !inner.is_empty() => inner,
+        _ => s,
+    };
+    let mut modifiers = KeyModifiers::empty();
+    // A lone `-` is the key itself, as in `ctrl--`.
+    while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "c" => KeyModifiers::CONTROL,
+            "alt" | "a" | "meta" | "m" => KeyModifiers::ALT,
+            "shift" | "s" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+        rest = key;
+    }
+    let code = match rest.to_lowercase().as_str() {
+        "enter" | "return" | "cr" => KeyCode::Enter,
+        "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+        "tab" => KeyCode::Tab,
+        "backtab" => {
+            modifiers |= KeyModifiers::SHIFT;
+            KeyCode::BackTab
+        }
+        "esc" | "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "backspace" | "bs" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pgup" | "pageup" => KeyCode::PageUp,
+        "pgdn" | "pagedown" => KeyCode::PageDown,
+        name if name.len() > 1 && name.starts_with('f') => KeyCode::F(name[1..].parse().ok()?),
+        _ => {
+            let mut chars = rest.chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            KeyCode::Char(c)
+        }
+    };
+    Some(normalize(KeyEvent::new(code, modifiers)))
+}
+
+/// `keys` written the way `config.toml` takes them.
+#[must_use]
+pub fn format_keys(keys: &[KeyEvent]) -> String {
+    keys.iter().map(format_key).collect::<Vec<_>>().join(" ")
+}
+
+fn format_key(key: &KeyEvent) -> String {
+    let mut s = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        s.push_str("ctrl-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        s.push_str("alt-");
+    }
+    match key.code {
+        KeyCode::Char(' ') => s.push_str("space"),
+        KeyCode::Char(c) => s.push(c),
+        KeyCode::F(n) => s.push_str(&format!("f{n}")),
+        KeyCode::BackTab => s.push_str("shift-tab"),
+        code => {
+            if key.modifiers.contains(KeyModifiers::SHIFT) {
+                s.push_str("shift-");
+            }
+            s.push_str(&code.to_string().to_lowercase().replace(' ', ""));
+        }
+    }
+    s
+}
+
+/// An action name, or `run <command>` for a `[commands]` entry.
+fn parse_binding(s: &str, config: &KeyConfig) -> Option<Binding> {
+    if let Some(name) = s.strip_prefix("run ") {
+        let name = name.trim();
+        return config
+            .commands
+            .contains_key(name)
+            .then(|| Action::RunCustomCommand(name.to_string(), None).into());
+    }
     match s.to_lowercase().as_str() {
-        "enter" => Some(key_code(KeyCode::Enter)),
-        "tab" => Some(key_code(KeyCode::Tab)),
-        "esc" => Some(key_code(KeyCode::Esc)),
-        "up" => Some(key_code(KeyCode::Up)),
-        "down" => Some(key_code(KeyCode::Down)),
-        "left" => Some(key_code(KeyCode::Left)),
-        "right" => Some(key_code(KeyCode::Right)),
-        "pgup" | "pageup" => Some(key_code(KeyCode::PageUp)),
-        "pgdn" | "pagedown" => Some(key_code(KeyCode::PageDown)),
-        s if s.len() == 1 => Some(key_char(s.chars().next().unwrap())),
-        _ => None,
+        "confirm" => Some(Binding::Confirm),
+        "deleteselectedbookmark" => Some(Binding::DeleteSelectedBookmark),
+        _ => parse_action(s).map(Binding::from),
     }
 }
 
+/// An action name, with a count for the scrolling ones, like
+/// `scrolldiffdown 5`.
 fn parse_action(s: &str) -> Option<Action> {
+    if let Some((name, count)) = s.split_once(' ') {
+        let count: u16 = count.trim().parse().ok()?;
+        return match name.to_lowercase().as_str() {
+            "scrolldiffdown" => Some(Action::ScrollDiffDown(count)),
+            "scrolldiffup" => Some(Action::ScrollDiffUp(count)),
+            "scrollevologdown" => Some(Action::ScrollEvologDown(count)),
+            "scrollevologup" => Some(Action::ScrollEvologUp(count)),
+            "selectoperationnext" => Some(Action::SelectOperationNext(count.into())),
+            "selectoperationprev" => Some(Action::SelectOperationPrev(count.into())),
+            _ => None,
+        };
+    }
     match s.to_lowercase().as_str() {
         "quit" => Some(Action::Quit),
         "togglediffs" => Some(Action::ToggleDiffs),
@@ -280,14 +816,18 @@ fn parse_action(s: &str) -> Option<Action> {
         "abandon" => Some(Action::AbandonRevision(None)),
         "setbookmark" => Some(Action::SetBookmarkIntent),
         "deletebookmark" => Some(Action::DeleteBookmarkIntent),
+        "bookmarks" => Some(Action::Bookmarks),
+        "tags" => Some(Action::TagMenu),
         "undo" => Some(Action::Undo),
         "redo" => Some(Action::Redo),
         "fetch" => Some(Action::Fetch),
+        "remotes" => Some(Action::Remotes),
         "push" => Some(Action::PushIntent),
         "filter" => Some(Action::EnterFilterMode),
         "help" => Some(Action::ToggleHelp),
         "nexthunk" => Some(Action::NextHunk),
         "prevhunk" => Some(Action::PrevHunk),
+        "toggledifflayout" | "splitdiff" => Some(Action::ToggleDiffLayout),
         "cancel" => Some(Action::CancelMode),
         "filterempty" => Some(Action::FilterEmpty),
         "filterdivergent" => Some(Action::FilterDivergent),
@@ -297,9 +837,235 @@ fn parse_action(s: &str) -> Option<Action> {
         "filterworking" => Some(Action::FilterWorking),
         "clearfilter" => Some(Action::ClearFilter),
         "split" => Some(Action::SplitRevision(None)),
+        "squashchanges" | "move" => Some(Action::EnterSquashMode(None)),
         "rebase" => Some(Action::RebaseRevisionIntent),
+        "previewrewrites" => Some(Action::TogglePreviewRewrites),
         "evolog" => Some(Action::EvologRevision(None)),
         "oplog" | "operationlog" => Some(Action::OperationLog),
+        "workspaces" => Some(Action::Workspaces),
+        "scrolldiffdown" => Some(Action::ScrollDiffDown(1)),
+        "scrolldiffup" => Some(Action::ScrollDiffUp(1)),
+        "toggleselection" => Some(Action::ToggleSelection(None)),
+        "togglepickfile" => Some(Action::TogglePickFile),
+        "filtermine" => Some(Action::FilterMine),
+        "filtertrunk" => Some(Action::FilterTrunk),
+        "filterconflicts" => Some(Action::FilterConflicts),
+        "themes" => Some(Action::EnterThemeSelection),
+        "commandpalette" => Some(Action::EnterCommandMode),
+        // Command palette and theme selection
+        "commandpalettenext" => Some(Action::CommandPaletteNext),
+        "commandpaletteprev" => Some(Action::CommandPalettePrev),
+        "commandpaletteselect" => Some(Action::CommandPaletteSelect),
+        "selectthemenext" => Some(Action::SelectThemeNext),
+        "selectthemeprev" => Some(Action::SelectThemePrev),
+        // Filter input
+        "togglefiltersource" => Some(Action::ToggleFilterSource),
+        "filternext" => Some(Action::FilterNext),
+        "filterprev" => Some(Action::FilterPrev),
+        // Rebase target picker
+        "rebasemoderevisions" => Some(Action::SetRebaseMode(RebaseMode::Revisions)),
+        "rebasemodesource" => Some(Action::SetRebaseMode(RebaseMode::Source)),
+        "rebasemodebranch" => Some(Action::SetRebaseMode(RebaseMode::Branch)),
+        "rebasedestination" => Some(Action::SetRebaseLocation(RebaseLocation::Destination)),
+        "rebaseinsertafter" => Some(Action::SetRebaseLocation(RebaseLocation::InsertAfter)),
+        "rebaseinsertbefore" => Some(Action::SetRebaseLocation(RebaseLocation::InsertBefore)),
+        // Picking hunks
+        "hunkselectnext" => Some(Action::HunkSelectNext),
+        "hunkselectprev" => Some(Action::HunkSelectPrev),
+        "hunkselecttoggle" => Some(Action::HunkSelectToggle),
+        "hunkselecttoggleall" => Some(Action::HunkSelectToggleAll),
+        "hunkselectconfirm" => Some(Action::HunkSelectConfirm),
+        // Conflict editor
+        "conflictnext" => Some(Action::ConflictNext),
+        "conflictprev" => Some(Action::ConflictPrev),
+        "conflictpickours" => Some(Action::ConflictPick(Resolution::Ours)),
+        "conflictpicktheirs" => Some(Action::ConflictPick(Resolution::Theirs)),
+        "conflictpickbase" => Some(Action::ConflictPick(Resolution::Base)),
+        "conflictpickboth" => Some(Action::ConflictPick(Resolution::Both)),
+        "conflictclear" => Some(Action::ConflictClear),
+        "conflictedit" => Some(Action::ConflictEditStart),
+        "conflictsave" => Some(Action::ConflictSave),
+        // Evolog and operation log
+        "closeevolog" => Some(Action::CloseEvolog),
+        "scrollevologdown" => Some(Action::ScrollEvologDown(1)),
+        "scrollevologup" => Some(Action::ScrollEvologUp(1)),
+        "closeoperationlog" => Some(Action::CloseOperationLog),
+        "selectoperationnext" => Some(Action::SelectOperationNext(1)),
+        "selectoperationprev" => Some(Action::SelectOperationPrev(1)),
+        "restoreoperation" => Some(Action::RestoreOperation),
+        "revertoperation" => Some(Action::RevertOperation),
+        // Workspaces
+        "closeworkspaces" => Some(Action::CloseWorkspaces),
+        "selectworkspacenext" => Some(Action::SelectWorkspaceNext),
+        "selectworkspaceprev" => Some(Action::SelectWorkspacePrev),
+        "addworkspace" => Some(Action::AddWorkspaceIntent),
+        "forgetworkspace" => Some(Action::ForgetWorkspace),
+        "updatestaleworkspace" => Some(Action::UpdateStaleWorkspace),
+        // Bookmarks
+        "closebookmarks" => Some(Action::CloseBookmarks),
+        "selectbookmarknext" => Some(Action::SelectBookmarkNext),
+        "selectbookmarkprev" => Some(Action::SelectBookmarkPrev),
+        "jumptobookmark" => Some(Action::JumpToBookmark),
+        "renamebookmark" => Some(Action::RenameBookmarkIntent),
+        "movebookmark" => Some(Action::MoveBookmarkIntent),
+        "toggletrackbookmark" => Some(Action::ToggleTrackBookmark),
+        "forgetbookmark" => Some(Action::ForgetBookmark),
+        "movebookmarkbackwards" => Some(Action::MoveBookmarkBackwards),
+        // Push
+        "closepush" => Some(Action::ClosePush),
+        "pushnextremote" => Some(Action::PushNextRemote),
+        "pushmodebookmarks" => Some(Action::SetPushMode(PushMode::Bookmarks)),
+        "pushmodechange" => Some(Action::SetPushMode(PushMode::Change)),
+        "pushmodeall" => Some(Action::SetPushMode(PushMode::All)),
+        "pushmodetracked" => Some(Action::SetPushMode(PushMode::Tracked)),
+        "pushselectnext" => Some(Action::PushSelectNext),
+        "pushselectprev" => Some(Action::PushSelectPrev),
+        "pushtogglebookmark" => Some(Action::PushToggleBookmark),
+        "confirmpush" => Some(Action::ConfirmPush),
+        // Remotes
+        "closeremotes" => Some(Action::CloseRemotes),
+        "selectremotenext" => Some(Action::SelectRemoteNext),
+        "selectremoteprev" => Some(Action::SelectRemotePrev),
+        "addremote" => Some(Action::AddRemoteIntent),
+        "renameremote" => Some(Action::RenameRemoteIntent),
+        "setremoteurl" => Some(Action::SetRemoteUrlIntent),
+        "removeremote" => Some(Action::RemoveRemote),
+        "fetchremote" => Some(Action::FetchRemote),
+        "fetchallremotes" => Some(Action::FetchAllRemotes),
+        "fetchbranches" => Some(Action::FetchBranchesIntent),
+        // Rewrite preview
+        "closerewritepreview" => Some(Action::CloseRewritePreview),
+        "selectpreviewnext" => Some(Action::SelectPreviewNext),
+        "selectpreviewprev" => Some(Action::SelectPreviewPrev),
+        "confirmrewrite" => Some(Action::ConfirmRewrite),
+        // Context menu
+        "closecontextmenu" => Some(Action::CloseContextMenu),
+        "selectcontextmenunext" => Some(Action::SelectContextMenuNext),
+        "selectcontextmenuprev" => Some(Action::SelectContextMenuPrev),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::reducer;
+    use crate::app::state::ErrorSeverity;
+
+    #[test]
+    fn test_bindings_from_config() {
+        let config: KeyConfig = toml::from_str(
+            r#"
+            profile = "vim"
+
+            [custom]
+            "<C-r>" = "redo"
+
+            [keys.normal]
+            "g g" = "oplog"
+            "x" = "none"
+
+            [keys.evolog]
+            "shift-j" = "scrollevologdown 10"
+
+            [keys.diff]
+            "h" = "fly"
+            "hyper-h" = "focusgraph"
+
+            [keys.graph]
+            "a" = "quit"
+
+            [commands.status]
+            run = "jj status"
+            key = "<space> s"
+
+            [commands.lint]
+            run = "make lint"
+            key = "g g x"
+            "#,
+        )
+        .unwrap();
+        let mut state = AppState::new(config);
+        assert_eq!(
+            state.keymap.problems,
+            [
+                "[keys.graph]: no such mode",
+                "[keys.diff] \"h\" = \"fly\": unknown action",
+                "[keys.diff] \"hyper-h\": unknown key",
+                "[commands.lint] \"g g x\" overlaps [keys.normal] \"g g\"",
+            ]
+        );
+        let warning = state.last_error.take().expect("problems are reported");
+        assert_eq!(warning.severity, ErrorSeverity::Warning);
+
+        // Stands in for the run loop, which ends a sequence on any other key.
+        let press = |state: &mut AppState, key: KeyEvent| {
+            let action = state.keymap.get_action(key, state);
+            match &action {
+                Some(pending @ Action::PendingKeys(_)) => {
+                    reducer::update(state, pending.clone());
+                }
+                _ => state.pending_keys.clear(),
+            }
+            action
+        };
+
+        // `g g` takes over from `g` alone.
+        assert_eq!(
+            press(&mut state, key_char('g')),
+            Some(Action::PendingKeys(vec![key_char('g')]))
+        );
+        assert_eq!(press(&mut state, key_char('g')), Some(Action::OperationLog));
+        press(&mut state, key_char('g'));
+        assert_eq!(
+            press(&mut state, key_char('z')),
+            Some(Action::PendingKeys(Vec::new()))
+        );
+        assert!(state.pending_keys.is_empty());
+
+        press(&mut state, key_char(' '));
+        assert_eq!(
+            press(&mut state, key_char('s')),
+            Some(Action::RunCustomCommand("status".to_string(), None))
+        );
+        assert_eq!(press(&mut state, key_ctrl('r')), Some(Action::Redo));
+        assert_eq!(
+            press(&mut state, key_char('r')),
+            Some(Action::RebaseRevisionIntent)
+        );
+        assert_eq!(press(&mut state, key_char('x')), None);
+
+        state.mode = AppMode::Evolog;
+        let shift_j = KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT);
+        assert_eq!(
+            press(&mut state, shift_j),
+            Some(Action::ScrollEvologDown(10))
+        );
+        assert_eq!(
+            press(&mut state, key_char('j')),
+            Some(Action::ScrollEvologDown(1))
+        );
+
+        state.mode = AppMode::Diff;
+        assert_eq!(press(&mut state, key_char('h')), Some(Action::FocusGraph));
+        assert_eq!(press(&mut state, key_char('u')), Some(Action::Undo));
+    }
+
+    #[test]
+    fn test_broken_config_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "profile = \"vim\"\n\n[graph]\nrow = 3\n").unwrap();
+        let config = KeyConfig::load_from(&path);
+        assert_eq!(
+            config.problems,
+            ["config.toml: line 4, column 7: invalid type: integer `3`, expected a sequence"]
+        );
+        assert_eq!(config.graph, GraphConfig::default());
+
+        let mut state = AppState::new(config);
+        let warning = state.last_error.take().expect("problems are reported");
+        assert_eq!(warning.suggestions.len(), 1);
+        assert!(warning.suggestions[0].starts_with("config.toml: line 4"));
+    }
+}
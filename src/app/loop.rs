@@ -4,7 +4,7 @@ use crate::app::{
 use crate::domain::vcs::VcsFacade;
 
 use anyhow::Result;
-use crossterm::event::{self, Event, MouseButton, MouseEventKind};
+use crossterm::event::{self, Event, KeyEventKind, MouseButton, MouseEventKind};
 use notify::{RecursiveMode, Watcher};
 use ratatui::{backend::Backend, Terminal};
 use std::sync::Arc;
@@ -86,7 +86,7 @@ pub async fn run_loop_with_events<B: Backend>(
     // Initial Load
     if app_state.mode != crate::app::state::AppMode::NoRepo {
         handle_command(
-            Command::LoadRepo(None, 100, None),
+            Command::LoadRepo(reducer::GRAPH_PAGE_SIZE, app_state.revset.clone()),
             adapter.clone(),
             action_tx.clone(),
         )?;
@@ -109,6 +109,14 @@ pub async fn run_loop_with_events<B: Backend>(
                     Err(e) => return Err(e.into()),
                 };
                 let action = map_event_to_action(event.clone(), &app_state, terminal.size()?);
+                if let Event::Key(key) = event {
+                    // Any other key ends a key sequence, finished or not.
+                    if key.kind != KeyEventKind::Release
+                        && !matches!(action, Some(Action::PendingKeys(_)))
+                    {
+                        app_state.pending_keys.clear();
+                    }
+                }
                 if let Event::Mouse(mouse) = event {
                     if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
                         app_state.last_click_time = Some(Instant::now());
@@ -157,6 +165,7 @@ pub async fn run_loop_with_events<B: Backend>(
                             terminal,
                             "jj",
                             &["resolve", &path],
+                            None,
                         )?;
 
                         // Trigger refresh
@@ -168,19 +177,19 @@ pub async fn run_loop_with_events<B: Backend>(
                             }))
                             .await;
                     }
-                    Command::Split(commit_id) => {
+                    Command::RunInteractiveCommand(name, line) => {
                         let success = crate::app::external::run_external_command(
                             terminal,
-                            "jj",
-                            &["split", "-r", &commit_id.0],
+                            "sh",
+                            &["-c", &line],
+                            Some(&adapter.workspace_root()),
                         )?;
 
-                        // Trigger refresh
                         let _ = action_tx
                             .send(Action::OperationCompleted(if success {
-                                Ok(format!("Split revision {}", commit_id.0))
+                                Ok(format!("{name} finished"))
                             } else {
-                                Err(format!("Split failed for revision {}", commit_id.0))
+                                Err(format!("{name} failed"))
                             }))
                             .await;
                     }
@@ -192,7 +201,7 @@ pub async fn run_loop_with_events<B: Backend>(
         }
     }
 
-    Ok(())
+    app_state.save_session()
 }
 
 pub(crate) fn handle_command(
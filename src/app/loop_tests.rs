@@ -2,6 +2,7 @@ use super::*;
 use crate::app::action::Action;
 use crate::app::command::Command;
 use crate::app::state::AppState;
+use crate::domain::diff::Diff;
 use crate::domain::models::CommitId;
 use crate::domain::vcs::MockVcsFacade;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
@@ -29,8 +30,8 @@ async fn test_handle_command_error_propagation() {
 
     // We expect a DiffLoaded action with an error message in it
     let action = rx.recv().await.unwrap();
-    if let Action::DiffLoaded(_, diff) = action {
-        assert!(diff.contains("Error: VCS Error"));
+    if let Action::DiffLoaded(_, result) = action {
+        assert!(result.unwrap_err().contains("Error: VCS Error"));
     } else {
         panic!("Expected Action::DiffLoaded, got {action:?}");
     }
@@ -45,7 +46,7 @@ async fn test_handle_command_success() {
     // Simulate a success
     mock.expect_get_commit_diff()
         .with(mockall::predicate::eq(commit_id_clone))
-        .returning(|_| Ok("Diff Content".to_string()));
+        .returning(|_| Ok(Diff::default()));
 
     let adapter = Arc::new(mock);
     let (tx, mut rx) = mpsc::channel(1);
@@ -53,8 +54,8 @@ async fn test_handle_command_success() {
     handle_command(Command::LoadDiff(commit_id), adapter, tx).unwrap();
 
     let action = rx.recv().await.unwrap();
-    if let Action::DiffLoaded(_, diff) = action {
-        assert_eq!(diff, "Diff Content");
+    if let Action::DiffLoaded(_, result) = action {
+        assert_eq!(result, Ok(Diff::default()));
     } else {
         panic!("Expected Action::DiffLoaded, got {action:?}");
     }
@@ -101,8 +102,19 @@ async fn test_keystroke_fuzzing() {
     // Setup mock to return some data to avoid crashes in UI
     mock.expect_workspace_root()
         .returning(|| std::path::PathBuf::from("/tmp"));
-    mock.expect_operation_log()
-        .returning(|| Ok("op log content".to_string()));
+    mock.expect_operation_log().returning(|_| {
+        Ok(vec![crate::domain::operation::Operation {
+            id: "op".to_string(),
+            id_short: "op".to_string(),
+            description: "snapshot working copy".to_string(),
+            is_current: true,
+            ..Default::default()
+        }])
+    });
+    mock.expect_operation_changes()
+        .returning(|_| Ok(Default::default()));
+    mock.expect_restore_operation().returning(|_| Ok(()));
+    mock.expect_revert_operation().returning(|_| Ok(()));
     mock.expect_get_operation_log().returning(|_, _, _| {
         Ok(crate::domain::models::RepoStatus {
             repo_name: "test-repo".to_string(),
@@ -117,22 +129,27 @@ async fn test_keystroke_fuzzing() {
                 change_id_short: "wc".to_string(),
                 description: "desc".to_string(),
                 author: "author".to_string(),
+                committer: "author".to_string(),
                 timestamp: "time".to_string(),
                 is_working_copy: true,
+                workspaces: vec!["default".to_string()],
                 is_immutable: false,
                 has_conflict: false,
                 parents: vec![],
                 bookmarks: vec![],
+                tags: vec![],
                 changed_files: vec![crate::domain::models::FileChange {
                     path: "file.txt".to_string(),
                     status: crate::domain::models::FileStatus::Modified,
                 }],
+                line_counts: None,
                 visual: crate::domain::models::GraphRowVisual::default(),
             }],
+            has_more: false,
         })
     });
     mock.expect_get_commit_diff()
-        .returning(|_| Ok("diff content".to_string()));
+        .returning(|_| Ok(Diff::default()));
     mock.expect_snapshot()
         .returning(|| Ok("snapshot".to_string()));
     mock.expect_new_child().returning(|_| Ok(()));
@@ -145,11 +162,11 @@ async fn test_keystroke_fuzzing() {
     mock.expect_delete_bookmark().returning(|_| Ok(()));
     mock.expect_undo().returning(|| Ok(()));
     mock.expect_redo().returning(|| Ok(()));
-    mock.expect_fetch().returning(|| Ok(()));
-    mock.expect_push().returning(|_| Ok(()));
+    mock.expect_fetch().returning(|_, _| Ok(()));
+    mock.expect_push().returning(|_, _| Ok(Vec::new()));
     mock.expect_describe_revision().returning(|_, _| Ok(()));
     mock.expect_evolog().returning(|_| Ok("evolog".to_string()));
-    mock.expect_rebase().returning(|_, _| Ok(()));
+    mock.expect_rebase().returning(|_, _, _, _| Ok(()));
     mock.expect_parallelize().returning(|_| Ok(()));
     mock.expect_revert().returning(|_| Ok(()));
 
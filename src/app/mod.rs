@@ -10,4 +10,5 @@ pub mod persistence;
 pub mod recovery;
 pub mod reducer;
 pub mod state;
+pub mod syntax;
 pub mod ui;
@@ -1,57 +1,209 @@
+use super::state::Panel;
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct RecentFilters {
-    pub filters: Vec<String>,
+/// What judo remembers about a repository between runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub revset: Option<String>,
+    pub recent_filters: Vec<String>,
+    pub diff_ratio: u16,
+    pub show_diffs: bool,
+    pub focused_panel: Panel,
+    /// Change id of the revision selected in the graph.
+    pub selected_change_id: Option<String>,
 }
 
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            revset: None,
+            recent_filters: Vec::new(),
+            diff_ratio: 50,
+            show_diffs: false,
+            focused_panel: Panel::Graph,
+            selected_change_id: None,
+        }
+    }
+}
+
+/// Longest part of a session's file name taken from the repository path,
+/// which keeps the names well under the usual 255-byte limit.
+const SESSION_NAME_LEN: usize = 100;
+
+/// Where the session of the repository at `repo_path` is kept: one file per
+/// repository, shared by its workspaces. The file is named after the end of
+/// the path, plus a hash of all of it.
 #[must_use]
-pub fn get_config_path() -> Option<PathBuf> {
+pub fn session_path(repo_path: &Path) -> Option<PathBuf> {
+    let repo_path = repo_path.to_string_lossy();
+    let mut name = String::new();
+    for byte in repo_path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' => name.push(byte as char),
+            _ => name.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    // Only ASCII is left, so any byte is a char boundary.
+    let name = &name[name.len().saturating_sub(SESSION_NAME_LEN)..];
+    // FNV-1a, which unlike `DefaultHasher` is the same in every build.
+    let hash = repo_path
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
     home::home_dir().map(|mut path| {
         path.push(".config");
         path.push("judo");
-        path.push("recent_filters.toml");
+        path.push("sessions");
+        path.push(format!("{name}-{hash:016x}.toml"));
         path
     })
 }
 
+/// The session saved at `path`, if there is one that can be read. A
+/// repository without one starts from the recent filters judo kept for all
+/// repositories before it had sessions.
 #[must_use]
-pub fn load_recent_filters() -> Vec<String> {
-    if let Some(path) = get_config_path() {
-        if path.exists() {
-            match std::fs::read_to_string(&path) {
-                Ok(content) => match toml::from_str::<RecentFilters>(&content) {
-                    Ok(recent) => return recent.filters,
-                    Err(e) => eprintln!("Failed to parse {}: {}", path.display(), e),
-                },
-                Err(e) => eprintln!("Failed to read {}: {}", path.display(), e),
-            }
+pub fn load_session(path: &Path) -> Option<Session> {
+    let recent_filters = home::home_dir().map(|mut legacy| {
+        legacy.push(".config");
+        legacy.push("judo");
+        legacy.push("recent_filters.toml");
+        legacy
+    });
+    load_or_migrate_session(path, recent_filters.as_deref())
+}
+
+/// The old `recent_filters.toml`, shared by every repository.
+#[derive(Deserialize)]
+struct RecentFilters {
+    filters: Vec<String>,
+}
+
+fn load_or_migrate_session(path: &Path, recent_filters: Option<&Path>) -> Option<Session> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => toml::from_str(&content).ok(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let content = std::fs::read_to_string(recent_filters?).ok()?;
+            let recent: RecentFilters = toml::from_str(&content).ok()?;
+            Some(Session {
+                recent_filters: recent.filters,
+                ..Session::default()
+            })
         }
+        Err(_) => None,
     }
-    Vec::new()
 }
 
-pub fn save_recent_filters(filters: &[String]) {
-    if let Some(path) = get_config_path() {
+pub fn save_session(path: &Path, session: &Session) -> anyhow::Result<()> {
+    let write = || -> anyhow::Result<()> {
         if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                eprintln!("Failed to create directory {}: {}", parent.display(), e);
-                return;
-            }
+            std::fs::create_dir_all(parent)?;
         }
+        std::fs::write(path, toml::to_string(session)?)?;
+        Ok(())
+    };
+    write().with_context(|| format!("Could not save the session to {}", path.display()))
+}
+
+/// Sets `theme` in `config.toml`, leaving the rest of the file as written.
+pub fn save_theme(name: &str) -> anyhow::Result<()> {
+    let Some(mut path) = home::home_dir() else {
+        anyhow::bail!("No home directory to keep config.toml in");
+    };
+    path.push(".config");
+    path.push("judo");
+    std::fs::create_dir_all(&path)?;
+    path.push("config.toml");
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut config: toml_edit::DocumentMut = content.parse()?;
+    config["theme"] = toml_edit::value(name);
+    std::fs::write(&path, config.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        action::Action,
+        reducer,
+        state::{AppMode, AppState},
+    };
+    use crate::domain::models::{CommitId, GraphRow, RepoStatus};
 
-        let recent = RecentFilters {
-            filters: filters.to_vec(),
+    #[test]
+    fn test_session_round_trip() {
+        assert_ne!(
+            session_path(Path::new("/src/a_b")),
+            session_path(Path::new("/src/a/b"))
+        );
+        let long = format!("/{}/.jj/repo", "nested/".repeat(100));
+        let long_path = session_path(Path::new(&long)).unwrap();
+        let name = long_path.file_name().unwrap().to_str().unwrap();
+        assert!(name.len() < 255);
+        assert!(name.contains("%2F.jj%2Frepo-"));
+        assert_ne!(
+            session_path(Path::new(&format!("/elsewhere{long}"))),
+            Some(long_path)
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions").join("repo.toml");
+        let session = Session {
+            revset: Some("mine()".to_string()),
+            recent_filters: vec!["mine()".to_string(), "trunk()".to_string()],
+            diff_ratio: 40,
+            show_diffs: true,
+            focused_panel: Panel::Diff,
+            selected_change_id: Some("change-b".to_string()),
         };
+        save_session(&path, &session).unwrap();
+        assert_eq!(load_session(&path), Some(session.clone()));
 
-        match toml::to_string(&recent) {
-            Ok(content) => {
-                if let Err(e) = std::fs::write(&path, content) {
-                    eprintln!("Failed to write {}: {}", path.display(), e);
-                }
-            }
-            Err(e) => eprintln!("Failed to serialize recent filters: {}", e),
-        }
+        let missing = dir.path().join("missing.toml");
+        let legacy = dir.path().join("recent_filters.toml");
+        assert_eq!(load_or_migrate_session(&missing, None), None);
+        assert_eq!(load_or_migrate_session(&missing, Some(&legacy)), None);
+        std::fs::write(&legacy, "filters = [\"mine()\", \"trunk()\"]\n").unwrap();
+        assert_eq!(
+            load_or_migrate_session(&missing, Some(&legacy)),
+            Some(Session {
+                recent_filters: session.recent_filters.clone(),
+                ..Session::default()
+            })
+        );
+        assert_eq!(
+            load_or_migrate_session(&path, Some(&legacy)),
+            Some(session.clone())
+        );
+
+        let mut state = AppState::default();
+        state.restore_session(session.clone());
+        assert_eq!(state.mode, AppMode::Diff);
+        let row = |id: &str| GraphRow {
+            commit_id: CommitId(id.to_string()),
+            change_id: format!("change-{id}"),
+            ..Default::default()
+        };
+        let repo = RepoStatus {
+            repo_name: "repo".to_string(),
+            operation_id: String::new(),
+            workspace_id: "default".to_string(),
+            working_copy_id: CommitId("a".to_string()),
+            graph: vec![row("a"), row("b")],
+            has_more: false,
+        };
+        reducer::update(&mut state, Action::RepoLoaded(Box::new(repo)));
+        assert_eq!(state.log.list_state.selected(), Some(1));
+        assert_eq!(state.session(), session);
     }
 }
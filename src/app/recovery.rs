@@ -18,6 +18,10 @@ pub fn get_suggestions(msg: &str) -> Vec<String> {
         suggestions.push("Try running: jj resolve (to open the external merge tool)".to_string());
     }
 
+    if msg_lower.contains("working copy is stale") {
+        suggestions.push("Press W to open the workspaces, then u to update it".to_string());
+    }
+
     if msg_lower.contains("no such bookmark") {
         suggestions.push("Check the bookmark name or try: jj bookmark list".to_string());
     }
@@ -1,4 +1,7 @@
-use crate::app::features::{filter, navigation, ui, vcs};
+use crate::app::features::{
+    bookmarks, conflict_editor, custom_commands, filter, hunk_select, navigation, push, remotes,
+    rewrite_preview, tags, ui, vcs, workspaces,
+};
 use crate::domain::graph_layout;
 use crate::app::{
     action::{Action, UpdateResult},
@@ -9,7 +12,9 @@ use crate::app::{
 use chrono::Local;
 use std::time::{Duration, Instant};
 
-const STATUS_CLEAR_DURATION: Duration = Duration::from_secs(5);
+pub(crate) const STATUS_CLEAR_DURATION: Duration = Duration::from_secs(5);
+/// Revisions loaded at a time as the graph is scrolled.
+pub(crate) const GRAPH_PAGE_SIZE: usize = 100;
 
 pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
     // 1. Feature delegation with short-circuit on handled
@@ -21,6 +26,42 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
         UpdateResult::Handled(cmd) => return cmd,
         UpdateResult::NotHandled => {}
     }
+    match hunk_select::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match conflict_editor::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match workspaces::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match bookmarks::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match push::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match remotes::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match tags::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match rewrite_preview::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
+    match custom_commands::update(state, &action) {
+        UpdateResult::Handled(cmd) => return cmd,
+        UpdateResult::NotHandled => {}
+    }
     match filter::update(state, &action) {
         UpdateResult::Handled(cmd) => return cmd,
         UpdateResult::NotHandled => {}
@@ -50,11 +91,13 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
         Action::CommandPaletteSelect => {
             if let Some(cp) = &state.command_palette {
                 if let Some(&cmd_idx) = cp.matches.get(cp.selected_index) {
-                    let cmd_def = crate::app::command_palette::get_commands().get(cmd_idx).cloned();
-                    if let Some(cmd_def) = cmd_def {
+                    let action = crate::app::command_palette::get_commands(&state.custom_commands)
+                        .get(cmd_idx)
+                        .map(|cmd_def| cmd_def.action.clone());
+                    if let Some(action) = action {
                         state.mode = AppMode::Normal;
                         state.command_palette = None;
-                        return update(state, cmd_def.action);
+                        return update(state, action);
                     }
                 }
             }
@@ -65,37 +108,24 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
                 return None;
             }
             if let Some(repo) = &state.repo {
-                use std::collections::HashSet;
-                let existing_ids: HashSet<crate::domain::models::CommitId> =
-                    repo.graph.iter().map(|r| r.commit_id.clone()).collect();
-                let mut heads = Vec::new();
-                for row in &repo.graph {
-                    for parent in &row.parents {
-                        if !existing_ids.contains(parent) {
-                            heads.push(parent.clone());
-                        }
-                    }
-                }
-                if heads.is_empty() {
-                    state.has_more = false;
-                } else {
-                    state.is_loading_more = true;
-                    heads.sort_by(|a, b| a.0.cmp(&b.0));
-                    heads.dedup();
-                    return Some(Command::LoadRepo(None, 100, state.revset.clone()));
-                }
+                state.is_loading_more = true;
+                return Some(Command::LoadGraphPage(
+                    repo.graph.len(),
+                    GRAPH_PAGE_SIZE,
+                    state.revset.clone(),
+                ));
             }
         }
 
         Action::RepoLoaded(repo_status) => {
             state.workspace_id = repo_status.workspace_id.clone();
+            state.has_more = repo_status.has_more;
             state.repo = Some(*repo_status);
             // Recompute graph layout for lane/connector rendering
             if let Some(repo) = &mut state.repo {
-                graph_layout::calculate_graph_layout(&mut repo.graph);
+                state.graph_layout = graph_layout::calculate_graph_layout(&mut repo.graph);
             }
             state.is_loading_more = false;
-            state.has_more = true;
             if state.mode == AppMode::Loading || state.mode == AppMode::NoRepo {
                 state.mode = match state.focused_panel {
                     Panel::Graph => AppMode::Normal,
@@ -104,6 +134,12 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
             }
             state.active_tasks.retain(|t| !t.contains("Syncing"));
             update_repository_derived_state(state);
+            // The revision selected when judo last quit in this workspace.
+            if let (Some(change_id), Some(repo)) = (state.restore_change_id.take(), &state.repo) {
+                if let Some(idx) = repo.graph.iter().position(|r| r.change_id == change_id) {
+                    state.log.list_state.select(Some(idx));
+                }
+            }
             if state.log.list_state.selected().is_none() {
                 state.log.list_state.select(Some(0));
             }
@@ -121,13 +157,13 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
                     None
                 };
 
+            state.has_more = repo_status.has_more;
             state.repo = Some(*repo_status);
             // Recompute graph layout since repo graph was refreshed
             if let Some(repo) = &mut state.repo {
-                graph_layout::calculate_graph_layout(&mut repo.graph);
+                state.graph_layout = graph_layout::calculate_graph_layout(&mut repo.graph);
             }
             state.is_loading_more = false;
-            state.has_more = true;
             update_repository_derived_state(state);
 
             if let Some(id) = selected_commit_id {
@@ -149,29 +185,68 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
         Action::GraphBatchLoaded(repo_status) => {
             state.is_loading_more = false;
             if let Some(repo) = &mut state.repo {
+                // A page read from another operation doesn't continue this
+                // graph; the reload for that operation replaces it anyway.
+                if repo.operation_id != repo_status.operation_id {
+                    return None;
+                }
                 use std::collections::HashSet;
                 let existing_ids: HashSet<crate::domain::models::CommitId> =
                     repo.graph.iter().map(|r| r.commit_id.clone()).collect();
+                let start = repo.graph.len();
                 for row in repo_status.graph {
                     if !existing_ids.contains(&row.commit_id) {
                         repo.graph.push(row);
                     }
                 }
-                graph_layout::calculate_graph_layout(&mut repo.graph);
+                graph_layout::extend_graph_layout(
+                    &mut state.graph_layout,
+                    &mut repo.graph[start..],
+                );
+                state.has_more = repo_status.has_more;
                 update_repository_derived_state(state);
             }
         }
 
-        Action::DiffLoaded(commit_id, diff) => {
-            state.log.diff_cache.insert(commit_id.clone(), diff.clone());
+        Action::DiffLoaded(commit_id, result) => {
+            // Failures are not cached, so reselecting the revision retries.
+            let highlight = match &result {
+                Ok(diff) => {
+                    state.log.diff_cache.insert(commit_id.clone(), diff.clone());
+                    (!state.log.highlight_cache.contains_key(&commit_id))
+                        .then(|| Command::HighlightDiff(commit_id.clone(), Box::new(diff.clone())))
+                }
+                Err(_) => None,
+            };
             if let (Some(repo), Some(idx)) = (&state.repo, state.log.list_state.selected()) {
                 if let Some(row) = repo.graph.get(idx) {
                     if row.commit_id == commit_id {
-                        state.log.current_diff = Some(diff);
+                        match result {
+                            Ok(diff) => {
+                                state.log.current_diff = Some(diff);
+                                state.log.current_highlights =
+                                    state.log.highlight_cache.get(&commit_id).cloned();
+                                state.log.diff_error = None;
+                            }
+                            Err(err) => {
+                                state.log.current_diff = None;
+                                state.log.diff_error = Some(err);
+                            }
+                        }
                         state.log.is_loading_diff = false;
                     }
                 }
             }
+            return highlight;
+        }
+
+        Action::DiffHighlighted(commit_id, highlights) => {
+            if let (Some(repo), Some(idx)) = (&state.repo, state.log.list_state.selected()) {
+                if repo.graph.get(idx).is_some_and(|row| row.commit_id == commit_id) {
+                    state.log.current_highlights = Some(highlights.clone());
+                }
+            }
+            state.log.highlight_cache.insert(commit_id, highlights);
         }
 
         Action::OperationStarted(msg) => {
@@ -200,7 +275,8 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
                     state.status_message = Some(msg);
                     state.status_clear_time = Some(Instant::now() + STATUS_CLEAR_DURATION);
                     state.log.diff_cache.clear();
-                    return Some(Command::LoadRepo(None, 100, state.revset.clone()));
+                    state.log.highlight_cache.clear();
+                    return Some(Command::LoadRepo(reload_limit(state), state.revset.clone()));
                 }
                 Err(err) => {
                     state.last_error = Some(ErrorState {
@@ -210,7 +286,7 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
                         severity: ErrorSeverity::Error,
                     });
                     if state.repo.is_some() {
-                        return Some(Command::LoadRepo(None, 100, state.revset.clone()));
+                        return Some(Command::LoadRepo(reload_limit(state), state.revset.clone()));
                     }
                 }
             }
@@ -246,7 +322,7 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
             }
 
             if is_revset_error {
-                return Some(Command::LoadRepo(None, 100, None));
+                return Some(Command::LoadRepo(GRAPH_PAGE_SIZE, None));
             }
         }
 
@@ -254,7 +330,10 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
             state
                 .active_tasks
                 .push("Syncing in background...".to_string());
-            return Some(Command::LoadRepoBackground(100, state.revset.clone()));
+            return Some(Command::LoadRepoBackground(
+                reload_limit(state),
+                state.revset.clone(),
+            ));
         }
 
         Action::Tick => {
@@ -285,6 +364,14 @@ pub fn update(state: &mut AppState, action: Action) -> Option<Command> {
     None
 }
 
+/// Reloads keep every page loaded so far, so the selection stays put.
+/// How many rows to reload so that the loaded part of the graph stays.
+pub(crate) fn reload_limit(state: &AppState) -> usize {
+    state.repo.as_ref().map_or(GRAPH_PAGE_SIZE, |repo| {
+        repo.graph.len().max(GRAPH_PAGE_SIZE)
+    })
+}
+
 fn update_spinner(state: &mut AppState) {
     let spinner_frames = vec!["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
     let idx = (state.frame_count / 5) as usize % spinner_frames.len();
@@ -0,0 +1,60 @@
+use crate::domain::conflict::{ConflictRegion, FileConflict, Resolution};
+
+/// Resolving the conflict in one file, one conflicting region at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictEditorState {
+    pub conflict: FileConflict,
+    /// One entry per conflicting region, in order.
+    pub resolutions: Vec<Option<Resolution>>,
+    /// Which conflicting region is selected.
+    pub current: usize,
+}
+
+impl ConflictEditorState {
+    #[must_use]
+    pub fn new(conflict: FileConflict) -> Self {
+        let count = conflict.conflict_indices().len();
+        Self {
+            conflict,
+            resolutions: vec![None; count],
+            current: 0,
+        }
+    }
+
+    /// The selected conflicting region.
+    #[must_use]
+    pub fn current_region(&self) -> Option<&ConflictRegion> {
+        let idx = *self.conflict.conflict_indices().get(self.current)?;
+        self.conflict.regions.get(idx)
+    }
+
+    #[must_use]
+    pub fn unresolved_count(&self) -> usize {
+        self.resolutions.iter().filter(|r| r.is_none()).count()
+    }
+
+    pub fn move_current(&mut self, delta: isize) {
+        let last = self.resolutions.len().saturating_sub(1);
+        self.current = self.current.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn resolve_current(&mut self, resolution: Option<Resolution>) {
+        if let Some(slot) = self.resolutions.get_mut(self.current) {
+            *slot = resolution;
+        }
+    }
+
+    /// Text to start editing the selected region from: its resolution so
+    /// far, or our side.
+    #[must_use]
+    pub fn current_text(&self) -> String {
+        let resolution = self.resolutions.get(self.current).cloned().flatten();
+        self.current_region()
+            .and_then(|region| {
+                region
+                    .resolved_text(resolution.as_ref())
+                    .or_else(|| region.resolved_text(Some(&Resolution::Ours)))
+            })
+            .unwrap_or_default()
+    }
+}
@@ -1,11 +1,124 @@
+use crate::domain::bookmark::Bookmark;
+use crate::domain::models::{CommitId, Rewrite, RewritePreview};
+use crate::domain::operation::{Operation, OperationChanges};
+use crate::domain::push::{PushPlan, PushScope, PushedBookmark};
+use crate::domain::remote::Remote;
+use crate::domain::workspace::Workspace;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct EvologState {
+    /// What the text is, e.g. the evolution log or a command's output.
+    pub title: String,
     pub content: Vec<String>,
     pub scroll: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct OperationLogState {
-    pub content: Vec<String>,
-    pub scroll: u16,
+    pub operations: Vec<Operation>,
+    pub selected: usize,
+    /// What the selected operation changed, once loaded.
+    pub changes: Option<OperationChanges>,
+}
+
+impl OperationLogState {
+    #[must_use]
+    pub fn selected_operation(&self) -> Option<&Operation> {
+        self.operations.get(self.selected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WorkspacesState {
+    pub workspaces: Vec<Workspace>,
+    pub selected: usize,
+}
+
+impl WorkspacesState {
+    #[must_use]
+    pub fn selected_workspace(&self) -> Option<&Workspace> {
+        self.workspaces.get(self.selected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BookmarksState {
+    pub bookmarks: Vec<Bookmark>,
+    pub selected: usize,
+}
+
+impl BookmarksState {
+    #[must_use]
+    pub fn selected_bookmark(&self) -> Option<&Bookmark> {
+        self.bookmarks.get(self.selected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RemotesState {
+    pub remotes: Vec<Remote>,
+    pub selected: usize,
+}
+
+impl RemotesState {
+    #[must_use]
+    pub fn selected_remote(&self) -> Option<&Remote> {
+        self.remotes.get(self.selected)
+    }
+}
+
+/// A rewrite worked out without being written, waiting to be confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewritePreviewState {
+    pub rewrite: Rewrite,
+    pub preview: RewritePreview,
+    /// Selection and scroll within the previewed graph, apart from the
+    /// main graph's.
+    pub list_state: ratatui::widgets::TableState,
+}
+
+/// A bookmark move that would go backwards or sideways, waiting to be
+/// confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookmarkMove {
+    pub name: String,
+    pub revision: String,
+}
+
+/// Which bookmarks the push dialog sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushMode {
+    Bookmarks,
+    Change,
+    All,
+    Tracked,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushState {
+    /// `None` until the first preview picks the remote `jj` would.
+    pub remote: Option<String>,
+    pub mode: PushMode,
+    /// The revision pushed with [`PushMode::Change`].
+    pub revision: CommitId,
+    /// Bookmarks pushed with [`PushMode::Bookmarks`].
+    pub picked: Vec<String>,
+    /// Index into the plan's bookmarks.
+    pub cursor: usize,
+    /// What the push would do, once previewed.
+    pub plan: Option<PushPlan>,
+    /// How each bookmark went, once pushed.
+    pub results: Option<Vec<PushedBookmark>>,
+}
+
+impl PushState {
+    #[must_use]
+    pub fn scope(&self) -> PushScope {
+        match self.mode {
+            PushMode::Bookmarks => PushScope::Bookmarks(self.picked.clone()),
+            PushMode::Change => PushScope::Change(self.revision.clone()),
+            PushMode::All => PushScope::All,
+            PushMode::Tracked => PushScope::Tracked,
+        }
+    }
 }
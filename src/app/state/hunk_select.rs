@@ -0,0 +1,60 @@
+use crate::domain::{
+    diff::{Diff, DiffLayout, DiffSelection},
+    models::CommitId,
+};
+
+/// What a hunk selection is for; decides what confirming it does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkSelectPurpose {
+    /// Split the selected changes off into a new parent revision.
+    Split,
+    /// Move the selected changes into another revision.
+    Squash,
+}
+
+/// Picking hunks or lines of a revision's diff in the diff panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HunkSelectState {
+    pub purpose: HunkSelectPurpose,
+    pub commit_id: CommitId,
+    /// The diff the selection indexes into, kept apart from the panel's
+    /// diff so background reloads can't shift it.
+    pub diff: Diff,
+    pub selection: DiffSelection,
+    /// Row of the unified layout under the cursor.
+    pub cursor: usize,
+}
+
+impl HunkSelectState {
+    #[must_use]
+    pub fn new(purpose: HunkSelectPurpose, commit_id: CommitId, diff: Diff) -> Self {
+        let cursor = diff.selectable_rows().first().copied().unwrap_or(0);
+        Self {
+            purpose,
+            commit_id,
+            diff,
+            selection: DiffSelection::default(),
+            cursor,
+        }
+    }
+
+    /// Moves the cursor to the next (`delta > 0`) or previous selectable row.
+    pub fn move_cursor(&mut self, delta: isize) {
+        let rows = self.diff.selectable_rows();
+        let next = if delta > 0 {
+            rows.iter().find(|&&row| row > self.cursor)
+        } else {
+            rows.iter().rev().find(|&&row| row < self.cursor)
+        };
+        if let Some(&row) = next {
+            self.cursor = row;
+        }
+    }
+
+    /// Toggles the file, hunk or line under the cursor.
+    pub fn toggle_at_cursor(&mut self) {
+        if let Some(&row) = self.diff.rows(DiffLayout::Unified).get(self.cursor) {
+            self.selection.toggle_row(&self.diff, row);
+        }
+    }
+}
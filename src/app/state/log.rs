@@ -1,8 +1,14 @@
-use crate::domain::models::CommitId;
+use crate::app::syntax::DiffHighlights;
+use crate::domain::{
+    diff::{Diff, DiffLayout},
+    models::CommitId,
+};
 use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Panel {
     Graph,
     Diff,
@@ -12,10 +18,24 @@ pub enum Panel {
 pub struct LogState {
     pub list_state: TableState,
     pub selected_file_index: Option<usize>,
-    pub current_diff: Option<String>,
+    /// Paths in the selected revision's file list picked to move elsewhere;
+    /// they start out selected when picking changes.
+    pub picked_files: HashSet<String>,
+    pub current_diff: Option<Diff>,
+    /// Why the selected revision's diff could not be loaded.
+    pub diff_error: Option<String>,
     pub is_loading_diff: bool,
     pub diff_scroll: u16,
-    pub diff_cache: HashMap<CommitId, String>,
+    /// Preferred layout; see [`super::AppState::diff_layout`].
+    pub diff_layout: DiffLayout,
+    /// Width of the diff panel at the last draw.
+    pub diff_width: u16,
+    /// Height of the diff panel's content area at the last draw.
+    pub diff_height: u16,
+    pub diff_cache: HashMap<CommitId, Diff>,
+    pub current_highlights: Option<DiffHighlights>,
+    /// Syntax highlighting for diffs in `diff_cache`, computed in the background.
+    pub highlight_cache: HashMap<CommitId, DiffHighlights>,
     pub selected_ids: HashSet<CommitId>,
 }
 
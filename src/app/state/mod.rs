@@ -1,13 +1,18 @@
-use super::keymap::{KeyConfig, KeyMap};
-use crate::domain::models::{CommitId, RepoStatus};
+use super::keymap::{CustomCommand, DiffConfig, KeyConfig, KeyMap};
+use super::persistence::Session;
+use crate::domain::graph_layout::GraphLayout;
+use crate::domain::models::{CommitId, RebaseLocation, RebaseMode, RepoStatus};
+use crate::domain::row_template::RowTemplate;
 use std::sync::Arc;
 use std::time::Instant;
 
 pub mod command_palette;
+pub mod conflict_editor;
 pub mod context_menu;
 pub mod error;
 pub mod extra;
 pub mod header;
+pub mod hunk_select;
 pub mod input;
 pub mod log;
 pub mod revset;
@@ -15,16 +20,21 @@ pub mod theme;
 
 // Re-exports
 pub use command_palette::CommandPaletteState;
+pub use conflict_editor::ConflictEditorState;
 pub use context_menu::ContextMenuState;
 pub use error::{ErrorSeverity, ErrorState};
-pub use extra::{EvologState, OperationLogState};
+pub use extra::{
+    BookmarkMove, BookmarksState, EvologState, OperationLogState, PushMode, PushState,
+    RemotesState, RewritePreviewState, WorkspacesState,
+};
 pub use header::HeaderState;
+pub use hunk_select::{HunkSelectPurpose, HunkSelectState};
 pub use input::{AppTextArea, InputState};
 pub use log::{LogState, Panel};
 pub use revset::{get_revset_reference, RevsetCategory, RevsetEntry};
 pub use theme::ThemeSelectionState;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum AppMode {
     Normal,         // Navigating the log
     SquashSelect,   // Selecting a target to squash into
@@ -43,6 +53,25 @@ pub enum AppMode {
     RebaseSelect,   // Selecting rebase destination
     Evolog,         // Viewing commit evolution log
     OperationLog,   // Viewing operation log
+    HunkSelect,     // Picking hunks or lines in the diff panel
+    SplitInput,     // Describing the first half of a split
+    ConflictEditor, // Resolving a conflicted file region by region
+    ConflictInput,  // Editing one conflict region by hand
+    Workspaces,     // Managing the repository's workspaces
+    WorkspaceInput, // Inputting the path of a new workspace
+    Bookmarks,      // Managing local and remote bookmarks
+    BookmarkRename, // Inputting a bookmark's new name
+    BookmarkMove,   // Inputting the revision to move a bookmark to
+    ConfirmMove,    // Confirming a backwards or sideways bookmark move
+    Push,           // Previewing and confirming a push
+    Remotes,        // Managing Git remotes and fetching from them
+    RemoteAdd,      // Inputting the name and URL of a new remote
+    RemoteRename,   // Inputting a remote's new name
+    RemoteUrl,      // Inputting a remote's new URL
+    RemoteFetch,    // Inputting the branches to fetch from a remote
+    TagInput,       // Inputting the name of a new tag
+    TagMove,        // Inputting the name of a tag to move to the selected revision
+    RewritePreview, // Looking over a rewritten graph before running the rewrite
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -60,8 +89,11 @@ pub struct AppState<'a> {
     // We wrap this in Option because we might start before the repo is loaded.
     pub repo: Option<RepoStatus>,
     pub revset: Option<String>,
+    pub revset_error: Option<crate::domain::error::RevsetError>,
     pub is_loading_more: bool,
     pub has_more: bool,
+    /// Lanes left open by the loaded rows, continued by the next page.
+    pub graph_layout: GraphLayout,
 
     // --- UI State (Selection, Scroll, Diff) ---
     pub log: LogState,
@@ -73,6 +105,7 @@ pub struct AppState<'a> {
 
     // --- Input Handling ---
     pub input: Option<InputState<'a>>,
+    pub pending_keys: Vec<crossterm::event::KeyEvent>, // Start of a key sequence, like `g` of `g g`
 
     // --- Click Tracking ---
     pub last_click_time: Option<Instant>,
@@ -97,9 +130,20 @@ pub struct AppState<'a> {
 
     // --- Config ---
     pub keymap: Arc<KeyMap>,
-    pub palette_type: crate::theme::PaletteType,
+    pub diff_split_min_width: u16,
+    /// Layout of the revision graph's rows, from `[graph]`.
+    pub row_template: RowTemplate,
+    /// The `[commands]` of `config.toml`, by name.
+    pub custom_commands: Arc<std::collections::BTreeMap<String, CustomCommand>>,
+    pub theme_name: String,
     pub theme: crate::theme::Theme,
 
+    // --- Session ---
+    /// Where the session is saved; `None` outside a workspace.
+    pub session_path: Option<std::path::PathBuf>,
+    /// Change to select once the graph first loads, from the last session.
+    pub restore_change_id: Option<String>,
+
     // --- Filters ---
     pub recent_filters: Vec<String>,
     pub preset_filters: Vec<String>,
@@ -112,21 +156,125 @@ pub struct AppState<'a> {
     // --- Operation Log ---
     pub operation_log_state: Option<OperationLogState>,
 
+    // --- Workspaces ---
+    pub workspaces_state: Option<WorkspacesState>,
+
+    // --- Bookmarks ---
+    pub bookmarks_state: Option<BookmarksState>,
+    pub bookmark_move: Option<BookmarkMove>,
+
+    // --- Push ---
+    pub push_state: Option<PushState>,
+
+    // --- Remotes ---
+    pub remotes_state: Option<RemotesState>,
+
     // --- Rebase State ---
     pub rebase_sources: Vec<CommitId>,
+    /// Kept between rebases, like the rest of the session's choices.
+    pub rebase_mode: RebaseMode,
+    pub rebase_location: RebaseLocation,
+
+    // --- Rewrite Preview ---
+    /// Whether rebase, abandon, squash and parallelize are previewed before
+    /// they run.
+    pub preview_rewrites: bool,
+    pub rewrite_preview: Option<RewritePreviewState>,
+
+    // --- Hunk Selection ---
+    pub hunk_select: Option<HunkSelectState>,
+
+    // --- Conflict Editor ---
+    pub conflict_editor: Option<ConflictEditorState>,
 }
 
 impl AppState<'_> {
     #[must_use]
     pub fn new(config: KeyConfig) -> Self {
+        let keymap = KeyMap::from_config(&config);
+        let mut problems = config.problems.clone();
+        problems.extend(keymap.problems.iter().cloned());
+        let (themes, theme_problems) =
+            crate::theme::load_themes(crate::theme::themes_dir().as_deref());
+        problems.extend(theme_problems);
+        let row_template = RowTemplate::parse(&config.graph.row).unwrap_or_else(|e| {
+            problems.push(format!("graph.row: {e}"));
+            RowTemplate::default()
+        });
+        let theme = match &config.theme {
+            Some(name) => {
+                let entry = themes.into_iter().find(|entry| entry.name == *name);
+                if entry.is_none() {
+                    problems.push(format!("theme = \"{name}\": no such theme"));
+                }
+                entry
+            }
+            None => None,
+        };
+        let last_error = (!problems.is_empty()).then(|| ErrorState {
+            message: "Some of the configuration was ignored".to_string(),
+            timestamp: chrono::Local::now(),
+            severity: ErrorSeverity::Warning,
+            suggestions: problems,
+        });
+        let (theme_name, theme) = match theme {
+            Some(entry) => (entry.name, entry.theme),
+            None => (
+                crate::theme::PaletteType::CatppuccinMocha.name().to_string(),
+                crate::theme::Theme::default(),
+            ),
+        };
         Self {
-            keymap: Arc::new(KeyMap::from_config(&config)),
-            recent_filters: super::persistence::load_recent_filters(),
+            last_error,
+            theme_name,
+            theme,
+            keymap: Arc::new(keymap),
+            diff_split_min_width: config.diff.split_min_width,
+            row_template,
+            custom_commands: Arc::new(config.commands),
             preset_filters: default_preset_filters(),
             ..Default::default()
         }
     }
 
+    /// What to remember of this run for the next one in the same workspace.
+    pub fn session(&self) -> Session {
+        let selected = self.repo.as_ref().zip(self.log.list_state.selected());
+        Session {
+            revset: self.revset.clone(),
+            recent_filters: self.recent_filters.clone(),
+            diff_ratio: self.diff_ratio,
+            show_diffs: self.show_diffs,
+            focused_panel: self.focused_panel,
+            selected_change_id: selected
+                .and_then(|(repo, idx)| repo.graph.get(idx))
+                .map(|row| row.change_id.clone())
+                // Quit before the graph loaded.
+                .or_else(|| self.restore_change_id.clone()),
+        }
+    }
+
+    pub fn restore_session(&mut self, session: Session) {
+        self.revset = session.revset;
+        self.recent_filters = session.recent_filters;
+        self.diff_ratio = session.diff_ratio.clamp(10, 90);
+        self.show_diffs = session.show_diffs;
+        if session.show_diffs && session.focused_panel == Panel::Diff {
+            self.focused_panel = Panel::Diff;
+            self.mode = AppMode::Diff;
+            self.log.selected_file_index = Some(0);
+        }
+        self.restore_change_id = session.selected_change_id;
+    }
+
+    /// Saves the session to `session_path`, if there is one.
+    pub fn save_session(&self) -> anyhow::Result<()> {
+        match &self.session_path {
+            Some(path) => super::persistence::save_session(path, &self.session()),
+            None => Ok(()),
+        }
+    }
+
     pub fn get_selected_file(&self) -> Option<&crate::domain::models::FileChange> {
         if let (Some(repo), Some(idx)) = (&self.repo, self.log.list_state.selected()) {
             if let Some(row) = repo.graph.get(idx) {
@@ -138,6 +286,18 @@ impl AppState<'_> {
         None
     }
 
+    /// The layout the diff panel actually uses: the preferred one, unless
+    /// the panel is too narrow to split.
+    pub fn diff_layout(&self) -> crate::domain::diff::DiffLayout {
+        use crate::domain::diff::DiffLayout;
+        match self.log.diff_layout {
+            DiffLayout::Split if self.log.diff_width >= self.diff_split_min_width => {
+                DiffLayout::Split
+            }
+            _ => DiffLayout::Unified,
+        }
+    }
+
     pub fn is_selected_file_conflicted(&self) -> bool {
         self.get_selected_file()
             .is_some_and(|f| f.status == crate::domain::models::FileStatus::Conflicted)
@@ -169,13 +329,16 @@ impl Default for AppState<'_> {
             active_tasks: Vec::new(),
             repo: None,
             revset: None,
+            revset_error: None,
             is_loading_more: false,
             has_more: true,
+            graph_layout: GraphLayout::default(),
             log: LogState::default(),
             show_diffs: false,
             header_state: HeaderState::default(),
             spinner: "⠋".to_string(),
             input: None,
+            pending_keys: Vec::new(),
             last_click_time: None,
             last_click_pos: None,
             context_menu: None,
@@ -186,15 +349,31 @@ impl Default for AppState<'_> {
             diff_ratio: 50,
             focused_panel: Panel::Graph,
             keymap: Arc::new(KeyMap::from_config(&KeyConfig::default())),
-            palette_type: crate::theme::PaletteType::CatppuccinMocha,
+            diff_split_min_width: DiffConfig::default().split_min_width,
+            row_template: RowTemplate::default(),
+            custom_commands: Arc::default(),
+            theme_name: crate::theme::PaletteType::CatppuccinMocha.name().to_string(),
             theme: crate::theme::Theme::default(),
+            session_path: None,
+            restore_change_id: None,
             recent_filters: Vec::new(),
             preset_filters: default_preset_filters(),
             selected_filter_index: None,
             is_selecting_presets: false,
             evolog_state: None,
             operation_log_state: None,
+            workspaces_state: None,
+            bookmarks_state: None,
+            bookmark_move: None,
+            push_state: None,
+            remotes_state: None,
             rebase_sources: Vec::new(),
+            rebase_mode: RebaseMode::default(),
+            rebase_location: RebaseLocation::default(),
+            preview_rewrites: false,
+            rewrite_preview: None,
+            hunk_select: None,
+            conflict_editor: None,
         }
     }
 }
@@ -1,14 +1,9 @@
+use crate::theme::{Theme, ThemeEntry};
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ThemeSelectionState {
     pub selected_index: usize,
-    pub themes: Vec<crate::theme::PaletteType>,
-}
-
-impl Default for ThemeSelectionState {
-    fn default() -> Self {
-        Self {
-            selected_index: 0,
-            themes: crate::theme::PaletteType::all().to_vec(),
-        }
-    }
+    pub themes: Vec<ThemeEntry>,
+    /// The theme in use before the selection opened, put back on cancel.
+    pub original: Theme,
 }
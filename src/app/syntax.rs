@@ -0,0 +1,255 @@
+use crate::domain::diff::{Diff, DiffLineKind, Hunk};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+/// Longer lines (minified files, generated data) are left unhighlighted.
+const MAX_HIGHLIGHT_LINE_LEN: usize = 1000;
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// Scope prefixes mapped to highlight classes, most specific first.
+const SCOPE_RULES: &[(&str, SyntaxKind)] = &[
+    ("comment", SyntaxKind::Comment),
+    ("string", SyntaxKind::String),
+    ("constant.character.escape", SyntaxKind::String),
+    ("constant", SyntaxKind::Constant),
+    ("keyword", SyntaxKind::Keyword),
+    ("storage", SyntaxKind::Keyword),
+    ("entity.name.function", SyntaxKind::Function),
+    ("support.function", SyntaxKind::Function),
+    ("variable.function", SyntaxKind::Function),
+    ("entity.name", SyntaxKind::Type),
+    ("support.type", SyntaxKind::Type),
+    ("support.class", SyntaxKind::Type),
+];
+
+/// Theme-independent token class; the theme decides the colour at render
+/// time, so cached highlights survive theme changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxKind {
+    Comment,
+    String,
+    Keyword,
+    Function,
+    Type,
+    Constant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxSpan {
+    /// Byte range within the line's content.
+    pub range: Range<usize>,
+    pub kind: SyntaxKind,
+}
+
+/// Syntax spans for every line of a [`Diff`], indexed by file, hunk and line.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DiffHighlights {
+    files: Vec<Vec<Vec<Vec<SyntaxSpan>>>>,
+}
+
+impl DiffHighlights {
+    #[must_use]
+    pub fn line(&self, file: usize, hunk: usize, line: usize) -> &[SyntaxSpan] {
+        self.files
+            .get(file)
+            .and_then(|hunks| hunks.get(hunk))
+            .and_then(|lines| lines.get(line))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Highlights the code in `diff`, choosing a syntax from each file's
+/// extension. Files with unknown extensions are left plain. This parses
+/// every changed line, so call it off the render thread.
+#[must_use]
+pub fn highlight_diff(diff: &Diff) -> DiffHighlights {
+    let mut classifier = Classifier::default();
+    let files = diff
+        .files
+        .iter()
+        .map(|file| {
+            let syntax = Path::new(&file.path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| SYNTAX_SET.find_syntax_by_extension(ext));
+            match syntax {
+                Some(syntax) => file
+                    .hunks
+                    .iter()
+                    .map(|hunk| highlight_hunk(hunk, syntax, &mut classifier))
+                    .collect(),
+                None => Vec::new(),
+            }
+        })
+        .collect();
+    DiffHighlights { files }
+}
+
+/// Parses the old version (context and removals) and the new version
+/// (context and additions) of a hunk as separate runs, so constructs that
+/// span several lines are followed within each version.
+fn highlight_hunk(
+    hunk: &Hunk,
+    syntax: &SyntaxReference,
+    classifier: &mut Classifier,
+) -> Vec<Vec<SyntaxSpan>> {
+    let mut spans = vec![Vec::new(); hunk.lines.len()];
+    for side in [DiffLineKind::Removed, DiffLineKind::Added] {
+        let mut parse_state = ParseState::new(syntax);
+        let mut stack = ScopeStack::new();
+        for (idx, line) in hunk.lines.iter().enumerate() {
+            if line.kind == side || line.kind == DiffLineKind::Context {
+                spans[idx] =
+                    highlight_line(&line.content, &mut parse_state, &mut stack, classifier);
+            }
+        }
+    }
+    spans
+}
+
+fn highlight_line(
+    content: &str,
+    parse_state: &mut ParseState,
+    stack: &mut ScopeStack,
+    classifier: &mut Classifier,
+) -> Vec<SyntaxSpan> {
+    if content.len() > MAX_HIGHLIGHT_LINE_LEN {
+        return Vec::new();
+    }
+    // The default syntaxes expect lines with their newline.
+    let line = format!("{content}\n");
+    let Ok(ops) = parse_state.parse_line(&line, &SYNTAX_SET) else {
+        return Vec::new();
+    };
+
+    let mut spans: Vec<SyntaxSpan> = Vec::new();
+    let mut push = |spans: &mut Vec<SyntaxSpan>, stack: &ScopeStack, range: Range<usize>| {
+        let Some(kind) = classifier.classify(stack) else {
+            return;
+        };
+        match spans.last_mut() {
+            Some(last) if last.kind == kind && last.range.end == range.start => {
+                last.range.end = range.end;
+            }
+            _ => spans.push(SyntaxSpan { range, kind }),
+        }
+    };
+
+    let mut pos = 0;
+    for (offset, op) in ops {
+        let offset = offset.min(content.len());
+        if offset > pos {
+            push(&mut spans, stack, pos..offset);
+            pos = offset;
+        }
+        if stack.apply(&op).is_err() {
+            return spans;
+        }
+    }
+    if pos < content.len() {
+        push(&mut spans, stack, pos..content.len());
+    }
+    spans
+}
+
+/// Maps scope stacks to highlight classes, memoising per scope.
+#[derive(Default)]
+struct Classifier {
+    cache: HashMap<Scope, Option<SyntaxKind>>,
+}
+
+impl Classifier {
+    /// The innermost scope with a known class wins.
+    fn classify(&mut self, stack: &ScopeStack) -> Option<SyntaxKind> {
+        stack
+            .as_slice()
+            .iter()
+            .rev()
+            .find_map(|scope| self.kind_of(*scope))
+    }
+
+    fn kind_of(&mut self, scope: Scope) -> Option<SyntaxKind> {
+        *self.cache.entry(scope).or_insert_with(|| {
+            let name = scope.build_string();
+            SCOPE_RULES
+                .iter()
+                .find(|(prefix, _)| {
+                    name == *prefix
+                        || name
+                            .strip_prefix(prefix)
+                            .is_some_and(|rest| rest.starts_with('.'))
+                })
+                .map(|(_, kind)| *kind)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::diff::{DiffLine, FileDiff};
+    use crate::domain::models::FileStatus;
+
+    fn file(path: &str, lines: &[(DiffLineKind, &str)]) -> FileDiff {
+        FileDiff {
+            path: path.to_string(),
+            status: FileStatus::Modified,
+            is_binary: false,
+            hunks: vec![Hunk {
+                old_start: 1,
+                old_len: 1,
+                new_start: 1,
+                new_len: 1,
+                lines: lines
+                    .iter()
+                    .map(|(kind, content)| DiffLine {
+                        kind: *kind,
+                        content: content.to_string(),
+                        old_lineno: None,
+                        new_lineno: None,
+                        emphasis: Vec::new(),
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_highlight_by_extension() {
+        let diff = Diff {
+            files: vec![
+                file(
+                    "src/main.rs",
+                    &[
+                        (DiffLineKind::Context, "/* multi-line"),
+                        (DiffLineKind::Removed, "   comment */ let a = 1;"),
+                        (DiffLineKind::Added, "   comment */ let s = \"hi\";"),
+                    ],
+                ),
+                file("notes.unknown-ext", &[(DiffLineKind::Added, "let x = 1;")]),
+            ],
+            ..Default::default()
+        };
+        let highlights = highlight_diff(&diff);
+
+        let kind_at = |line: usize, byte: usize| {
+            highlights
+                .line(0, 0, line)
+                .iter()
+                .find(|span| span.range.contains(&byte))
+                .map(|span| span.kind)
+        };
+        // The comment opened on the context line carries into both sides.
+        assert_eq!(kind_at(1, 3), Some(SyntaxKind::Comment));
+        assert_eq!(kind_at(2, 3), Some(SyntaxKind::Comment));
+        assert_eq!(kind_at(1, 14), Some(SyntaxKind::Keyword));
+        assert_eq!(kind_at(1, 22), Some(SyntaxKind::Constant));
+        assert_eq!(kind_at(2, 23), Some(SyntaxKind::String));
+
+        assert!(highlights.line(1, 0, 0).is_empty());
+    }
+}
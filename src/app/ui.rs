@@ -7,7 +7,9 @@ use crate::components::revision_graph::RevisionGraphPanel;
 use crate::components::welcome::Welcome;
 
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
     Frame,
 };
 
@@ -52,6 +54,7 @@ pub fn draw(f: &mut Frame, app_state: &mut AppState) {
     if app_state.mode == AppMode::NoRepo {
         let welcome = Welcome { app_state, theme };
         f.render_widget(welcome, f.area());
+        fill_background(f.buffer_mut(), app_state.theme.background);
         return;
     }
 
@@ -66,28 +69,62 @@ pub fn draw(f: &mut Frame, app_state: &mut AppState) {
     f.render_widget(header, layout.main[0]);
 
     // --- Left: Revision Graph Panel ---
-    let panel = RevisionGraphPanel {
-        repo: app_state.repo.as_ref(),
-        theme,
-        show_diffs: app_state.show_diffs,
-        selected_file_index: app_state.log.selected_file_index,
-        spinner: &app_state.spinner,
-        focused_panel: app_state.focused_panel,
-        mode: app_state.mode,
-        revset: app_state.revset.as_deref(),
-        selected_ids: &app_state.log.selected_ids,
-    };
-    f.render_stateful_widget(panel, layout.body[0], &mut app_state.log.list_state);
+    if let (AppMode::RewritePreview, Some(preview)) =
+        (app_state.mode, app_state.rewrite_preview.as_mut())
+    {
+        // The graph as it would be after the rewrite, with its own selection.
+        let panel = RevisionGraphPanel {
+            repo: Some(&preview.preview.status),
+            theme,
+            template: &app_state.row_template,
+            show_diffs: false,
+            selected_file_index: None,
+            spinner: &app_state.spinner,
+            focused_panel: app_state.focused_panel,
+            mode: app_state.mode,
+            revset: app_state.revset.as_deref(),
+            selected_ids: &app_state.log.selected_ids,
+            picked_files: &app_state.log.picked_files,
+            is_loading_more: false,
+            new_conflicts: Some(&preview.preview.new_conflicts),
+        };
+        f.render_stateful_widget(panel, layout.body[0], &mut preview.list_state);
+    } else {
+        let panel = RevisionGraphPanel {
+            repo: app_state.repo.as_ref(),
+            theme,
+            template: &app_state.row_template,
+            show_diffs: app_state.show_diffs,
+            selected_file_index: app_state.log.selected_file_index,
+            spinner: &app_state.spinner,
+            focused_panel: app_state.focused_panel,
+            mode: app_state.mode,
+            revset: app_state.revset.as_deref(),
+            selected_ids: &app_state.log.selected_ids,
+            picked_files: &app_state.log.picked_files,
+            is_loading_more: app_state.is_loading_more,
+            new_conflicts: None,
+        };
+        f.render_stateful_widget(panel, layout.body[0], &mut app_state.log.list_state);
+    }
 
     // --- Right: Diff View Panel ---
     if app_state.show_diffs {
+        app_state.log.diff_width = layout.body[1].width;
+        // Less the top and bottom borders.
+        app_state.log.diff_height = layout.body[1].height.saturating_sub(2);
+        let theme = &app_state.theme;
         let panel = DiffViewPanel {
-            diff_content: app_state.log.current_diff.as_deref(),
+            diff: app_state.log.current_diff.as_ref(),
+            highlights: app_state.log.current_highlights.as_ref(),
+            error: app_state.log.diff_error.as_deref(),
+            layout: app_state.diff_layout(),
             scroll_offset: app_state.log.diff_scroll,
             theme,
             hunk_highlight_time: app_state.hunk_highlight_time,
             focused_panel: app_state.focused_panel,
             mode: app_state.mode,
+            selection: app_state.hunk_select.as_ref(),
         };
         f.render_widget(panel, layout.body[1]);
     }
@@ -103,4 +140,21 @@ pub fn draw(f: &mut Frame, app_state: &mut AppState) {
 
     // --- Modals & Overlays ---
     f.render_widget(ModalManager { theme, app_state }, f.area());
+    fill_background(f.buffer_mut(), app_state.theme.background);
+}
+
+/// Gives the cells left to the terminal's colors the theme's, so a light
+/// theme stays light on a dark terminal.
+fn fill_background(buf: &mut Buffer, background: Style) {
+    if background == Style::default() {
+        return;
+    }
+    for cell in &mut buf.content {
+        if let Some(bg) = background.bg.filter(|_| cell.bg == Color::Reset) {
+            cell.bg = bg;
+        }
+        if let Some(fg) = background.fg.filter(|_| cell.fg == Color::Reset) {
+            cell.fg = fg;
+        }
+    }
 }
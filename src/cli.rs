@@ -0,0 +1,239 @@
+use crate::domain::{
+    error::VcsError,
+    models::{FileStatus, GraphRow, RepoStatus},
+    vcs::VcsFacade,
+};
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A terminal UI for Jujutsu. Run without a subcommand to open it; the
+/// subcommands print what it shows for use in scripts.
+#[derive(Debug, Parser)]
+#[command(name = "judo", version)]
+pub struct Cli {
+    /// Repository to use instead of the one in the current directory
+    #[arg(short = 'R', long, global = true)]
+    pub repository: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Print revisions from the graph, newest first
+    Log {
+        /// Revisions to print, instead of the whole graph
+        #[arg(short, long)]
+        revset: Option<String>,
+        /// How many revisions to print at most
+        #[arg(short = 'n', long, default_value_t = 100)]
+        limit: usize,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Print the changes in a revision
+    Diff {
+        /// Revision to show
+        #[arg(default_value = "@")]
+        revision: String,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Print the working-copy revision and its changed files
+    Status {
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// Runs `command` against `adapter`, writing its output to `out`.
+pub async fn run(command: CliCommand, adapter: &dyn VcsFacade, out: &mut dyn Write) -> Result<()> {
+    // Like `jj`, record the files on disk first so the working copy isn't
+    // printed as it was at the last snapshot.
+    adapter.snapshot().await?;
+    match command {
+        CliCommand::Log {
+            revset,
+            limit,
+            format,
+        } => {
+            let status = adapter.get_operation_log(0, limit, revset).await?;
+            match format {
+                Format::Json => write_json(out, &status),
+                Format::Text => {
+                    for row in &status.graph {
+                        write_row(out, row)?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        CliCommand::Diff { revision, format } => {
+            let row = resolve_single(adapter, &revision).await?;
+            let diff = adapter.get_commit_diff(&row.commit_id).await?;
+            match format {
+                Format::Json => write_json(out, &diff),
+                Format::Text => Ok(write!(out, "{diff}")?),
+            }
+        }
+        CliCommand::Status { format } => {
+            let status = adapter.get_operation_log(0, 1, Some("@".into())).await?;
+            match format {
+                Format::Json => write_json(out, &status),
+                Format::Text => write_status(out, &status),
+            }
+        }
+    }
+}
+
+/// The one row `revision` resolves to.
+async fn resolve_single(adapter: &dyn VcsFacade, revision: &str) -> Result<GraphRow> {
+    let status = adapter
+        .get_operation_log(0, 1, Some(revision.to_string()))
+        .await?;
+    if status.has_more {
+        return Err(VcsError::AmbiguousRevision {
+            revset: revision.to_string(),
+        }
+        .into());
+    }
+    status
+        .graph
+        .into_iter()
+        .next()
+        .ok_or_else(|| VcsError::RevisionNotFound(revision.to_string()).into())
+}
+
+fn write_json(out: &mut dyn Write, value: &impl Serialize) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// A row laid out like the revision graph shows it, without the graph.
+fn write_row(out: &mut dyn Write, row: &GraphRow) -> Result<()> {
+    let marker = if row.is_working_copy { '@' } else { ' ' };
+    let mut line = format!(
+        "{marker} {} {} {}",
+        row.change_id_short, row.author, row.timestamp
+    );
+    for name in row.bookmarks.iter().chain(&row.tags) {
+        line.push(' ');
+        line.push_str(name);
+    }
+    line.push(' ');
+    line.push_str(&row.commit_id_short);
+    if row.has_conflict {
+        line.push_str(" (conflict)");
+    }
+    writeln!(out, "{line}")?;
+    writeln!(out, "  {}", summary(row))?;
+    Ok(())
+}
+
+fn write_status(out: &mut dyn Write, status: &RepoStatus) -> Result<()> {
+    let Some(row) = status.graph.first() else {
+        return Err(VcsError::RevisionNotFound("@".to_string()).into());
+    };
+    if row.changed_files.is_empty() {
+        writeln!(out, "The working copy has no changes.")?;
+    } else {
+        writeln!(out, "Working copy changes:")?;
+        for file in &row.changed_files {
+            let status = match file.status {
+                FileStatus::Added => 'A',
+                FileStatus::Modified => 'M',
+                FileStatus::Deleted => 'D',
+                FileStatus::Conflicted => 'C',
+            };
+            writeln!(out, "{status} {}", file.path)?;
+        }
+    }
+    writeln!(
+        out,
+        "Working copy (@) : {} {} {}",
+        row.change_id_short,
+        row.commit_id_short,
+        summary(row)
+    )?;
+    Ok(())
+}
+
+fn summary(row: &GraphRow) -> &str {
+    match row.description.lines().next() {
+        Some(line) if !line.is_empty() => line,
+        _ => "(no description set)",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{CommitId, FileChange};
+    use crate::domain::vcs::MockVcsFacade;
+
+    #[tokio::test]
+    async fn test_log_and_status_output() {
+        let mut adapter = MockVcsFacade::new();
+        adapter
+            .expect_snapshot()
+            .times(2)
+            .returning(|| Ok("snapshot".to_string()));
+        adapter
+            .expect_get_operation_log()
+            .returning(|_, _, revset| {
+                Ok(RepoStatus {
+                    repo_name: "repo".to_string(),
+                    operation_id: "op".to_string(),
+                    workspace_id: "default".to_string(),
+                    working_copy_id: CommitId("abc".to_string()),
+                    graph: vec![GraphRow {
+                        commit_id: CommitId("abc".to_string()),
+                        commit_id_short: "abc".to_string(),
+                        change_id_short: "kkk".to_string(),
+                        description: revset.unwrap_or_default(),
+                        is_working_copy: true,
+                        changed_files: vec![FileChange {
+                            path: "a.rs".to_string(),
+                            status: FileStatus::Added,
+                        }],
+                        ..Default::default()
+                    }],
+                    has_more: false,
+                })
+            });
+
+        let mut out = Vec::new();
+        let command = CliCommand::Log {
+            revset: Some("mine()".to_string()),
+            limit: 10,
+            format: Format::Json,
+        };
+        run(command, &adapter, &mut out).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["working_copy_id"], "abc");
+        assert_eq!(json["graph"][0]["description"], "mine()");
+        assert_eq!(json["graph"][0]["changed_files"][0]["status"], "added");
+        assert!(json["graph"][0].get("visual").is_none());
+
+        let mut out = Vec::new();
+        let command = CliCommand::Status {
+            format: Format::Text,
+        };
+        run(command, &adapter, &mut out).await.unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Working copy changes:\nA a.rs\nWorking copy (@) : kkk abc @\n"
+        );
+    }
+}
@@ -1,25 +1,48 @@
-use crate::app::state::{AppMode, Panel};
+use crate::app::state::{AppMode, HunkSelectPurpose, HunkSelectState, Panel};
+use crate::app::syntax::{DiffHighlights, SyntaxKind, SyntaxSpan};
+use crate::domain::diff::{Diff, DiffLayout, DiffLine, DiffLineKind, DiffRow, SelectionState};
 use crate::theme::{glyphs, Theme};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::Style,
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap},
 };
 use std::time::Instant;
 
 pub struct DiffView<'a> {
-    pub diff_content: Option<&'a str>,
+    pub diff: Option<&'a Diff>,
+    pub highlights: Option<&'a DiffHighlights>,
+    pub error: Option<&'a str>,
+    pub layout: DiffLayout,
     pub scroll_offset: u16,
     pub theme: &'a Theme,
     pub hunk_highlight_time: Option<Instant>,
+    /// While picking changes, the selection to mark; its diff is shown
+    /// instead of `diff`, in the unified layout.
+    pub selection: Option<&'a HunkSelectState>,
 }
 
+/// Width of the pick marker shown in front of each row while selecting.
+const MARKER_WIDTH: usize = 4;
+
 impl Widget for DiffView<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let content = match self.diff_content {
-            Some(c) if !c.is_empty() => c,
-            _ => {
+        if let Some(error) = self.error {
+            let lines: Vec<Line> = error
+                .lines()
+                .map(|line| Line::from(Span::styled(line.to_string(), self.theme.diff_remove)))
+                .collect();
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .render(area, buf);
+            return;
+        }
+
+        let diff = match self.selection.map(|sel| &sel.diff).or(self.diff) {
+            Some(d) => d,
+            None => {
                 let text = vec![
                     Line::from(""),
                     Line::from(Span::styled(
@@ -49,47 +72,77 @@ impl Widget for DiffView<'_> {
             .hunk_highlight_time
             .is_some_and(|t| t.elapsed().as_millis() < 200);
 
-        let width = area.width as usize;
+        let (layout, width) = match self.selection {
+            Some(_) => (
+                DiffLayout::Unified,
+                (area.width as usize).saturating_sub(MARKER_WIDTH),
+            ),
+            None => (self.layout, area.width as usize),
+        };
+        let gutter = line_number_width(diff);
 
         let mut lines = Vec::new();
-        for (i, line) in content.lines().enumerate() {
-            let mut style = if line.starts_with("Bookmarks:") {
-                self.theme.bookmark
-            } else if line.starts_with("Commit ID:")
-                || line.starts_with("Change ID:")
-                || line.starts_with("Author:")
-                || line.starts_with("Author   :")
-                || line.starts_with("Committer:")
-                || line.starts_with("Timestamp:")
-                || line.starts_with("Date:")
-                || line.starts_with("File:")
-                || line.starts_with("Status:")
+        for (i, row) in diff.rows(layout).into_iter().enumerate() {
+            let highlight = (is_highlighting && i == self.scroll_offset as usize)
+                || self.selection.is_some_and(|sel| sel.cursor == i);
+            let marker = self.selection.map(|sel| self.marker(sel, row));
+            if let DiffRow::SplitLine {
+                file,
+                hunk,
+                old,
+                new,
+            } = row
             {
-                self.theme.diff_header
-            } else if line.starts_with('+') {
-                // Full-line green background tint for additions
-                self.theme.diff_add_bg
-            } else if line.starts_with('-') {
-                // Full-line red background tint for removals
-                self.theme.diff_remove_bg
-            } else if line.starts_with("@@") {
-                self.theme.diff_hunk
-            } else {
-                self.theme.diff_context
+                let hunk_lines = &diff.files[file].hunks[hunk].lines;
+                let old = old.map(|idx| (&hunk_lines[idx], self.syntax(file, hunk, idx)));
+                let new = new.map(|idx| (&hunk_lines[idx], self.syntax(file, hunk, idx)));
+                lines.push(self.split_line(old, new, width, gutter, highlight));
+                continue;
+            }
+            if let DiffRow::Line { file, hunk, line } = row {
+                let syntax = self.syntax(file, hunk, line);
+                let line = &diff.files[file].hunks[hunk].lines[line];
+                let (base, _) = self.line_styles(line.kind);
+                let mut segments = vec![(line.kind.prefix().to_string(), base)];
+                segments.extend(self.content_segments(line, syntax));
+                // Pad to the full width so the background tint fills the row.
+                let mut spans = fit_segments(segments, width, base, false);
+                spans.splice(0..0, marker);
+                lines.push(Line::from(reverse_if(spans, highlight)));
+                continue;
+            }
+
+            let mut style = match row {
+                DiffRow::Bookmarks => self.theme.bookmark,
+                DiffRow::Tags => self.theme.tag,
+                DiffRow::CommitId
+                | DiffRow::ChangeId
+                | DiffRow::Author
+                | DiffRow::FileHeader(_)
+                | DiffRow::FileStatus(_) => self.theme.diff_header,
+                DiffRow::HunkHeader { .. } => self.theme.diff_hunk,
+                DiffRow::Description(_)
+                | DiffRow::Blank
+                | DiffRow::Binary(_)
+                | DiffRow::Line { .. }
+                | DiffRow::SplitLine { .. } => self.theme.diff_context,
             };
 
-            if is_highlighting && i == self.scroll_offset as usize {
+            if highlight {
                 style = style.add_modifier(ratatui::style::Modifier::REVERSED);
             }
 
             // Pad the line to the full terminal width so the background tint fills the row.
-            let padded = if line.len() < width {
-                format!("{line:<width$}")
+            let text = diff.row_text(row);
+            let padded = if text.len() < width {
+                format!("{text:<width$}")
             } else {
-                line.to_string()
+                text
             };
 
-            lines.push(Line::from(Span::styled(padded, style)));
+            let mut spans: Vec<Span> = marker.into_iter().collect();
+            spans.push(Span::styled(padded, style));
+            lines.push(Line::from(spans));
         }
 
         Paragraph::new(lines)
@@ -99,15 +152,186 @@ impl Widget for DiffView<'_> {
     }
 }
 
+impl DiffView<'_> {
+    /// Whether the file, hunk or line shown by `row` is picked.
+    fn marker(&self, hunk_select: &HunkSelectState, row: DiffRow) -> Span<'static> {
+        let (diff, selection) = (&hunk_select.diff, &hunk_select.selection);
+        let state = match row {
+            DiffRow::FileHeader(file) => Some(selection.file_state(diff, file)),
+            DiffRow::HunkHeader { file, hunk } => Some(selection.hunk_state(diff, file, hunk)),
+            DiffRow::Line { file, hunk, line }
+                if diff.files[file].hunks[hunk].lines[line].kind != DiffLineKind::Context =>
+            {
+                Some(if selection.is_line_selected(file, hunk, line) {
+                    SelectionState::All
+                } else {
+                    SelectionState::None
+                })
+            }
+            _ => None,
+        };
+        match state {
+            Some(SelectionState::All) => Span::styled("[x] ", self.theme.diff_add),
+            Some(SelectionState::Partial) => Span::styled("[~] ", self.theme.diff_modify),
+            Some(SelectionState::None) => Span::styled("[ ] ", self.theme.timestamp),
+            None => Span::raw(" ".repeat(MARKER_WIDTH)),
+        }
+    }
+
+    /// Full-line tint and changed-word emphasis for a line.
+    fn line_styles(&self, kind: DiffLineKind) -> (Style, Style) {
+        match kind {
+            DiffLineKind::Added => (self.theme.diff_add_bg, self.theme.diff_add_emphasis),
+            DiffLineKind::Removed => (self.theme.diff_remove_bg, self.theme.diff_remove_emphasis),
+            DiffLineKind::Context => (self.theme.diff_context, self.theme.diff_context),
+        }
+    }
+
+    fn syntax(&self, file: usize, hunk: usize, line: usize) -> &[SyntaxSpan] {
+        self.highlights
+            .map_or(&[], |highlights| highlights.line(file, hunk, line))
+    }
+
+    /// Splits a line's content into styled pieces: the line tint or the
+    /// changed-word emphasis underneath, with syntax colours on top.
+    fn content_segments(&self, line: &DiffLine, syntax: &[SyntaxSpan]) -> Vec<(String, Style)> {
+        let (base, emphasis) = self.line_styles(line.kind);
+        let mut segments = Vec::new();
+        let mut offset = 0;
+        for (emphasized, text) in line.segments() {
+            let style = if emphasized { emphasis } else { base };
+            let range = offset..offset + text.len();
+            let mut pos = range.start;
+            for span in syntax
+                .iter()
+                .filter(|span| span.range.start < range.end && span.range.end > range.start)
+            {
+                let start = span.range.start.max(range.start);
+                let end = span.range.end.min(range.end);
+                if start > pos {
+                    segments.push((line.content[pos..start].to_string(), style));
+                }
+                let syntax_style = self.syntax_style(span.kind);
+                segments.push((
+                    line.content[start..end].to_string(),
+                    style.patch(syntax_style),
+                ));
+                pos = end;
+            }
+            if pos < range.end {
+                segments.push((line.content[pos..range.end].to_string(), style));
+            }
+            offset = range.end;
+        }
+        segments
+    }
+
+    fn syntax_style(&self, kind: SyntaxKind) -> Style {
+        match kind {
+            SyntaxKind::Comment => self.theme.syntax_comment,
+            SyntaxKind::String => self.theme.syntax_string,
+            SyntaxKind::Keyword => self.theme.syntax_keyword,
+            SyntaxKind::Function => self.theme.syntax_function,
+            SyntaxKind::Type => self.theme.syntax_type,
+            SyntaxKind::Constant => self.theme.syntax_constant,
+        }
+    }
+
+    /// Renders one row of the side-by-side layout: the old line on the left
+    /// and the new line on the right, each with its own line number.
+    fn split_line(
+        &self,
+        old: Option<(&DiffLine, &[SyntaxSpan])>,
+        new: Option<(&DiffLine, &[SyntaxSpan])>,
+        width: usize,
+        gutter: usize,
+        highlight: bool,
+    ) -> Line<'static> {
+        let left_width = width.saturating_sub(1) / 2;
+        let right_width = width.saturating_sub(left_width + 1);
+        let side =
+            |line: Option<(&DiffLine, &[SyntaxSpan])>, lineno: Option<usize>, side_width: usize| {
+                let Some((line, syntax)) = line else {
+                    return fit_segments(Vec::new(), side_width, self.theme.diff_context, true);
+                };
+                let (base, _) = self.line_styles(line.kind);
+                let number = lineno.map(|n| n.to_string()).unwrap_or_default();
+                let mut segments = vec![(format!("{number:>gutter$} "), base)];
+                segments.extend(self.content_segments(line, syntax));
+                fit_segments(segments, side_width, base, true)
+            };
+        let mut spans = side(old, old.and_then(|(l, _)| l.old_lineno), left_width);
+        spans.push(Span::styled("│", self.theme.border));
+        spans.extend(side(new, new.and_then(|(l, _)| l.new_lineno), right_width));
+        Line::from(reverse_if(spans, highlight))
+    }
+}
+
+/// Digits needed for the largest line number in the diff.
+fn line_number_width(diff: &Diff) -> usize {
+    let max_line = diff
+        .files
+        .iter()
+        .flat_map(|f| &f.hunks)
+        .map(|h| (h.old_start + h.old_len).max(h.new_start + h.new_len))
+        .max()
+        .unwrap_or(0);
+    max_line.to_string().len().max(3)
+}
+
+/// Turns styled segments into spans padded to `width` characters with
+/// `pad_style`, and cut off at `width` if `truncate` is set.
+fn fit_segments(
+    segments: Vec<(String, Style)>,
+    width: usize,
+    pad_style: Style,
+    truncate: bool,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(segments.len() + 1);
+    let mut used = 0;
+    for (text, style) in segments {
+        let len = text.chars().count();
+        if truncate && used + len > width {
+            let remaining: String = text.chars().take(width - used).collect();
+            used = width;
+            spans.push(Span::styled(remaining, style));
+            break;
+        }
+        used += len;
+        spans.push(Span::styled(text, style));
+    }
+    if used < width {
+        spans.push(Span::styled(" ".repeat(width - used), pad_style));
+    }
+    spans
+}
+
+fn reverse_if(spans: Vec<Span<'static>>, highlight: bool) -> Vec<Span<'static>> {
+    if !highlight {
+        return spans;
+    }
+    spans
+        .into_iter()
+        .map(|span| {
+            let style = span.style.add_modifier(ratatui::style::Modifier::REVERSED);
+            span.style(style)
+        })
+        .collect()
+}
+
 /// Panel wrapper for the diff view that owns the Block, borders, and focus styling.
 /// Used by `ui.rs` in place of the previously inlined logic.
 pub struct DiffViewPanel<'a> {
-    pub diff_content: Option<&'a str>,
+    pub diff: Option<&'a Diff>,
+    pub highlights: Option<&'a DiffHighlights>,
+    pub error: Option<&'a str>,
+    pub layout: DiffLayout,
     pub scroll_offset: u16,
     pub theme: &'a Theme,
     pub hunk_highlight_time: Option<Instant>,
     pub focused_panel: Panel,
     pub mode: AppMode,
+    pub selection: Option<&'a HunkSelectState>,
 }
 
 impl Widget for DiffViewPanel<'_> {
@@ -116,11 +340,11 @@ impl Widget for DiffViewPanel<'_> {
             return;
         }
 
-        let is_diff_focused = self.focused_panel == Panel::Diff;
         let is_body_active = self.mode == AppMode::Normal || self.mode == AppMode::Diff;
+        let is_diff_focused = (self.focused_panel == Panel::Diff && is_body_active)
+            || self.mode == AppMode::HunkSelect;
 
-        let (border_style, title_style, borders, border_type) = if is_diff_focused && is_body_active
-        {
+        let (border_style, title_style, borders, border_type) = if is_diff_focused {
             (
                 self.theme.border_focus,
                 self.theme.header_active,
@@ -143,29 +367,55 @@ impl Widget for DiffViewPanel<'_> {
             )
         };
 
-        let title_spans = if is_diff_focused && is_body_active {
+        let title = match self.selection.map(|sel| sel.purpose) {
+            Some(HunkSelectPurpose::Split) => format!("{} PICK CHANGES TO SPLIT OFF", glyphs::DIFF),
+            Some(HunkSelectPurpose::Squash) => format!("{} PICK CHANGES TO MOVE", glyphs::DIFF),
+            None => format!("{} DIFF VIEW", glyphs::DIFF),
+        };
+        let title_spans = if is_diff_focused {
             vec![
                 Span::styled(format!(" {} ", glyphs::FOCUS), self.theme.border_focus),
-                Span::styled(format!("{} DIFF VIEW", glyphs::DIFF), title_style),
+                Span::styled(title, title_style),
                 Span::raw(" "),
             ]
         } else {
             vec![
                 Span::raw(" "),
-                Span::styled(format!("{} DIFF VIEW", glyphs::DIFF), title_style),
+                Span::styled(title, title_style),
                 Span::raw(" "),
             ]
         };
 
-        let block = Block::default()
-            .title(Line::from(title_spans))
-            .title_bottom(Line::from(vec![
+        let hints = if self.selection.is_some() {
+            vec![
+                Span::raw(" "),
+                Span::styled("Space", self.theme.footer_segment_key),
+                Span::raw(": pick "),
+                Span::styled("a", self.theme.footer_segment_key),
+                Span::raw(": all "),
+                Span::styled("Enter", self.theme.footer_segment_key),
+                Span::raw(": confirm "),
+                Span::styled("Esc", self.theme.footer_segment_key),
+                Span::raw(": cancel "),
+            ]
+        } else {
+            vec![
                 Span::raw(" "),
                 Span::styled("PgUp/PgDn", self.theme.footer_segment_key),
                 Span::raw(": scroll "),
                 Span::styled("[/]", self.theme.footer_segment_key),
                 Span::raw(": hunks "),
-            ]))
+                Span::styled("|", self.theme.footer_segment_key),
+                Span::raw(match self.layout {
+                    DiffLayout::Unified => ": split ",
+                    DiffLayout::Split => ": unified ",
+                }),
+            ]
+        };
+
+        let block = Block::default()
+            .title(Line::from(title_spans))
+            .title_bottom(Line::from(hints))
             .borders(borders)
             .border_type(border_type)
             .border_style(border_style);
@@ -173,10 +423,14 @@ impl Widget for DiffViewPanel<'_> {
         let inner = block.inner(area);
 
         let diff_view = DiffView {
-            diff_content: self.diff_content,
+            diff: self.diff,
+            highlights: self.highlights,
+            error: self.error,
+            layout: self.layout,
             scroll_offset: self.scroll_offset,
             theme: self.theme,
             hunk_highlight_time: self.hunk_highlight_time,
+            selection: self.selection,
         };
         Widget::render(diff_view, inner, buf);
         block.render(area, buf);
@@ -1,5 +1,6 @@
 use super::types::{FooterGroup, FooterItem};
-use crate::app::state::{AppMode, AppState};
+use crate::app::state::{AppMode, AppState, HunkSelectPurpose};
+use crate::domain::diff::DiffLayout;
 
 pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
     if state.last_error.is_some() {
@@ -63,6 +64,11 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
                             desc: "hunk",
                             highlighted: false,
                         },
+                        FooterItem {
+                            key: "|",
+                            desc: "split",
+                            highlighted: state.log.diff_layout == DiffLayout::Split,
+                        },
                     ],
                 });
             }
@@ -131,11 +137,18 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
 
             groups.push(FooterGroup {
                 name: "APP",
-                items: vec![FooterItem {
-                    key: "q",
-                    desc: "quit",
-                    highlighted: false,
-                }],
+                items: vec![
+                    FooterItem {
+                        key: "P",
+                        desc: "prvw",
+                        highlighted: state.preview_rewrites,
+                    },
+                    FooterItem {
+                        key: "q",
+                        desc: "quit",
+                        highlighted: false,
+                    },
+                ],
             });
             groups
         }
@@ -177,6 +190,16 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
         AppMode::Input
         | AppMode::BookmarkInput
         | AppMode::CommitInput
+        | AppMode::SplitInput
+        | AppMode::WorkspaceInput
+        | AppMode::BookmarkRename
+        | AppMode::BookmarkMove
+        | AppMode::RemoteAdd
+        | AppMode::RemoteRename
+        | AppMode::RemoteUrl
+        | AppMode::RemoteFetch
+        | AppMode::TagInput
+        | AppMode::TagMove
         | AppMode::FilterInput
         | AppMode::RebaseInput => vec![FooterGroup {
             name: "INPUT",
@@ -214,18 +237,87 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
             ],
         }],
         AppMode::SquashSelect => vec![FooterGroup {
-            name: "SQUASH",
+            name: "MOVE",
             items: vec![
                 FooterItem {
                     key: "j/k",
                     desc: "select",
                     highlighted: false,
                 },
+                FooterItem {
+                    key: "ENTER",
+                    desc: "move here",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "Esc",
+                    desc: "cancel",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::HunkSelect => vec![FooterGroup {
+            name: match state.hunk_select.as_ref().map(|h| h.purpose) {
+                Some(HunkSelectPurpose::Split) | None => "SPLIT",
+                Some(HunkSelectPurpose::Squash) => "MOVE",
+            },
+            items: vec![
+                FooterItem {
+                    key: "j/k",
+                    desc: "move",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "Space",
+                    desc: "pick",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "a",
+                    desc: "all",
+                    highlighted: false,
+                },
                 FooterItem {
                     key: "ENTER",
                     desc: "confirm",
+                    highlighted: state
+                        .hunk_select
+                        .as_ref()
+                        .is_some_and(|h| !h.selection.is_empty()),
+                },
+                FooterItem {
+                    key: "Esc",
+                    desc: "cancel",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::ConflictEditor => vec![FooterGroup {
+            name: "RESOLVE",
+            items: vec![
+                FooterItem {
+                    key: "j/k",
+                    desc: "region",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "o/t/b/a",
+                    desc: "ours/theirs/base/both",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "e",
+                    desc: "edit",
                     highlighted: false,
                 },
+                FooterItem {
+                    key: "ENTER",
+                    desc: "save",
+                    highlighted: state
+                        .conflict_editor
+                        .as_ref()
+                        .is_some_and(|c| c.unresolved_count() == 0),
+                },
                 FooterItem {
                     key: "Esc",
                     desc: "cancel",
@@ -233,6 +325,14 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
                 },
             ],
         }],
+        AppMode::ConflictInput => vec![FooterGroup {
+            name: "EDIT",
+            items: vec![FooterItem {
+                key: "Esc",
+                desc: "done",
+                highlighted: false,
+            }],
+        }],
         AppMode::RebaseSelect => vec![FooterGroup {
             name: "REBASE",
             items: vec![
@@ -241,6 +341,16 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
                     desc: "select target",
                     highlighted: false,
                 },
+                FooterItem {
+                    key: "r/s/b",
+                    desc: "revisions/descendants/branch",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "d/A/B",
+                    desc: "onto/after/before",
+                    highlighted: false,
+                },
                 FooterItem {
                     key: "ENTER",
                     desc: "confirm",
@@ -330,7 +440,47 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
             items: vec![
                 FooterItem {
                     key: "j/k",
-                    desc: "scroll",
+                    desc: "select",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "r",
+                    desc: "restore to",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "R",
+                    desc: "revert",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "q/Esc",
+                    desc: "close",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::Workspaces => vec![FooterGroup {
+            name: "WORKSPACES",
+            items: vec![
+                FooterItem {
+                    key: "j/k",
+                    desc: "select",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "a",
+                    desc: "add",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "d",
+                    desc: "forget",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "u",
+                    desc: "update stale",
                     highlighted: false,
                 },
                 FooterItem {
@@ -340,6 +490,166 @@ pub fn get_groups(state: &AppState) -> Vec<FooterGroup> {
                 },
             ],
         }],
+        AppMode::Bookmarks => vec![FooterGroup {
+            name: "BOOKMARKS",
+            items: vec![
+                FooterItem {
+                    key: "j/k",
+                    desc: "select",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "ENTER",
+                    desc: "jump to",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "r",
+                    desc: "rename",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "m",
+                    desc: "move",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "t",
+                    desc: "track/untrack",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "f",
+                    desc: "forget",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "d",
+                    desc: "delete",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "q/Esc",
+                    desc: "close",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::ConfirmMove => vec![FooterGroup {
+            name: "CONFIRM",
+            items: vec![
+                FooterItem {
+                    key: "y/ENTER",
+                    desc: "move anyway",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "n/Esc",
+                    desc: "cancel",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::Remotes => vec![FooterGroup {
+            name: "REMOTES",
+            items: vec![
+                FooterItem {
+                    key: "j/k",
+                    desc: "select",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "f/ENTER",
+                    desc: "fetch",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "F",
+                    desc: "fetch all",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "b",
+                    desc: "fetch branches",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "a",
+                    desc: "add",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "r",
+                    desc: "rename",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "u",
+                    desc: "set url",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "d",
+                    desc: "remove",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "Esc",
+                    desc: "close",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::RewritePreview => vec![FooterGroup {
+            name: "PREVIEW",
+            items: vec![
+                FooterItem {
+                    key: "j/k",
+                    desc: "move",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "ENTER",
+                    desc: "apply",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "Esc",
+                    desc: "cancel",
+                    highlighted: false,
+                },
+            ],
+        }],
+        AppMode::Push => vec![FooterGroup {
+            name: "PUSH",
+            items: vec![
+                FooterItem {
+                    key: "TAB",
+                    desc: "remote",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "b/c/a/t",
+                    desc: "bookmarks/change/all/tracked",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "SPACE",
+                    desc: "pick",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "ENTER",
+                    desc: "push",
+                    highlighted: false,
+                },
+                FooterItem {
+                    key: "Esc",
+                    desc: "close",
+                    highlighted: false,
+                },
+            ],
+        }],
         AppMode::NoRepo => vec![
             FooterGroup {
                 name: "INIT",
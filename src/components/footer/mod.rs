@@ -32,6 +32,9 @@ impl Widget for Footer<'_> {
         // Status segment
         let status_span = if let Some(err) = &state.last_error {
             Span::styled(format!("  ERROR: {}  ", err.message), theme.status_error)
+        } else if !state.pending_keys.is_empty() {
+            let keys = crate::app::keymap::format_keys(&state.pending_keys);
+            Span::styled(format!("  {keys} …  "), theme.status_info)
         } else if let Some(msg) = &state.status_message {
             Span::styled(format!("  {msg}  "), theme.status_info)
         } else {
@@ -0,0 +1,141 @@
+use crate::app::state::BookmarksState;
+use crate::domain::bookmark::Bookmark;
+use crate::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget,
+    },
+};
+
+use super::helpers::{centered_rect, draw_drop_shadow};
+
+pub struct BookmarksModal<'a> {
+    pub theme: &'a Theme,
+    pub state: &'a BookmarksState,
+}
+
+impl Widget for BookmarksModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_area = centered_rect(80, 70, area);
+        if modal_area.width == 0 || modal_area.height == 0 {
+            return;
+        }
+
+        draw_drop_shadow(buf, modal_area, area);
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled(" BOOKMARKS ", self.theme.header_active),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.border_focus);
+
+        let inner_area = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Bookmarks
+                Constraint::Length(1), // Hints
+            ])
+            .split(inner_area);
+
+        if self.state.bookmarks.is_empty() {
+            Paragraph::new(Span::styled("No bookmarks", self.theme.timestamp)).render(rows[0], buf);
+        } else {
+            let items: Vec<ListItem> = self
+                .state
+                .bookmarks
+                .iter()
+                .enumerate()
+                .map(|(idx, bookmark)| {
+                    // Remote bookmarks sit under the local one they belong to.
+                    let under_local = idx > 0
+                        && bookmark.remote.is_some()
+                        && self.state.bookmarks[idx - 1].name == bookmark.name;
+                    self.bookmark_item(bookmark, under_local)
+                })
+                .collect();
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.state.selected));
+            let list = List::new(items).highlight_style(self.theme.list_selected);
+            StatefulWidget::render(list, rows[0], buf, &mut list_state);
+        }
+
+        let hint = Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Enter", self.theme.footer_segment_key),
+            Span::raw(" jump to | "),
+            Span::styled("r", self.theme.footer_segment_key),
+            Span::raw(" rename | "),
+            Span::styled("m", self.theme.footer_segment_key),
+            Span::raw(" move | "),
+            Span::styled("t", self.theme.footer_segment_key),
+            Span::raw(" track/untrack | "),
+            Span::styled("f", self.theme.footer_segment_key),
+            Span::raw(" forget | "),
+            Span::styled("d", self.theme.footer_segment_key),
+            Span::raw(" delete | "),
+            Span::styled("Esc", self.theme.footer_segment_key),
+            Span::raw(" close "),
+        ]);
+        Paragraph::new(hint)
+            .alignment(Alignment::Center)
+            .render(rows[1], buf);
+    }
+}
+
+impl BookmarksModal<'_> {
+    fn bookmark_item(&self, bookmark: &Bookmark, under_local: bool) -> ListItem<'static> {
+        let mut spans = match (&bookmark.remote, under_local) {
+            (Some(remote), true) => vec![Span::styled(format!("  @{remote}"), self.theme.bookmark)],
+            _ => vec![Span::styled(bookmark.label(), self.theme.bookmark)],
+        };
+        if bookmark.is_conflicted() {
+            spans.push(Span::styled("??", self.theme.diff_conflict));
+        }
+        if bookmark.remote.is_some() {
+            let mut status = vec![if bookmark.is_tracked {
+                "tracked".to_string()
+            } else {
+                "untracked".to_string()
+            }];
+            if bookmark.ahead > 0 {
+                status.push(format!("{} to push", bookmark.ahead));
+            }
+            if bookmark.behind > 0 {
+                status.push(format!("{} to pull", bookmark.behind));
+            }
+            spans.push(Span::styled(
+                format!(" ({})", status.join(", ")),
+                self.theme.timestamp,
+            ));
+        }
+        spans.push(Span::raw(": "));
+        for (idx, target) in bookmark.targets.iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::styled(" | ", self.theme.diff_conflict));
+            }
+            spans.push(Span::styled(
+                target.change_id_short.clone(),
+                self.theme.change_id_mutable,
+            ));
+            spans.push(Span::raw(" "));
+            if target.summary.is_empty() {
+                spans.push(Span::styled("(no description set)", self.theme.timestamp));
+            } else {
+                spans.push(Span::raw(target.summary.clone()));
+            }
+        }
+        ListItem::new(Line::from(spans))
+    }
+}
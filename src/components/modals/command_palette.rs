@@ -11,6 +11,7 @@ use super::helpers::{centered_rect, draw_drop_shadow};
 pub struct CommandPaletteModal<'a> {
     pub theme: &'a Theme,
     pub state: &'a crate::app::state::CommandPaletteState,
+    pub custom_commands: &'a std::collections::BTreeMap<String, crate::app::keymap::CustomCommand>,
 }
 
 impl Widget for CommandPaletteModal<'_> {
@@ -63,7 +64,7 @@ impl Widget for CommandPaletteModal<'_> {
         buf.set_string(layout[1].x, layout[1].y, separator, self.theme.border_focus);
 
         // Render Results
-        let commands = crate::app::command_palette::get_commands();
+        let commands = crate::app::command_palette::get_commands(self.custom_commands);
         let items: Vec<ListItem> = self
             .state
             .matches
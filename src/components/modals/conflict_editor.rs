@@ -0,0 +1,182 @@
+use crate::app::state::{AppTextArea, ConflictEditorState};
+use crate::domain::conflict::ConflictRegion;
+use crate::theme::{glyphs, Theme};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use super::helpers::{centered_rect, draw_drop_shadow};
+
+/// Rows of the result pane kept above the selected region.
+const CONTEXT_ROWS: usize = 3;
+
+pub struct ConflictEditorModal<'a> {
+    pub theme: &'a Theme,
+    pub state: &'a ConflictEditorState,
+    /// The selected region's text while it is being edited by hand.
+    pub text_area: Option<&'a AppTextArea<'a>>,
+}
+
+impl Widget for ConflictEditorModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_area = centered_rect(90, 90, area);
+        if modal_area.width == 0 || modal_area.height == 0 {
+            return;
+        }
+
+        draw_drop_shadow(buf, modal_area, area);
+        Clear.render(modal_area, buf);
+
+        let total = self.state.resolutions.len();
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled(
+                    format!(" RESOLVE {} ", self.state.conflict.path),
+                    self.theme.header_active,
+                ),
+                Span::raw(" "),
+                Span::styled(
+                    format!(
+                        " conflict {}/{total}, {} unresolved ",
+                        (self.state.current + 1).min(total),
+                        self.state.unresolved_count()
+                    ),
+                    self.theme.header_item,
+                ),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.border_focus);
+
+        let inner_area = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40), // Base | Ours | Theirs
+                Constraint::Min(0),         // Result
+                Constraint::Length(1),      // Hints
+            ])
+            .split(inner_area);
+        let sides = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 3); 3])
+            .split(rows[0]);
+
+        if let Some(ConflictRegion::Conflict { base, ours, theirs }) = self.state.current_region() {
+            for (area, title, text, style) in [
+                (sides[0], " BASE ", base, self.theme.diff_context),
+                (sides[1], " OURS (o) ", ours, self.theme.diff_add),
+                (sides[2], " THEIRS (t) ", theirs, self.theme.diff_modify),
+            ] {
+                self.render_pane(area, buf, title, text, style);
+            }
+        }
+
+        let result_block = Block::default()
+            .title(Span::styled(" RESULT ", self.theme.header_item))
+            .borders(Borders::TOP)
+            .border_style(self.theme.border);
+        let result_area = result_block.inner(rows[1]);
+        result_block.render(rows[1], buf);
+        match self.text_area {
+            Some(text_area) => Widget::render(text_area, result_area, buf),
+            None => self.render_result(result_area, buf),
+        }
+
+        let hint = if self.text_area.is_some() {
+            Line::from(vec![
+                Span::raw(" Editing the selected region | "),
+                Span::styled("Esc", self.theme.footer_segment_key),
+                Span::raw(" done "),
+            ])
+        } else {
+            Line::from(vec![
+                Span::raw(" "),
+                Span::styled("o/t/b/a", self.theme.footer_segment_key),
+                Span::raw(" ours/theirs/base/both | "),
+                Span::styled("e", self.theme.footer_segment_key),
+                Span::raw(" edit | "),
+                Span::styled("Enter", self.theme.footer_segment_key),
+                Span::raw(" save | "),
+                Span::styled("Esc", self.theme.footer_segment_key),
+                Span::raw(" cancel "),
+            ])
+        };
+        Paragraph::new(hint)
+            .alignment(Alignment::Center)
+            .render(rows[2], buf);
+    }
+}
+
+impl ConflictEditorModal<'_> {
+    fn render_pane(&self, area: Rect, buf: &mut Buffer, title: &str, text: &str, style: Style) {
+        let block = Block::default()
+            .title(Span::styled(title, self.theme.header_item))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.border);
+        let lines: Vec<Line> = text
+            .lines()
+            .map(|line| Line::from(Span::styled(line, style)))
+            .collect();
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+
+    /// The whole file as it will be written, scrolled to the selected region.
+    fn render_result(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines: Vec<Line> = Vec::new();
+        let mut current_start = 0;
+        let mut conflict_idx = 0;
+        for region in &self.state.conflict.regions {
+            let ConflictRegion::Conflict { .. } = region else {
+                if let Some(text) = region.resolved_text(None) {
+                    lines.extend(text.lines().map(|l| Line::from(format!("  {l}"))));
+                }
+                continue;
+            };
+            let is_current = conflict_idx == self.state.current;
+            let marker = if is_current {
+                Span::styled(format!("{} ", glyphs::FOCUS), self.theme.border_focus)
+            } else {
+                Span::raw("  ")
+            };
+            if is_current {
+                current_start = lines.len();
+            }
+            let resolution = self.state.resolutions.get(conflict_idx).cloned().flatten();
+            match region.resolved_text(resolution.as_ref()) {
+                Some(text) if text.is_empty() => lines.push(Line::from(vec![
+                    marker,
+                    Span::styled("(removed)", self.theme.timestamp),
+                ])),
+                Some(text) => lines.extend(text.lines().map(|l| {
+                    Line::from(vec![
+                        marker.clone(),
+                        Span::styled(l.to_string(), self.theme.diff_add),
+                    ])
+                })),
+                None => lines.push(Line::from(vec![
+                    marker,
+                    Span::styled(
+                        format!("<<< conflict {} unresolved >>>", conflict_idx + 1),
+                        self.theme.diff_conflict,
+                    ),
+                ])),
+            }
+            conflict_idx += 1;
+        }
+
+        let scroll = current_start.saturating_sub(CONTEXT_ROWS);
+        Paragraph::new(lines)
+            .scroll((u16::try_from(scroll).unwrap_or(u16::MAX), 0))
+            .render(area, buf);
+    }
+}
@@ -26,7 +26,7 @@ impl Widget for EvologModal<'_> {
         let block = Block::default()
             .title(Line::from(vec![
                 Span::raw(" "),
-                Span::styled(" EVOLUTION LOG ", self.theme.header_active),
+                Span::styled(format!(" {} ", self.state.title), self.theme.header_active),
                 Span::raw(" "),
             ]))
             .borders(Borders::ALL)
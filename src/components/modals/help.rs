@@ -61,6 +61,10 @@ impl Widget for HelpModal<'_> {
                 Cell::from(Span::styled(" h", key_style)),
                 Cell::from(Span::styled("Focus revision graph", desc_style)),
             ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" |", key_style)),
+                Cell::from(Span::styled("Toggle side-by-side diff", desc_style)),
+            ]),
             Row::new(vec![Cell::from(""), Cell::from("")]),
             // Operations
             Row::new(vec![
@@ -87,9 +91,23 @@ impl Widget for HelpModal<'_> {
                 Cell::from(Span::styled(" a", key_style)),
                 Cell::from(Span::styled("Abandon revision", desc_style)),
             ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" r", key_style)),
+                Cell::from(Span::styled(
+                    "Rebase (pick the target in the graph)",
+                    desc_style,
+                )),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" P", key_style)),
+                Cell::from(Span::styled(
+                    "Preview rewrites in the graph first",
+                    desc_style,
+                )),
+            ]),
             Row::new(vec![
                 Cell::from(Span::styled(" S", key_style)),
-                Cell::from(Span::styled("Squash into parent", desc_style)),
+                Cell::from(Span::styled("Move changes (squash)", desc_style)),
             ]),
             Row::new(vec![
                 Cell::from(Span::styled(" b", key_style)),
@@ -99,13 +117,29 @@ impl Widget for HelpModal<'_> {
                 Cell::from(Span::styled(" B", key_style)),
                 Cell::from(Span::styled("Delete bookmark", desc_style)),
             ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" M", key_style)),
+                Cell::from(Span::styled("Manage bookmarks", desc_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" g", key_style)),
+                Cell::from(Span::styled("Tags", desc_style)),
+            ]),
             Row::new(vec![
                 Cell::from(Span::styled(" u / U", key_style)),
                 Cell::from(Span::styled("Undo / Redo", desc_style)),
             ]),
             Row::new(vec![
                 Cell::from(Span::styled(" f / p", key_style)),
-                Cell::from(Span::styled("Fetch / Push", desc_style)),
+                Cell::from(Span::styled("Fetch / Push (with preview)", desc_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" W", key_style)),
+                Cell::from(Span::styled("Workspaces", desc_style)),
+            ]),
+            Row::new(vec![
+                Cell::from(Span::styled(" F", key_style)),
+                Cell::from(Span::styled("Remotes", desc_style)),
             ]),
             Row::new(vec![Cell::from(""), Cell::from("")]),
             // Filtering
@@ -1,4 +1,5 @@
 use crate::app::state::{AppMode, AppState};
+use crate::domain::models::{RebaseLocation, RebaseMode};
 use crate::theme::Theme;
 use ratatui::{
     buffer::Buffer,
@@ -7,15 +8,20 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
 };
 
+use super::bookmarks::BookmarksModal;
 use super::command_palette::CommandPaletteModal;
+use super::conflict_editor::ConflictEditorModal;
 use super::context_menu::ContextMenuModal;
 use super::error::ErrorModal;
 use super::evolog::EvologModal;
 use super::help::HelpModal;
 use super::helpers::{dim_area, draw_drop_shadow, render_revset_categories};
 use super::operation_log::OperationLogModal;
+use super::push::PushModal;
+use super::remotes::RemotesModal;
 use super::text_input::TextInputModal;
 use super::theme_selection::ThemeSelectionModal;
+use super::workspaces::WorkspacesModal;
 
 pub struct ModalManager<'a> {
     pub theme: &'a Theme,
@@ -27,7 +33,13 @@ impl Widget for ModalManager<'_> {
         // --- Visual Dimming ---
         let is_modal_active = !matches!(
             self.app_state.mode,
-            AppMode::Normal | AppMode::Diff | AppMode::NoRepo | AppMode::Loading
+            AppMode::Normal
+                | AppMode::Diff
+                | AppMode::HunkSelect
+                | AppMode::SquashSelect
+                | AppMode::RewritePreview
+                | AppMode::NoRepo
+                | AppMode::Loading
         ) || self.app_state.last_error.is_some();
 
         if is_modal_active {
@@ -41,10 +53,41 @@ impl Widget for ModalManager<'_> {
 
         // --- Input Modals (Describe, Bookmark, Filter) ---
         match self.app_state.mode {
-            AppMode::Input | AppMode::BookmarkInput => {
+            AppMode::Input
+            | AppMode::BookmarkInput
+            | AppMode::SplitInput
+            | AppMode::WorkspaceInput
+            | AppMode::BookmarkRename
+            | AppMode::BookmarkMove
+            | AppMode::RemoteAdd
+            | AppMode::RemoteRename
+            | AppMode::RemoteUrl
+            | AppMode::RemoteFetch
+            | AppMode::TagInput
+            | AppMode::TagMove => {
                 if let Some(input) = &self.app_state.input {
                     let title = if self.app_state.mode == AppMode::BookmarkInput {
                         " SET BOOKMARK "
+                    } else if self.app_state.mode == AppMode::SplitInput {
+                        " DESCRIBE SPLIT-OFF CHANGES "
+                    } else if self.app_state.mode == AppMode::WorkspaceInput {
+                        " NEW WORKSPACE PATH "
+                    } else if self.app_state.mode == AppMode::BookmarkRename {
+                        " RENAME BOOKMARK "
+                    } else if self.app_state.mode == AppMode::BookmarkMove {
+                        " MOVE BOOKMARK TO REVISION "
+                    } else if self.app_state.mode == AppMode::RemoteAdd {
+                        " NEW REMOTE: NAME URL "
+                    } else if self.app_state.mode == AppMode::RemoteRename {
+                        " RENAME REMOTE "
+                    } else if self.app_state.mode == AppMode::RemoteUrl {
+                        " REMOTE URL "
+                    } else if self.app_state.mode == AppMode::RemoteFetch {
+                        " BRANCHES TO FETCH "
+                    } else if self.app_state.mode == AppMode::TagInput {
+                        " NEW TAG "
+                    } else if self.app_state.mode == AppMode::TagMove {
+                        " MOVE TAG HERE "
                     } else if self.app_state.mode == AppMode::RebaseInput {
                         " REBASE DESTINATION "
                     } else {
@@ -60,7 +103,7 @@ impl Widget for ModalManager<'_> {
                 }
             }
             AppMode::RebaseSelect => {
-                let modal_area = super::helpers::centered_rect_fixed_height(80, 5, area);
+                let modal_area = super::helpers::centered_rect_fixed_height(80, 6, area);
                 draw_drop_shadow(buf, modal_area, area);
                 Clear.render(modal_area, buf);
                 let block = Block::default()
@@ -72,8 +115,40 @@ impl Widget for ModalManager<'_> {
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
                     .border_style(self.theme.border_focus);
+                let option = |key: &'static str, label: &'static str, active: bool| {
+                    let style = if active {
+                        self.theme.list_selected
+                    } else {
+                        self.theme.list_item
+                    };
+                    [
+                        Span::raw("  "),
+                        Span::styled(key, self.theme.footer_segment_key),
+                        Span::styled(format!(" {label}"), style),
+                    ]
+                };
+                let mode = self.app_state.rebase_mode;
+                let location = self.app_state.rebase_location;
+                let mut move_line = vec![Span::raw("Move:")];
+                move_line.extend(option("r", "revisions", mode == RebaseMode::Revisions));
+                move_line.extend(option("s", "with descendants", mode == RebaseMode::Source));
+                move_line.extend(option("b", "whole branch", mode == RebaseMode::Branch));
+                let mut place_line = vec![Span::raw("Place:")];
+                place_line.extend(option("d", "onto", location == RebaseLocation::Destination));
+                place_line.extend(option(
+                    "A",
+                    "after",
+                    location == RebaseLocation::InsertAfter,
+                ));
+                place_line.extend(option(
+                    "B",
+                    "before",
+                    location == RebaseLocation::InsertBefore,
+                ));
                 let text = vec![
-                    Line::from("Select the destination revision in the log and press Enter."),
+                    Line::from(move_line),
+                    Line::from(place_line),
+                    Line::from("Select the target revision in the log and press Enter."),
                     Line::from("Or press Esc to cancel."),
                 ];
                 Paragraph::new(text)
@@ -81,6 +156,33 @@ impl Widget for ModalManager<'_> {
                     .block(block)
                     .render(modal_area, buf);
             }
+            AppMode::ConfirmMove => {
+                if let Some(pending) = &self.app_state.bookmark_move {
+                    let modal_area = super::helpers::centered_rect_fixed_height(60, 5, area);
+                    draw_drop_shadow(buf, modal_area, area);
+                    Clear.render(modal_area, buf);
+                    let block = Block::default()
+                        .title(Line::from(vec![
+                            Span::raw(" "),
+                            Span::styled(" MOVE BOOKMARK BACKWARDS ", self.theme.header_warn),
+                            Span::raw(" "),
+                        ]))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(self.theme.border_focus);
+                    let text = vec![
+                        Line::from(format!(
+                            "Moving {} to {} goes backwards or sideways.",
+                            pending.name, pending.revision
+                        )),
+                        Line::from("Press y to move it anyway, or n to cancel."),
+                    ];
+                    Paragraph::new(text)
+                        .alignment(ratatui::layout::Alignment::Center)
+                        .block(block)
+                        .render(modal_area, buf);
+                }
+            }
             AppMode::FilterInput => {
                 if let Some(input) = &self.app_state.input {
                     let modal_area = super::helpers::centered_rect(80, 80, area);
@@ -129,14 +231,38 @@ impl Widget for ModalManager<'_> {
                     // Render Input
                     Widget::render(&input.text_area, main_layout[0], buf);
 
-                    // Separator
-                    let separator = "─".repeat(main_layout[1].width as usize);
-                    buf.set_string(
-                        main_layout[1].x,
-                        main_layout[1].y,
-                        separator,
-                        self.theme.border_focus,
-                    );
+                    // Separator, or the last revset error with its span underlined
+                    if let Some(err) = &self.app_state.revset_error {
+                        if let Some(span) = &err.span {
+                            let input_area = main_layout[0];
+                            for col in span.clone() {
+                                if col >= input_area.width as usize {
+                                    break;
+                                }
+                                let x = input_area.x + col as u16;
+                                buf[(x, input_area.y)].set_style(self.theme.input_error);
+                            }
+                        }
+                        let message = Line::from(vec![
+                            Span::styled(" ✗ ", self.theme.status_error),
+                            Span::raw(" "),
+                            Span::styled(err.message.clone(), self.theme.diff_remove),
+                        ]);
+                        buf.set_line(
+                            main_layout[1].x,
+                            main_layout[1].y,
+                            &message,
+                            main_layout[1].width,
+                        );
+                    } else {
+                        let separator = "─".repeat(main_layout[1].width as usize);
+                        buf.set_string(
+                            main_layout[1].x,
+                            main_layout[1].y,
+                            separator,
+                            self.theme.border_focus,
+                        );
+                    }
 
                     // Side-by-side: Recent Filters | Preset Filters
                     let list_layout = Layout::default()
@@ -276,6 +402,7 @@ impl Widget for ModalManager<'_> {
             CommandPaletteModal {
                 theme: self.theme,
                 state: cp,
+                custom_commands: &self.app_state.custom_commands,
             }
             .render(area, buf);
         }
@@ -300,6 +427,22 @@ impl Widget for ModalManager<'_> {
             .render(area, buf);
         }
 
+        // --- Conflict Editor ---
+        if let (AppMode::ConflictEditor | AppMode::ConflictInput, Some(editor)) =
+            (self.app_state.mode, &self.app_state.conflict_editor)
+        {
+            let text_area = match self.app_state.mode {
+                AppMode::ConflictInput => self.app_state.input.as_ref().map(|i| &i.text_area),
+                _ => None,
+            };
+            ConflictEditorModal {
+                theme: self.theme,
+                state: editor,
+                text_area,
+            }
+            .render(area, buf);
+        }
+
         // --- Operation Log ---
         if let (AppMode::OperationLog, Some(op)) =
             (self.app_state.mode, &self.app_state.operation_log_state)
@@ -311,6 +454,48 @@ impl Widget for ModalManager<'_> {
             .render(area, buf);
         }
 
+        // --- Workspaces ---
+        if let (AppMode::Workspaces, Some(workspaces)) =
+            (self.app_state.mode, &self.app_state.workspaces_state)
+        {
+            WorkspacesModal {
+                theme: self.theme,
+                state: workspaces,
+            }
+            .render(area, buf);
+        }
+
+        // --- Bookmarks ---
+        if let (AppMode::Bookmarks, Some(bookmarks)) =
+            (self.app_state.mode, &self.app_state.bookmarks_state)
+        {
+            BookmarksModal {
+                theme: self.theme,
+                state: bookmarks,
+            }
+            .render(area, buf);
+        }
+
+        // --- Remotes ---
+        if let (AppMode::Remotes, Some(remotes)) =
+            (self.app_state.mode, &self.app_state.remotes_state)
+        {
+            RemotesModal {
+                theme: self.theme,
+                state: remotes,
+            }
+            .render(area, buf);
+        }
+
+        // --- Push ---
+        if let (AppMode::Push, Some(push)) = (self.app_state.mode, &self.app_state.push_state) {
+            PushModal {
+                theme: self.theme,
+                state: push,
+            }
+            .render(area, buf);
+        }
+
         // --- Error Modal ---
         if let Some(err) = &self.app_state.last_error {
             ErrorModal {
@@ -1,4 +1,6 @@
+pub mod bookmarks;
 pub mod command_palette;
+pub mod conflict_editor;
 pub mod context_menu;
 pub mod error;
 pub mod evolog;
@@ -6,15 +8,23 @@ pub mod help;
 pub mod helpers;
 pub mod manager;
 pub mod operation_log;
+pub mod push;
+pub mod remotes;
 pub mod text_input;
 pub mod theme_selection;
+pub mod workspaces;
 
+pub use bookmarks::BookmarksModal;
 pub use command_palette::CommandPaletteModal;
+pub use conflict_editor::ConflictEditorModal;
 pub use context_menu::ContextMenuModal;
 pub use error::ErrorModal;
 pub use evolog::EvologModal;
 pub use help::HelpModal;
 pub use manager::ModalManager;
 pub use operation_log::OperationLogModal;
+pub use push::PushModal;
+pub use remotes::RemotesModal;
 pub use text_input::TextInputModal;
 pub use theme_selection::ThemeSelectionModal;
+pub use workspaces::WorkspacesModal;
@@ -1,16 +1,22 @@
+use crate::app::state::OperationLogState;
+use crate::domain::operation::{ChangedCommit, Operation, OperationChanges};
 use crate::theme::Theme;
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, Paragraph, Widget},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget, Wrap,
+    },
 };
 
 use super::helpers::{centered_rect, draw_drop_shadow};
 
 pub struct OperationLogModal<'a> {
     pub theme: &'a Theme,
-    pub state: &'a crate::app::state::OperationLogState,
+    pub state: &'a OperationLogState,
 }
 
 impl Widget for OperationLogModal<'_> {
@@ -36,34 +42,110 @@ impl Widget for OperationLogModal<'_> {
         let inner_area = block.inner(modal_area);
         block.render(modal_area, buf);
 
-        let lines: Vec<ListItem> = self
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(55), // Operations
+                Constraint::Min(0),         // Changes of the selected one
+                Constraint::Length(1),      // Hints
+            ])
+            .split(inner_area);
+
+        let items: Vec<ListItem> = self
             .state
-            .content
+            .operations
             .iter()
-            .map(|l: &String| ListItem::new(l.as_str()).style(self.theme.list_item))
+            .map(|op| self.operation_item(op))
             .collect();
+        let mut list_state = ListState::default();
+        list_state.select(Some(self.state.selected));
+        let list = List::new(items).highlight_style(self.theme.list_selected);
+        StatefulWidget::render(list, rows[0], buf, &mut list_state);
 
-        let mut list_state = ratatui::widgets::ListState::default();
-        list_state.select(Some(self.state.scroll as usize));
-
-        let list = List::new(lines);
-        ratatui::widgets::StatefulWidget::render(list, inner_area, buf, &mut list_state);
+        let changes_block = Block::default()
+            .title(Span::styled(" CHANGES ", self.theme.header_item))
+            .borders(Borders::TOP)
+            .border_style(self.theme.border);
+        let lines = match &self.state.changes {
+            Some(changes) => self.change_lines(changes),
+            None => vec![Line::from(Span::styled("Loading...", self.theme.timestamp))],
+        };
+        Paragraph::new(lines)
+            .block(changes_block)
+            .wrap(Wrap { trim: false })
+            .render(rows[1], buf);
 
-        // Render scroll hint/footer
-        let hint_area = Rect::new(
-            modal_area.x,
-            modal_area.y + modal_area.height - 1,
-            modal_area.width,
-            1,
-        );
         let hint = Line::from(vec![
-            Span::raw(" Press "),
+            Span::raw(" "),
+            Span::styled("r", self.theme.footer_segment_key),
+            Span::raw(" restore to | "),
+            Span::styled("R", self.theme.footer_segment_key),
+            Span::raw(" revert | "),
             Span::styled("Esc", self.theme.footer_segment_key),
-            Span::raw(" to close | "),
-            Span::styled("j/k", self.theme.footer_segment_key),
-            Span::raw(" to scroll "),
+            Span::raw(" close "),
         ]);
-        let hint_paragraph = Paragraph::new(hint).alignment(ratatui::layout::Alignment::Center);
-        hint_paragraph.render(hint_area, buf);
+        Paragraph::new(hint)
+            .alignment(Alignment::Center)
+            .render(rows[2], buf);
+    }
+}
+
+impl OperationLogModal<'_> {
+    fn operation_item(&self, op: &Operation) -> ListItem<'static> {
+        let marker = if op.is_current { "@ " } else { "  " };
+        let description_style = if op.is_snapshot {
+            self.theme.timestamp
+        } else {
+            self.theme.list_item
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(marker, self.theme.graph_node_wc),
+            Span::styled(op.id_short.clone(), self.theme.change_id_mutable),
+            Span::raw(" "),
+            Span::styled(op.timestamp.clone(), self.theme.timestamp),
+            Span::raw(" "),
+            Span::styled(op.user.clone(), self.theme.author),
+            Span::raw(" "),
+            Span::styled(op.description.clone(), description_style),
+        ]))
+    }
+
+    fn change_lines(&self, changes: &OperationChanges) -> Vec<Line<'static>> {
+        if changes.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No changes to revisions or bookmarks",
+                self.theme.timestamp,
+            ))];
+        }
+        let commit_line = |sign: &'static str, style: Style, commit: &ChangedCommit| {
+            Line::from(vec![
+                Span::styled(sign, style),
+                Span::styled(commit.change_id_short.clone(), self.theme.change_id_mutable),
+                Span::raw(" "),
+                Span::styled(commit.commit_id_short.clone(), self.theme.commit_id_dim),
+                Span::raw(" "),
+                Span::raw(commit.summary.clone()),
+            ])
+        };
+        let mut lines = Vec::new();
+        for commit in &changes.added {
+            lines.push(commit_line("+ ", self.theme.diff_add, commit));
+        }
+        for commit in &changes.removed {
+            lines.push(commit_line("- ", self.theme.diff_remove, commit));
+        }
+        for bookmark in &changes.bookmarks {
+            let target = |id: &Option<String>| id.clone().unwrap_or_else(|| "(absent)".into());
+            lines.push(Line::from(vec![
+                Span::styled("~ ", self.theme.diff_modify),
+                Span::styled(bookmark.name.clone(), self.theme.bookmark),
+                Span::raw(format!(
+                    ": {} -> {}",
+                    target(&bookmark.from),
+                    target(&bookmark.to)
+                )),
+            ]));
+        }
+        lines
     }
 }
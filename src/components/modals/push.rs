@@ -0,0 +1,216 @@
+use crate::app::state::{PushMode, PushState};
+use crate::domain::push::PushPlan;
+use crate::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget, Wrap,
+    },
+};
+
+use super::helpers::{centered_rect, draw_drop_shadow};
+
+pub struct PushModal<'a> {
+    pub theme: &'a Theme,
+    pub state: &'a PushState,
+}
+
+impl Widget for PushModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_area = centered_rect(70, 70, area);
+        if modal_area.width == 0 || modal_area.height == 0 {
+            return;
+        }
+
+        draw_drop_shadow(buf, modal_area, area);
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled(" PUSH ", self.theme.header_active),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.border_focus);
+
+        let inner_area = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),      // Remote and mode
+                Constraint::Min(0),         // What the push does or did
+                Constraint::Percentage(35), // Bookmarks to pick from
+                Constraint::Length(1),      // Hints
+            ])
+            .split(inner_area);
+
+        Paragraph::new(vec![self.remote_line(), self.mode_line()]).render(rows[0], buf);
+
+        let lines = match (&self.state.results, &self.state.plan) {
+            (Some(_), _) => self.result_lines(),
+            (None, Some(plan)) => self.plan_lines(plan),
+            (None, None) => vec![Line::from(Span::styled("Loading...", self.theme.timestamp))],
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                if self.state.results.is_some() {
+                    " RESULT "
+                } else {
+                    " PREVIEW "
+                },
+                self.theme.header_item,
+            ))
+            .borders(Borders::TOP)
+            .border_style(self.theme.border);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(rows[1], buf);
+
+        let block = Block::default()
+            .title(Span::styled(" BOOKMARKS ", self.theme.header_item))
+            .borders(Borders::TOP)
+            .border_style(self.theme.border);
+        let bookmarks = self
+            .state
+            .plan
+            .as_ref()
+            .map(|plan| plan.bookmarks.as_slice())
+            .unwrap_or_default();
+        let picking = self.state.mode == PushMode::Bookmarks;
+        let items: Vec<ListItem> = bookmarks
+            .iter()
+            .map(|name| {
+                let picked = picking && self.state.picked.contains(name);
+                ListItem::new(Line::from(vec![
+                    Span::raw(if picked { "[x] " } else { "[ ] " }),
+                    Span::styled(name.clone(), self.theme.bookmark),
+                ]))
+            })
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select((!bookmarks.is_empty()).then_some(self.state.cursor));
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(self.theme.list_selected);
+        StatefulWidget::render(list, rows[2], buf, &mut list_state);
+
+        let hint = Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Space", self.theme.footer_segment_key),
+            Span::raw(" pick | "),
+            Span::styled("Enter", self.theme.footer_segment_key),
+            Span::raw(if self.state.results.is_some() {
+                " done | "
+            } else {
+                " push | "
+            }),
+            Span::styled("Esc", self.theme.footer_segment_key),
+            Span::raw(" close "),
+        ]);
+        Paragraph::new(hint)
+            .alignment(Alignment::Center)
+            .render(rows[3], buf);
+    }
+}
+
+impl PushModal<'_> {
+    fn remote_line(&self) -> Line<'static> {
+        let remote = self
+            .state
+            .remote
+            .clone()
+            .unwrap_or_else(|| "...".to_string());
+        let mut spans = vec![
+            Span::raw(" Remote: "),
+            Span::styled(remote, self.theme.header_branch),
+        ];
+        if let Some(plan) = &self.state.plan {
+            if plan.remotes.len() > 1 {
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled("Tab", self.theme.footer_segment_key));
+                spans.push(Span::raw(format!(" next of {}", plan.remotes.len())));
+            }
+        }
+        Line::from(spans)
+    }
+
+    fn mode_line(&self) -> Line<'static> {
+        let modes = [
+            (PushMode::Bookmarks, "b", "picked bookmarks"),
+            (PushMode::Change, "c", "--change"),
+            (PushMode::All, "a", "--all"),
+            (PushMode::Tracked, "t", "--tracked"),
+        ];
+        let mut spans = vec![Span::raw(" Push: ")];
+        for (mode, key, label) in modes {
+            spans.push(Span::styled(key, self.theme.footer_segment_key));
+            let style = if self.state.mode == mode {
+                self.theme.highlight
+            } else {
+                self.theme.timestamp
+            };
+            spans.push(Span::styled(format!(" {label}"), style));
+            spans.push(Span::raw("  "));
+        }
+        Line::from(spans)
+    }
+
+    fn plan_lines(&self, plan: &PushPlan) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line> = plan
+            .updates
+            .iter()
+            .map(|update| {
+                Line::from(vec![
+                    Span::styled("~ ", self.theme.diff_modify),
+                    Span::raw(update.describe()),
+                ])
+            })
+            .collect();
+        for (name, reason) in &plan.skipped {
+            lines.push(Line::from(vec![
+                Span::styled("- ", self.theme.status_warn),
+                Span::styled(name.clone(), self.theme.bookmark),
+                Span::styled(format!(": {reason}"), self.theme.timestamp),
+            ]));
+        }
+        if plan.updates.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Nothing to push",
+                self.theme.timestamp,
+            )));
+        }
+        lines
+    }
+
+    fn result_lines(&self) -> Vec<Line<'static>> {
+        let results = self.state.results.as_deref().unwrap_or_default();
+        if results.is_empty() {
+            return vec![Line::from(Span::styled(
+                "Nothing was pushed",
+                self.theme.timestamp,
+            ))];
+        }
+        results
+            .iter()
+            .map(|result| match &result.error {
+                None => Line::from(vec![
+                    Span::styled("✓ ", self.theme.status_ready),
+                    Span::styled(result.name.clone(), self.theme.bookmark),
+                ]),
+                Some(error) => Line::from(vec![
+                    Span::styled("✗ ", self.theme.status_error),
+                    Span::styled(result.name.clone(), self.theme.bookmark),
+                    Span::raw(format!(": {error}")),
+                ]),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,107 @@
+use crate::app::state::RemotesState;
+use crate::domain::remote::Remote;
+use crate::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget,
+    },
+};
+
+use super::helpers::{centered_rect, draw_drop_shadow};
+
+pub struct RemotesModal<'a> {
+    pub theme: &'a Theme,
+    pub state: &'a RemotesState,
+}
+
+impl Widget for RemotesModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_area = centered_rect(70, 50, area);
+        if modal_area.width == 0 || modal_area.height == 0 {
+            return;
+        }
+
+        draw_drop_shadow(buf, modal_area, area);
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled(" REMOTES ", self.theme.header_active),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.border_focus);
+
+        let inner_area = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Remotes
+                Constraint::Length(1), // Hints
+            ])
+            .split(inner_area);
+
+        if self.state.remotes.is_empty() {
+            Paragraph::new(Line::from(Span::styled(
+                "No Git remotes yet. Press a to add one.",
+                self.theme.timestamp,
+            )))
+            .render(rows[0], buf);
+        } else {
+            let items: Vec<ListItem> = self
+                .state
+                .remotes
+                .iter()
+                .map(|remote| self.remote_item(remote))
+                .collect();
+            let mut list_state = ListState::default();
+            list_state.select(Some(self.state.selected));
+            let list = List::new(items).highlight_style(self.theme.list_selected);
+            StatefulWidget::render(list, rows[0], buf, &mut list_state);
+        }
+
+        let hint = Line::from(vec![
+            Span::raw(" "),
+            Span::styled("f", self.theme.footer_segment_key),
+            Span::raw(" fetch | "),
+            Span::styled("F", self.theme.footer_segment_key),
+            Span::raw(" fetch all | "),
+            Span::styled("b", self.theme.footer_segment_key),
+            Span::raw(" fetch branches | "),
+            Span::styled("a", self.theme.footer_segment_key),
+            Span::raw(" add | "),
+            Span::styled("Esc", self.theme.footer_segment_key),
+            Span::raw(" close "),
+        ]);
+        Paragraph::new(hint)
+            .alignment(Alignment::Center)
+            .render(rows[1], buf);
+    }
+}
+
+impl RemotesModal<'_> {
+    fn remote_item(&self, remote: &Remote) -> ListItem<'static> {
+        let mut lines = vec![
+            Line::from(Span::styled(remote.name.clone(), self.theme.header_branch)),
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled(remote.url.clone(), self.theme.timestamp),
+            ]),
+        ];
+        if let Some(push_url) = &remote.push_url {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(format!("push: {push_url}"), self.theme.timestamp),
+            ]));
+        }
+        ListItem::new(lines)
+    }
+}
@@ -38,7 +38,7 @@ impl Widget for ThemeSelectionModal<'_> {
             .themes
             .iter()
             .enumerate()
-            .map(|(i, palette)| {
+            .map(|(i, entry)| {
                 let style = if i == self.state.selected_index {
                     self.theme.list_selected
                 } else {
@@ -53,7 +53,7 @@ impl Widget for ThemeSelectionModal<'_> {
 
                 ListItem::new(Line::from(vec![
                     Span::styled(prefix, style),
-                    Span::styled(palette.label(), style),
+                    Span::styled(entry.label.as_str(), style),
                 ]))
             })
             .collect();
@@ -0,0 +1,112 @@
+use crate::app::state::WorkspacesState;
+use crate::domain::workspace::Workspace;
+use crate::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget,
+        Widget,
+    },
+};
+
+use super::helpers::{centered_rect, draw_drop_shadow};
+
+pub struct WorkspacesModal<'a> {
+    pub theme: &'a Theme,
+    pub state: &'a WorkspacesState,
+}
+
+impl Widget for WorkspacesModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_area = centered_rect(70, 50, area);
+        if modal_area.width == 0 || modal_area.height == 0 {
+            return;
+        }
+
+        draw_drop_shadow(buf, modal_area, area);
+        Clear.render(modal_area, buf);
+
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled(" WORKSPACES ", self.theme.header_active),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.border_focus);
+
+        let inner_area = block.inner(modal_area);
+        block.render(modal_area, buf);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Workspaces
+                Constraint::Length(1), // Hints
+            ])
+            .split(inner_area);
+
+        let items: Vec<ListItem> = self
+            .state
+            .workspaces
+            .iter()
+            .map(|ws| self.workspace_item(ws))
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select(Some(self.state.selected));
+        let list = List::new(items).highlight_style(self.theme.list_selected);
+        StatefulWidget::render(list, rows[0], buf, &mut list_state);
+
+        let hint = Line::from(vec![
+            Span::raw(" "),
+            Span::styled("a", self.theme.footer_segment_key),
+            Span::raw(" add | "),
+            Span::styled("d", self.theme.footer_segment_key),
+            Span::raw(" forget | "),
+            Span::styled("u", self.theme.footer_segment_key),
+            Span::raw(" update stale | "),
+            Span::styled("Esc", self.theme.footer_segment_key),
+            Span::raw(" close "),
+        ]);
+        Paragraph::new(hint)
+            .alignment(Alignment::Center)
+            .render(rows[1], buf);
+    }
+}
+
+impl WorkspacesModal<'_> {
+    fn workspace_item(&self, ws: &Workspace) -> ListItem<'static> {
+        let marker = if ws.is_current { "@ " } else { "  " };
+        let summary = if ws.summary.is_empty() {
+            Span::styled("(no description set)", self.theme.timestamp)
+        } else {
+            Span::raw(ws.summary.clone())
+        };
+        let mut first_line = vec![
+            Span::styled(marker, self.theme.graph_node_wc),
+            Span::styled(format!("{}@", ws.name), self.theme.change_id_wc),
+            Span::raw(" "),
+            Span::styled(ws.change_id_short.clone(), self.theme.change_id_mutable),
+            Span::raw(" "),
+            summary,
+        ];
+        if ws.is_stale {
+            first_line.push(Span::raw(" "));
+            first_line.push(Span::styled("(stale)", self.theme.status_warn));
+        }
+        let root = ws
+            .root
+            .clone()
+            .unwrap_or_else(|| "(location unknown)".into());
+        ListItem::new(vec![
+            Line::from(first_line),
+            Line::from(vec![
+                Span::raw("    "),
+                Span::styled(root, self.theme.timestamp),
+            ]),
+        ])
+    }
+}
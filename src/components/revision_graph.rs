@@ -1,5 +1,6 @@
 use crate::app::state::{AppMode, Panel};
 use crate::domain::models::{FileStatus, GraphRow, RepoStatus};
+use crate::domain::row_template::{self, Field, RowTemplate, Segment};
 use crate::theme::{glyphs, Theme};
 use ratatui::{
     buffer::Buffer,
@@ -12,22 +13,168 @@ use ratatui::{
 };
 
 #[must_use]
-pub fn calculate_row_height(row: &GraphRow, is_selected: bool, show_diffs: bool) -> u16 {
+pub fn calculate_row_height(
+    row: &GraphRow,
+    template: &RowTemplate,
+    is_selected: bool,
+    show_diffs: bool,
+) -> u16 {
     let num_files = if is_selected && show_diffs {
         row.changed_files.len()
     } else {
         0
     };
-    2 + num_files as u16
+    details_height(row, template) + num_files as u16
+}
+
+/// Lines a row takes before its files. The branch and merge connectors go
+/// on the line below the node, so a row that forks or merges gets one more
+/// than a one-line template has.
+#[must_use]
+pub fn details_height(row: &GraphRow, template: &RowTemplate) -> u16 {
+    let forks = row
+        .visual
+        .parent_columns
+        .iter()
+        .any(|&column| column != row.visual.column);
+    if forks {
+        template.height().max(2)
+    } else {
+        template.height()
+    }
 }
 
 pub struct RevisionGraph<'a> {
     pub repo: &'a RepoStatus,
     pub theme: &'a Theme,
+    pub template: &'a RowTemplate,
     pub show_diffs: bool,
     pub selected_file_index: Option<usize>,
     pub selected_ids: &'a std::collections::HashSet<crate::domain::models::CommitId>,
+    pub picked_files: &'a std::collections::HashSet<String>,
     pub now_secs: i64,
+    /// Spinner frame for the row shown while the next page loads.
+    pub loading_more: Option<&'a str>,
+    /// Revisions a previewed rewrite would leave newly conflicted.
+    pub new_conflicts: Option<&'a std::collections::HashSet<crate::domain::models::CommitId>>,
+}
+
+impl<'a> RevisionGraph<'a> {
+    /// One line of `row` laid out by the template. A placeholder with
+    /// nothing to show takes the space after it along, or else the one
+    /// before it, so rows without bookmarks have no gaps.
+    fn template_line(&self, segments: &'a [Segment], row: &'a GraphRow) -> Line<'a> {
+        let mut spans: Vec<Span<'a>> = Vec::new();
+        let mut swallow_space = false;
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => {
+                    let text = if swallow_space {
+                        text.strip_prefix(' ').unwrap_or(text)
+                    } else {
+                        text
+                    };
+                    swallow_space = false;
+                    if !text.is_empty() {
+                        spans.push(Span::raw(text));
+                    }
+                }
+                Segment::Field(field) => {
+                    let values = self.field_spans(field, row);
+                    swallow_space = values.is_empty();
+                    for (i, value) in values.into_iter().enumerate() {
+                        if i > 0 {
+                            spans.push(Span::raw(" "));
+                        }
+                        spans.push(value);
+                    }
+                }
+            }
+        }
+        if swallow_space {
+            if let Some(last) = spans.last_mut() {
+                if let Some(text) = last.content.strip_suffix(' ') {
+                    last.content = text.to_string().into();
+                }
+            }
+        }
+        Line::from(spans)
+    }
+
+    fn field_spans(&self, field: &Field, row: &'a GraphRow) -> Vec<Span<'a>> {
+        let theme = self.theme;
+        match field {
+            Field::ChangeId => {
+                let style = if row.has_conflict {
+                    theme.graph_node_conflict
+                } else if row.is_working_copy {
+                    theme.change_id_wc
+                } else if row.is_immutable {
+                    theme.change_id_immutable
+                } else {
+                    theme.change_id_mutable
+                };
+                vec![Span::styled(&row.change_id_short, style)]
+            }
+            Field::CommitId => vec![Span::styled(&row.commit_id_short, theme.commit_id_dim)],
+            Field::Author => vec![Span::styled(&row.author, theme.author)],
+            Field::Committer => vec![Span::styled(&row.committer, theme.author)],
+            Field::Timestamp => vec![Span::styled(&row.timestamp, theme.timestamp)],
+            // Working copies of every workspace, like `default@` in `jj log`
+            Field::Workspaces => row
+                .workspaces
+                .iter()
+                .map(|workspace| Span::styled(format!("{workspace}@"), theme.change_id_wc))
+                .collect(),
+            Field::Bookmarks => row
+                .bookmarks
+                .iter()
+                .map(|bookmark| Span::styled(bookmark, theme.bookmark))
+                .collect(),
+            Field::Tags => row
+                .tags
+                .iter()
+                .map(|tag| Span::styled(tag, theme.tag))
+                .collect(),
+            Field::Conflict if row.has_conflict => {
+                let is_new = self
+                    .new_conflicts
+                    .is_some_and(|ids| ids.contains(&row.commit_id));
+                if is_new {
+                    vec![Span::styled("(new conflict)", theme.status_error)]
+                } else {
+                    vec![Span::styled("(conflict)", theme.graph_node_conflict)]
+                }
+            }
+            Field::Empty if !row.parents.is_empty() && row.changed_files.is_empty() => {
+                vec![Span::styled("(empty)", theme.diff_add)]
+            }
+            Field::Description => match row.description.lines().next() {
+                Some(line) if !line.is_empty() => vec![Span::raw(line)],
+                _ => vec![Span::styled("(no description set)", theme.timestamp)],
+            },
+            Field::Files if !row.changed_files.is_empty() => {
+                let count = row.changed_files.len();
+                let noun = if count == 1 { "file" } else { "files" };
+                vec![Span::styled(format!("{count} {noun}"), theme.timestamp)]
+            }
+            Field::Lines => row
+                .line_counts
+                .map(|counts| {
+                    vec![
+                        Span::styled(format!("+{}", counts.added), theme.diff_add),
+                        Span::styled(format!("-{}", counts.removed), theme.diff_remove),
+                    ]
+                })
+                .unwrap_or_default(),
+            Field::Trailer(key) => row_template::trailers(&row.description)
+                .into_iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| Span::styled(value, theme.author))
+                .collect(),
+            Field::Conflict | Field::Empty | Field::Files => Vec::new(),
+        }
+    }
 }
 
 /// Returns a copy of `style` with its `Color::Rgb` foreground dimmed by `factor` (0.0–1.0).
@@ -84,7 +231,7 @@ impl StatefulWidget for RevisionGraph<'_> {
 
         for (i, row) in self.repo.graph.iter().enumerate() {
             let is_selected = state.selected() == Some(i);
-            let row_height = calculate_row_height(row, is_selected, self.show_diffs);
+            let row_height = calculate_row_height(row, self.template, is_selected, self.show_diffs);
 
             // Compute age-based brightness for this commit's connector lines.
             let age_days = (now_secs - row.timestamp_secs).max(0) as f32 / 86_400.0;
@@ -235,57 +382,15 @@ impl StatefulWidget for RevisionGraph<'_> {
             }
 
             // Prepare Details Column
-            let mut detail_lines = Vec::new();
-
-            // Line 1: ChangeId Author Timestamp CommitId
-
-            let change_id_style = if row.has_conflict {
-                self.theme.graph_node_conflict
-            } else if row.is_working_copy {
-                self.theme.change_id_wc
-            } else if row.is_immutable {
-                self.theme.change_id_immutable
-            } else {
-                self.theme.change_id_mutable
-            };
-
-
-            let mut line_1_details = vec![
-                Span::styled(&row.change_id_short, change_id_style),
-                Span::raw(" "),
-                Span::styled(&row.author, self.theme.author),
-                Span::raw(" "),
-                Span::styled(&row.timestamp, self.theme.timestamp),
-                Span::raw(" "),
-            ];
-
-            // Add bookmarks if any
-            for bookmark in &row.bookmarks {
-                line_1_details.push(Span::styled(bookmark.clone(), self.theme.bookmark));
-                line_1_details.push(Span::raw(" "));
-            }
-
-            line_1_details.push(Span::styled(&row.commit_id_short, self.theme.commit_id_dim));
-
-            if row.has_conflict {
-                line_1_details.push(Span::raw(" "));
-                line_1_details.push(Span::styled("(conflict)", self.theme.graph_node_conflict));
-            }
-
-            detail_lines.push(Line::from(line_1_details));
-
-            // Line 2: Description
-            let description = row.description.lines().next().unwrap_or("");
-            if description.is_empty() {
-                detail_lines.push(Line::from(Span::styled(
-                    "(no description set)",
-                    self.theme.timestamp,
-                )));
-            } else {
-                detail_lines.push(Line::from(Span::raw(description)));
-            }
+            let mut detail_lines: Vec<Line> = self
+                .template
+                .lines()
+                .iter()
+                .map(|segments| self.template_line(segments, row))
+                .collect();
+            detail_lines.resize(details_height(row, self.template).into(), Line::default());
 
-            // Line 3+: Files
+            // Then the files
             if is_selected && self.show_diffs {
                 for (file_idx, file) in row.changed_files.iter().enumerate() {
                     let is_file_selected = self.selected_file_index == Some(file_idx);
@@ -298,10 +403,12 @@ impl StatefulWidget for RevisionGraph<'_> {
                     if is_file_selected {
                         style = self.theme.list_selected;
                     }
-                    detail_lines.push(Line::from(vec![
-                        Span::styled(prefix, style),
-                        Span::styled(&file.path, style),
-                    ]));
+                    let mut spans =
+                        vec![Span::styled(prefix, style), Span::styled(&file.path, style)];
+                    if self.picked_files.contains(&file.path) {
+                        spans.push(Span::styled(" (picked)", self.theme.diff_add));
+                    }
+                    detail_lines.push(Line::from(spans));
                 }
             }
 
@@ -317,6 +424,16 @@ impl StatefulWidget for RevisionGraph<'_> {
             );
         }
 
+        if let Some(spinner) = self.loading_more {
+            rows.push(Row::new(vec![
+                Cell::from(""),
+                Cell::from(Line::from(Span::styled(
+                    format!("{spinner} Loading more revisions..."),
+                    self.theme.timestamp,
+                ))),
+            ]));
+        }
+
         let table = Table::new(
             rows,
             [
@@ -336,6 +453,7 @@ impl StatefulWidget for RevisionGraph<'_> {
 pub struct RevisionGraphPanel<'a> {
     pub repo: Option<&'a crate::domain::models::RepoStatus>,
     pub theme: &'a Theme,
+    pub template: &'a RowTemplate,
     pub show_diffs: bool,
     pub selected_file_index: Option<usize>,
     pub spinner: &'a str,
@@ -343,6 +461,9 @@ pub struct RevisionGraphPanel<'a> {
     pub mode: AppMode,
     pub revset: Option<&'a str>,
     pub selected_ids: &'a std::collections::HashSet<crate::domain::models::CommitId>,
+    pub picked_files: &'a std::collections::HashSet<String>,
+    pub is_loading_more: bool,
+    pub new_conflicts: Option<&'a std::collections::HashSet<crate::domain::models::CommitId>>,
 }
 
 impl StatefulWidget for RevisionGraphPanel<'_> {
@@ -354,7 +475,9 @@ impl StatefulWidget for RevisionGraphPanel<'_> {
         }
 
         let is_graph_focused = self.focused_panel == Panel::Graph;
-        let is_body_active = self.mode == AppMode::Normal || self.mode == AppMode::Diff;
+        let is_preview = self.mode == AppMode::RewritePreview;
+        let is_body_active =
+            self.mode == AppMode::Normal || self.mode == AppMode::Diff || is_preview;
 
         let (border_style, title_style, borders, border_type) =
             if is_graph_focused && is_body_active {
@@ -380,29 +503,46 @@ impl StatefulWidget for RevisionGraphPanel<'_> {
                 )
             };
 
+        let title = if is_preview {
+            "REVISION GRAPH (PREVIEW)"
+        } else {
+            "REVISION GRAPH"
+        };
         let title_spans = if is_graph_focused && is_body_active {
             vec![
                 Span::styled(format!(" {} ", glyphs::FOCUS), self.theme.border_focus),
-                Span::styled("REVISION GRAPH", title_style),
+                Span::styled(title, title_style),
                 Span::raw(" "),
             ]
         } else {
             vec![
                 Span::raw(" "),
-                Span::styled("REVISION GRAPH", title_style),
+                Span::styled(title, title_style),
                 Span::raw(" "),
             ]
         };
 
-        let block = Block::default()
-            .title(Line::from(title_spans))
-            .title_bottom(Line::from(vec![
+        let hint = if is_preview {
+            vec![
+                Span::raw(" "),
+                Span::styled("Enter", self.theme.footer_segment_key),
+                Span::raw(": apply "),
+                Span::styled("Esc", self.theme.footer_segment_key),
+                Span::raw(": cancel "),
+            ]
+        } else {
+            vec![
                 Span::raw(" "),
                 Span::styled("j/k", self.theme.footer_segment_key),
                 Span::raw(": navigate "),
                 Span::styled("d", self.theme.footer_segment_key),
                 Span::raw(": describe "),
-            ]))
+            ]
+        };
+
+        let block = Block::default()
+            .title(Line::from(title_spans))
+            .title_bottom(Line::from(hint))
             .borders(borders)
             .border_type(border_type)
             .border_style(border_style);
@@ -443,10 +583,14 @@ impl StatefulWidget for RevisionGraphPanel<'_> {
                 let graph = RevisionGraph {
                     repo,
                     theme: self.theme,
+                    template: self.template,
                     show_diffs: self.show_diffs,
                     selected_file_index: self.selected_file_index,
                     selected_ids: self.selected_ids,
+                    picked_files: self.picked_files,
                     now_secs: chrono::Utc::now().timestamp(),
+                    loading_more: self.is_loading_more.then_some(self.spinner),
+                    new_conflicts: self.new_conflicts,
                 };
                 StatefulWidget::render(graph, inner, buf, state);
             }
@@ -485,3 +629,72 @@ impl StatefulWidget for RevisionGraphPanel<'_> {
         block.render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::graph_layout::calculate_graph_layout;
+    use crate::domain::models::CommitId;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_one_line_rows_draw_merges() {
+        // merge <- (left, right) <- root
+        let row = |id: &str, parents: &[&str]| GraphRow {
+            commit_id: CommitId(id.to_string()),
+            change_id_short: id.to_string(),
+            parents: parents.iter().map(|p| CommitId(p.to_string())).collect(),
+            ..Default::default()
+        };
+        let mut graph = vec![
+            row("merge", &["left", "right"]),
+            row("left", &["root"]),
+            row("right", &["root"]),
+            row("root", &[]),
+        ];
+        calculate_graph_layout(&mut graph);
+        let repo = RepoStatus {
+            repo_name: "repo".to_string(),
+            operation_id: String::new(),
+            workspace_id: "default".to_string(),
+            working_copy_id: CommitId("merge".to_string()),
+            graph,
+            has_more: false,
+        };
+        let template = RowTemplate::parse(&["{change_id}"]).unwrap();
+        let theme = Theme::default();
+        let no_ids = HashSet::new();
+        let no_files = HashSet::new();
+        let graph = RevisionGraph {
+            repo: &repo,
+            theme: &theme,
+            template: &template,
+            show_diffs: false,
+            selected_file_index: None,
+            selected_ids: &no_ids,
+            picked_files: &no_files,
+            now_secs: 0,
+            loading_more: None,
+            new_conflicts: None,
+        };
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 8));
+        StatefulWidget::render(graph, buf.area, &mut buf, &mut TableState::default());
+
+        let lines: Vec<String> = (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        // The merge gets a line for its connectors; rows that go straight
+        // down keep to one.
+        assert!(lines[0].ends_with("merge"));
+        assert_eq!(lines[1], "├─╮");
+        assert!(lines[2].starts_with("○ │") && lines[2].ends_with("left"));
+        assert!(lines[3].ends_with("right"));
+        assert!(lines[5].ends_with("root"));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::domain::models::CommitId;
+
+/// A local bookmark, or one of its counterparts on a remote.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bookmark {
+    pub name: String,
+    /// The remote it was fetched from; `None` for the local bookmark.
+    pub remote: Option<String>,
+    /// Commits it points to: none once deleted locally, more than one while
+    /// conflicted.
+    pub targets: Vec<BookmarkTarget>,
+    /// Whether the local bookmark follows this remote one.
+    pub is_tracked: bool,
+    /// For a tracked remote bookmark, how many commits the local bookmark
+    /// has that it doesn't (to push) and the other way around (to pull).
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BookmarkTarget {
+    pub commit_id: CommitId,
+    pub change_id_short: String,
+    /// First line of the description.
+    pub summary: String,
+}
+
+impl Bookmark {
+    /// `name` for a local bookmark, `name@remote` for a remote one.
+    #[must_use]
+    pub fn label(&self) -> String {
+        match &self.remote {
+            Some(remote) => format!("{}@{remote}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_conflicted(&self) -> bool {
+        self.targets.len() > 1
+    }
+}
@@ -0,0 +1,160 @@
+use crate::domain::models::CommitId;
+
+/// A two-sided conflict in one file of a revision, split into the regions
+/// the sides agree on and the regions they don't.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileConflict {
+    pub commit_id: CommitId,
+    pub path: String,
+    pub regions: Vec<ConflictRegion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictRegion {
+    /// Lines that merged cleanly.
+    Resolved(String),
+    /// Lines the two sides changed differently from their common base.
+    Conflict {
+        base: String,
+        ours: String,
+        theirs: String,
+    },
+}
+
+/// How a conflicting region is resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    Base,
+    /// Our lines followed by theirs, on a new line even if ours has no
+    /// final newline.
+    Both,
+    /// Text written by hand.
+    Edited(String),
+}
+
+impl ConflictRegion {
+    /// The text this region resolves to, or `None` if it is a conflict.
+    #[must_use]
+    pub fn resolved_text(&self, resolution: Option<&Resolution>) -> Option<String> {
+        match (self, resolution) {
+            (Self::Resolved(text), _) => Some(text.clone()),
+            (Self::Conflict { .. }, None) => None,
+            (Self::Conflict { base, ours, theirs }, Some(resolution)) => Some(match resolution {
+                Resolution::Ours => ours.clone(),
+                Resolution::Theirs => theirs.clone(),
+                Resolution::Base => base.clone(),
+                Resolution::Both if ours.is_empty() || ours.ends_with('\n') => {
+                    format!("{ours}{theirs}")
+                }
+                Resolution::Both => format!("{ours}\n{theirs}"),
+                Resolution::Edited(text) => text.clone(),
+            }),
+        }
+    }
+
+    /// Whether the region's text ends in a newline, which a conflict at the
+    /// end of a file without one doesn't.
+    #[must_use]
+    pub fn ends_with_newline(&self) -> bool {
+        match self {
+            Self::Resolved(text) => text.ends_with('\n'),
+            Self::Conflict { ours, theirs, .. } => ours.ends_with('\n') || theirs.ends_with('\n'),
+        }
+    }
+}
+
+impl FileConflict {
+    /// Indices into `regions` of the conflicting regions, in order.
+    #[must_use]
+    pub fn conflict_indices(&self) -> Vec<usize> {
+        self.regions
+            .iter()
+            .enumerate()
+            .filter(|(_, region)| matches!(region, ConflictRegion::Conflict { .. }))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Joins the file back together with one resolution per conflicting
+    /// region. Returns `None` while any of them is unresolved.
+    #[must_use]
+    pub fn resolve(&self, resolutions: &[Option<Resolution>]) -> Option<String> {
+        let mut resolutions = resolutions.iter();
+        let mut content = String::new();
+        for region in &self.regions {
+            let resolution = match region {
+                ConflictRegion::Resolved(_) => None,
+                ConflictRegion::Conflict { .. } => resolutions.next()?.as_ref(),
+            };
+            content.push_str(&region.resolved_text(resolution)?);
+        }
+        Some(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_joins_regions() {
+        let conflict = FileConflict {
+            commit_id: CommitId("abc".to_string()),
+            path: "a.txt".to_string(),
+            regions: vec![
+                ConflictRegion::Resolved("start\n".to_string()),
+                ConflictRegion::Conflict {
+                    base: "base\n".to_string(),
+                    ours: "ours\n".to_string(),
+                    theirs: "theirs\n".to_string(),
+                },
+                ConflictRegion::Resolved("middle\n".to_string()),
+                ConflictRegion::Conflict {
+                    base: String::new(),
+                    ours: "a\n".to_string(),
+                    theirs: "b\n".to_string(),
+                },
+            ],
+        };
+        assert_eq!(conflict.conflict_indices(), vec![1, 3]);
+        assert_eq!(conflict.resolve(&[Some(Resolution::Theirs), None]), None);
+        assert_eq!(
+            conflict.resolve(&[Some(Resolution::Base), Some(Resolution::Both)]),
+            Some("start\nbase\nmiddle\na\nb\n".to_string())
+        );
+        assert_eq!(
+            conflict.resolve(&[
+                Some(Resolution::Edited("mine\n".to_string())),
+                Some(Resolution::Ours)
+            ]),
+            Some("start\nmine\nmiddle\na\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_without_final_newline() {
+        let conflict = FileConflict {
+            commit_id: CommitId("abc".to_string()),
+            path: "a.txt".to_string(),
+            regions: vec![
+                ConflictRegion::Resolved("start\n".to_string()),
+                ConflictRegion::Conflict {
+                    base: "base".to_string(),
+                    ours: "ours".to_string(),
+                    theirs: "theirs".to_string(),
+                },
+            ],
+        };
+        assert!(!conflict.regions[1].ends_with_newline());
+        assert_eq!(
+            conflict.resolve(&[Some(Resolution::Both)]),
+            Some("start\nours\ntheirs".to_string())
+        );
+        assert_eq!(
+            conflict.resolve(&[Some(Resolution::Ours)]),
+            Some("start\nours".to_string())
+        );
+    }
+}
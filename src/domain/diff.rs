@@ -0,0 +1,686 @@
+use crate::domain::models::FileStatus;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::Range;
+
+/// The changes introduced by a single revision, relative to the merge of
+/// its parents.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Diff {
+    pub header: DiffHeader,
+    pub files: Vec<FileDiff>,
+}
+
+/// Revision metadata shown above the file diffs.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct DiffHeader {
+    pub commit_id: String,
+    pub change_id: String,
+    pub bookmarks: Vec<String>,
+    pub tags: Vec<String>,
+    pub author_name: String,
+    pub author_email: String,
+    pub timestamp: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub status: FileStatus,
+    /// Binary files carry no hunks.
+    pub is_binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+/// A contiguous group of changes with surrounding context. Line numbers are
+/// 1-based; a zero-length side starts at the line before the change, as in
+/// unified diff headers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line content without the trailing newline.
+    pub content: String,
+    pub old_lineno: Option<usize>,
+    pub new_lineno: Option<usize>,
+    /// Byte ranges of `content` holding the words that changed, for lines
+    /// paired with a similar line on the other side. Empty otherwise.
+    #[serde(skip)]
+    pub emphasis: Vec<Range<usize>>,
+}
+
+impl DiffLine {
+    /// Splits `content` into `(emphasized, text)` segments.
+    #[must_use]
+    pub fn segments(&self) -> Vec<(bool, &str)> {
+        let mut segments = Vec::new();
+        let mut pos = 0;
+        for range in &self.emphasis {
+            if range.start > pos {
+                segments.push((false, &self.content[pos..range.start]));
+            }
+            segments.push((true, &self.content[range.clone()]));
+            pos = range.end;
+        }
+        if pos < self.content.len() || segments.is_empty() {
+            segments.push((false, &self.content[pos..]));
+        }
+        segments
+    }
+}
+
+impl DiffLineKind {
+    #[must_use]
+    pub fn prefix(self) -> char {
+        match self {
+            Self::Context => ' ',
+            Self::Added => '+',
+            Self::Removed => '-',
+        }
+    }
+}
+
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_len, self.new_start, self.new_len
+        )
+    }
+}
+
+/// How file changes are laid out in the diff panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffLayout {
+    #[default]
+    Unified,
+    /// Old and new versions in side-by-side columns.
+    Split,
+}
+
+/// One display row of a diff. The diff panel renders these in order, so a
+/// row's index is its scroll position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffRow {
+    CommitId,
+    ChangeId,
+    Bookmarks,
+    Tags,
+    Author,
+    Description(usize),
+    Blank,
+    FileHeader(usize),
+    FileStatus(usize),
+    Binary(usize),
+    HunkHeader {
+        file: usize,
+        hunk: usize,
+    },
+    Line {
+        file: usize,
+        hunk: usize,
+        line: usize,
+    },
+    /// A row of the split layout: indices into the hunk's lines for the old
+    /// and new columns. Runs of removals and additions are paired up, with
+    /// the shorter side left empty.
+    SplitLine {
+        file: usize,
+        hunk: usize,
+        old: Option<usize>,
+        new: Option<usize>,
+    },
+}
+
+impl Diff {
+    #[must_use]
+    pub fn rows(&self, layout: DiffLayout) -> Vec<DiffRow> {
+        let mut rows = vec![DiffRow::CommitId, DiffRow::ChangeId];
+        if !self.header.bookmarks.is_empty() {
+            rows.push(DiffRow::Bookmarks);
+        }
+        if !self.header.tags.is_empty() {
+            rows.push(DiffRow::Tags);
+        }
+        rows.push(DiffRow::Author);
+        let description_lines = self.header.description.split('\n').count();
+        rows.extend((0..description_lines).map(DiffRow::Description));
+        rows.push(DiffRow::Blank);
+
+        for (file_idx, file) in self.files.iter().enumerate() {
+            rows.push(DiffRow::FileHeader(file_idx));
+            rows.push(DiffRow::FileStatus(file_idx));
+            if file.is_binary {
+                rows.push(DiffRow::Binary(file_idx));
+            }
+            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+                rows.push(DiffRow::HunkHeader {
+                    file: file_idx,
+                    hunk: hunk_idx,
+                });
+                match layout {
+                    DiffLayout::Unified => {
+                        rows.extend((0..hunk.lines.len()).map(|line| DiffRow::Line {
+                            file: file_idx,
+                            hunk: hunk_idx,
+                            line,
+                        }));
+                    }
+                    DiffLayout::Split => {
+                        rows.extend(hunk.split_pairs().into_iter().map(|(old, new)| {
+                            DiffRow::SplitLine {
+                                file: file_idx,
+                                hunk: hunk_idx,
+                                old,
+                                new,
+                            }
+                        }));
+                    }
+                }
+            }
+            rows.push(DiffRow::Blank);
+        }
+        rows
+    }
+
+    /// Row index of the header of the file at `path`.
+    #[must_use]
+    pub fn file_row(&self, path: &str, layout: DiffLayout) -> Option<usize> {
+        let file_idx = self.files.iter().position(|f| f.path == path)?;
+        self.rows(layout)
+            .iter()
+            .position(|row| *row == DiffRow::FileHeader(file_idx))
+    }
+
+    /// Row indices of every hunk header, in display order.
+    #[must_use]
+    pub fn hunk_rows(&self, layout: DiffLayout) -> Vec<usize> {
+        self.rows(layout)
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, DiffRow::HunkHeader { .. }))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Row indices, in the unified layout, that a selection cursor can rest
+    /// on: file headers, hunk headers and changed lines.
+    #[must_use]
+    pub fn selectable_rows(&self) -> Vec<usize> {
+        self.rows(DiffLayout::Unified)
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| match **row {
+                DiffRow::FileHeader(_) | DiffRow::HunkHeader { .. } => true,
+                DiffRow::Line { file, hunk, line } => {
+                    self.files[file].hunks[hunk].lines[line].kind != DiffLineKind::Context
+                }
+                _ => false,
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Plain-text rendering of a single row.
+    #[must_use]
+    pub fn row_text(&self, row: DiffRow) -> String {
+        let header = &self.header;
+        match row {
+            DiffRow::CommitId => format!("Commit ID: {}", header.commit_id),
+            DiffRow::ChangeId => format!("Change ID: {}", header.change_id),
+            DiffRow::Bookmarks => format!("Bookmarks: {}", header.bookmarks.join(", ")),
+            DiffRow::Tags => format!("Tags     : {}", header.tags.join(", ")),
+            DiffRow::Author => format!(
+                "Author   : {} <{}> ({})",
+                header.author_name, header.author_email, header.timestamp
+            ),
+            DiffRow::Description(idx) => {
+                let line = header.description.split('\n').nth(idx).unwrap_or_default();
+                format!("    {line}")
+            }
+            DiffRow::Blank => String::new(),
+            DiffRow::FileHeader(idx) => format!("File: {}", self.files[idx].path),
+            DiffRow::FileStatus(idx) => format!("Status: {:?}", self.files[idx].status),
+            DiffRow::Binary(_) => "    (binary file)".to_string(),
+            DiffRow::HunkHeader { file, hunk } => self.files[file].hunks[hunk].to_string(),
+            DiffRow::Line { file, hunk, line } => {
+                let line = &self.files[file].hunks[hunk].lines[line];
+                format!("{}{}", line.kind.prefix(), line.content)
+            }
+            DiffRow::SplitLine {
+                file,
+                hunk,
+                old,
+                new,
+            } => {
+                let lines = &self.files[file].hunks[hunk].lines;
+                let side = |idx: Option<usize>| {
+                    idx.map(|i| format!("{}{}", lines[i].kind.prefix(), lines[i].content))
+                        .unwrap_or_default()
+                };
+                format!("{} | {}", side(old), side(new))
+            }
+        }
+    }
+}
+
+impl Hunk {
+    /// Pairs the hunk's lines into `(old, new)` rows for the split layout.
+    /// Context lines appear on both sides; each run of removals is matched
+    /// line by line against the additions that follow it.
+    #[must_use]
+    pub fn split_pairs(&self) -> Vec<(Option<usize>, Option<usize>)> {
+        let mut pairs = Vec::new();
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        let flush = |pairs: &mut Vec<_>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+            for i in 0..removed.len().max(added.len()) {
+                pairs.push((removed.get(i).copied(), added.get(i).copied()));
+            }
+            removed.clear();
+            added.clear();
+        };
+        for (idx, line) in self.lines.iter().enumerate() {
+            match line.kind {
+                DiffLineKind::Context => {
+                    flush(&mut pairs, &mut removed, &mut added);
+                    pairs.push((Some(idx), Some(idx)));
+                }
+                DiffLineKind::Removed => {
+                    // A removal after additions starts a new run.
+                    if !added.is_empty() {
+                        flush(&mut pairs, &mut removed, &mut added);
+                    }
+                    removed.push(idx);
+                }
+                DiffLineKind::Added => added.push(idx),
+            }
+        }
+        flush(&mut pairs, &mut removed, &mut added);
+        pairs
+    }
+}
+
+/// Changes picked out of a [`Diff`], e.g. the part of a revision to split
+/// off. Indices refer to the diff the selection was made on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DiffSelection {
+    /// Selected added or removed lines, as `(file, hunk, line)`.
+    lines: BTreeSet<(usize, usize, usize)>,
+    /// Files that can only be taken whole; see [`FileDiff::is_whole_only`].
+    files: BTreeSet<usize>,
+}
+
+/// How much of a hunk or file is selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionState {
+    None,
+    Partial,
+    All,
+}
+
+/// The selected changes to one file, as the VCS layer applies them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSelection {
+    pub path: String,
+    /// Selected `(hunk, line)` indices into `hunks`, or `None` to take every
+    /// change to the file, including deletion, mode and binary changes.
+    pub lines: Option<Vec<(usize, usize)>>,
+    /// The hunks the selection was made on, so the VCS layer can refuse it
+    /// if the file changed since.
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    /// Binary and conflicted files, and files whose change has no lines
+    /// (an empty new file, a mode change), can't be split by line.
+    #[must_use]
+    pub fn is_whole_only(&self) -> bool {
+        self.is_binary || self.status == FileStatus::Conflicted || self.hunks.is_empty()
+    }
+}
+
+impl DiffSelection {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.files.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_line_selected(&self, file: usize, hunk: usize, line: usize) -> bool {
+        self.files.contains(&file) || self.lines.contains(&(file, hunk, line))
+    }
+
+    /// Selects or deselects a changed line. Context lines can't be selected;
+    /// in whole-only files this toggles the file.
+    pub fn toggle_line(&mut self, diff: &Diff, file: usize, hunk: usize, line: usize) {
+        let Some(file_diff) = diff.files.get(file) else {
+            return;
+        };
+        if file_diff.is_whole_only() {
+            self.toggle_file(diff, file);
+            return;
+        }
+        let is_change = file_diff
+            .hunks
+            .get(hunk)
+            .and_then(|h| h.lines.get(line))
+            .is_some_and(|l| l.kind != DiffLineKind::Context);
+        if is_change && !self.lines.remove(&(file, hunk, line)) {
+            self.lines.insert((file, hunk, line));
+        }
+    }
+
+    /// Selects every change in the hunk, or deselects them all if they
+    /// already are.
+    pub fn toggle_hunk(&mut self, diff: &Diff, file: usize, hunk: usize) {
+        if diff.files.get(file).is_some_and(FileDiff::is_whole_only) {
+            self.toggle_file(diff, file);
+            return;
+        }
+        let keys: Vec<_> = changed_lines(diff, file)
+            .filter(|&(_, h, _)| h == hunk)
+            .collect();
+        self.toggle_all(keys);
+    }
+
+    /// Selects every change in the file, or deselects them all if they
+    /// already are.
+    pub fn toggle_file(&mut self, diff: &Diff, file: usize) {
+        let Some(file_diff) = diff.files.get(file) else {
+            return;
+        };
+        if file_diff.is_whole_only() {
+            if !self.files.remove(&file) {
+                self.files.insert(file);
+            }
+            return;
+        }
+        let keys: Vec<_> = changed_lines(diff, file).collect();
+        self.toggle_all(keys);
+    }
+
+    /// Selects every change in the diff, or clears the selection if
+    /// everything is already selected.
+    pub fn toggle_everything(&mut self, diff: &Diff) {
+        if (0..diff.files.len()).all(|file| self.file_state(diff, file) == SelectionState::All) {
+            self.lines.clear();
+            self.files.clear();
+            return;
+        }
+        for (file, file_diff) in diff.files.iter().enumerate() {
+            if file_diff.is_whole_only() {
+                self.files.insert(file);
+            } else {
+                self.lines.extend(changed_lines(diff, file));
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn hunk_state(&self, diff: &Diff, file: usize, hunk: usize) -> SelectionState {
+        if self.files.contains(&file) {
+            return SelectionState::All;
+        }
+        self.state_of(changed_lines(diff, file).filter(|&(_, h, _)| h == hunk))
+    }
+
+    #[must_use]
+    pub fn file_state(&self, diff: &Diff, file: usize) -> SelectionState {
+        if self.files.contains(&file) {
+            return SelectionState::All;
+        }
+        if diff.files.get(file).is_some_and(FileDiff::is_whole_only) {
+            return SelectionState::None;
+        }
+        self.state_of(changed_lines(diff, file))
+    }
+
+    /// The selection grouped by file, in diff order. Fully selected files
+    /// are taken whole.
+    #[must_use]
+    pub fn file_selections(&self, diff: &Diff) -> Vec<FileSelection> {
+        diff.files
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, file)| {
+                let lines = match self.file_state(diff, idx) {
+                    SelectionState::None => return None,
+                    SelectionState::All => None,
+                    SelectionState::Partial => Some(
+                        self.lines
+                            .range((idx, 0, 0)..(idx + 1, 0, 0))
+                            .map(|&(_, hunk, line)| (hunk, line))
+                            .collect(),
+                    ),
+                };
+                Some(FileSelection {
+                    path: file.path.clone(),
+                    lines,
+                    hunks: file.hunks.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Toggles whatever `row` shows: a file, a hunk or a line.
+    pub fn toggle_row(&mut self, diff: &Diff, row: DiffRow) {
+        match row {
+            DiffRow::FileHeader(file) | DiffRow::FileStatus(file) | DiffRow::Binary(file) => {
+                self.toggle_file(diff, file);
+            }
+            DiffRow::HunkHeader { file, hunk } => self.toggle_hunk(diff, file, hunk),
+            DiffRow::Line { file, hunk, line } => self.toggle_line(diff, file, hunk, line),
+            _ => {}
+        }
+    }
+
+    fn toggle_all(&mut self, keys: Vec<(usize, usize, usize)>) {
+        if keys.iter().all(|key| self.lines.contains(key)) {
+            for key in &keys {
+                self.lines.remove(key);
+            }
+        } else {
+            self.lines.extend(keys);
+        }
+    }
+
+    fn state_of(&self, mut keys: impl Iterator<Item = (usize, usize, usize)>) -> SelectionState {
+        let Some(first) = keys.next() else {
+            return SelectionState::None;
+        };
+        let first_selected = self.lines.contains(&first);
+        if keys.any(|key| self.lines.contains(&key) != first_selected) {
+            SelectionState::Partial
+        } else if first_selected {
+            SelectionState::All
+        } else {
+            SelectionState::None
+        }
+    }
+}
+
+/// `(file, hunk, line)` of every added or removed line in a file.
+fn changed_lines(diff: &Diff, file: usize) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    diff.files
+        .get(file)
+        .into_iter()
+        .flat_map(|f| f.hunks.iter().enumerate())
+        .flat_map(move |(hunk_idx, hunk)| {
+            hunk.lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.kind != DiffLineKind::Context)
+                .map(move |(line_idx, _)| (file, hunk_idx, line_idx))
+        })
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows(DiffLayout::Unified) {
+            writeln!(f, "{}", self.row_text(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Diff {
+        let line = |kind, content: &str| DiffLine {
+            kind,
+            content: content.to_string(),
+            old_lineno: None,
+            new_lineno: None,
+            emphasis: Vec::new(),
+        };
+        Diff {
+            header: DiffHeader {
+                commit_id: "abc".to_string(),
+                description: "subject\n\nbody".to_string(),
+                ..Default::default()
+            },
+            files: vec![
+                FileDiff {
+                    path: "a.rs".to_string(),
+                    status: FileStatus::Modified,
+                    is_binary: false,
+                    hunks: vec![Hunk {
+                        old_start: 1,
+                        old_len: 1,
+                        new_start: 1,
+                        new_len: 1,
+                        // A source line that looks like a header must not
+                        // confuse navigation.
+                        lines: vec![
+                            line(DiffLineKind::Removed, "File: fake.rs"),
+                            line(DiffLineKind::Added, "@@ not a hunk"),
+                        ],
+                    }],
+                },
+                FileDiff {
+                    path: "b.bin".to_string(),
+                    status: FileStatus::Added,
+                    is_binary: true,
+                    hunks: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_rows_follow_structure() {
+        let diff = sample();
+        // CommitId, ChangeId, Author, 3 description lines, blank
+        assert_eq!(diff.file_row("a.rs", DiffLayout::Unified), Some(7));
+        assert_eq!(diff.hunk_rows(DiffLayout::Unified), vec![9]);
+        assert_eq!(diff.file_row("b.bin", DiffLayout::Unified), Some(13));
+        assert_eq!(diff.file_row("fake.rs", DiffLayout::Unified), None);
+        assert_eq!(
+            diff.row_text(DiffRow::Line {
+                file: 0,
+                hunk: 0,
+                line: 0
+            }),
+            "-File: fake.rs"
+        );
+        assert!(diff
+            .to_string()
+            .contains("@@ -1,1 +1,1 @@\n-File: fake.rs\n+@@ not a hunk\n"));
+    }
+
+    #[test]
+    fn test_selection_by_line_hunk_and_file() {
+        let diff = sample();
+        let mut selection = DiffSelection::default();
+        assert_eq!(diff.selectable_rows(), vec![7, 9, 10, 11, 13]);
+
+        selection.toggle_line(&diff, 0, 0, 1);
+        assert_eq!(selection.hunk_state(&diff, 0, 0), SelectionState::Partial);
+        assert_eq!(
+            selection.file_selections(&diff),
+            vec![FileSelection {
+                path: "a.rs".to_string(),
+                lines: Some(vec![(0, 1)]),
+                hunks: diff.files[0].hunks.clone(),
+            }]
+        );
+
+        // Toggling a partly selected hunk selects the rest of it, and a
+        // fully selected file is taken whole.
+        selection.toggle_hunk(&diff, 0, 0);
+        assert_eq!(selection.file_state(&diff, 0), SelectionState::All);
+        assert_eq!(selection.file_selections(&diff)[0].lines, None);
+
+        // Binary files have no lines, so any row selects the whole file.
+        selection.toggle_row(&diff, DiffRow::Binary(1));
+        assert_eq!(selection.file_state(&diff, 1), SelectionState::All);
+
+        selection.toggle_everything(&diff);
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    fn test_split_layout_pairs_changes() {
+        let diff = sample();
+        // The removal and addition share a row, pulling later files up.
+        assert_eq!(diff.hunk_rows(DiffLayout::Split), vec![9]);
+        assert_eq!(diff.file_row("b.bin", DiffLayout::Split), Some(12));
+
+        let kinds = [
+            DiffLineKind::Context,
+            DiffLineKind::Removed,
+            DiffLineKind::Removed,
+            DiffLineKind::Added,
+            DiffLineKind::Context,
+            DiffLineKind::Added,
+        ];
+        let hunk = Hunk {
+            old_start: 1,
+            old_len: 4,
+            new_start: 1,
+            new_len: 4,
+            lines: kinds
+                .iter()
+                .map(|&kind| DiffLine {
+                    kind,
+                    content: String::new(),
+                    old_lineno: None,
+                    new_lineno: None,
+                    emphasis: Vec::new(),
+                })
+                .collect(),
+        };
+        assert_eq!(
+            hunk.split_pairs(),
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(3)),
+                (Some(2), None),
+                (Some(4), Some(4)),
+                (None, Some(5)),
+            ]
+        );
+    }
+}
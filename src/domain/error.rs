@@ -0,0 +1,137 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Errors raised by the VCS layer itself, as opposed to failures bubbling up
+/// from the storage backend. Callers can `downcast_ref` these out of an
+/// `anyhow::Error` to react to a specific failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VcsError {
+    NoRepository,
+    InvalidCommitId(String),
+    CommitNotFound(String),
+    RootCommit,
+    /// An ancestor of `immutable_heads()`, by its short commit ID.
+    ImmutableRevision(String),
+    RevisionNotFound(String),
+    InvalidRevset(RevsetError),
+    AmbiguousRevision {
+        revset: String,
+    },
+    InvalidBookmarkName(String),
+    NoSuchBookmark(String),
+    BookmarkExists(String),
+    BookmarkMovedBackwards(String),
+    InvalidTagName(String),
+    NoSuchTag(String),
+    TagExists(String),
+    SquashWithoutSingleParent,
+    SquashIntoSource,
+    InvalidRebaseDestination,
+    StaleWorkingCopy,
+    /// A file changed after some of its lines were selected.
+    SelectionOutdated(String),
+    /// A file too large for the diff panel to show in full, so its lines
+    /// can't be picked.
+    FileTooLarge(String),
+    /// A conflict the built-in editor can't show; `jj resolve` still can.
+    UnsupportedConflict {
+        path: String,
+        reason: String,
+    },
+    /// A conflicted file changed after its conflict was read.
+    ConflictOutdated(String),
+    OperationNotFound(String),
+    OperationWithoutSingleParent,
+    WorkspaceExists(String),
+    WorkspaceNotFound(String),
+    NoRemotes,
+    NoSuchRemote(String),
+}
+
+impl fmt::Display for VcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoRepository => write!(f, "No repository found"),
+            Self::InvalidCommitId(id) => write!(f, "Invalid commit ID format: {id}"),
+            Self::CommitNotFound(id) => write!(
+                f,
+                "Commit {id} is no longer valid or has been rewritten/abandoned."
+            ),
+            Self::RootCommit => write!(f, "The root commit is immutable and cannot be rewritten"),
+            Self::ImmutableRevision(id) => write!(
+                f,
+                "Commit {id} is immutable. Immutable revisions are set by `revset-aliases.\"immutable_heads()\"`."
+            ),
+            Self::RevisionNotFound(revset) => write!(f, "Revision \"{revset}\" doesn't exist"),
+            Self::InvalidRevset(err) => err.fmt(f),
+            Self::AmbiguousRevision { revset } => {
+                write!(f, "Revset \"{revset}\" resolved to more than one revision")
+            }
+            Self::InvalidBookmarkName(name) => write!(f, "Invalid bookmark name: \"{name}\""),
+            Self::NoSuchBookmark(name) => write!(f, "No such bookmark: {name}"),
+            Self::BookmarkExists(name) => write!(f, "Bookmark {name} already exists"),
+            Self::BookmarkMovedBackwards(name) => {
+                write!(f, "Refusing to move bookmark {name} backwards or sideways")
+            }
+            Self::InvalidTagName(name) => write!(f, "Invalid tag name: \"{name}\""),
+            Self::NoSuchTag(name) => write!(f, "No such tag: {name}"),
+            Self::TagExists(name) => write!(f, "Tag {name} already exists"),
+            Self::SquashWithoutSingleParent => write!(
+                f,
+                "Cannot squash: the selected revisions must share a single parent"
+            ),
+            Self::SquashIntoSource => {
+                write!(f, "Cannot move changes into the revision they come from")
+            }
+            Self::InvalidRebaseDestination => {
+                write!(f, "Cannot rebase revisions onto themselves or their descendants")
+            }
+            Self::StaleWorkingCopy => write!(
+                f,
+                "The working copy is stale. Run `jj workspace update-stale` to update it."
+            ),
+            Self::SelectionOutdated(path) => write!(
+                f,
+                "{path} changed since its lines were selected. Reload the diff and select again."
+            ),
+            Self::FileTooLarge(path) => write!(
+                f,
+                "{path} is over 1 MB, too large to pick lines from. Select the whole file instead."
+            ),
+            Self::UnsupportedConflict { path, reason } => write!(
+                f,
+                "Cannot resolve {path} here: {reason}. Use `jj resolve` instead."
+            ),
+            Self::ConflictOutdated(path) => write!(
+                f,
+                "{path} changed since its conflict was opened. Open it again to resolve it."
+            ),
+            Self::OperationNotFound(id) => write!(f, "Operation {id} doesn't exist"),
+            Self::OperationWithoutSingleParent => write!(
+                f,
+                "Cannot revert an operation that doesn't have a single parent operation"
+            ),
+            Self::WorkspaceExists(name) => write!(f, "Workspace {name} already exists"),
+            Self::WorkspaceNotFound(name) => write!(f, "No such workspace: {name}"),
+            Self::NoRemotes => write!(f, "The repository has no Git remotes"),
+            Self::NoSuchRemote(name) => write!(f, "No Git remote named {name}"),
+        }
+    }
+}
+
+impl std::error::Error for VcsError {}
+
+/// A revset expression that failed to parse or resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevsetError {
+    pub revset: String,
+    pub message: String,
+    /// Character range within `revset` that caused the error, if known.
+    pub span: Option<Range<usize>>,
+}
+
+impl fmt::Display for RevsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid revset \"{}\": {}", self.revset, self.message)
+    }
+}
@@ -1,13 +1,27 @@
 use crate::domain::models::{CommitId, GraphRow};
 use std::collections::HashMap;
 
-pub fn calculate_graph_layout(rows: &mut [GraphRow]) {
-    if rows.is_empty() {
-        return;
-    }
+/// Lays out a graph from scratch, returning the lanes still open after its
+/// last row so later pages can continue it.
+pub fn calculate_graph_layout(rows: &mut [GraphRow]) -> GraphLayout {
+    let mut layout = GraphLayout::default();
+    extend_graph_layout(&mut layout, rows);
+    layout
+}
+
+/// Lanes left open by the rows laid out so far.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GraphLayout {
+    active_lanes: Vec<Option<CommitId>>,
+    commit_to_lane: HashMap<CommitId, usize>,
+}
 
-    let mut active_lanes: Vec<Option<CommitId>> = Vec::new();
-    let mut commit_to_lane: HashMap<CommitId, usize> = HashMap::new();
+/// Lays out `rows`, which continue the rows `layout` has already laid out.
+pub fn extend_graph_layout(layout: &mut GraphLayout, rows: &mut [GraphRow]) {
+    let GraphLayout {
+        active_lanes,
+        commit_to_lane,
+    } = layout;
 
     // Pass 1: Simple column assignment and active/connector lane tracking
     for row in rows.iter_mut() {
@@ -115,7 +129,7 @@ mod tests {
         assert_eq!(rows[2].visual.column, 0);
         assert_eq!(rows[2].visual.active_lanes, vec![true]);
         assert_eq!(rows[2].visual.connector_lanes, vec![false]);
-        assert_eq!(rows[2].visual.parent_columns, vec![]);
+        assert_eq!(rows[2].visual.parent_columns, Vec::<usize>::new());
     }
 
     #[test]
@@ -154,4 +168,30 @@ mod tests {
         // c1 is at lane 0
         assert_eq!(rows[2].visual.column, 0);
     }
+
+    #[test]
+    fn test_extend_layout_matches_full_layout() {
+        let row = |id: &str, parents: &[&str]| GraphRow {
+            commit_id: CommitId(id.to_string()),
+            parents: parents.iter().map(|p| CommitId(p.to_string())).collect(),
+            ..Default::default()
+        };
+        let mut full = vec![
+            row("c4", &["c3", "c2"]),
+            row("c3", &["c1"]),
+            row("c2", &["c1"]),
+            row("c1", &["c0"]),
+            row("c0", &[]),
+        ];
+        let mut paged = full.clone();
+
+        calculate_graph_layout(&mut full);
+        // The lanes open after the first page carry over to the second.
+        let (first, second) = paged.split_at_mut(2);
+        let mut layout = calculate_graph_layout(first);
+        extend_graph_layout(&mut layout, second);
+
+        assert_eq!(paged, full);
+        assert_eq!(full[2].visual.column, 1);
+    }
 }
@@ -1,3 +1,12 @@
+pub mod bookmark;
+pub mod conflict;
+pub mod diff;
+pub mod error;
 pub mod models;
+pub mod operation;
+pub mod push;
+pub mod remote;
+pub mod row_template;
 pub mod vcs;
+pub mod workspace;
 pub mod graph_layout;
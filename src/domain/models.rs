@@ -1,6 +1,7 @@
+use serde::Serialize;
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize)]
 pub struct CommitId(pub String);
 
 impl fmt::Display for CommitId {
@@ -9,7 +10,8 @@ impl fmt::Display for CommitId {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FileStatus {
     Added,
     Modified,
@@ -17,12 +19,49 @@ pub enum FileStatus {
     Conflicted,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct FileChange {
     pub path: String,
     pub status: FileStatus,
 }
 
+/// Where changes moved out of a revision end up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SquashDestination {
+    /// The revision's only parent, like `jj squash`.
+    Parent,
+    /// Any other revision, like `jj squash --into`.
+    Revision(CommitId),
+    /// A new revision inserted between the source and its children.
+    NewChild,
+}
+
+/// Which revisions a rebase moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RebaseMode {
+    /// Only the revisions; their descendants move onto their parents
+    /// (`jj rebase -r`).
+    #[default]
+    Revisions,
+    /// The revisions along with their descendants (`jj rebase -s`).
+    Source,
+    /// The whole branch of each revision: everything that isn't already an
+    /// ancestor of the destination (`jj rebase -b`).
+    Branch,
+}
+
+/// Where a rebase puts the revisions relative to the target revision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RebaseLocation {
+    /// On top of the target (`jj rebase -d`).
+    #[default]
+    Destination,
+    /// Between the target and its children (`jj rebase -A`).
+    InsertAfter,
+    /// Between the target and its parents (`jj rebase -B`).
+    InsertBefore,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct GraphRowVisual {
     pub column: usize,
@@ -35,7 +74,7 @@ pub struct GraphRowVisual {
     pub continuing_lanes: Vec<(usize, usize)>, // (from_lane, to_lane)
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct GraphRow {
     pub commit_id: CommitId,
     pub commit_id_short: String,
@@ -43,22 +82,71 @@ pub struct GraphRow {
     pub change_id_short: String,
     pub description: String, // Full description now
     pub author: String,
+    pub committer: String,
     pub timestamp: String,
     pub timestamp_secs: i64,
     pub is_working_copy: bool,
+    /// Names of the workspaces, this one included, whose working-copy commit
+    /// this is.
+    pub workspaces: Vec<String>,
     pub is_immutable: bool,
     pub has_conflict: bool,
     pub parents: Vec<CommitId>,
     pub bookmarks: Vec<String>,
+    pub tags: Vec<String>,
     pub changed_files: Vec<FileChange>,
+    /// Only counted when the graph shows them.
+    pub line_counts: Option<LineCounts>,
+    #[serde(skip)]
     pub visual: GraphRowVisual,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Lines a revision adds and removes across its text files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct LineCounts {
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RepoStatus {
     pub repo_name: String,
     pub operation_id: String,
     pub workspace_id: String,
     pub working_copy_id: CommitId,
     pub graph: Vec<GraphRow>,
+    /// Whether the graph continues past the loaded rows.
+    pub has_more: bool,
+}
+
+/// A rewrite of the graph that can be previewed before it is run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rewrite {
+    Abandon(Vec<CommitId>),
+    /// Squashes the revisions into their parent (`jj squash`).
+    Squash(Vec<CommitId>),
+    Parallelize(Vec<CommitId>),
+    /// Revisions, what to move along with them, where to put them and the
+    /// target revision.
+    Rebase(Vec<CommitId>, RebaseMode, RebaseLocation, String),
+}
+
+impl Rewrite {
+    /// The revisions the rewrite was started on.
+    #[must_use]
+    pub fn commit_ids(&self) -> &[CommitId] {
+        match self {
+            Self::Abandon(ids) | Self::Squash(ids) | Self::Parallelize(ids) => ids,
+            Self::Rebase(ids, ..) => ids,
+        }
+    }
+}
+
+/// The graph as a rewrite would leave it, worked out without writing an
+/// operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewritePreview {
+    pub status: RepoStatus,
+    /// Rows that would be conflicted when no version of their change is now.
+    pub new_conflicts: std::collections::HashSet<CommitId>,
 }
@@ -0,0 +1,51 @@
+/// One entry of the operation log, newest first.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Operation {
+    pub id: String,
+    pub id_short: String,
+    pub parent_ids: Vec<String>,
+    /// When the operation finished, in local time.
+    pub timestamp: String,
+    /// `user@host` that ran the operation.
+    pub user: String,
+    pub description: String,
+    /// Whether the operation only recorded on-disk changes.
+    pub is_snapshot: bool,
+    /// Whether the repository is currently at this operation.
+    pub is_current: bool,
+}
+
+/// What an operation changed, compared with its first parent (`jj op show`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OperationChanges {
+    /// Commits that became visible.
+    pub added: Vec<ChangedCommit>,
+    /// Commits that were hidden, e.g. because they were rewritten or
+    /// abandoned.
+    pub removed: Vec<ChangedCommit>,
+    pub bookmarks: Vec<BookmarkChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedCommit {
+    pub change_id_short: String,
+    pub commit_id_short: String,
+    /// First line of the description.
+    pub summary: String,
+}
+
+/// A local bookmark that was created, moved or deleted. Targets are short
+/// commit IDs; a conflicted target lists every side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookmarkChange {
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl OperationChanges {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.bookmarks.is_empty()
+    }
+}
@@ -0,0 +1,75 @@
+use crate::domain::models::CommitId;
+
+/// Which bookmarks to push, like the arguments of `jj git push`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushScope {
+    /// The named local bookmarks (`--bookmark`).
+    Bookmarks(Vec<String>),
+    /// A bookmark named after the revision's change, created if needed
+    /// (`--change`).
+    Change(CommitId),
+    /// Every local bookmark (`--all`).
+    All,
+    /// Every bookmark tracking the remote, including deleted ones
+    /// (`--tracked`).
+    Tracked,
+}
+
+/// What pushing a scope to a remote would do (`jj git push --dry-run`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PushPlan {
+    pub remote: String,
+    /// Every Git remote that could be pushed to instead.
+    pub remotes: Vec<String>,
+    /// Local bookmarks, to pick from.
+    pub bookmarks: Vec<String>,
+    pub updates: Vec<BookmarkUpdate>,
+    /// Bookmarks in scope that won't be pushed, with the reason why.
+    pub skipped: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookmarkUpdate {
+    pub name: String,
+    pub kind: UpdateKind,
+    /// Short commit IDs the remote bookmark moves from and to.
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateKind {
+    Add,
+    MoveForward,
+    MoveBackward,
+    MoveSideways,
+    Delete,
+}
+
+/// How pushing one bookmark went; `error` is `None` if the remote took it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushedBookmark {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+impl BookmarkUpdate {
+    /// Describes the update the way `jj git push` does.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let old = self.old.as_deref().unwrap_or_default();
+        let new = self.new.as_deref().unwrap_or_default();
+        let name = &self.name;
+        match self.kind {
+            UpdateKind::Add => format!("Add bookmark {name} to {new}"),
+            UpdateKind::MoveForward => format!("Move forward bookmark {name} from {old} to {new}"),
+            UpdateKind::MoveBackward => {
+                format!("Move backward bookmark {name} from {old} to {new}")
+            }
+            UpdateKind::MoveSideways => {
+                format!("Move sideways bookmark {name} from {old} to {new}")
+            }
+            UpdateKind::Delete => format!("Delete bookmark {name} from {old}"),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+/// A Git remote of the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Remote {
+    pub name: String,
+    pub url: String,
+    /// Where pushes go instead, if set apart from `url`.
+    pub push_url: Option<String>,
+}
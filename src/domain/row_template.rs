@@ -0,0 +1,201 @@
+/// Rows as the revision graph has always shown them: the change, who made
+/// it and when, its names and commit, then the description.
+pub const DEFAULT_ROW: [&str; 2] = [
+    "{change_id} {author} {timestamp} {workspaces} {bookmarks} {tags} {commit_id} {conflict}",
+    "{description}",
+];
+
+/// How a row of the revision graph is laid out: one line of text with
+/// placeholders like `{change_id}` for each line of the row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowTemplate {
+    lines: Vec<Vec<Segment>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Field(Field),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    ChangeId,
+    CommitId,
+    Author,
+    Committer,
+    Timestamp,
+    Workspaces,
+    Bookmarks,
+    Tags,
+    Conflict,
+    Empty,
+    Description,
+    Files,
+    /// Lines added and removed.
+    Lines,
+    /// Values of the description's trailers with this key, like
+    /// `Reviewed-by`.
+    Trailer(String),
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "change_id" => Self::ChangeId,
+            "commit_id" => Self::CommitId,
+            "author" => Self::Author,
+            "committer" => Self::Committer,
+            "timestamp" => Self::Timestamp,
+            "workspaces" => Self::Workspaces,
+            "bookmarks" => Self::Bookmarks,
+            "tags" => Self::Tags,
+            "conflict" => Self::Conflict,
+            "empty" => Self::Empty,
+            "description" => Self::Description,
+            "files" => Self::Files,
+            "lines" => Self::Lines,
+            _ => match name.strip_prefix("trailer:") {
+                Some(key) if !key.is_empty() => Self::Trailer(key.to_string()),
+                _ => return None,
+            },
+        })
+    }
+}
+
+impl RowTemplate {
+    /// Reads one template per line of a row. `{{` and `}}` stand for
+    /// braces.
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self, String> {
+        if lines.is_empty() {
+            return Err("a row needs at least one line".to_string());
+        }
+        let lines = lines
+            .iter()
+            .map(|line| parse_line(line.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { lines })
+    }
+
+    #[must_use]
+    pub fn lines(&self) -> &[Vec<Segment>] {
+        &self.lines
+    }
+
+    /// Lines taken by a row, before its file list.
+    #[must_use]
+    pub fn height(&self) -> u16 {
+        self.lines.len() as u16
+    }
+
+    /// Whether the rows show `{lines}`, which takes reading every changed
+    /// file of every row.
+    #[must_use]
+    pub fn counts_lines(&self) -> bool {
+        self.lines
+            .iter()
+            .flatten()
+            .any(|segment| *segment == Segment::Field(Field::Lines))
+    }
+}
+
+impl Default for RowTemplate {
+    fn default() -> Self {
+        Self::parse(&DEFAULT_ROW).expect("the default row template parses")
+    }
+}
+
+fn parse_line(line: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or("unclosed { (write {{ for a brace)")?;
+                let name = &rest[..end];
+                let field =
+                    Field::parse(name).ok_or_else(|| format!("no placeholder {{{name}}}"))?;
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Field(field));
+                chars = rest[end + 1..].chars();
+            }
+            '}' => return Err("unmatched } (write }} for a brace)".to_string()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+/// The trailers ending `description`, like `Signed-off-by: A <a@b.c>`: its
+/// last paragraph, if every line of it is a `Key: value` pair.
+#[must_use]
+pub fn trailers(description: &str) -> Vec<(&str, &str)> {
+    let description = description.trim_end();
+    let Some((_, last)) = description.rsplit_once("\n\n") else {
+        // A description that is only trailers has no subject.
+        return Vec::new();
+    };
+    let mut trailers = Vec::new();
+    for line in last.lines() {
+        let Some((key, value)) = line.split_once(": ") else {
+            return Vec::new();
+        };
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Vec::new();
+        }
+        trailers.push((key, value.trim()));
+    }
+    trailers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_row_template() {
+        let template =
+            RowTemplate::parse(&["{change_id} {{x}} {trailer:Reviewed-by}", "{lines}"]).unwrap();
+        assert_eq!(template.height(), 2);
+        assert!(template.counts_lines());
+        assert_eq!(
+            template.lines()[0],
+            [
+                Segment::Field(Field::ChangeId),
+                Segment::Text(" {x} ".to_string()),
+                Segment::Field(Field::Trailer("Reviewed-by".to_string())),
+            ]
+        );
+        assert!(!RowTemplate::default().counts_lines());
+
+        assert_eq!(
+            RowTemplate::parse(&["{change_id} {hash}"]),
+            Err("no placeholder {hash}".to_string())
+        );
+        assert!(RowTemplate::parse(&["{change_id"]).is_err());
+        assert!(RowTemplate::parse(&["change_id}"]).is_err());
+        assert!(RowTemplate::parse::<&str>(&[]).is_err());
+
+        assert_eq!(
+            trailers("Fix it\n\nMore words.\n\nReviewed-by: A <a@b.c>\nBug: 12\n"),
+            [("Reviewed-by", "A <a@b.c>"), ("Bug", "12")]
+        );
+        assert!(trailers("Fix it\n\nSee: the docs, or not\nmaybe").is_empty());
+        assert!(trailers("Bug: 12").is_empty());
+    }
+}
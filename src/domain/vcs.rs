@@ -1,20 +1,33 @@
-use crate::domain::models::{CommitId, RepoStatus};
+use crate::domain::{
+    bookmark::Bookmark,
+    conflict::FileConflict,
+    diff::{Diff, FileSelection},
+    models::{
+        CommitId, RebaseLocation, RebaseMode, RepoStatus, Rewrite, RewritePreview,
+        SquashDestination,
+    },
+    operation::{Operation, OperationChanges},
+    push::{PushPlan, PushScope, PushedBookmark},
+    remote::Remote,
+    workspace::Workspace,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait VcsFacade: Send + Sync {
-    // Returns the graph for the main view
+    /// Returns up to `limit` rows of the graph for the main view, after the
+    /// first `offset` rows.
     async fn get_operation_log(
         &self,
-        heads: Option<Vec<CommitId>>,
+        offset: usize,
         limit: usize,
         revset: Option<String>,
     ) -> Result<RepoStatus>;
 
     // Get diff for a specific commit
-    async fn get_commit_diff(&self, commit_id: &CommitId) -> Result<String>;
+    async fn get_commit_diff(&self, commit_id: &CommitId) -> Result<Diff>;
 
     // JJ specific: "Describe" and "Commit"
     async fn describe_revision(&self, commit_id: &str, message: &str) -> Result<()>;
@@ -25,25 +38,103 @@ pub trait VcsFacade: Send + Sync {
 
     async fn edit(&self, commit_id: &CommitId) -> Result<()>;
     async fn squash(&self, commit_ids: &[CommitId]) -> Result<()>;
+    /// Moves the selected changes out of a revision into `destination`.
+    async fn squash_changes(
+        &self,
+        commit_id: &CommitId,
+        files: &[FileSelection],
+        destination: &SquashDestination,
+    ) -> Result<()>;
+    /// Splits the selected changes out of a revision into a new parent.
+    async fn split(
+        &self,
+        commit_id: &CommitId,
+        files: &[FileSelection],
+        message: &str,
+    ) -> Result<()>;
+    /// Reads the two-sided conflict in `path` at a revision.
+    async fn get_file_conflict(&self, commit_id: &CommitId, path: &str) -> Result<FileConflict>;
+    /// Replaces a conflicted file with `content`, as long as it still has
+    /// the conflict that was read.
+    async fn resolve_conflict(&self, conflict: &FileConflict, content: &str) -> Result<()>;
     async fn new_child(&self, commit_id: &CommitId) -> Result<()>;
     async fn abandon(&self, commit_ids: &[CommitId]) -> Result<()>;
     async fn revert(&self, commit_ids: &[CommitId]) -> Result<()>;
     async fn absorb(&self) -> Result<()>;
     async fn duplicate(&self, commit_ids: &[CommitId]) -> Result<()>;
     async fn parallelize(&self, commit_ids: &[CommitId]) -> Result<()>;
-    async fn rebase(&self, commit_ids: &[CommitId], destination: &str) -> Result<()>;
+    /// Rebases the revisions `mode` picks relative to the revision
+    /// `destination` resolves to, like `jj rebase`.
+    async fn rebase(
+        &self,
+        commit_ids: &[CommitId],
+        mode: RebaseMode,
+        location: RebaseLocation,
+        destination: &str,
+    ) -> Result<()>;
+    /// The first `limit` rows of the graph, filtered by `revset`, as the
+    /// rewrite would leave them. Nothing is written, not even a snapshot of
+    /// the working copy.
+    async fn preview_rewrite(
+        &self,
+        rewrite: &Rewrite,
+        limit: usize,
+        revset: Option<String>,
+    ) -> Result<RewritePreview>;
     async fn set_bookmark(&self, commit_id: &CommitId, name: &str) -> Result<()>;
     async fn delete_bookmark(&self, name: &str) -> Result<()>;
+    /// Local bookmarks and their remote counterparts, sorted by name.
+    async fn bookmarks(&self) -> Result<Vec<Bookmark>>;
+    async fn rename_bookmark(&self, old_name: &str, new_name: &str) -> Result<()>;
+    /// Points a bookmark at the revision `revision` resolves to. Moving it
+    /// backwards or sideways fails unless `allow_backwards` is set.
+    async fn move_bookmark(&self, name: &str, revision: &str, allow_backwards: bool) -> Result<()>;
+    async fn track_bookmark(&self, name: &str, remote: &str) -> Result<()>;
+    async fn untrack_bookmark(&self, name: &str, remote: &str) -> Result<()>;
+    /// Forgets a local bookmark without deleting it on its remotes, which
+    /// stop being tracked (`jj bookmark forget`).
+    async fn forget_bookmark(&self, name: &str) -> Result<()>;
+    /// Points a tag at the commit. An existing tag is only moved if
+    /// `allow_move` is set, which in turn requires the tag to exist.
+    async fn set_tag(&self, commit_id: &CommitId, name: &str, allow_move: bool) -> Result<()>;
+    async fn delete_tag(&self, name: &str) -> Result<()>;
+    /// Pushes a tag to the default push remote (`git.push`, else `origin`).
+    async fn push_tag(&self, name: &str) -> Result<()>;
 
     async fn evolog(&self, commit_id: &CommitId) -> Result<String>;
 
-    async fn operation_log(&self) -> Result<String>;
+    /// The most recent `limit` operations, newest first.
+    async fn operation_log(&self, limit: usize) -> Result<Vec<Operation>>;
+    async fn operation_changes(&self, op_id: &str) -> Result<OperationChanges>;
+    async fn restore_operation(&self, op_id: &str) -> Result<()>;
+    async fn revert_operation(&self, op_id: &str) -> Result<()>;
+
+    /// Every workspace of the repository, the current one first.
+    async fn workspaces(&self) -> Result<Vec<Workspace>>;
+    /// Creates a workspace at `path`, named after its directory, with a new
+    /// working-copy commit on the current one's parents (`jj workspace add`).
+    async fn add_workspace(&self, path: &str) -> Result<()>;
+    async fn forget_workspace(&self, name: &str) -> Result<()>;
+    /// Updates a stale workspace's files to its working-copy commit.
+    async fn update_stale_workspace(&self, name: &str) -> Result<()>;
 
     async fn undo(&self) -> Result<()>;
     async fn redo(&self) -> Result<()>;
 
-    async fn fetch(&self) -> Result<()>;
-    async fn push(&self, bookmark: Option<String>) -> Result<()>;
+    /// Fetches from `remotes`, or the default remote (`git.fetch`, else
+    /// `origin`) if empty. Non-empty `branches` limits it to matching
+    /// branches (`jj git fetch -b`).
+    async fn fetch(&self, remotes: &[String], branches: &[String]) -> Result<()>;
+    async fn remotes(&self) -> Result<Vec<Remote>>;
+    async fn add_remote(&self, name: &str, url: &str) -> Result<()>;
+    async fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<()>;
+    /// Removes the remote along with its remote bookmarks.
+    async fn remove_remote(&self, name: &str) -> Result<()>;
+    async fn set_remote_url(&self, name: &str, url: &str) -> Result<()>;
+    /// What pushing `scope` would do, to `remote` or else the remote `jj`
+    /// would pick (`jj git push --dry-run`).
+    async fn push_preview(&self, remote: Option<String>, scope: &PushScope) -> Result<PushPlan>;
+    async fn push(&self, remote: &str, scope: &PushScope) -> Result<Vec<PushedBookmark>>;
     async fn init_repo(&self) -> Result<()>;
 
     async fn is_valid(&self) -> bool;
@@ -0,0 +1,18 @@
+use crate::domain::models::CommitId;
+
+/// A workspace of the repository and the revision checked out in it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Workspace {
+    pub name: String,
+    /// Where the workspace lives on disk, if the repository recorded it.
+    pub root: Option<String>,
+    pub working_copy_id: CommitId,
+    pub change_id_short: String,
+    /// First line of the working-copy commit's description.
+    pub summary: String,
+    /// Whether Judo was launched in this workspace.
+    pub is_current: bool,
+    /// Whether the files on disk lag behind the working-copy commit, as
+    /// after it was rewritten from another workspace.
+    pub is_stale: bool,
+}
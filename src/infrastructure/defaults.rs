@@ -7,4 +7,23 @@ hostname = "judo-host"
 username = "judo-user"
 [fsmonitor]
 backend = "none"
+
+# Built-in aliases that jj's CLI provides on top of jj-lib. Users can override
+# any of these in their own `[revset-aliases]` table.
+[revset-aliases]
+'trunk()' = '''
+latest(
+  remote_bookmarks(exact:"main", exact:"origin") |
+  remote_bookmarks(exact:"master", exact:"origin") |
+  remote_bookmarks(exact:"trunk", exact:"origin") |
+  remote_bookmarks(exact:"main", exact:"upstream") |
+  remote_bookmarks(exact:"master", exact:"upstream") |
+  remote_bookmarks(exact:"trunk", exact:"upstream") |
+  root()
+)
+'''
+'builtin_immutable_heads()' = 'present(trunk()) | tags() | untracked_remote_bookmarks()'
+'immutable_heads()' = 'builtin_immutable_heads()'
+'immutable()' = '::(immutable_heads() | root())'
+'mutable()' = '~immutable()'
 "#;
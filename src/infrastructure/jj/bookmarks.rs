@@ -1,42 +1,251 @@
 use super::JjAdapter;
-use crate::domain::models::CommitId;
-use anyhow::{anyhow, Result};
+use crate::domain::{
+    bookmark::{Bookmark, BookmarkTarget},
+    error::VcsError,
+    models::CommitId,
+};
+use anyhow::Result;
+use jj_lib::{
+    commit::Commit,
+    git::REMOTE_NAME_FOR_LOCAL_GIT_REPO,
+    object_id::ObjectId,
+    op_store::RefTarget,
+    ref_name::{RefNameBuf, RemoteNameBuf},
+    repo::{MutableRepo, ReadonlyRepo, Repo},
+    revset::ResolvedRevsetExpression,
+};
 
 impl JjAdapter {
     pub(crate) async fn set_bookmark_impl(&self, commit_id: &CommitId, name: &str) -> Result<()> {
-        self.validate_commit(commit_id).await?;
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("bookmark")
-            .arg("set")
-            .arg(name)
-            .arg("-r")
-            .arg(&commit_id.0)
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj bookmark set failed: {}", stderr.trim()))
-        }
+        let name = parse_bookmark_name(name)?;
+        let commit_id = commit_id.clone();
+        let description = format!(
+            "point bookmark {} to commit {}",
+            name.as_symbol(),
+            commit_id.0
+        );
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let commit = ctx.load_commit(mut_repo, &commit_id)?;
+            point_bookmark(mut_repo, &name, &commit, false)
+        })
+        .await
     }
 
     pub(crate) async fn delete_bookmark_impl(&self, name: &str) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("bookmark")
-            .arg("delete")
-            .arg(name)
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
+        let name = parse_bookmark_name(name)?;
+        let description = format!("delete bookmark {}", name.as_symbol());
+        self.run_transaction(description, move |mut_repo, _| {
+            if mut_repo.get_local_bookmark(&name).is_absent() {
+                return Err(VcsError::NoSuchBookmark(name.as_str().to_string()).into());
+            }
+            mut_repo.set_local_bookmark_target(&name, RefTarget::absent());
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn bookmarks_impl(&self) -> Result<Vec<Bookmark>> {
+        let (repo, _) = self.get_repo_and_ws().await?;
+        tokio::task::spawn_blocking(move || {
+            let mut bookmarks = Vec::new();
+            for (name, targets) in repo.view().bookmarks() {
+                let local = targets.local_target;
+                if local.is_present() {
+                    bookmarks.push(Bookmark {
+                        name: name.as_str().to_string(),
+                        targets: bookmark_targets(&repo, local)?,
+                        ..Default::default()
+                    });
+                }
+                for (remote, remote_ref) in targets.remote_refs {
+                    // The colocated Git repository's branches mirror the
+                    // local bookmarks, so `jj bookmark list` hides them too.
+                    if remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO || remote_ref.target.is_absent() {
+                        continue;
+                    }
+                    let is_tracked = remote_ref.is_tracked();
+                    // A deleted local bookmark has nothing to compare against;
+                    // pushing deletes the remote one instead.
+                    let (ahead, behind) = if is_tracked && local.is_present() {
+                        (
+                            count_range(&repo, &remote_ref.target, local)?,
+                            count_range(&repo, local, &remote_ref.target)?,
+                        )
+                    } else {
+                        (0, 0)
+                    };
+                    bookmarks.push(Bookmark {
+                        name: name.as_str().to_string(),
+                        remote: Some(remote.as_str().to_string()),
+                        targets: bookmark_targets(&repo, &remote_ref.target)?,
+                        is_tracked,
+                        ahead,
+                        behind,
+                    });
+                }
+            }
+            Ok(bookmarks)
+        })
+        .await?
+    }
+
+    /// Gives a local bookmark a new name, like `jj bookmark rename`. The
+    /// remote bookmarks it tracked keep the old name.
+    pub(crate) async fn rename_bookmark_impl(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let old_name = parse_bookmark_name(old_name)?;
+        let new_name = parse_bookmark_name(new_name)?;
+        let description = format!(
+            "rename bookmark {} to {}",
+            old_name.as_symbol(),
+            new_name.as_symbol()
+        );
+        self.run_transaction(description, move |mut_repo, _| {
+            let target = mut_repo.get_local_bookmark(&old_name);
+            if target.is_absent() {
+                return Err(VcsError::NoSuchBookmark(old_name.as_str().to_string()).into());
+            }
+            if mut_repo.get_local_bookmark(&new_name).is_present() {
+                return Err(VcsError::BookmarkExists(new_name.as_str().to_string()).into());
+            }
+            mut_repo.set_local_bookmark_target(&old_name, RefTarget::absent());
+            mut_repo.set_local_bookmark_target(&new_name, target);
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn move_bookmark_impl(
+        &self,
+        name: &str,
+        revision: &str,
+        allow_backwards: bool,
+    ) -> Result<()> {
+        let name = parse_bookmark_name(name)?;
+        let revision = revision.trim().to_string();
+        let description = format!("point bookmark {} to {revision}", name.as_symbol());
+        self.run_transaction(description, move |mut_repo, ctx| {
+            if mut_repo.get_local_bookmark(&name).is_absent() {
+                return Err(VcsError::NoSuchBookmark(name.as_str().to_string()).into());
+            }
+            let id = ctx.revset_env().resolve_single(mut_repo, &revision)?;
+            let commit = mut_repo.store().get_commit(&id)?;
+            point_bookmark(mut_repo, &name, &commit, allow_backwards)
+        })
+        .await
+    }
+
+    /// Makes the local bookmark follow a remote one, merging in where the
+    /// remote bookmark points (`jj bookmark track`).
+    pub(crate) async fn track_bookmark_impl(&self, name: &str, remote: &str) -> Result<()> {
+        let name = parse_bookmark_name(name)?;
+        let remote = RemoteNameBuf::from(remote);
+        let description = format!("track remote bookmark {}", name.to_remote_symbol(&remote));
+        self.run_transaction(description, move |mut_repo, _| {
+            let symbol = name.to_remote_symbol(&remote);
+            if mut_repo.get_remote_bookmark(symbol).target.is_absent() {
+                return Err(VcsError::NoSuchBookmark(symbol.to_string()).into());
+            }
+            mut_repo.track_remote_bookmark(symbol)?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn untrack_bookmark_impl(&self, name: &str, remote: &str) -> Result<()> {
+        let name = parse_bookmark_name(name)?;
+        let remote = RemoteNameBuf::from(remote);
+        let description = format!("untrack remote bookmark {}", name.to_remote_symbol(&remote));
+        self.run_transaction(description, move |mut_repo, _| {
+            let symbol = name.to_remote_symbol(&remote);
+            if mut_repo.get_remote_bookmark(symbol).target.is_absent() {
+                return Err(VcsError::NoSuchBookmark(symbol.to_string()).into());
+            }
+            mut_repo.untrack_remote_bookmark(symbol);
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn forget_bookmark_impl(&self, name: &str) -> Result<()> {
+        let name = parse_bookmark_name(name)?;
+        let description = format!("forget bookmark {}", name.as_symbol());
+        self.run_transaction(description, move |mut_repo, _| {
+            let remotes: Vec<RemoteNameBuf> = mut_repo
+                .view()
+                .all_remote_bookmarks()
+                .filter(|(symbol, _)| *symbol.name == *name)
+                .map(|(symbol, _)| symbol.remote.to_owned())
+                .collect();
+            if mut_repo.get_local_bookmark(&name).is_absent() && remotes.is_empty() {
+                return Err(VcsError::NoSuchBookmark(name.as_str().to_string()).into());
+            }
+            mut_repo.set_local_bookmark_target(&name, RefTarget::absent());
+            // Untracked, the remote bookmarks won't bring it back on the
+            // next fetch. Git-tracking ones can't be untracked.
+            for remote in remotes {
+                if remote != *REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+                    mut_repo.untrack_remote_bookmark(name.to_remote_symbol(&remote));
+                }
+            }
             Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj bookmark delete failed: {}", stderr.trim()))
+        })
+        .await
+    }
+}
+
+fn parse_bookmark_name(name: &str) -> Result<RefNameBuf> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.chars().any(char::is_whitespace) {
+        return Err(VcsError::InvalidBookmarkName(name.to_string()).into());
+    }
+    Ok(trimmed.into())
+}
+
+fn point_bookmark(
+    mut_repo: &mut MutableRepo,
+    name: &RefNameBuf,
+    commit: &Commit,
+    allow_backwards: bool,
+) -> Result<()> {
+    let old_target = mut_repo.get_local_bookmark(name);
+    // Like `jj bookmark set`, only allow moving a bookmark forwards unless
+    // asked to.
+    if !allow_backwards {
+        for old_id in old_target.added_ids() {
+            if !mut_repo.index().is_ancestor(old_id, commit.id())? {
+                return Err(VcsError::BookmarkMovedBackwards(name.as_str().to_string()).into());
+            }
         }
     }
+    mut_repo.set_local_bookmark_target(name, RefTarget::normal(commit.id().clone()));
+    Ok(())
+}
+
+fn bookmark_targets(repo: &ReadonlyRepo, target: &RefTarget) -> Result<Vec<BookmarkTarget>> {
+    target
+        .added_ids()
+        .map(|id| {
+            let commit = repo.store().get_commit(id)?;
+            let change_id = super::format_change_id(commit.change_id());
+            Ok(BookmarkTarget {
+                commit_id: CommitId(id.hex()),
+                change_id_short: change_id[..8.min(change_id.len())].to_string(),
+                summary: commit
+                    .description()
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Number of commits in `from..to`.
+fn count_range(repo: &ReadonlyRepo, from: &RefTarget, to: &RefTarget) -> Result<usize> {
+    let commits = |target: &RefTarget| {
+        ResolvedRevsetExpression::commits(target.added_ids().cloned().collect())
+    };
+    let revset = commits(from).range(&commits(to)).evaluate(repo)?;
+    Ok(revset.iter().count())
 }
@@ -0,0 +1,181 @@
+use super::JjAdapter;
+use crate::domain::{
+    conflict::{ConflictRegion, FileConflict},
+    error::VcsError,
+    models::CommitId,
+};
+use anyhow::Result;
+use futures::executor::block_on;
+use jj_lib::{
+    backend::{CommitId as JjCommitId, CopyId, TreeValue},
+    files::{self, FileMergeHunkLevel, MergeResult},
+    merge::{Merge, MergedTreeValue, SameChange},
+    merged_tree::MergedTree,
+    merged_tree_builder::MergedTreeBuilder,
+    repo::Repo,
+    repo_path::{RepoPath, RepoPathBuf},
+    store::Store,
+    tree_merge::MergeOptions,
+};
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+
+impl JjAdapter {
+    pub(crate) async fn get_file_conflict_impl(
+        &self,
+        commit_id: &CommitId,
+        path: &str,
+    ) -> Result<FileConflict> {
+        let (repo, _) = self.get_repo_and_ws().await?;
+        let id = JjCommitId::try_from_hex(&commit_id.0)
+            .ok_or_else(|| VcsError::InvalidCommitId(commit_id.0.clone()))?;
+        let commit = repo.store().get_commit(&id)?;
+        let regions = read_conflict(repo.store(), &commit.tree(), path).await?;
+        Ok(FileConflict {
+            commit_id: commit_id.clone(),
+            path: path.to_string(),
+            regions,
+        })
+    }
+
+    pub(crate) async fn resolve_conflict_impl(
+        &self,
+        conflict: &FileConflict,
+        content: &str,
+    ) -> Result<()> {
+        let conflict = conflict.clone();
+        let content = content.to_string();
+        let description = format!(
+            "resolve conflict in {} at {}",
+            conflict.path, conflict.commit_id
+        );
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let commit = ctx.load_mutable_commit(mut_repo, &conflict.commit_id)?;
+            let store = mut_repo.store().clone();
+            let tree = commit.tree();
+            // Refuse to overwrite a conflict that isn't the one on screen,
+            // including one in a revision that has since been rewritten.
+            let mut is_visible = false;
+            for head in mut_repo.view().heads() {
+                if mut_repo.index().is_ancestor(commit.id(), head)? {
+                    is_visible = true;
+                    break;
+                }
+            }
+            let regions = block_on(read_conflict(&store, &tree, &conflict.path)).ok();
+            if !is_visible || regions.as_ref() != Some(&conflict.regions) {
+                return Err(VcsError::ConflictOutdated(conflict.path.clone()).into());
+            }
+
+            let path = RepoPathBuf::from_internal_string(conflict.path.as_str())?;
+            let value = tree.path_value(&path)?;
+            let id = block_on(store.write_file(&path, &mut content.as_bytes()))?;
+            let executable = value
+                .to_executable_merge()
+                .and_then(|merge| merge.resolve_trivial(SameChange::Accept).copied())
+                .flatten()
+                .unwrap_or(false);
+            let copy_id = value
+                .adds()
+                .find_map(|term| match term {
+                    Some(TreeValue::File { copy_id, .. }) => Some(copy_id.clone()),
+                    _ => None,
+                })
+                .unwrap_or_else(CopyId::placeholder);
+
+            let mut builder = MergedTreeBuilder::new(tree);
+            builder.set_or_remove(
+                path,
+                Merge::normal(TreeValue::File {
+                    id,
+                    executable,
+                    copy_id,
+                }),
+            );
+            mut_repo
+                .rewrite_commit(&commit)
+                .set_tree(builder.write_tree()?)
+                .write()?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Splits the two-sided conflict in `path` into regions, merging what merges
+/// cleanly the way `jj` materializes conflicts.
+async fn read_conflict(
+    store: &Arc<Store>,
+    tree: &MergedTree,
+    path: &str,
+) -> Result<Vec<ConflictRegion>> {
+    let unsupported = |reason: &str| VcsError::UnsupportedConflict {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    };
+    let repo_path = RepoPathBuf::from_internal_string(path)?;
+    let value = tree.path_value(&repo_path)?;
+    if value.is_resolved() {
+        return Err(unsupported("it has no conflict").into());
+    }
+    if value.num_sides() != 2 {
+        return Err(unsupported("it has more than two sides").into());
+    }
+    let contents = read_terms(store, &repo_path, &value)
+        .await?
+        .ok_or_else(|| unsupported("not every side is a text file"))?;
+
+    let options = MergeOptions {
+        hunk_level: FileMergeHunkLevel::Line,
+        same_change: SameChange::Accept,
+    };
+    let hunks = match files::merge_hunks(&contents, &options) {
+        MergeResult::Resolved(text) => vec![Merge::resolved(text)],
+        MergeResult::Conflict(hunks) => hunks,
+    };
+    let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+    let mut regions: Vec<ConflictRegion> = Vec::new();
+    for hunk in hunks {
+        if let Some(resolved) = hunk.as_resolved() {
+            match regions.last_mut() {
+                Some(ConflictRegion::Resolved(last)) => last.push_str(&text(resolved)),
+                _ => regions.push(ConflictRegion::Resolved(text(resolved))),
+            }
+        } else {
+            regions.push(ConflictRegion::Conflict {
+                base: hunk.get_remove(0).map(|t| text(t)).unwrap_or_default(),
+                ours: hunk.get_add(0).map(|t| text(t)).unwrap_or_default(),
+                theirs: hunk.get_add(1).map(|t| text(t)).unwrap_or_default(),
+            });
+        }
+    }
+    Ok(regions)
+}
+
+/// Reads every term of a file conflict; an absent term reads as empty.
+/// Returns `None` if a term is not a UTF-8 text file.
+async fn read_terms(
+    store: &Arc<Store>,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+) -> Result<Option<Merge<Vec<u8>>>> {
+    let Some(file_ids) = value.to_file_merge() else {
+        return Ok(None);
+    };
+    let mut terms = Vec::new();
+    for id in file_ids.iter() {
+        let mut content = Vec::new();
+        if let Some(id) = id {
+            store
+                .read_file(path, id)
+                .await?
+                .read_to_end(&mut content)
+                .await?;
+        }
+        if super::is_binary(&content) || std::str::from_utf8(&content).is_err() {
+            return Ok(None);
+        }
+        terms.push(content);
+    }
+    Ok(Some(Merge::from_vec(terms)))
+}
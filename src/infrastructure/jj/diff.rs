@@ -1,36 +1,41 @@
 use super::{JjAdapter, MAX_DIFF_SIZE};
-use crate::domain::models::CommitId;
+use crate::domain::{
+    diff::{Diff, DiffHeader, DiffLine, DiffLineKind, FileDiff, FileSelection, Hunk},
+    error::VcsError,
+    models::{CommitId, FileStatus},
+};
 use anyhow::{anyhow, Result};
-use futures::StreamExt;
+use futures::{executor::block_on, StreamExt};
 use jj_lib::{
     backend::{CommitId as JjCommitId, TreeValue},
     matchers::EverythingMatcher,
+    merge::{Merge, MergedTreeValue},
+    merged_tree::MergedTree,
+    merged_tree_builder::MergedTreeBuilder,
     object_id::ObjectId,
     repo::Repo,
+    repo_path::{RepoPath, RepoPathBuf},
+    store::Store,
 };
+use similar::{ChangeTag, InlineChange, TextDiff};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
 use tokio::io::AsyncReadExt;
 
 impl JjAdapter {
-    pub(crate) async fn get_commit_diff_impl(&self, commit_id: &CommitId) -> Result<String> {
+    pub(crate) async fn get_commit_diff_impl(&self, commit_id: &CommitId) -> Result<Diff> {
         let (repo, _) = self.get_repo_and_ws().await?;
 
         let id =
             JjCommitId::try_from_hex(&commit_id.0).ok_or_else(|| anyhow!("Invalid commit ID"))?;
         let commit = repo.store().get_commit(&id)?;
 
-        let mut output = String::new();
         let author = commit.author();
         let timestamp_sec = author.timestamp.timestamp.0 / 1000;
         let datetime = chrono::DateTime::from_timestamp(timestamp_sec, 0)
             .unwrap_or_default()
             .with_timezone(&chrono::Local);
-        let timestamp = datetime.format("%Y-%m-%d %H:%M").to_string();
-
-        output.push_str(&format!("Commit ID: {}\n", commit.id().hex()));
-        output.push_str(&format!(
-            "Change ID: {}\n",
-            super::format_change_id(commit.change_id())
-        ));
 
         let bookmarks = repo
             .view()
@@ -38,28 +43,30 @@ impl JjAdapter {
             .filter(|(_, target)| target.added_ids().any(|added_id| *added_id == id))
             .map(|(name, _)| name.as_str().to_string())
             .collect::<Vec<_>>();
-        if !bookmarks.is_empty() {
-            output.push_str(&format!("Bookmarks: {}\n", bookmarks.join(", ")));
-        }
+        let tags = repo
+            .view()
+            .local_tags()
+            .filter(|(_, target)| target.added_ids().any(|added_id| *added_id == id))
+            .map(|(name, _)| name.as_str().to_string())
+            .collect::<Vec<_>>();
 
-        output.push_str(&format!(
-            "Author   : {} <{}> ({})\n",
-            author.name, author.email, timestamp
-        ));
-        output.push_str(&format!(
-            "    {}\n\n",
-            commit.description().replace('\n', "\n    ")
-        ));
+        let header = DiffHeader {
+            commit_id: commit.id().hex(),
+            change_id: super::format_change_id(commit.change_id()),
+            bookmarks,
+            tags,
+            author_name: author.name.clone(),
+            author_email: author.email.clone(),
+            timestamp: datetime.format("%Y-%m-%d %H:%M").to_string(),
+            description: commit.description().to_string(),
+        };
 
-        let mut parents = commit.parents();
+        // Against the merged parents, like `jj diff` and the split and
+        // squash selections made on these hunks.
         let tree = commit.tree();
-        let parent_tree = if let Some(parent_res) = parents.next() {
-            let parent: jj_lib::commit::Commit = parent_res?;
-            parent.tree()
-        } else {
-            tree.clone()
-        };
+        let parent_tree = commit.parent_tree(repo.as_ref())?;
 
+        let mut files = Vec::new();
         let mut stream = parent_tree.diff_stream(&tree, &EverythingMatcher);
         while let Some(entry) = stream.next().await {
             let _permit = self
@@ -74,73 +81,305 @@ impl JjAdapter {
             }
             let values = entry.values?;
 
-            output.push_str(&format!("File: {path_str}\n"));
-
-            if !values.after.is_resolved() {
-                output.push_str("Status: Conflicted\n");
+            let status = if !values.after.is_resolved() {
+                FileStatus::Conflicted
             } else if values.before.is_absent() {
-                output.push_str("Status: Added\n");
+                FileStatus::Added
             } else if values.after.is_absent() {
-                output.push_str("Status: Deleted\n");
+                FileStatus::Deleted
             } else {
-                output.push_str("Status: Modified\n");
+                FileStatus::Modified
+            };
+
+            let before = read_side(repo.store(), &repo_path, &values.before).await?;
+            let after = read_side(repo.store(), &repo_path, &values.after).await?;
+            let (is_binary, hunks) = match (before, after) {
+                (Some(before), Some(after)) => (
+                    false,
+                    build_hunks(
+                        &String::from_utf8_lossy(&before),
+                        &String::from_utf8_lossy(&after),
+                    ),
+                ),
+                _ => (true, Vec::new()),
+            };
+
+            files.push(FileDiff {
+                path: path_str.to_string(),
+                status,
+                is_binary,
+                hunks,
+            });
+        }
+
+        Ok(Diff { header, files })
+    }
+}
+
+/// Reads the file contents on one side of a change, concatenating the terms
+/// of a conflict. Returns `None` if any of them looks binary.
+async fn read_side(
+    store: &Arc<Store>,
+    repo_path: &RepoPath,
+    values: &MergedTreeValue,
+) -> Result<Option<Vec<u8>>> {
+    let mut content = Vec::new();
+    for value in values {
+        if let Some(TreeValue::File { id, .. }) = value.as_ref() {
+            let mut reader = store.read_file(repo_path, id).await?.take(MAX_DIFF_SIZE);
+            let mut chunk = vec![0u8; 1024];
+            let n = reader.read(&mut chunk).await?;
+            chunk.truncate(n);
+            if super::is_binary(&chunk) {
+                return Ok(None);
             }
+            content.extend_from_slice(&chunk);
+            reader.read_to_end(&mut content).await?;
+        }
+    }
+    Ok(Some(content))
+}
 
-            let mut before_content = Vec::new();
-            let mut before_is_binary = false;
-            for value in &values.before {
-                if let Some(TreeValue::File { id, .. }) = value.as_ref() {
-                    let mut reader = repo
-                        .store()
-                        .read_file(&repo_path, id)
-                        .await?
-                        .take(MAX_DIFF_SIZE);
-                    let mut chunk = vec![0u8; 1024];
-                    let n = reader.read(&mut chunk).await?;
-                    chunk.truncate(n);
-                    if super::is_binary(&chunk) {
-                        before_is_binary = true;
-                        break;
-                    }
-                    before_content.extend_from_slice(&chunk);
-                    reader.read_to_end(&mut before_content).await?;
-                }
+/// Lines added to and removed from a file. A file that is binary on either
+/// side counts as `(0, 0)`, since it has no lines.
+pub(crate) async fn count_lines(
+    store: &Arc<Store>,
+    repo_path: &RepoPath,
+    before: &MergedTreeValue,
+    after: &MergedTreeValue,
+) -> Result<(usize, usize)> {
+    let (Some(before), Some(after)) = (
+        read_side(store, repo_path, before).await?,
+        read_side(store, repo_path, after).await?,
+    ) else {
+        return Ok((0, 0));
+    };
+    let before = String::from_utf8_lossy(&before);
+    let after = String::from_utf8_lossy(&after);
+    let mut counts = (0, 0);
+    for change in TextDiff::from_lines(before.as_ref(), after.as_ref()).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => counts.0 += 1,
+            ChangeTag::Delete => counts.1 += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    Ok(counts)
+}
+
+/// Builds the tree with only the selected parts of the changes from `base`
+/// to `target` applied on top of `base`.
+pub(crate) fn select_changes(
+    base: &MergedTree,
+    target: &MergedTree,
+    files: &[FileSelection],
+) -> Result<MergedTree> {
+    let store = base.store().clone();
+    let mut builder = MergedTreeBuilder::new(base.clone());
+    for file in files {
+        let path = RepoPathBuf::from_internal_string(file.path.as_str())?;
+        let after = target.path_value(&path)?;
+        let value = match &file.lines {
+            None => after,
+            Some(lines) => {
+                let before = base.path_value(&path)?;
+                block_on(partial_file(&store, &path, &before, &after, file, lines))?
             }
+        };
+        builder.set_or_remove(path, value);
+    }
+    Ok(builder.write_tree()?)
+}
+
+/// Writes the version of a file with only the selected lines changed. The
+/// selection is refused if the file no longer diffs to the hunks it was
+/// made on, or is too large for the diff panel to have shown all of them.
+async fn partial_file(
+    store: &Arc<Store>,
+    path: &RepoPath,
+    before: &MergedTreeValue,
+    after: &MergedTreeValue,
+    file: &FileSelection,
+    lines: &[(usize, usize)],
+) -> Result<MergedTreeValue> {
+    let outdated = || VcsError::SelectionOutdated(file.path.clone());
+    let (Some(old), Some(new)) = (
+        read_plain_file(store, path, before).await?,
+        read_plain_file(store, path, after).await?,
+    ) else {
+        return Err(outdated().into());
+    };
+    if old.len() as u64 > MAX_DIFF_SIZE || new.len() as u64 > MAX_DIFF_SIZE {
+        return Err(VcsError::FileTooLarge(file.path.clone()).into());
+    }
+    let hunks = build_hunks(
+        &String::from_utf8_lossy(&old),
+        &String::from_utf8_lossy(&new),
+    );
+    if hunks != file.hunks {
+        return Err(outdated().into());
+    }
+
+    let selected: HashSet<_> = lines.iter().copied().collect();
+    let content = apply_lines(&old, &new, &hunks, &selected);
+    let id = store.write_file(path, &mut content.as_slice()).await?;
+    let metadata = [before, after]
+        .into_iter()
+        .find_map(|value| match value.as_resolved() {
+            Some(Some(TreeValue::File {
+                executable,
+                copy_id,
+                ..
+            })) => Some((*executable, copy_id.clone())),
+            _ => None,
+        });
+    let Some((executable, copy_id)) = metadata else {
+        return Err(outdated().into());
+    };
+    Ok(Merge::normal(TreeValue::File {
+        id,
+        executable,
+        copy_id,
+    }))
+}
+
+/// Reads a resolved regular file, up to one byte past `MAX_DIFF_SIZE` so
+/// oversized files can be told apart; an absent file reads as empty.
+/// Returns `None` for conflicts, symlinks and other non-file values.
+async fn read_plain_file(
+    store: &Arc<Store>,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+) -> Result<Option<Vec<u8>>> {
+    match value.as_resolved() {
+        Some(None) => Ok(Some(Vec::new())),
+        Some(Some(TreeValue::File { id, .. })) => {
+            let mut content = Vec::new();
+            store
+                .read_file(path, id)
+                .await?
+                .take(MAX_DIFF_SIZE + 1)
+                .read_to_end(&mut content)
+                .await?;
+            Ok(Some(content))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Rebuilds `old` with only the selected `(hunk, line)` changes of `hunks`
+/// applied: selected removals are dropped and selected additions taken
+/// from `new`.
+pub(crate) fn apply_lines(
+    old: &[u8],
+    new: &[u8],
+    hunks: &[Hunk],
+    selected: &HashSet<(usize, usize)>,
+) -> Vec<u8> {
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|&b| b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.split_inclusive(|&b| b == b'\n').collect();
+    let mut content = Vec::with_capacity(old.len().max(new.len()));
+    // Index of the next old line not yet copied or dropped.
+    let mut pos = 0;
+    let copy_old_until = |content: &mut Vec<u8>, pos: &mut usize, end: usize| {
+        for line in old_lines.get(*pos..end).unwrap_or_default() {
+            content.extend_from_slice(line);
+        }
+        *pos = (*pos).max(end);
+    };
 
-            let mut after_content = Vec::new();
-            let mut after_is_binary = false;
-            for value in &values.after {
-                if let Some(TreeValue::File { id, .. }) = value.as_ref() {
-                    let mut reader = repo
-                        .store()
-                        .read_file(&repo_path, id)
-                        .await?
-                        .take(MAX_DIFF_SIZE);
-                    let mut chunk = vec![0u8; 1024];
-                    let n = reader.read(&mut chunk).await?;
-                    chunk.truncate(n);
-                    if super::is_binary(&chunk) {
-                        after_is_binary = true;
-                        break;
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        for (line_idx, line) in hunk.lines.iter().enumerate() {
+            let is_selected = selected.contains(&(hunk_idx, line_idx));
+            match (line.kind, line.old_lineno, line.new_lineno) {
+                (DiffLineKind::Context, Some(n), _) => copy_old_until(&mut content, &mut pos, n),
+                (DiffLineKind::Removed, Some(n), _) => {
+                    copy_old_until(&mut content, &mut pos, n - 1);
+                    if is_selected {
+                        pos = n;
+                    } else {
+                        copy_old_until(&mut content, &mut pos, n);
                     }
-                    after_content.extend_from_slice(&chunk);
-                    reader.read_to_end(&mut after_content).await?;
                 }
+                (DiffLineKind::Added, _, Some(n)) if is_selected => {
+                    if let Some(line) = new_lines.get(n - 1) {
+                        content.extend_from_slice(line);
+                    }
+                }
+                _ => {}
             }
+        }
+    }
+    copy_old_until(&mut content, &mut pos, old_lines.len());
+    content
+}
 
-            if before_is_binary || after_is_binary {
-                output.push_str("    (binary file)\n\n");
-                continue;
+/// Groups a line diff into hunks with three lines of context. Replaced
+/// lines that are similar enough to their counterparts get word-level
+/// emphasis, like `jj diff --color-words`.
+pub(crate) fn build_hunks(before: &str, after: &str) -> Vec<Hunk> {
+    let diff = TextDiff::from_lines(before, after);
+    diff.grouped_ops(3)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_inline_changes(op))
+                .map(|change| inline_change_to_line(&change))
+                .collect();
+            Some(Hunk {
+                old_start: hunk_start(&old_range),
+                old_len: old_range.len(),
+                new_start: hunk_start(&new_range),
+                new_len: new_range.len(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+fn inline_change_to_line(change: &InlineChange<'_, str>) -> DiffLine {
+    let mut content = String::new();
+    let mut emphasis: Vec<Range<usize>> = Vec::new();
+    for (emphasized, value) in change.iter_strings_lossy() {
+        let start = content.len();
+        content.push_str(&value);
+        if emphasized {
+            match emphasis.last_mut() {
+                Some(last) if last.end == start => last.end = content.len(),
+                _ => emphasis.push(start..content.len()),
             }
+        }
+    }
 
-            let before_str = String::from_utf8_lossy(&before_content);
-            let after_str = String::from_utf8_lossy(&after_content);
+    let trimmed_len = content.trim_end_matches(['\n', '\r']).len();
+    content.truncate(trimmed_len);
+    emphasis.retain_mut(|range| {
+        range.end = range.end.min(trimmed_len);
+        range.start < range.end
+    });
 
-            let diff = similar::TextDiff::from_lines(&before_str, &after_str);
-            output.push_str(&diff.unified_diff().context_radius(3).to_string());
-            output.push('\n');
-        }
+    DiffLine {
+        kind: match change.tag() {
+            ChangeTag::Equal => DiffLineKind::Context,
+            ChangeTag::Insert => DiffLineKind::Added,
+            ChangeTag::Delete => DiffLineKind::Removed,
+        },
+        content,
+        old_lineno: change.old_index().map(|i| i + 1),
+        new_lineno: change.new_index().map(|i| i + 1),
+        emphasis,
+    }
+}
 
-        Ok(output)
+/// 1-based start line, as written in a unified diff hunk header.
+fn hunk_start(range: &Range<usize>) -> usize {
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
     }
 }
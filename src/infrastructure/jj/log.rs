@@ -1,206 +1,105 @@
+use super::repo::CommitInfo;
+use super::revset::RevsetEnv;
 use super::JjAdapter;
-use crate::domain::models::{CommitId, FileChange, FileStatus, GraphRow, RepoStatus};
+use crate::domain::models::{
+    CommitId, FileChange, FileStatus, GraphRow, LineCounts, RepoStatus, Rewrite, RewritePreview,
+};
 use anyhow::{anyhow, Result};
 use futures::StreamExt;
 use jj_lib::{
-    backend::CommitId as JjCommitId, matchers::EverythingMatcher, object_id::ObjectId,
-    ref_name::WorkspaceNameBuf,
+    backend::{ChangeId, CommitId as JjCommitId},
+    matchers::EverythingMatcher,
+    object_id::ObjectId,
+    repo::Repo,
 };
 use std::collections::{HashSet, VecDeque};
+use std::path::Path;
 
 impl JjAdapter {
     pub(crate) async fn get_operation_log_impl(
         &self,
-        heads: Option<Vec<CommitId>>,
+        offset: usize,
         limit: usize,
         revset: Option<String>,
     ) -> Result<RepoStatus> {
         let (repo, ws_root) = self.get_repo_and_ws().await?;
         let op_id = repo.operation().id().clone().hex();
 
-        let (workspace_id, _): (&WorkspaceNameBuf, &JjCommitId) = repo
-            .view()
-            .wc_commit_ids()
-            .iter()
-            .next()
-            .ok_or_else(|| anyhow!("No working copy found"))?;
-
-        let workspace_id_buf: WorkspaceNameBuf = workspace_id.clone();
+        let workspace_id = self.workspace_name().await?;
         let repo_arc = repo.clone();
-        let ws_id_clone = workspace_id_buf.clone();
+        let ws_id_clone = workspace_id.clone();
         let ws_root_for_closure = ws_root.clone();
+        let settings = self.user_settings.clone();
 
-        let commit_infos = tokio::task::spawn_blocking(move || {
-            let mut visited = HashSet::<JjCommitId>::new();
-            let mut queue = VecDeque::new();
-            let mut results = Vec::new();
-
-            if let Some(revset_str) = revset {
-                let output = std::process::Command::new("jj")
-                    .arg("--color")
-                    .arg("never")
-                    .arg("--no-pager")
-                    .arg("--repository")
-                    .arg(".")
-                    .arg("log")
-                    .arg("-r")
-                    .arg(&revset_str)
-                    .arg("-T")
-                    .arg("commit_id ++ \"\\n\"")
-                    .arg("--no-graph")
-                    .current_dir(&ws_root_for_closure)
-                    .output();
-
-                match output {
-                    Ok(output) => {
-                        if output.status.success() {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            let ids: Vec<String> = stdout
-                                .lines()
-                                .map(|l| l.trim().to_string())
-                                .filter(|l| !l.is_empty())
-                                .collect();
-
-                            for id_hex in ids.iter().take(limit) {
-                                if let Some(id) = JjCommitId::try_from_hex(id_hex) {
-                                    if let Ok(info) = super::repo::build_commit_info(&repo_arc, &id, &ws_id_clone) {
-                                        results.push(info);
-                                    }
-                                }
-                            }
-                            return Ok(results);
-                        }
-                        let stderr = String::from_utf8_lossy(&output.stderr);
-                        return Err(anyhow!("Jujutsu error: {}", stderr.trim()));
-                    }
-                    Err(e) => {
-                        return Err(anyhow!("Failed to execute 'jj' command: {e}. Is 'jj' installed and in your PATH?"));
-                    }
-                }
-            }
-
-            if let Some(heads) = heads {
-                for head in heads {
-                    if let Some(id) = JjCommitId::try_from_hex(&head.0) {
-                        queue.push_back(id);
-                    }
-                }
-            } else {
-                for head_id in repo_arc.view().heads() {
-                    queue.push_back(head_id.clone());
-                }
-            }
-
-            while let Some(id) = queue.pop_front() {
-                if results.len() >= limit {
-                    break;
-                }
-                if visited.contains(&id) {
-                    continue;
-                }
-                visited.insert(id.clone());
-
-                if let Ok(info) = super::repo::build_commit_info(&repo_arc, &id, &ws_id_clone) {
-                    for parent_id in info.commit.parent_ids() {
-                        queue.push_back(parent_id.clone());
-                    }
-                    results.push(info);
-                }
-            }
-            Ok::<_, anyhow::Error>(results)
+        let (commit_infos, has_more) = tokio::task::spawn_blocking(move || {
+            let env = RevsetEnv {
+                settings: &settings,
+                workspace_name: &ws_id_clone,
+                workspace_root: &ws_root_for_closure,
+            };
+            commit_infos(repo_arc.as_ref(), &env, offset, limit, revset.as_deref())
         })
         .await??;
 
-        let graph_rows = futures::stream::iter(commit_infos)
-            .map(|info| async move {
-                let commit = info.commit;
-                let parent_tree = info.parent_tree;
-                let parent_ids = info.parent_ids;
-                let is_working_copy = info.is_working_copy;
-                let is_immutable = info.is_immutable;
-                let has_conflict = info.has_conflict;
-                let bookmarks = info.bookmarks;
-
-                let description = commit.description().to_string();
-                let change_id = super::format_change_id(commit.change_id());
-                let author = commit.author().email.clone();
-                let timestamp_secs = commit.author().timestamp.timestamp.0 / 1000;
-                let datetime = chrono::DateTime::from_timestamp(timestamp_secs, 0)
-                    .unwrap_or_default()
-                    .with_timezone(&chrono::Local);
-                let timestamp = datetime.format("%Y-%m-%d %H:%M").to_string();
-                let commit_id_str = commit.id().hex();
-                let commit_id_short = commit_id_str[..8.min(commit_id_str.len())].to_string();
-                let commit_id = CommitId(commit_id_str);
-
-                let mut changed_files = Vec::new();
-                if let Some(p_tree) = parent_tree {
-                    let commit_tree = commit.tree();
-                    let mut stream = p_tree.diff_stream(&commit_tree, &EverythingMatcher);
-                    while let Some(entry) = stream.next().await {
-                        let status = if let Ok(values) = entry.values {
-                            if !values.after.is_resolved() {
-                                FileStatus::Conflicted
-                            } else if values.before.is_absent() {
-                                FileStatus::Added
-                            } else if values.after.is_absent() {
-                                FileStatus::Deleted
-                            } else {
-                                FileStatus::Modified
-                            }
-                        } else {
-                            FileStatus::Modified
-                        };
-
-                        let path = entry.path.as_internal_file_string().to_string();
-                        if path.contains("..") {
-                            continue;
-                        }
-                        changed_files.push(FileChange { path, status });
-                    }
-                }
-
-                let change_id_short = change_id[..8.min(change_id.len())].to_string();
-
-                GraphRow {
-                    commit_id,
-                    commit_id_short,
-                    change_id,
-                    change_id_short,
-                    description,
-                    author,
-                    timestamp,
-                    timestamp_secs,
-                    is_working_copy,
-                    is_immutable,
-                    has_conflict,
-                    parents: parent_ids,
-                    bookmarks,
-                    changed_files,
-                    visual: crate::domain::models::GraphRowVisual::default(),
-                }
-            })
-            .buffered(50)
-            .collect::<Vec<_>>()
-            .await;
+        let graph_rows = graph_rows(commit_infos, self.count_lines).await;
 
-        let wc_id = match repo.view().get_wc_commit_id(workspace_id) {
+        let wc_id = match repo.view().get_wc_commit_id(&workspace_id) {
             Some(id) => CommitId(id.hex()),
             None => CommitId(String::new()),
         };
 
-        let repo_name = ws_root
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-
         Ok(RepoStatus {
-            repo_name,
+            repo_name: repo_name(&ws_root),
             operation_id: op_id,
             workspace_id: workspace_id.as_str().to_string(),
             working_copy_id: wc_id,
             graph: graph_rows,
+            has_more,
+        })
+    }
+
+    /// The first `limit` rows of the graph as `rewrite` would leave it,
+    /// read from a transaction that is never committed.
+    pub(crate) async fn preview_rewrite_impl(
+        &self,
+        rewrite: &Rewrite,
+        limit: usize,
+        revset: Option<String>,
+    ) -> Result<RewritePreview> {
+        let rewrite = rewrite.clone();
+        let (commit_infos, has_more, new_conflicts, op_id, wc_id) = self
+            .dry_run(move |mut_repo, ctx| {
+                super::ops::apply_rewrite(mut_repo, ctx, &rewrite)?;
+                mut_repo.rebase_descendants()?;
+                let (infos, has_more) =
+                    commit_infos(mut_repo, &ctx.revset_env(), 0, limit, revset.as_deref())?;
+
+                let base_repo = mut_repo.base_repo().clone();
+                let mut new_conflicts = HashSet::new();
+                for info in infos.iter().filter(|info| info.has_conflict) {
+                    if !was_conflicted(base_repo.as_ref(), info.commit.change_id())? {
+                        new_conflicts.insert(CommitId(info.commit.id().hex()));
+                    }
+                }
+                let wc_id = match mut_repo.view().get_wc_commit_id(&ctx.workspace_name) {
+                    Some(id) => CommitId(id.hex()),
+                    None => CommitId(String::new()),
+                };
+                let op_id = base_repo.operation().id().hex();
+                Ok((infos, has_more, new_conflicts, op_id, wc_id))
+            })
+            .await?;
+
+        Ok(RewritePreview {
+            status: RepoStatus {
+                repo_name: repo_name(&self.workspace_root),
+                operation_id: op_id,
+                workspace_id: self.workspace_name().await?.as_str().to_string(),
+                working_copy_id: wc_id,
+                graph: graph_rows(commit_infos, self.count_lines).await,
+                has_more,
+            },
+            new_conflicts,
         })
     }
 
@@ -222,22 +121,184 @@ impl JjAdapter {
             Err(anyhow!("jj evolog failed: {}", stderr.trim()))
         }
     }
+}
 
-    pub(crate) async fn operation_log_impl(&self) -> Result<String> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("op")
-            .arg("log")
-            .arg("--color")
-            .arg("always")
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj op log failed: {}", stderr.trim()))
+/// Reads `limit` rows of the graph of `repo`, after the first `offset`: the
+/// commits `revset` selects, or else everything reachable from the heads.
+/// Also says whether there are more.
+fn commit_infos(
+    repo: &dyn Repo,
+    env: &RevsetEnv,
+    offset: usize,
+    limit: usize,
+    revset: Option<&str>,
+) -> Result<(Vec<CommitInfo>, bool)> {
+    let mut visited = HashSet::<JjCommitId>::new();
+    let mut queue = VecDeque::new();
+    let mut results = Vec::new();
+
+    let is_immutable = env.immutable_fn(repo)?;
+    let build = |id: &JjCommitId| {
+        super::repo::build_commit_info(repo, id, env.workspace_name, is_immutable(id)?)
+    };
+
+    if let Some(revset_str) = revset {
+        // One extra commit tells whether there is another page.
+        let ids = env.evaluate(repo, revset_str, offset + limit + 1)?;
+        let has_more = ids.len() > offset + limit;
+        for id in ids.iter().skip(offset).take(limit) {
+            results.push(build(id)?);
+        }
+        return Ok((results, has_more));
+    }
+
+    for head_id in repo.view().heads() {
+        queue.push_back(head_id.clone());
+    }
+
+    // Earlier pages are walked again, without loading their details,
+    // so that this page continues where they stopped.
+    let mut skipped = 0;
+    while let Some(id) = queue.pop_front() {
+        if results.len() >= limit {
+            queue.push_front(id);
+            break;
+        }
+        if visited.contains(&id) {
+            continue;
+        }
+        visited.insert(id.clone());
+
+        if skipped < offset {
+            if let Ok(commit) = repo.store().get_commit(&id) {
+                queue.extend(commit.parent_ids().iter().cloned());
+                skipped += 1;
+            }
+            continue;
+        }
+        if let Ok(info) = build(&id) {
+            for parent_id in info.commit.parent_ids() {
+                queue.push_back(parent_id.clone());
+            }
+            results.push(info);
+        }
+    }
+    let has_more = queue.iter().any(|id| !visited.contains(id));
+    Ok((results, has_more))
+}
+
+async fn graph_rows(commit_infos: Vec<CommitInfo>, count_lines: bool) -> Vec<GraphRow> {
+    futures::stream::iter(commit_infos)
+        .map(|info| async move {
+            let commit = info.commit;
+            let parent_tree = info.parent_tree;
+            let parent_ids = info.parent_ids;
+            let is_working_copy = info.is_working_copy;
+            let workspaces = info.workspaces;
+            let is_immutable = info.is_immutable;
+            let has_conflict = info.has_conflict;
+            let bookmarks = info.bookmarks;
+            let tags = info.tags;
+
+            let description = commit.description().to_string();
+            let change_id = super::format_change_id(commit.change_id());
+            let author = commit.author().email.clone();
+            let committer = commit.committer().email.clone();
+            let timestamp_secs = commit.author().timestamp.timestamp.0 / 1000;
+            let datetime = chrono::DateTime::from_timestamp(timestamp_secs, 0)
+                .unwrap_or_default()
+                .with_timezone(&chrono::Local);
+            let timestamp = datetime.format("%Y-%m-%d %H:%M").to_string();
+            let commit_id_str = commit.id().hex();
+            let commit_id_short = commit_id_str[..8.min(commit_id_str.len())].to_string();
+            let commit_id = CommitId(commit_id_str);
+
+            let mut changed_files = Vec::new();
+            let mut line_counts = count_lines.then(LineCounts::default);
+            if let Some(p_tree) = parent_tree {
+                let commit_tree = commit.tree();
+                let mut stream = p_tree.diff_stream(&commit_tree, &EverythingMatcher);
+                while let Some(entry) = stream.next().await {
+                    let path = entry.path.as_internal_file_string().to_string();
+                    if path.contains("..") {
+                        continue;
+                    }
+                    let status = if let Ok(values) = &entry.values {
+                        if let Some(counts) = &mut line_counts {
+                            let (added, removed) = super::diff::count_lines(
+                                commit.store(),
+                                &entry.path,
+                                &values.before,
+                                &values.after,
+                            )
+                            .await
+                            .unwrap_or_default();
+                            counts.added += added;
+                            counts.removed += removed;
+                        }
+                        if !values.after.is_resolved() {
+                            FileStatus::Conflicted
+                        } else if values.before.is_absent() {
+                            FileStatus::Added
+                        } else if values.after.is_absent() {
+                            FileStatus::Deleted
+                        } else {
+                            FileStatus::Modified
+                        }
+                    } else {
+                        FileStatus::Modified
+                    };
+
+                    changed_files.push(FileChange { path, status });
+                }
+            }
+
+            let change_id_short = change_id[..8.min(change_id.len())].to_string();
+
+            GraphRow {
+                commit_id,
+                commit_id_short,
+                change_id,
+                change_id_short,
+                description,
+                author,
+                committer,
+                timestamp,
+                timestamp_secs,
+                is_working_copy,
+                workspaces,
+                is_immutable,
+                has_conflict,
+                parents: parent_ids,
+                bookmarks,
+                tags,
+                changed_files,
+                line_counts,
+                visual: crate::domain::models::GraphRowVisual::default(),
+            }
+        })
+        .buffered(50)
+        .collect::<Vec<_>>()
+        .await
+}
+
+fn repo_name(ws_root: &Path) -> String {
+    ws_root
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Whether any visible revision of the change has a conflict.
+fn was_conflicted(repo: &dyn Repo, change_id: &ChangeId) -> Result<bool> {
+    let Some(targets) = repo.resolve_change_id(change_id)? else {
+        return Ok(false);
+    };
+    for (_, id) in targets.visible_with_offsets() {
+        if repo.store().get_commit(id)?.tree().has_conflict() {
+            return Ok(true);
         }
     }
+    Ok(false)
 }
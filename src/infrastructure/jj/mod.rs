@@ -1,4 +1,14 @@
-use crate::domain::{models::CommitId, vcs::VcsFacade};
+use crate::domain::{
+    bookmark::Bookmark,
+    conflict::FileConflict,
+    diff::{Diff, FileSelection},
+    models::{CommitId, RebaseLocation, RebaseMode, Rewrite, RewritePreview, SquashDestination},
+    operation::{Operation, OperationChanges},
+    push::{PushPlan, PushScope, PushedBookmark},
+    remote::Remote,
+    vcs::VcsFacade,
+    workspace::Workspace as WorkspaceInfo,
+};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use jj_lib::{
@@ -11,17 +21,25 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, Semaphore};
 
 pub mod bookmarks;
+pub mod conflict;
 pub mod diff;
 pub mod log;
+pub mod operation;
 pub mod ops;
 pub mod remote;
 pub mod repo;
+pub mod revset;
+pub mod tags;
+pub mod transaction;
+pub mod workspace;
 
 pub struct JjAdapter {
     pub(crate) workspace: Arc<Mutex<Option<Workspace>>>,
     pub(crate) workspace_root: PathBuf,
     pub(crate) user_settings: UserSettings,
     pub(crate) diff_semaphore: Arc<Semaphore>,
+    /// Whether graph rows come with the lines each revision changes.
+    pub(crate) count_lines: bool,
 }
 
 pub(crate) const MAX_DIFF_SIZE: u64 = 1024 * 1024; // 1MB
@@ -65,40 +83,27 @@ impl JjAdapter {
             }
         }
 
-        let mut current = Some(path.as_path());
-        let mut found_ws_root = None;
-        while let Some(current_path) = current {
-            let jj_repo_config = current_path.join(".jj").join("repo").join("config.toml");
+        let found_ws_root = path
+            .ancestors()
+            .find(|dir| dir.join(".jj").is_dir())
+            .map(std::path::Path::to_path_buf);
+        if let Some(ws_root) = &found_ws_root {
+            let jj_repo_config = repo_dir(&ws_root.join(".jj"))?.join("config.toml");
             if jj_repo_config.is_file() {
                 let text = std::fs::read_to_string(&jj_repo_config)
                     .with_context(|| format!("Failed to read repo config at {jj_repo_config:?}"))?;
                 let layer =
-                    jj_lib::config::ConfigLayer::parse(jj_lib::config::ConfigSource::User, &text)
+                    jj_lib::config::ConfigLayer::parse(jj_lib::config::ConfigSource::Repo, &text)
                         .with_context(|| {
-                        format!("Failed to parse user config at {jj_repo_config:?}")
+                        format!("Failed to parse repo config at {jj_repo_config:?}")
                     })?;
                 config.add_layer(layer);
-                found_ws_root = Some(current_path.to_path_buf());
-                break;
             }
-            current = current_path.parent();
         }
 
         let user_settings = UserSettings::from_config(config)?;
-        let store_factories = StoreFactories::default();
-        let mut working_copy_factories: HashMap<String, Box<dyn WorkingCopyFactory>> =
-            HashMap::new();
-        working_copy_factories.insert("local".to_string(), Box::new(LocalWorkingCopyFactory {}));
-
         let ws_root = found_ws_root.unwrap_or_else(|| path.clone());
-
-        let workspace = Workspace::load(
-            &user_settings,
-            &ws_root,
-            &store_factories,
-            &working_copy_factories,
-        )
-        .ok();
+        let workspace = load_workspace(&user_settings, &ws_root).ok();
 
         let workspace_root = if let Some(ws) = &workspace {
             ws.workspace_root().to_path_buf()
@@ -111,29 +116,54 @@ impl JjAdapter {
             workspace_root,
             user_settings,
             diff_semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_DIFFS)),
+            count_lines: false,
         })
     }
 
-    pub async fn check_version() -> Result<()> {
-        let output = tokio::process::Command::new("jj")
+    /// Also counts the lines each revision in the graph adds and removes,
+    /// which reads every file they change.
+    #[must_use]
+    pub fn counting_lines(mut self, count_lines: bool) -> Self {
+        self.count_lines = count_lines;
+        self
+    }
+
+    /// The repository behind the workspace, which every workspace of it
+    /// shares; `None` outside a workspace.
+    pub async fn repo_path(&self) -> Option<PathBuf> {
+        let ws = self.workspace.lock().await;
+        ws.as_ref().map(|ws| ws.repo_path().to_path_buf())
+    }
+
+    /// Checks the installed `jj` CLI, which is still used for the commands
+    /// Judo does not run natively yet. Returns a warning to surface in the UI
+    /// rather than failing, since describe/new/edit/rebase and friends work
+    /// without it.
+    pub async fn check_version() -> Option<String> {
+        let output = match tokio::process::Command::new("jj")
             .arg("--version")
             .output()
             .await
-            .context("Failed to execute 'jj --version'. Is 'jj' installed and in your PATH?")?;
-
-        if !output.status.success() {
-            return Err(anyhow::anyhow!("'jj --version' failed"));
-        }
+        {
+            Ok(output) if output.status.success() => output,
+            _ => {
+                return Some(
+                    "'jj' was not found in your PATH. Fetch, undo and other commands that \
+                     still shell out to jj will be unavailable."
+                        .to_string(),
+                )
+            }
+        };
 
         let version_str = String::from_utf8_lossy(&output.stdout);
         if !version_str.contains("0.38") {
-            return Err(anyhow::anyhow!(
+            return Some(format!(
                 "Judo expects jj version 0.38.x, but found: {}. \
-                 Using mismatched versions may lead to repository corruption or crashes.",
+                 Commands that shell out to jj may behave unexpectedly.",
                 version_str.trim()
             ));
         }
-        Ok(())
+        None
     }
 }
 
@@ -141,14 +171,14 @@ impl JjAdapter {
 impl VcsFacade for JjAdapter {
     async fn get_operation_log(
         &self,
-        heads: Option<Vec<CommitId>>,
+        offset: usize,
         limit: usize,
         revset: Option<String>,
     ) -> Result<crate::domain::models::RepoStatus> {
-        self.get_operation_log_impl(heads, limit, revset).await
+        self.get_operation_log_impl(offset, limit, revset).await
     }
 
-    async fn get_commit_diff(&self, commit_id: &CommitId) -> Result<String> {
+    async fn get_commit_diff(&self, commit_id: &CommitId) -> Result<Diff> {
         self.get_commit_diff_impl(commit_id).await
     }
 
@@ -172,6 +202,33 @@ impl VcsFacade for JjAdapter {
         self.squash_impl(commit_ids).await
     }
 
+    async fn squash_changes(
+        &self,
+        commit_id: &CommitId,
+        files: &[FileSelection],
+        destination: &SquashDestination,
+    ) -> Result<()> {
+        self.squash_changes_impl(commit_id, files, destination)
+            .await
+    }
+
+    async fn split(
+        &self,
+        commit_id: &CommitId,
+        files: &[FileSelection],
+        message: &str,
+    ) -> Result<()> {
+        self.split_impl(commit_id, files, message).await
+    }
+
+    async fn get_file_conflict(&self, commit_id: &CommitId, path: &str) -> Result<FileConflict> {
+        self.get_file_conflict_impl(commit_id, path).await
+    }
+
+    async fn resolve_conflict(&self, conflict: &FileConflict, content: &str) -> Result<()> {
+        self.resolve_conflict_impl(conflict, content).await
+    }
+
     async fn new_child(&self, commit_id: &CommitId) -> Result<()> {
         self.new_child_impl(commit_id).await
     }
@@ -196,8 +253,24 @@ impl VcsFacade for JjAdapter {
         self.parallelize_impl(commit_ids).await
     }
 
-    async fn rebase(&self, commit_ids: &[CommitId], destination: &str) -> Result<()> {
-        self.rebase_impl(commit_ids, destination).await
+    async fn rebase(
+        &self,
+        commit_ids: &[CommitId],
+        mode: RebaseMode,
+        location: RebaseLocation,
+        destination: &str,
+    ) -> Result<()> {
+        self.rebase_impl(commit_ids, mode, location, destination)
+            .await
+    }
+
+    async fn preview_rewrite(
+        &self,
+        rewrite: &Rewrite,
+        limit: usize,
+        revset: Option<String>,
+    ) -> Result<RewritePreview> {
+        self.preview_rewrite_impl(rewrite, limit, revset).await
     }
 
     async fn set_bookmark(&self, commit_id: &CommitId, name: &str) -> Result<()> {
@@ -208,12 +281,77 @@ impl VcsFacade for JjAdapter {
         self.delete_bookmark_impl(name).await
     }
 
+    async fn set_tag(&self, commit_id: &CommitId, name: &str, allow_move: bool) -> Result<()> {
+        self.set_tag_impl(commit_id, name, allow_move).await
+    }
+
+    async fn delete_tag(&self, name: &str) -> Result<()> {
+        self.delete_tag_impl(name).await
+    }
+
+    async fn push_tag(&self, name: &str) -> Result<()> {
+        self.push_tag_impl(name).await
+    }
+
+    async fn bookmarks(&self) -> Result<Vec<Bookmark>> {
+        self.bookmarks_impl().await
+    }
+
+    async fn rename_bookmark(&self, old_name: &str, new_name: &str) -> Result<()> {
+        self.rename_bookmark_impl(old_name, new_name).await
+    }
+
+    async fn move_bookmark(&self, name: &str, revision: &str, allow_backwards: bool) -> Result<()> {
+        self.move_bookmark_impl(name, revision, allow_backwards)
+            .await
+    }
+
+    async fn track_bookmark(&self, name: &str, remote: &str) -> Result<()> {
+        self.track_bookmark_impl(name, remote).await
+    }
+
+    async fn untrack_bookmark(&self, name: &str, remote: &str) -> Result<()> {
+        self.untrack_bookmark_impl(name, remote).await
+    }
+
+    async fn forget_bookmark(&self, name: &str) -> Result<()> {
+        self.forget_bookmark_impl(name).await
+    }
+
     async fn evolog(&self, commit_id: &CommitId) -> Result<String> {
         self.evolog_impl(commit_id).await
     }
 
-    async fn operation_log(&self) -> Result<String> {
-        self.operation_log_impl().await
+    async fn operation_log(&self, limit: usize) -> Result<Vec<Operation>> {
+        self.operation_log_impl(limit).await
+    }
+
+    async fn operation_changes(&self, op_id: &str) -> Result<OperationChanges> {
+        self.operation_changes_impl(op_id).await
+    }
+
+    async fn restore_operation(&self, op_id: &str) -> Result<()> {
+        self.restore_operation_impl(op_id).await
+    }
+
+    async fn revert_operation(&self, op_id: &str) -> Result<()> {
+        self.revert_operation_impl(op_id).await
+    }
+
+    async fn workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
+        self.workspaces_impl().await
+    }
+
+    async fn add_workspace(&self, path: &str) -> Result<()> {
+        self.add_workspace_impl(path).await
+    }
+
+    async fn forget_workspace(&self, name: &str) -> Result<()> {
+        self.forget_workspace_impl(name).await
+    }
+
+    async fn update_stale_workspace(&self, name: &str) -> Result<()> {
+        self.update_stale_workspace_impl(name).await
     }
 
     async fn undo(&self) -> Result<()> {
@@ -224,12 +362,36 @@ impl VcsFacade for JjAdapter {
         self.redo_impl().await
     }
 
-    async fn fetch(&self) -> Result<()> {
-        self.fetch_impl().await
+    async fn fetch(&self, remotes: &[String], branches: &[String]) -> Result<()> {
+        self.fetch_impl(remotes, branches).await
+    }
+
+    async fn remotes(&self) -> Result<Vec<Remote>> {
+        self.remotes_impl().await
+    }
+
+    async fn add_remote(&self, name: &str, url: &str) -> Result<()> {
+        self.add_remote_impl(name, url).await
+    }
+
+    async fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<()> {
+        self.rename_remote_impl(old_name, new_name).await
+    }
+
+    async fn remove_remote(&self, name: &str) -> Result<()> {
+        self.remove_remote_impl(name).await
     }
 
-    async fn push(&self, bookmark: Option<String>) -> Result<()> {
-        self.push_impl(bookmark).await
+    async fn set_remote_url(&self, name: &str, url: &str) -> Result<()> {
+        self.set_remote_url_impl(name, url).await
+    }
+
+    async fn push_preview(&self, remote: Option<String>, scope: &PushScope) -> Result<PushPlan> {
+        self.push_preview_impl(remote, scope.clone()).await
+    }
+
+    async fn push(&self, remote: &str, scope: &PushScope) -> Result<Vec<PushedBookmark>> {
+        self.push_impl(remote, scope).await
     }
 
     async fn init_repo(&self) -> Result<()> {
@@ -245,6 +407,34 @@ impl VcsFacade for JjAdapter {
     }
 }
 
+/// The repository a workspace's `.jj` directory belongs to. Workspaces
+/// added with `jj workspace add` keep a file at `.jj/repo` holding the path
+/// to the shared repository, relative to `.jj`.
+fn repo_dir(jj_dir: &std::path::Path) -> Result<PathBuf> {
+    let repo_dir = jj_dir.join("repo");
+    if !repo_dir.is_file() {
+        return Ok(repo_dir);
+    }
+    let pointer = std::fs::read_to_string(&repo_dir)
+        .with_context(|| format!("Failed to read repo pointer at {repo_dir:?}"))?;
+    let target = jj_dir.join(pointer.trim_end_matches(['\n', '\r']));
+    std::fs::canonicalize(&target)
+        .with_context(|| format!("Workspace points to a missing repo at {target:?}"))
+}
+
+/// Loads the workspace at `root`, which uses the local working copy like
+/// every workspace `jj` creates.
+pub(crate) fn load_workspace(settings: &UserSettings, root: &std::path::Path) -> Result<Workspace> {
+    let mut working_copy_factories: HashMap<String, Box<dyn WorkingCopyFactory>> = HashMap::new();
+    working_copy_factories.insert("local".to_string(), Box::new(LocalWorkingCopyFactory {}));
+    Ok(Workspace::load(
+        settings,
+        root,
+        &StoreFactories::default(),
+        &working_copy_factories,
+    )?)
+}
+
 pub(crate) fn is_binary(chunk: &[u8]) -> bool {
     if chunk.is_empty() {
         return false;
@@ -287,6 +477,1141 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_graph_pages() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path)?;
+
+        // Four revisions on top of the root: @ and three ancestors.
+        for _ in 0..3 {
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter.new_child(&status.working_copy_id).await?;
+        }
+        let ids = |status: &crate::domain::models::RepoStatus| {
+            status
+                .graph
+                .iter()
+                .map(|row| row.commit_id.clone())
+                .collect::<Vec<_>>()
+        };
+        for revset in [None, Some("::@".to_string())] {
+            let full = adapter.get_operation_log(0, 100, revset.clone()).await?;
+            assert_eq!(full.graph.len(), 5);
+            assert!(!full.has_more);
+
+            let mut paged = Vec::new();
+            for offset in [0, 2, 4] {
+                let page = adapter.get_operation_log(offset, 2, revset.clone()).await?;
+                assert_eq!(page.has_more, offset < 4);
+                paged.extend(ids(&page));
+            }
+            assert_eq!(paged, ids(&full));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_immutable_heads_from_config() -> Result<()> {
+        use crate::domain::error::VcsError;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        // root <- main <- @
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let main = status.working_copy_id.clone();
+        adapter.set_bookmark(&main, "main").await?;
+        adapter.new_child(&main).await?;
+        let immutable = |status: &crate::domain::models::RepoStatus| {
+            status
+                .graph
+                .iter()
+                .map(|row| row.is_immutable)
+                .collect::<Vec<_>>()
+        };
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(immutable(&status), [false, false, true]);
+
+        let repo_config = path.join(".jj/repo/config.toml");
+        std::fs::write(
+            &repo_config,
+            "[revset-aliases]\n'immutable_heads()' = 'main'\n",
+        )?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(immutable(&status), [false, true, true]);
+        let status = adapter.get_operation_log(0, 100, Some("@-".into())).await?;
+        assert_eq!(immutable(&status), [true]);
+
+        // Transactions refuse to rewrite it, whether it's picked, a
+        // destination, the parent changes move into or on a moved branch.
+        let main = status.graph[0].commit_id.clone();
+        let work = status.working_copy_id.clone();
+        let expected = VcsError::ImmutableRevision(main.0[..12].to_string());
+        let errors = [
+            adapter.describe_revision(&main.0, "changed").await,
+            adapter.abandon(std::slice::from_ref(&main)).await,
+            adapter
+                .squash_changes(&work, &[], &SquashDestination::Parent)
+                .await,
+            adapter
+                .rebase(
+                    std::slice::from_ref(&work),
+                    RebaseMode::Revisions,
+                    RebaseLocation::InsertBefore,
+                    "main",
+                )
+                .await,
+            adapter
+                .rebase(
+                    std::slice::from_ref(&work),
+                    RebaseMode::Branch,
+                    RebaseLocation::Destination,
+                    "root()",
+                )
+                .await,
+        ];
+        for result in errors {
+            assert_eq!(
+                result.unwrap_err().downcast_ref::<VcsError>(),
+                Some(&expected)
+            );
+        }
+
+        // A broken alias is reported as such, not as a bad filter.
+        std::fs::write(
+            &repo_config,
+            "[revset-aliases]\n'immutable_heads()' = 'nope'\n",
+        )?;
+        let adapter = JjAdapter::for_path(path)?;
+        let err = adapter.get_operation_log(0, 100, None).await.unwrap_err();
+        assert!(err.to_string().contains("immutable_heads()"));
+        assert!(err.downcast_ref::<VcsError>().is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_workspaces() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("main");
+        std::fs::create_dir(&path)?;
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        adapter.add_workspace("../second").await?;
+        let second_root = temp_dir.path().join("second");
+        assert!(second_root.join(".jj").is_dir());
+        let workspaces = adapter.workspaces().await?;
+        let names: Vec<_> = workspaces.iter().map(|ws| ws.name.as_str()).collect();
+        assert_eq!(names, ["default", "second"]);
+        assert!(workspaces[0].is_current);
+        assert!(!workspaces.iter().any(|ws| ws.is_stale));
+
+        // Each working copy is labelled, but only ours is `@`.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(status.workspace_id, "default");
+        let row = |id: &CommitId| status.graph.iter().find(|row| row.commit_id == *id);
+        let ours = row(&workspaces[0].working_copy_id).expect("default@ row");
+        assert!(ours.is_working_copy);
+        assert_eq!(ours.workspaces, ["default"]);
+        let theirs = row(&workspaces[1].working_copy_id).expect("second@ row");
+        assert!(!theirs.is_working_copy);
+        assert_eq!(theirs.workspaces, ["second"]);
+
+        // Opened from deep inside the other workspace, it finds its root and
+        // the shared repo's config through the `.jj/repo` pointer.
+        std::fs::write(
+            path.join(".jj/repo/config.toml"),
+            "[revset-aliases]\n'base' = 'root()'\n",
+        )?;
+        let subdir = second_root.join("sub").join("dir");
+        std::fs::create_dir_all(&subdir)?;
+        let other = JjAdapter::for_path(subdir)?;
+        assert!(other.is_valid().await);
+        let status = other.get_operation_log(0, 100, Some("base".into())).await?;
+        assert_eq!(status.workspace_id, "second");
+        assert_eq!(status.graph.len(), 1);
+        assert!(status.graph[0].parents.is_empty());
+
+        // Rebasing the other working copy from here leaves its files behind.
+        std::fs::write(path.join("hello.txt"), "hello\n")?;
+        adapter
+            .describe_revision(&workspaces[0].working_copy_id.0, "hello")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .rebase(
+                std::slice::from_ref(&workspaces[1].working_copy_id),
+                RebaseMode::Revisions,
+                RebaseLocation::Destination,
+                &status.working_copy_id.0,
+            )
+            .await?;
+        let workspaces = adapter.workspaces().await?;
+        assert!(workspaces[1].is_stale);
+        adapter.update_stale_workspace("second").await?;
+        assert!(second_root.join("hello.txt").is_file());
+        assert!(!adapter.workspaces().await?[1].is_stale);
+
+        adapter.forget_workspace("second").await?;
+        let workspaces = adapter.workspaces().await?;
+        assert_eq!(workspaces.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_line_counts() -> Result<()> {
+        use crate::domain::models::LineCounts;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+
+        let adapter = JjAdapter::for_path(path.clone())?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert!(status.graph.iter().all(|row| row.line_counts.is_none()));
+
+        let adapter = adapter.counting_lines(true);
+        std::fs::write(path.join("a.txt"), "one\ntwo\nthree\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "add a")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter.new_child(&status.working_copy_id).await?;
+        std::fs::write(path.join("a.txt"), "one\n2\nthree\nfour\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "change a")
+            .await?;
+
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let counts: Vec<_> = status
+            .graph
+            .iter()
+            .map(|row| (row.description.trim(), row.line_counts))
+            .collect();
+        assert_eq!(
+            counts[..2],
+            [
+                (
+                    "change a",
+                    Some(LineCounts {
+                        added: 2,
+                        removed: 1
+                    })
+                ),
+                (
+                    "add a",
+                    Some(LineCounts {
+                        added: 3,
+                        removed: 0
+                    })
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_native_mutations() -> Result<()> {
+        use crate::domain::error::VcsError;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        std::fs::write(path.join("hello.txt"), "hello\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let first = status.working_copy_id.clone();
+        adapter.describe_revision(&first.0, "first").await?;
+
+        // The describe snapshotted the new file and rewrote the working copy.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let described = status
+            .graph
+            .iter()
+            .find(|row| row.is_working_copy)
+            .expect("working copy row");
+        assert_eq!(described.description.trim(), "first");
+        assert_eq!(described.changed_files.len(), 1);
+        let first = described.commit_id.clone();
+
+        adapter.new_child(&first).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let wc = status
+            .graph
+            .iter()
+            .find(|row| row.is_working_copy)
+            .expect("working copy row");
+        assert_eq!(wc.parents, vec![first.clone()]);
+
+        std::fs::write(path.join("world.txt"), "world\n")?;
+        adapter.squash(std::slice::from_ref(&wc.commit_id)).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let squashed = status
+            .graph
+            .iter()
+            .find(|row| row.description.trim() == "first")
+            .expect("squashed row");
+        assert_eq!(squashed.changed_files.len(), 2);
+        let first = squashed.commit_id.clone();
+
+        adapter.duplicate(std::slice::from_ref(&first)).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let copy = status
+            .graph
+            .iter()
+            .find(|row| row.description.trim() == "first" && row.commit_id != first)
+            .expect("duplicated row")
+            .commit_id
+            .clone();
+        adapter
+            .rebase(
+                std::slice::from_ref(&copy),
+                RebaseMode::Revisions,
+                RebaseLocation::Destination,
+                &first.0,
+            )
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert!(status
+            .graph
+            .iter()
+            .any(|row| row.description.trim() == "first" && row.parents == vec![first.clone()]));
+
+        adapter.set_bookmark(&first, "feature").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert!(status
+            .graph
+            .iter()
+            .any(|row| row.commit_id == first && row.bookmarks == vec!["feature".to_string()]));
+        adapter.delete_bookmark("feature").await?;
+        let err = adapter.delete_bookmark("feature").await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::NoSuchBookmark("feature".to_string()))
+        );
+
+        let root = status.graph.last().expect("root row").commit_id.clone();
+        let err = adapter.abandon(&[root]).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<VcsError>(), Some(&VcsError::RootCommit));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks() -> Result<()> {
+        use crate::domain::error::VcsError;
+        use jj_lib::op_store::{RefTarget, RemoteRef, RemoteRefState};
+        use jj_lib::ref_name::{RefNameBuf, RemoteNameBuf};
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path)?;
+
+        // root <- old <- @ with main at @, while origin has main (tracked)
+        // and fix (untracked) at old.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let old = status.working_copy_id.clone();
+        adapter.new_child(&old).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let wc = status.working_copy_id.clone();
+        adapter.set_bookmark(&wc, "main").await?;
+        let old_id = jj_lib::backend::CommitId::try_from_hex(&old.0).expect("commit id");
+        adapter
+            .run_transaction("fetch".to_string(), move |mut_repo, _| {
+                let origin = RemoteNameBuf::from("origin");
+                for (name, state) in [
+                    ("main", RemoteRefState::Tracked),
+                    ("fix", RemoteRefState::New),
+                ] {
+                    let name = RefNameBuf::from(name);
+                    let remote_ref = RemoteRef {
+                        target: RefTarget::normal(old_id.clone()),
+                        state,
+                    };
+                    mut_repo.set_remote_bookmark(name.to_remote_symbol(&origin), remote_ref);
+                }
+                Ok(())
+            })
+            .await?;
+
+        let bookmarks = adapter.bookmarks().await?;
+        let labels: Vec<String> = bookmarks.iter().map(|b| b.label()).collect();
+        assert_eq!(labels, ["fix@origin", "main", "main@origin"]);
+        assert!(!bookmarks[0].is_tracked);
+        assert_eq!(bookmarks[1].targets[0].commit_id, wc);
+        assert!(bookmarks[2].is_tracked);
+        assert_eq!((bookmarks[2].ahead, bookmarks[2].behind), (1, 0));
+
+        // Moving backwards needs to be allowed explicitly.
+        let err = adapter
+            .move_bookmark("main", &old.0, false)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::BookmarkMovedBackwards("main".to_string()))
+        );
+        adapter.move_bookmark("main", &old.0, true).await?;
+        let bookmarks = adapter.bookmarks().await?;
+        assert_eq!((bookmarks[2].ahead, bookmarks[2].behind), (0, 0));
+
+        adapter.track_bookmark("fix", "origin").await?;
+        let err = adapter.rename_bookmark("main", "fix").await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::BookmarkExists("fix".to_string()))
+        );
+        adapter.rename_bookmark("main", "trunk").await?;
+        adapter.forget_bookmark("fix").await?;
+        let bookmarks = adapter.bookmarks().await?;
+        let labels: Vec<String> = bookmarks.iter().map(|b| b.label()).collect();
+        assert_eq!(labels, ["fix@origin", "main@origin", "trunk"]);
+        assert!(!bookmarks[0].is_tracked);
+        assert_eq!((bookmarks[1].ahead, bookmarks[1].behind), (0, 0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push() -> Result<()> {
+        use crate::domain::error::VcsError;
+        use crate::domain::push::{PushScope, UpdateKind};
+
+        let temp_dir = tempfile::tempdir()?;
+        let git = |args: &[&str]| -> Result<String> {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()?;
+            anyhow::ensure!(output.status.success(), "git {args:?} failed");
+            Ok(String::from_utf8(output.stdout)?.trim().to_string())
+        };
+        git(&["init", "--bare", "-q", "remote.git"])?;
+        let path = temp_dir.path().join("repo");
+        std::fs::create_dir(&path)?;
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_colocated_git(&user_settings, &path)?;
+        let url = temp_dir.path().join("remote.git").display().to_string();
+        git(&["-C", "repo", "remote", "add", "origin", &url])?;
+        let adapter = JjAdapter::for_path(path)?;
+
+        // main at a described @, and wip at an undescribed child of it.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let main = status.working_copy_id.clone();
+        adapter.describe_revision(&main.0, "first").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let main = status.working_copy_id.clone();
+        adapter.set_bookmark(&main, "main").await?;
+        adapter.new_child(&main).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter.set_bookmark(&status.working_copy_id, "wip").await?;
+
+        let scope = PushScope::Bookmarks(vec!["main".into(), "wip".into()]);
+        let plan = adapter.push_preview(None, &scope).await?;
+        assert_eq!(plan.remote, "origin");
+        assert_eq!(plan.bookmarks, ["main", "wip"]);
+        assert_eq!(plan.updates.len(), 1);
+        assert_eq!(plan.updates[0].kind, UpdateKind::Add);
+        assert_eq!(
+            plan.updates[0].describe(),
+            format!("Add bookmark main to {}", &main.0[..8])
+        );
+        assert_eq!(plan.skipped.len(), 1);
+        assert!(plan.skipped[0].1.contains("no description"));
+
+        let results = adapter.push("origin", &scope).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].error, None);
+        assert_eq!(
+            git(&["--git-dir", "remote.git", "rev-parse", "main"])?,
+            main.0
+        );
+        let bookmarks = adapter.bookmarks().await?;
+        assert!(bookmarks
+            .iter()
+            .any(|b| b.label() == "main@origin" && b.is_tracked));
+
+        // Up to date now, and `--change` names a new bookmark after @-.
+        let plan = adapter.push_preview(None, &PushScope::Tracked).await?;
+        assert!(plan.updates.is_empty() && plan.skipped.is_empty());
+        let plan = adapter
+            .push_preview(None, &PushScope::Change(main.clone()))
+            .await?;
+        assert!(plan.updates[0].name.starts_with("push-"));
+
+        let err = adapter
+            .push_preview(Some("upstream".into()), &PushScope::All)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::NoSuchRemote("upstream".into()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remotes_and_fetch() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let git = |args: &[&str]| -> Result<String> {
+            let output = std::process::Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()?;
+            anyhow::ensure!(output.status.success(), "git {args:?} failed");
+            Ok(String::from_utf8(output.stdout)?.trim().to_string())
+        };
+        // A bare upstream with main and feature branches.
+        git(&["init", "--bare", "-q", "upstream.git"])?;
+        git(&["init", "-q", "clone"])?;
+        git(&[
+            "-C",
+            "clone",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "first",
+        ])?;
+        let upstream = temp_dir.path().join("upstream.git").display().to_string();
+        for branch in ["main", "feature"] {
+            let refspec = format!("HEAD:refs/heads/{branch}");
+            git(&["-C", "clone", "push", "-q", &upstream, &refspec])?;
+        }
+
+        let path = temp_dir.path().join("repo");
+        std::fs::create_dir(&path)?;
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_colocated_git(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path)?;
+
+        adapter.add_remote("upstream", &upstream).await?;
+        let remotes = adapter.remotes().await?;
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(
+            (remotes[0].name.as_str(), remotes[0].url.as_str()),
+            ("upstream", upstream.as_str())
+        );
+
+        // jj fetches with `git fetch --porcelain`, which Git 2.41 added.
+        let version = git(&["--version"])?;
+        let version: Vec<u32> = version
+            .trim_start_matches("git version ")
+            .split('.')
+            .take(2)
+            .filter_map(|part| part.parse().ok())
+            .collect();
+        let can_fetch = version >= vec![2, 41];
+        let labels = || async {
+            let bookmarks = adapter.bookmarks().await?;
+            anyhow::Ok(bookmarks.iter().map(|b| b.label()).collect::<Vec<_>>())
+        };
+        if can_fetch {
+            // Only the branches asked for, then the rest.
+            adapter
+                .fetch(&["upstream".into()], &["main".into()])
+                .await?;
+            assert_eq!(labels().await?, ["main@upstream"]);
+            adapter.fetch(&["upstream".into()], &[]).await?;
+            assert_eq!(labels().await?, ["feature@upstream", "main@upstream"]);
+        }
+
+        adapter.rename_remote("upstream", "fork").await?;
+        adapter.set_remote_url("fork", "/elsewhere.git").await?;
+        let remotes = adapter.remotes().await?;
+        assert_eq!(
+            (remotes[0].name.as_str(), remotes[0].url.as_str()),
+            ("fork", "/elsewhere.git")
+        );
+        if can_fetch {
+            assert_eq!(labels().await?, ["feature@fork", "main@fork"]);
+        }
+
+        adapter.remove_remote("fork").await?;
+        assert!(adapter.remotes().await?.is_empty());
+        assert!(labels().await?.is_empty());
+        assert!(adapter.fetch(&[], &[]).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tags() -> Result<()> {
+        use crate::domain::error::VcsError;
+
+        let temp_dir = tempfile::tempdir()?;
+        let git = |args: &[&str]| -> Result<String> {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()?;
+            anyhow::ensure!(output.status.success(), "git {args:?} failed");
+            Ok(String::from_utf8(output.stdout)?.trim().to_string())
+        };
+        git(&["init", "--bare", "-q", "remote.git"])?;
+        let path = temp_dir.path().join("repo");
+        std::fs::create_dir(&path)?;
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_colocated_git(&user_settings, &path)?;
+        let url = temp_dir.path().join("remote.git").display().to_string();
+        git(&["-C", "repo", "remote", "add", "origin", &url])?;
+        let adapter = JjAdapter::for_path(path)?;
+
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let first = status.working_copy_id.clone();
+        adapter.describe_revision(&first.0, "first").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let first = status.working_copy_id.clone();
+        adapter.set_tag(&first, "v1", false).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(status.graph[0].tags, ["v1"]);
+        assert_eq!(adapter.get_commit_diff(&first).await?.header.tags, ["v1"]);
+        assert_eq!(git(&["-C", "repo", "rev-parse", "v1"])?, first.0);
+
+        // Tags are only moved when asked to, and only if they exist.
+        adapter.new_child(&first).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let second = status.working_copy_id.clone();
+        let err = adapter.set_tag(&second, "v1", false).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::TagExists("v1".into()))
+        );
+        let err = adapter.set_tag(&second, "v2", true).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::NoSuchTag("v2".into()))
+        );
+
+        adapter.push_tag("v1").await?;
+        assert_eq!(
+            git(&["--git-dir", "remote.git", "rev-parse", "v1"])?,
+            first.0
+        );
+        // The remote's tag is known, so moving it pushes over it.
+        adapter.set_tag(&second, "v1", true).await?;
+        adapter.push_tag("v1").await?;
+        assert_eq!(
+            git(&["--git-dir", "remote.git", "rev-parse", "v1"])?,
+            second.0
+        );
+
+        adapter.delete_tag("v1").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert!(status.graph.iter().all(|row| row.tags.is_empty()));
+        assert!(git(&["-C", "repo", "rev-parse", "--verify", "-q", "v1"]).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rebase_modes() -> Result<()> {
+        use crate::domain::error::VcsError;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path)?;
+        let row = |status: &crate::domain::models::RepoStatus, description: &str| {
+            status
+                .graph
+                .iter()
+                .find(|row| row.description.trim() == description)
+                .cloned()
+                .expect("row")
+        };
+        let parents = |status: &crate::domain::models::RepoStatus, description: &str| {
+            row(status, description)
+                .parents
+                .iter()
+                .map(|id| {
+                    status
+                        .graph
+                        .iter()
+                        .find(|row| row.commit_id == *id)
+                        .map_or("root", |row| row.description.trim())
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // a-b-c, with d on a too.
+        let mut parent = None;
+        for description in ["a", "b", "c", "d"] {
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            if let Some(parent) = parent {
+                adapter.new_child(&row(&status, parent).commit_id).await?;
+            }
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter
+                .describe_revision(&status.working_copy_id.0, description)
+                .await?;
+            parent = Some(if description == "c" { "a" } else { description });
+        }
+
+        let rebase = |description: &'static str,
+                      mode: RebaseMode,
+                      location: RebaseLocation,
+                      target: &'static str| {
+            let adapter = &adapter;
+            async move {
+                let status = adapter.get_operation_log(0, 100, None).await?;
+                let source = row(&status, description).commit_id;
+                let target = row(&status, target).commit_id;
+                adapter.rebase(&[source], mode, location, &target.0).await?;
+                adapter.get_operation_log(0, 100, None).await
+            }
+        };
+
+        // -s takes the descendants along.
+        let status = rebase("b", RebaseMode::Source, RebaseLocation::Destination, "d").await?;
+        assert_eq!(parents(&status, "b"), ["d"]);
+        assert_eq!(parents(&status, "c"), ["b"]);
+
+        // -r -B slots c in between d and b, leaving nothing behind.
+        let status = rebase(
+            "c",
+            RebaseMode::Revisions,
+            RebaseLocation::InsertBefore,
+            "b",
+        )
+        .await?;
+        assert_eq!(parents(&status, "c"), ["d"]);
+        assert_eq!(parents(&status, "b"), ["c"]);
+
+        // -r -A slots b in between a and d.
+        let status = rebase("b", RebaseMode::Revisions, RebaseLocation::InsertAfter, "a").await?;
+        assert_eq!(parents(&status, "b"), ["a"]);
+        assert_eq!(parents(&status, "d"), ["b"]);
+        assert_eq!(parents(&status, "c"), ["d"]);
+
+        // -b moves everything from where the branch forks off: here, all of
+        // it, onto the new commit e.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let root = status.graph.last().expect("root").commit_id.clone();
+        adapter.new_child(&root).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "e")
+            .await?;
+        let status = rebase("c", RebaseMode::Branch, RebaseLocation::Destination, "e").await?;
+        assert_eq!(parents(&status, "a"), ["e"]);
+        assert_eq!(parents(&status, "c"), ["d"]);
+
+        let err = rebase("a", RebaseMode::Source, RebaseLocation::Destination, "c")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::InvalidRebaseDestination)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_selected_lines() -> Result<()> {
+        use crate::domain::{diff::DiffSelection, error::VcsError};
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        let before = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+        std::fs::write(path.join("a.txt"), &before)?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "base")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter.new_child(&status.working_copy_id).await?;
+
+        let after = before
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "");
+        std::fs::write(path.join("a.txt"), &after)?;
+        std::fs::write(path.join("b.txt"), "new file\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let wc = status.working_copy_id.clone();
+        adapter.describe_revision(&wc.0, "both").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let wc = status.working_copy_id.clone();
+        let diff = adapter.get_commit_diff(&wc).await?;
+        let a_idx = diff.files.iter().position(|f| f.path == "a.txt").unwrap();
+        let b_idx = diff.files.iter().position(|f| f.path == "b.txt").unwrap();
+
+        // Split off only the added line of the first hunk, plus all of b.txt.
+        let mut selection = DiffSelection::default();
+        selection.toggle_line(&diff, a_idx, 0, 2);
+        selection.toggle_file(&diff, b_idx);
+        let files = selection.file_selections(&diff);
+        adapter.split(&wc, &files, "first half").await?;
+
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let first = status
+            .graph
+            .iter()
+            .find(|row| row.description.trim() == "first half")
+            .expect("first commit");
+        let second = status
+            .graph
+            .iter()
+            .find(|row| row.is_working_copy)
+            .expect("working copy row");
+        assert_eq!(second.description.trim(), "both");
+        assert_eq!(second.parents, vec![first.commit_id.clone()]);
+
+        let first_diff = adapter.get_commit_diff(&first.commit_id).await?;
+        assert_eq!(first_diff.files.len(), 2);
+        let first_text = first_diff.to_string();
+        assert!(first_text.contains("\n+line two\n"));
+        assert!(!first_text.contains("\n-line 2\n"));
+        let second_text = adapter
+            .get_commit_diff(&second.commit_id)
+            .await?
+            .to_string();
+        assert!(second_text.contains("\n-line 2\n"));
+        assert!(second_text.contains("\n-line 18\n"));
+        assert_eq!(std::fs::read_to_string(path.join("a.txt"))?, after);
+
+        // A selection made on a diff the file no longer matches is refused.
+        std::fs::write(path.join("a.txt"), "rewritten\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let err = adapter
+            .split(&status.working_copy_id, &files, "stale")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::SelectionOutdated("a.txt".to_string()))
+        );
+        Ok(())
+    }
+
+    /// Makes the working copy a new merge of `parents`, which the adapter
+    /// has no operation for.
+    async fn edit_new_merge(adapter: &JjAdapter, parents: &[CommitId]) -> Result<()> {
+        let parents = parents.to_vec();
+        adapter
+            .run_transaction("new merge".to_string(), move |mut_repo, ctx| {
+                let parents = parents
+                    .iter()
+                    .map(|id| ctx.load_commit(mut_repo, id))
+                    .collect::<Result<Vec<_>>>()?;
+                let tree = futures::executor::block_on(jj_lib::rewrite::merge_commit_trees(
+                    mut_repo, &parents,
+                ))?;
+                let parent_ids = parents.iter().map(|p| p.id().clone()).collect();
+                let merge = mut_repo.new_commit(parent_ids, tree).write()?;
+                mut_repo.edit(ctx.workspace_name.clone(), &merge)?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Sets up `left` and `right` off the root, each adding a file, and makes
+    /// the working copy their merge with an extra line in `a.txt`.
+    async fn merge_with_change(adapter: &JjAdapter, path: &std::path::Path) -> Result<Diff> {
+        std::fs::write(path.join("a.txt"), "one\ntwo\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let left = status.working_copy_id.clone();
+        adapter.describe_revision(&left.0, "left").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let left = status.working_copy_id.clone();
+        let root = status.graph.last().expect("root row").commit_id.clone();
+        adapter.new_child(&root).await?;
+        std::fs::write(path.join("b.txt"), "b\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let right = status.working_copy_id.clone();
+        adapter.describe_revision(&right.0, "right").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let right = status.working_copy_id.clone();
+
+        edit_new_merge(adapter, &[left, right]).await?;
+        std::fs::write(path.join("a.txt"), "one\n2\ntwo\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "merge")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let diff = adapter.get_commit_diff(&status.working_copy_id).await?;
+        // Against both parents, b.txt is unchanged.
+        assert_eq!(
+            diff.files.iter().map(|f| &f.path).collect::<Vec<_>>(),
+            vec!["a.txt"]
+        );
+        Ok(diff)
+    }
+
+    /// Adds `big.txt` small, so `jj` snapshots it, then grows it past what
+    /// the diff panel shows in a new working-copy commit. Returns that
+    /// commit, its diff and the file's index in it.
+    async fn grow_big_file(
+        adapter: &JjAdapter,
+        path: &std::path::Path,
+    ) -> Result<(CommitId, Diff, usize)> {
+        std::fs::write(path.join("big.txt"), "x\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "small")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter.new_child(&status.working_copy_id).await?;
+        let big = "x\n".repeat(MAX_DIFF_SIZE as usize / 2 + 1);
+        std::fs::write(path.join("big.txt"), big)?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "big")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let diff = adapter.get_commit_diff(&status.working_copy_id).await?;
+        let idx = diff.files.iter().position(|f| f.path == "big.txt").unwrap();
+        Ok((status.working_copy_id, diff, idx))
+    }
+
+    #[tokio::test]
+    async fn test_split_selection_limits() -> Result<()> {
+        use crate::domain::{diff::DiffSelection, error::VcsError};
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        // Lines picked from a merge's diff split off cleanly.
+        let diff = merge_with_change(&adapter, &path).await?;
+        let mut selection = DiffSelection::default();
+        selection.toggle_line(&diff, 0, 0, 1);
+        let wc = CommitId(diff.header.commit_id.clone());
+        adapter
+            .split(&wc, &selection.file_selections(&diff), "picked")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let picked = status
+            .graph
+            .iter()
+            .find(|row| row.description.trim() == "picked")
+            .expect("picked row");
+        assert_eq!(picked.parents.len(), 2);
+        let picked_diff = adapter.get_commit_diff(&picked.commit_id).await?;
+        assert!(picked_diff.to_string().contains("\n+2\n"));
+
+        // Lines of a file too large to show in full can't be picked.
+        let (big, diff, big_idx) = grow_big_file(&adapter, &path).await?;
+        let mut selection = DiffSelection::default();
+        selection.toggle_line(&diff, big_idx, 0, 1);
+        let err = adapter
+            .split(&big, &selection.file_selections(&diff), "first line")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::FileTooLarge("big.txt".to_string()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_squash_selected_changes() -> Result<()> {
+        use crate::domain::{diff::DiffSelection, error::VcsError, models::SquashDestination};
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        std::fs::write(path.join("a.txt"), "one\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "base")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter.new_child(&status.working_copy_id).await?;
+        std::fs::write(path.join("a.txt"), "one\ntwo\n")?;
+        std::fs::write(path.join("b.txt"), "new file\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "work")
+            .await?;
+
+        let pick = |diff: &Diff, name: &str| {
+            let idx = diff.files.iter().position(|f| f.path == name).unwrap();
+            let mut selection = DiffSelection::default();
+            selection.toggle_file(diff, idx);
+            selection.file_selections(diff)
+        };
+        let row = |status: &crate::domain::models::RepoStatus, description: &str| {
+            status
+                .graph
+                .iter()
+                .find(|row| row.description.trim() == description)
+                .cloned()
+                .expect("row")
+        };
+
+        // Move b.txt into the parent.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let work = status.working_copy_id.clone();
+        let diff = adapter.get_commit_diff(&work).await?;
+        adapter
+            .squash_changes(&work, &pick(&diff, "b.txt"), &SquashDestination::Parent)
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let base = row(&status, "base");
+        let base_diff = adapter.get_commit_diff(&base.commit_id).await?;
+        assert!(base_diff.files.iter().any(|f| f.path == "b.txt"));
+        let work = status.working_copy_id.clone();
+        let work_diff = adapter.get_commit_diff(&work).await?;
+        assert_eq!(work_diff.header.description.trim(), "work");
+        assert_eq!(
+            work_diff.files.iter().map(|f| &f.path).collect::<Vec<_>>(),
+            vec!["a.txt"]
+        );
+
+        // Moving changes into their own revision is refused.
+        let err = adapter
+            .squash_changes(
+                &work,
+                &pick(&work_diff, "a.txt"),
+                &SquashDestination::Revision(work.clone()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::SquashIntoSource)
+        );
+
+        // Move a.txt out into a new child, which becomes the working copy.
+        adapter
+            .squash_changes(
+                &work,
+                &pick(&work_diff, "a.txt"),
+                &SquashDestination::NewChild,
+            )
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let work = row(&status, "work");
+        let child = status
+            .graph
+            .iter()
+            .find(|row| row.is_working_copy)
+            .expect("working copy row");
+        assert_eq!(child.parents, vec![work.commit_id.clone()]);
+        assert!(adapter
+            .get_commit_diff(&work.commit_id)
+            .await?
+            .files
+            .is_empty());
+        let child_diff = adapter.get_commit_diff(&child.commit_id).await?;
+        assert!(child_diff.to_string().contains("\n+two\n"));
+        assert_eq!(std::fs::read_to_string(path.join("a.txt"))?, "one\ntwo\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_squash_selection_limits() -> Result<()> {
+        use crate::domain::{diff::DiffSelection, error::VcsError, models::SquashDestination};
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        // Lines picked from a merge's diff move out into a new child.
+        let diff = merge_with_change(&adapter, &path).await?;
+        let mut selection = DiffSelection::default();
+        selection.toggle_line(&diff, 0, 0, 1);
+        let merge = CommitId(diff.header.commit_id.clone());
+        adapter
+            .squash_changes(
+                &merge,
+                &selection.file_selections(&diff),
+                &SquashDestination::NewChild,
+            )
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let child_diff = adapter.get_commit_diff(&status.working_copy_id).await?;
+        assert!(child_diff.to_string().contains("\n+2\n"));
+        let merge = status
+            .graph
+            .iter()
+            .find(|row| row.description.trim() == "merge")
+            .expect("merge row");
+        assert!(adapter
+            .get_commit_diff(&merge.commit_id)
+            .await?
+            .files
+            .is_empty());
+
+        // Lines of a file too large to show in full can't be picked, but
+        // the whole file still moves.
+        let (big, diff, big_idx) = grow_big_file(&adapter, &path).await?;
+        let mut selection = DiffSelection::default();
+        selection.toggle_line(&diff, big_idx, 0, 1);
+        let err = adapter
+            .squash_changes(
+                &big,
+                &selection.file_selections(&diff),
+                &SquashDestination::Parent,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::FileTooLarge("big.txt".to_string()))
+        );
+        let mut selection = DiffSelection::default();
+        selection.toggle_file(&diff, big_idx);
+        adapter
+            .squash_changes(
+                &big,
+                &selection.file_selections(&diff),
+                &SquashDestination::Parent,
+            )
+            .await?;
+        // Everything moved, so the working copy is left empty over it.
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let wc_diff = adapter.get_commit_diff(&status.working_copy_id).await?;
+        assert!(wc_diff.files.is_empty());
+        assert_eq!(
+            std::fs::metadata(path.join("big.txt"))?.len(),
+            MAX_DIFF_SIZE + 2
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_is_binary() {
         assert!(!is_binary(b"this is some text"));
@@ -300,6 +1625,39 @@ mod tests {
         assert!(!is_binary("🦀 rust is great".as_bytes()));
     }
 
+    #[test]
+    fn test_build_hunks() {
+        use crate::domain::diff::DiffLineKind;
+
+        let before = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+        let after = before
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "");
+        let hunks = diff::build_hunks(&before, &after);
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].to_string(), "@@ -1,5 +1,5 @@");
+        assert_eq!(hunks[1].to_string(), "@@ -15,6 +15,5 @@");
+
+        let removed = &hunks[0].lines[1];
+        assert_eq!(removed.kind, DiffLineKind::Removed);
+        assert_eq!(removed.content, "line 2");
+        assert_eq!((removed.old_lineno, removed.new_lineno), (Some(2), None));
+        let added = &hunks[0].lines[2];
+        assert_eq!(added.kind, DiffLineKind::Added);
+        assert_eq!((added.old_lineno, added.new_lineno), (None, Some(2)));
+
+        // Only the changed word is emphasized.
+        assert_eq!(removed.emphasis, vec![5..6]);
+        assert_eq!(added.emphasis, vec![5..8]);
+        assert_eq!(added.segments(), vec![(false, "line "), (true, "two")]);
+        assert!(hunks[0].lines[0].emphasis.is_empty());
+
+        // Adding a file yields a single hunk starting at line 0 on the old side.
+        let hunks = diff::build_hunks("", "new\n");
+        assert_eq!(hunks[0].to_string(), "@@ -0,0 +1,1 @@");
+    }
+
     #[test]
     fn test_format_change_id() {
         use jj_lib::backend::CommitId;
@@ -309,6 +1667,294 @@ mod tests {
         // 5->p, 2->m, f->z, b->v, 4->o, 2->m, 8->s, 4->o, e->y, 4->o, 4->o, 4->o, 9->t, c->w, 4->o, 1->l, 3->n, f->z, e->y, 5->p, f->z, 8->s, a->u, 9->t, 5->p, 2->m, b->v, 1->l, a->u, 2->m, f->z, 8->s, e->y, 1->l, d->x, 4->o, 8->s, d->x, 2->m, b->v
         // Wait, it's 40 characters for 20 bytes.
         assert_eq!(formatted.len(), 40);
-        assert!(formatted.chars().all(|c| c >= 'k' && c <= 'z'));
+        assert!(formatted.chars().all(|c| ('k'..='z').contains(&c)));
+    }
+
+    #[tokio::test]
+    async fn test_preview_rewrite() -> Result<()> {
+        use crate::domain::models::Rewrite;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+        let id = |status: &crate::domain::models::RepoStatus, description: &str| {
+            status
+                .graph
+                .iter()
+                .find(|row| row.description.trim() == description)
+                .map(|row| row.commit_id.clone())
+                .expect("row")
+        };
+        let parents = |status: &crate::domain::models::RepoStatus, description: &str| {
+            let row = &status
+                .graph
+                .iter()
+                .find(|row| row.commit_id == id(status, description));
+            row.expect("row")
+                .parents
+                .iter()
+                .map(|parent| {
+                    status
+                        .graph
+                        .iter()
+                        .find(|row| row.commit_id == *parent)
+                        .map_or("root", |row| row.description.trim())
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Two siblings changing the same line of base.
+        std::fs::write(path.join("a.txt"), "1\n2\n3\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "base")
+            .await?;
+        for side in ["ours", "theirs"] {
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter.new_child(&id(&status, "base")).await?;
+            std::fs::write(path.join("a.txt"), format!("1\n{side}\n3\n"))?;
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter
+                .describe_revision(&status.working_copy_id.0, side)
+                .await?;
+        }
+        // Leave the files as they are in the working copy.
+        adapter.snapshot().await?;
+
+        let before = adapter.get_operation_log(0, 100, None).await?;
+        let rebase = Rewrite::Rebase(
+            vec![id(&before, "theirs")],
+            RebaseMode::Revisions,
+            RebaseLocation::Destination,
+            id(&before, "ours").0,
+        );
+        let preview = adapter.preview_rewrite(&rebase, 100, None).await?;
+        assert_eq!(parents(&preview.status, "theirs"), ["ours"]);
+        let theirs = id(&preview.status, "theirs");
+        assert_eq!(preview.new_conflicts, [theirs].into_iter().collect());
+        assert_eq!(
+            preview.status.working_copy_id,
+            id(&preview.status, "theirs")
+        );
+
+        // Nothing was written, not even the working copy.
+        let after = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(after.operation_id, before.operation_id);
+        assert_eq!(parents(&after, "theirs"), ["base"]);
+        assert_eq!(
+            std::fs::read_to_string(path.join("a.txt"))?,
+            "1\ntheirs\n3\n"
+        );
+
+        // The preview matches what running it does:
+        // theirs-x-y-@, with x and y becoming siblings under @.
+        for description in ["x", "y"] {
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter.new_child(&status.working_copy_id).await?;
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter
+                .describe_revision(&status.working_copy_id.0, description)
+                .await?;
+        }
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter.new_child(&status.working_copy_id).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let parallelize = Rewrite::Parallelize(vec![id(&status, "x"), id(&status, "y")]);
+        let preview = adapter.preview_rewrite(&parallelize, 100, None).await?;
+        assert_eq!(parents(&preview.status, "y"), ["theirs"]);
+        assert!(preview.new_conflicts.is_empty());
+        adapter
+            .parallelize(&[id(&status, "x"), id(&status, "y")])
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        for description in ["x", "y"] {
+            assert_eq!(parents(&status, description), ["theirs"]);
+        }
+        let wc = |status: &crate::domain::models::RepoStatus| {
+            let mut parents = status
+                .graph
+                .iter()
+                .find(|row| row.is_working_copy)
+                .expect("working copy")
+                .parents
+                .iter()
+                .map(|parent| {
+                    let row = status.graph.iter().find(|row| row.commit_id == *parent);
+                    row.expect("parent").description.trim().to_string()
+                })
+                .collect::<Vec<_>>();
+            parents.sort();
+            parents
+        };
+        assert_eq!(wc(&status), ["x", "y"]);
+        assert_eq!(wc(&preview.status), ["x", "y"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_conflict() -> Result<()> {
+        use crate::domain::{
+            conflict::{ConflictRegion, Resolution},
+            error::VcsError,
+        };
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        // Change the same line differently on two siblings, then rebase one
+        // onto the other.
+        std::fs::write(path.join("a.txt"), "1\n2\n3\n")?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let base = status.working_copy_id.clone();
+        adapter.describe_revision(&base.0, "base").await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let base = status.working_copy_id.clone();
+        for side in ["ours", "theirs"] {
+            adapter.new_child(&base).await?;
+            std::fs::write(path.join("a.txt"), format!("1\n{side}\n3\n"))?;
+            let status = adapter.get_operation_log(0, 100, None).await?;
+            adapter
+                .describe_revision(&status.working_copy_id.0, side)
+                .await?;
+        }
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let theirs = status.working_copy_id.clone();
+        let ours = status
+            .graph
+            .iter()
+            .find(|row| row.description.trim() == "ours")
+            .expect("ours row")
+            .commit_id
+            .clone();
+        adapter
+            .rebase(
+                &[theirs],
+                RebaseMode::Revisions,
+                RebaseLocation::Destination,
+                &ours.0,
+            )
+            .await?;
+
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let conflicted = status.working_copy_id.clone();
+        let conflict = adapter.get_file_conflict(&conflicted, "a.txt").await?;
+        assert_eq!(
+            conflict.regions,
+            vec![
+                ConflictRegion::Resolved("1\n".to_string()),
+                ConflictRegion::Conflict {
+                    base: "2\n".to_string(),
+                    ours: "ours\n".to_string(),
+                    theirs: "theirs\n".to_string(),
+                },
+                ConflictRegion::Resolved("3\n".to_string()),
+            ]
+        );
+
+        let content = conflict.resolve(&[Some(Resolution::Both)]).unwrap();
+        adapter.resolve_conflict(&conflict, &content).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        let row = status
+            .graph
+            .iter()
+            .find(|row| row.is_working_copy)
+            .expect("working copy row");
+        assert!(!row.has_conflict);
+        assert_eq!(row.parents, vec![ours]);
+        assert_eq!(
+            std::fs::read_to_string(path.join("a.txt"))?,
+            "1\nours\ntheirs\n3\n"
+        );
+
+        // The revision was rewritten, so resolving the old conflict is refused.
+        let err = adapter
+            .resolve_conflict(&conflict, "stale\n")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::ConflictOutdated("a.txt".to_string()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_and_revert_operations() -> Result<()> {
+        use crate::domain::error::VcsError;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().to_path_buf();
+        let config = jj_lib::config::StackedConfig::with_defaults();
+        let user_settings = UserSettings::from_config(config)?;
+        Workspace::init_simple(&user_settings, &path)?;
+        let adapter = JjAdapter::for_path(path.clone())?;
+
+        let wc_description = |status: &crate::domain::models::RepoStatus| {
+            let row = status.graph.iter().find(|row| row.is_working_copy);
+            row.expect("working copy row")
+                .description
+                .trim()
+                .to_string()
+        };
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "first")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .set_bookmark(&status.working_copy_id, "main")
+            .await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        adapter
+            .describe_revision(&status.working_copy_id.0, "second")
+            .await?;
+
+        let ops = adapter.operation_log(100).await?;
+        assert!(ops[0].is_current);
+        assert_eq!(ops.iter().filter(|op| op.is_current).count(), 1);
+        assert_eq!(ops[0].parent_ids, vec![ops[1].id.clone()]);
+        let root = ops.last().expect("root operation");
+        assert!(root.parent_ids.is_empty());
+
+        // Rewriting the description hides the old commit and moves the
+        // bookmark along with it.
+        let changes = adapter.operation_changes(&ops[0].id).await?;
+        let summaries = |commits: &[crate::domain::operation::ChangedCommit]| {
+            commits
+                .iter()
+                .map(|c| c.summary.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summaries(&changes.added), ["second"]);
+        assert_eq!(summaries(&changes.removed), ["first"]);
+        assert_eq!(changes.bookmarks.len(), 1);
+        assert_eq!(changes.bookmarks[0].name, "main");
+        assert_eq!(
+            changes.bookmarks[0].to.as_deref(),
+            Some(changes.added[0].commit_id_short.as_str())
+        );
+
+        adapter.revert_operation(&ops[0].id).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(wc_description(&status), "first");
+
+        adapter.restore_operation(&ops[0].id).await?;
+        let status = adapter.get_operation_log(0, 100, None).await?;
+        assert_eq!(wc_description(&status), "second");
+
+        let err = adapter.revert_operation(&root.id).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<VcsError>(),
+            Some(&VcsError::OperationWithoutSingleParent)
+        );
+        Ok(())
     }
 }
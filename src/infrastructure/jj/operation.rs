@@ -0,0 +1,188 @@
+use super::JjAdapter;
+use crate::domain::{
+    error::VcsError,
+    operation::{BookmarkChange, ChangedCommit, Operation, OperationChanges},
+};
+use anyhow::Result;
+use jj_lib::{
+    backend::CommitId as JjCommitId,
+    object_id::ObjectId,
+    op_store::{self, OperationId, RefTarget},
+    op_walk,
+    operation::Operation as JjOperation,
+    repo::{ReadonlyRepo, Repo, RepoLoader},
+    revset::ResolvedRevsetExpression,
+};
+use std::collections::BTreeSet;
+
+impl JjAdapter {
+    pub(crate) async fn operation_log_impl(&self, limit: usize) -> Result<Vec<Operation>> {
+        let (repo, _) = self.get_repo_and_ws().await?;
+        tokio::task::spawn_blocking(move || {
+            let current = repo.operation();
+            op_walk::walk_ancestors(std::slice::from_ref(current))
+                .take(limit)
+                .map(|op| {
+                    let op = op?;
+                    Ok(to_operation(&op, op.id() == current.id()))
+                })
+                .collect()
+        })
+        .await?
+    }
+
+    pub(crate) async fn operation_changes_impl(&self, op_id: &str) -> Result<OperationChanges> {
+        let (repo, _) = self.get_repo_and_ws().await?;
+        let op_id = op_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let loader = repo.loader();
+            let op = load_operation(loader, &op_id)?;
+            let Some(parent) = op.parents().next().transpose()? else {
+                return Ok(OperationChanges::default());
+            };
+            // The operation's index also holds every commit its parent saw.
+            let repo = loader.load_at(&op)?;
+            let parent_view = parent.view()?;
+            let parent_view = parent_view.store_view();
+            let heads = |ids: &std::collections::HashSet<JjCommitId>| {
+                ResolvedRevsetExpression::commits(ids.iter().cloned().collect())
+            };
+            let (old_heads, new_heads) = (heads(&parent_view.head_ids), heads(repo.view().heads()));
+            let added = changed_commits(&repo, old_heads.range(&new_heads))?;
+            let removed = changed_commits(&repo, new_heads.range(&old_heads))?;
+
+            let old_bookmarks = &parent_view.local_bookmarks;
+            let new_bookmarks = &repo.view().store_view().local_bookmarks;
+            let names: BTreeSet<_> = old_bookmarks.keys().chain(new_bookmarks.keys()).collect();
+            let absent = RefTarget::absent();
+            let bookmarks = names
+                .into_iter()
+                .filter_map(|name| {
+                    let from = old_bookmarks.get(name).unwrap_or(&absent);
+                    let to = new_bookmarks.get(name).unwrap_or(&absent);
+                    (from != to).then(|| BookmarkChange {
+                        name: name.as_str().to_string(),
+                        from: format_target(from),
+                        to: format_target(to),
+                    })
+                })
+                .collect();
+            Ok(OperationChanges {
+                added,
+                removed,
+                bookmarks,
+            })
+        })
+        .await?
+    }
+
+    /// Puts the repository back the way it was right after `op_id`, like
+    /// `jj op restore`.
+    pub(crate) async fn restore_operation_impl(&self, op_id: &str) -> Result<()> {
+        let op_id = op_id.to_string();
+        let description = format!("restore to operation {op_id}");
+        self.run_transaction(description, move |mut_repo, _| {
+            let op = load_operation(mut_repo.base_repo().loader(), &op_id)?;
+            let view = restored_view(op.view()?.store_view(), mut_repo.view().store_view());
+            mut_repo.set_view(view);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Undoes just `op_id`, keeping everything done after it, like
+    /// `jj op revert`.
+    pub(crate) async fn revert_operation_impl(&self, op_id: &str) -> Result<()> {
+        let op_id = op_id.to_string();
+        let description = format!("revert operation {op_id}");
+        self.run_transaction(description, move |mut_repo, _| {
+            let base_repo = mut_repo.base_repo().clone();
+            let loader = base_repo.loader();
+            let op = load_operation(loader, &op_id)?;
+            let mut parents = op.parents();
+            let (Some(parent), None) = (parents.next(), parents.next()) else {
+                return Err(VcsError::OperationWithoutSingleParent.into());
+            };
+            let op_repo = loader.load_at(&op)?;
+            let parent_repo = loader.load_at(&parent?)?;
+            mut_repo.merge(&op_repo, &parent_repo)?;
+            let view = restored_view(mut_repo.view().store_view(), base_repo.view().store_view());
+            mut_repo.set_view(view);
+            Ok(())
+        })
+        .await
+    }
+}
+
+fn load_operation(loader: &RepoLoader, op_id: &str) -> Result<JjOperation> {
+    let id = OperationId::try_from_hex(op_id)
+        .ok_or_else(|| VcsError::OperationNotFound(op_id.to_string()))?;
+    loader
+        .load_operation(&id)
+        .map_err(|_| VcsError::OperationNotFound(op_id.to_string()).into())
+}
+
+/// `view` with the Git refs of `current`, which the export to Git keeps in
+/// sync with the restored bookmarks.
+fn restored_view(view: &op_store::View, current: &op_store::View) -> op_store::View {
+    op_store::View {
+        git_refs: current.git_refs.clone(),
+        git_head: current.git_head.clone(),
+        ..view.clone()
+    }
+}
+
+fn to_operation(op: &JjOperation, is_current: bool) -> Operation {
+    let metadata = op.metadata();
+    let id = op.id().hex();
+    let end = &metadata.time.end;
+    let timestamp = chrono::DateTime::from_timestamp_millis(end.timestamp.0)
+        .unwrap_or_default()
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    Operation {
+        id_short: id[..12.min(id.len())].to_string(),
+        id,
+        parent_ids: op.parent_ids().iter().map(ObjectId::hex).collect(),
+        timestamp,
+        user: format!("{}@{}", metadata.username, metadata.hostname),
+        description: metadata.description.clone(),
+        is_snapshot: metadata.is_snapshot,
+        is_current,
+    }
+}
+
+fn changed_commits(
+    repo: &ReadonlyRepo,
+    expression: std::sync::Arc<ResolvedRevsetExpression>,
+) -> Result<Vec<ChangedCommit>> {
+    let mut commits = Vec::new();
+    for id in expression.evaluate(repo)?.iter() {
+        let commit = repo.store().get_commit(&id?)?;
+        let change_id = super::format_change_id(commit.change_id());
+        let commit_id = commit.id().hex();
+        commits.push(ChangedCommit {
+            change_id_short: change_id[..8.min(change_id.len())].to_string(),
+            commit_id_short: commit_id[..8.min(commit_id.len())].to_string(),
+            summary: commit
+                .description()
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+fn format_target(target: &RefTarget) -> Option<String> {
+    if target.is_absent() {
+        return None;
+    }
+    let ids: Vec<String> = target
+        .added_ids()
+        .map(|id| id.hex()[..8].to_string())
+        .collect();
+    Some(ids.join(", "))
+}
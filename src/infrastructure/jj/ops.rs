@@ -1,11 +1,27 @@
+use super::diff::select_changes;
+use super::transaction::{reverse_topological, TxContext};
 use super::JjAdapter;
-use crate::domain::models::CommitId;
+use crate::domain::{
+    diff::FileSelection,
+    error::VcsError,
+    models::{CommitId, RebaseLocation, RebaseMode, Rewrite, SquashDestination},
+};
 use anyhow::{anyhow, Context, Result};
+use futures::executor::block_on;
 use jj_lib::{
-    local_working_copy::LocalWorkingCopyFactory, repo::StoreFactories,
-    working_copy::WorkingCopyFactory, workspace::Workspace,
+    backend::CommitId as JjCommitId,
+    commit::Commit,
+    local_working_copy::LocalWorkingCopyFactory,
+    merge::Merge,
+    merged_tree::MergedTree,
+    repo::{MutableRepo, Repo, StoreFactories},
+    revset::ResolvedRevsetExpression,
+    rewrite::{self, CommitWithSelection, MoveCommitsLocation, MoveCommitsTarget, RebaseOptions},
+    working_copy::WorkingCopyFactory,
+    workspace::Workspace,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 impl JjAdapter {
     pub(crate) async fn describe_revision_impl(
@@ -13,21 +29,18 @@ impl JjAdapter {
         commit_id: &str,
         message: &str,
     ) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("describe")
-            .arg(commit_id)
-            .arg("-m")
-            .arg(message)
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
+        let commit_id = CommitId(commit_id.to_string());
+        let message = message.to_string();
+        let description = format!("describe commit {}", commit_id.0);
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let commit = ctx.load_mutable_commit(mut_repo, &commit_id)?;
+            mut_repo
+                .rewrite_commit(&commit)
+                .set_description(message)
+                .write()?;
             Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj describe failed: {}", stderr.trim()))
-        }
+        })
+        .await
     }
 
     pub(crate) async fn commit_impl(&self, message: &str) -> Result<()> {
@@ -48,88 +61,163 @@ impl JjAdapter {
     }
 
     pub(crate) async fn snapshot_impl(&self) -> Result<String> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("status")
-            .current_dir(&ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
-            Ok("Snapshot created.".to_string())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj snapshot failed: {}", stderr.trim()))
-        }
+        self.snapshot_working_copy().await?;
+        Ok("Snapshot created.".to_string())
     }
 
     pub(crate) async fn edit_impl(&self, commit_id: &CommitId) -> Result<()> {
-        self.validate_commit(commit_id).await?;
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("edit")
-            .arg(&commit_id.0)
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
+        let commit_id = commit_id.clone();
+        let description = format!("edit commit {}", commit_id.0);
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let commit = ctx.load_mutable_commit(mut_repo, &commit_id)?;
+            mut_repo.edit(ctx.workspace_name.clone(), &commit)?;
             Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj edit failed: {}", stderr.trim()))
-        }
+        })
+        .await
     }
 
     pub(crate) async fn squash_impl(&self, commit_ids: &[CommitId]) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let mut cmd = tokio::process::Command::new("jj");
-        cmd.arg("squash");
-        for id in commit_ids {
-            self.validate_commit(id).await?;
-            cmd.arg("-r").arg(&id.0);
-        }
-        let output = cmd.current_dir(ws_root).output().await?;
+        let commit_ids = commit_ids.to_vec();
+        let description = format!("squash commits into parent of {}", join_ids(&commit_ids));
+        self.run_transaction(description, move |mut_repo, ctx| {
+            squash(mut_repo, ctx, &commit_ids)
+        })
+        .await
+    }
 
-        if output.status.success() {
+    pub(crate) async fn squash_changes_impl(
+        &self,
+        commit_id: &CommitId,
+        files: &[FileSelection],
+        destination: &SquashDestination,
+    ) -> Result<()> {
+        let commit_id = commit_id.clone();
+        let files = files.to_vec();
+        let destination = destination.clone();
+        let description = match &destination {
+            SquashDestination::Parent => format!("squash changes into parent of {commit_id}"),
+            SquashDestination::Revision(target) => {
+                format!("squash changes from {commit_id} into {target}")
+            }
+            SquashDestination::NewChild => format!("move changes from {commit_id} into new child"),
+        };
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let source = ctx.load_mutable_commit(mut_repo, &commit_id)?;
+            let parent_tree = source.parent_tree(mut_repo)?;
+            let selection = CommitWithSelection {
+                selected_tree: select_changes(&parent_tree, &source.tree(), &files)?,
+                parent_tree,
+                commit: source.clone(),
+            };
+
+            let target = match destination {
+                SquashDestination::Parent => {
+                    let [parent_id] = source.parent_ids() else {
+                        return Err(VcsError::SquashWithoutSingleParent.into());
+                    };
+                    ctx.check_mutable(mut_repo, parent_id)?;
+                    mut_repo.store().get_commit(parent_id)?
+                }
+                SquashDestination::Revision(target_id) => {
+                    let target = ctx.load_mutable_commit(mut_repo, &target_id)?;
+                    if target.id() == source.id() {
+                        return Err(VcsError::SquashIntoSource.into());
+                    }
+                    target
+                }
+                SquashDestination::NewChild => {
+                    // Keep the unpicked changes in the source, then put a new
+                    // child with the original tree on top. The child takes over
+                    // the source's children, bookmarks and working copy.
+                    let diff = selection.diff_with_labels("parents", "picked changes", "source")?;
+                    let remaining_tree = block_on(MergedTree::merge(Merge::from_diffs(
+                        (source.tree(), source.conflict_label()),
+                        [diff.invert()],
+                    )))?;
+                    let remaining = mut_repo
+                        .rewrite_commit(&source)
+                        .set_tree(remaining_tree)
+                        .write()?;
+                    mut_repo
+                        .rewrite_commit(&source)
+                        .generate_new_change_id()
+                        .set_parents(vec![remaining.id().clone()])
+                        .set_description("")
+                        .write()?;
+                    return Ok(());
+                }
+            };
+
+            // Squashing everything abandons the source, so its description
+            // moves along with the changes.
+            let description = if selection.is_full_selection() {
+                combine_descriptions([&target, &source].into_iter())
+            } else {
+                target.description().to_string()
+            };
+            if let Some(squashed) = rewrite::squash_commits(mut_repo, &[selection], &target, false)?
+            {
+                squashed
+                    .commit_builder
+                    .set_description(description)
+                    .write()?;
+            }
             Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj squash failed: {}", stderr.trim()))
-        }
+        })
+        .await
+    }
+
+    pub(crate) async fn split_impl(
+        &self,
+        commit_id: &CommitId,
+        files: &[FileSelection],
+        message: &str,
+    ) -> Result<()> {
+        let commit_id = commit_id.clone();
+        let files = files.to_vec();
+        let message = message.to_string();
+        let description = format!("split commit {}", commit_id.0);
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let commit = ctx.load_mutable_commit(mut_repo, &commit_id)?;
+            let parent_tree = commit.parent_tree(mut_repo)?;
+            let selected_tree = select_changes(&parent_tree, &commit.tree(), &files)?;
+
+            // Like `jj split`: the first commit keeps the change ID and takes
+            // the selected changes; the second gets the rest, the original
+            // description, and the bookmarks, children and working copy.
+            let first = mut_repo
+                .rewrite_commit(&commit)
+                .set_tree(selected_tree)
+                .set_description(message)
+                .write()?;
+            mut_repo
+                .rewrite_commit(&commit)
+                .generate_new_change_id()
+                .set_parents(vec![first.id().clone()])
+                .write()?;
+            Ok(())
+        })
+        .await
     }
 
     pub(crate) async fn new_child_impl(&self, commit_id: &CommitId) -> Result<()> {
-        self.validate_commit(commit_id).await?;
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("new")
-            .arg(&commit_id.0)
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
+        let commit_id = commit_id.clone();
+        let description = format!("new empty commit on {}", commit_id.0);
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let parent = ctx.load_commit(mut_repo, &commit_id)?;
+            mut_repo.check_out(ctx.workspace_name.clone(), &parent)?;
             Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj new failed: {}", stderr.trim()))
-        }
+        })
+        .await
     }
 
     pub(crate) async fn abandon_impl(&self, commit_ids: &[CommitId]) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let mut cmd = tokio::process::Command::new("jj");
-        cmd.arg("abandon");
-        for id in commit_ids {
-            self.validate_commit(id).await?;
-            cmd.arg("-r").arg(&id.0);
-        }
-        let output = cmd.current_dir(ws_root).output().await?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj abandon failed: {}", stderr.trim()))
-        }
+        let commit_ids = commit_ids.to_vec();
+        let description = format!("abandon commits {}", join_ids(&commit_ids));
+        self.run_transaction(description, move |mut_repo, ctx| {
+            abandon(mut_repo, ctx, &commit_ids)
+        })
+        .await
     }
 
     pub(crate) async fn revert_impl(&self, commit_ids: &[CommitId]) -> Result<()> {
@@ -165,62 +253,56 @@ impl JjAdapter {
     }
 
     pub(crate) async fn duplicate_impl(&self, commit_ids: &[CommitId]) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let mut cmd = tokio::process::Command::new("jj");
-        cmd.arg("duplicate");
-        for id in commit_ids {
-            self.validate_commit(id).await?;
-            cmd.arg("-r").arg(&id.0);
-        }
-        let output = cmd.current_dir(ws_root).output().await?;
-
-        if output.status.success() {
+        let commit_ids = commit_ids.to_vec();
+        let description = format!("duplicate commits {}", join_ids(&commit_ids));
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let ids = commit_ids
+                .iter()
+                .map(|id| {
+                    ctx.load_mutable_commit(mut_repo, id)
+                        .map(|c| c.id().clone())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let ordered = reverse_topological(mut_repo, ids)?;
+            rewrite::duplicate_commits_onto_parents(mut_repo, &ordered, &HashMap::new())?;
             Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj duplicate failed: {}", stderr.trim()))
-        }
+        })
+        .await
     }
 
     pub(crate) async fn parallelize_impl(&self, commit_ids: &[CommitId]) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let mut cmd = tokio::process::Command::new("jj");
-        cmd.arg("parallelize");
-        for id in commit_ids {
-            self.validate_commit(id).await?;
-            cmd.arg("-r").arg(&id.0);
-        }
-        let output = cmd.current_dir(ws_root).output().await?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj parallelize failed: {}", stderr.trim()))
-        }
+        let commit_ids = commit_ids.to_vec();
+        let description = format!("parallelize commits {}", join_ids(&commit_ids));
+        self.run_transaction(description, move |mut_repo, ctx| {
+            parallelize(mut_repo, ctx, &commit_ids)
+        })
+        .await
     }
 
     pub(crate) async fn rebase_impl(
         &self,
         commit_ids: &[CommitId],
+        mode: RebaseMode,
+        location: RebaseLocation,
         destination: &str,
     ) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let mut cmd = tokio::process::Command::new("jj");
-        cmd.arg("rebase");
-        for id in commit_ids {
-            self.validate_commit(id).await?;
-            cmd.arg("-r").arg(&id.0);
-        }
-        cmd.arg("-d").arg(destination);
-        let output = cmd.current_dir(ws_root).output().await?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj rebase failed: {}", stderr.trim()))
-        }
+        let commit_ids = commit_ids.to_vec();
+        let destination = destination.to_string();
+        let what = match mode {
+            RebaseMode::Revisions => format!("commits {}", join_ids(&commit_ids)),
+            RebaseMode::Source => format!("commits {} and descendants", join_ids(&commit_ids)),
+            RebaseMode::Branch => format!("branch of commits {}", join_ids(&commit_ids)),
+        };
+        let place = match location {
+            RebaseLocation::Destination => "onto",
+            RebaseLocation::InsertAfter => "after",
+            RebaseLocation::InsertBefore => "before",
+        };
+        let description = format!("rebase {what} {place} {}", destination.trim());
+        self.run_transaction(description, move |mut_repo, ctx| {
+            rebase(mut_repo, ctx, &commit_ids, mode, location, &destination)
+        })
+        .await
     }
 
     pub(crate) async fn undo_impl(&self) -> Result<()> {
@@ -285,3 +367,234 @@ impl JjAdapter {
         }
     }
 }
+
+/// Applies `rewrite` the way the matching `*_impl` method does, so that it
+/// can be previewed in a transaction that is never committed.
+pub(super) fn apply_rewrite(
+    mut_repo: &mut MutableRepo,
+    ctx: &TxContext,
+    rewrite: &Rewrite,
+) -> Result<()> {
+    match rewrite {
+        Rewrite::Abandon(ids) => abandon(mut_repo, ctx, ids),
+        Rewrite::Squash(ids) => squash(mut_repo, ctx, ids),
+        Rewrite::Parallelize(ids) => parallelize(mut_repo, ctx, ids),
+        Rewrite::Rebase(ids, mode, location, destination) => {
+            rebase(mut_repo, ctx, ids, *mode, *location, destination)
+        }
+    }
+}
+
+fn squash(mut_repo: &mut MutableRepo, ctx: &TxContext, commit_ids: &[CommitId]) -> Result<()> {
+    let sources = commit_ids
+        .iter()
+        .map(|id| ctx.load_mutable_commit(mut_repo, id))
+        .collect::<Result<Vec<_>>>()?;
+
+    // The destination is the one parent outside the selection that
+    // all of the selected revisions hang off.
+    let source_ids: HashSet<_> = sources.iter().map(|c| c.id().clone()).collect();
+    let outside_parents: HashSet<_> = sources
+        .iter()
+        .flat_map(|c| c.parent_ids().iter())
+        .filter(|id| !source_ids.contains(*id))
+        .collect();
+    let [destination_id] = outside_parents.into_iter().collect::<Vec<_>>()[..] else {
+        return Err(VcsError::SquashWithoutSingleParent.into());
+    };
+    ctx.check_mutable(mut_repo, destination_id)?;
+    let destination = mut_repo.store().get_commit(destination_id)?;
+
+    let ordered = reverse_topological(mut_repo, source_ids.into_iter().collect())?;
+    let mut selections = Vec::new();
+    for id in ordered.iter().rev() {
+        let commit = mut_repo.store().get_commit(id)?;
+        selections.push(CommitWithSelection {
+            selected_tree: commit.tree(),
+            parent_tree: commit.parent_tree(mut_repo)?,
+            commit,
+        });
+    }
+
+    let combined_description = combine_descriptions(
+        std::iter::once(&destination).chain(selections.iter().map(|s| &s.commit)),
+    );
+
+    if let Some(squashed) = rewrite::squash_commits(mut_repo, &selections, &destination, false)? {
+        squashed
+            .commit_builder
+            .set_description(combined_description)
+            .write()?;
+    }
+    Ok(())
+}
+
+fn abandon(mut_repo: &mut MutableRepo, ctx: &TxContext, commit_ids: &[CommitId]) -> Result<()> {
+    let commits = commit_ids
+        .iter()
+        .map(|id| ctx.load_mutable_commit(mut_repo, id))
+        .collect::<Result<Vec<_>>>()?;
+    for commit in &commits {
+        mut_repo.record_abandoned_commit(commit);
+    }
+    Ok(())
+}
+
+/// Makes the revisions siblings, like `jj parallelize`: each one keeps the
+/// parents it has outside the set, and the children of each one gain the
+/// revisions of the set it descended from as parents.
+fn parallelize(mut_repo: &mut MutableRepo, ctx: &TxContext, commit_ids: &[CommitId]) -> Result<()> {
+    let ids = commit_ids
+        .iter()
+        .map(|id| {
+            ctx.load_mutable_commit(mut_repo, id)
+                .map(|c| c.id().clone())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ordered = reverse_topological(mut_repo, ids)?;
+
+    // Parents first, so that a revision's parents in the set are already
+    // mapped to theirs.
+    let mut target_parents: HashMap<JjCommitId, Vec<JjCommitId>> = HashMap::new();
+    let mut child_parents: HashMap<JjCommitId, Vec<JjCommitId>> = HashMap::new();
+    for id in ordered.iter().rev() {
+        let commit = mut_repo.store().get_commit(id)?;
+        let mut new_parents = Vec::new();
+        let mut in_set = Vec::new();
+        for parent_id in commit.parent_ids() {
+            match target_parents.get(parent_id) {
+                Some(grandparents) => new_parents.extend(grandparents.iter().cloned()),
+                None => new_parents.push(parent_id.clone()),
+            }
+            if let Some(parents) = child_parents.get(parent_id) {
+                in_set.extend(parents.iter().cloned());
+            }
+        }
+        in_set.push(id.clone());
+        target_parents.insert(id.clone(), new_parents);
+        child_parents.insert(id.clone(), in_set);
+    }
+
+    mut_repo.transform_descendants(ordered, async |mut rewriter| {
+        let old_parents = rewriter.old_commit().parent_ids().to_vec();
+        if let Some(new_parents) = target_parents.get(rewriter.old_commit().id()) {
+            rewriter.set_new_rewritten_parents(new_parents);
+        } else if old_parents.iter().any(|id| child_parents.contains_key(id)) {
+            let new_parents: Vec<_> = old_parents
+                .iter()
+                .flat_map(|id| {
+                    child_parents
+                        .get(id)
+                        .cloned()
+                        .unwrap_or_else(|| vec![id.clone()])
+                })
+                .collect();
+            rewriter.set_new_rewritten_parents(&new_parents);
+        }
+        if rewriter.parents_changed() {
+            rewriter.rebase().await?.write()?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn rebase(
+    mut_repo: &mut MutableRepo,
+    ctx: &TxContext,
+    commit_ids: &[CommitId],
+    mode: RebaseMode,
+    location: RebaseLocation,
+    destination: &str,
+) -> Result<()> {
+    let ids = commit_ids
+        .iter()
+        .map(|id| {
+            ctx.load_mutable_commit(mut_repo, id)
+                .map(|c| c.id().clone())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let target_id = ctx.revset_env().resolve_single(mut_repo, destination)?;
+    let target = ResolvedRevsetExpression::commits(vec![target_id.clone()]);
+    let (new_parent_ids, new_child_ids) = match location {
+        RebaseLocation::Destination => (vec![target_id], vec![]),
+        RebaseLocation::InsertAfter => (vec![target_id], evaluate(mut_repo, target.children())?),
+        RebaseLocation::InsertBefore => (evaluate(mut_repo, target.parents())?, vec![target_id]),
+    };
+    // The commits inserted before get new parents.
+    for id in &new_child_ids {
+        ctx.check_mutable(mut_repo, id)?;
+    }
+
+    let sources = ResolvedRevsetExpression::commits(ids.clone());
+    let (target, moved) = match mode {
+        RebaseMode::Revisions => (
+            MoveCommitsTarget::Commits(reverse_topological(mut_repo, ids)?),
+            sources,
+        ),
+        RebaseMode::Source => (MoveCommitsTarget::Roots(ids), sources.descendants()),
+        RebaseMode::Branch => {
+            // Everything on the branches that the new parents don't
+            // already have, as `jj rebase -b` does.
+            let roots = ResolvedRevsetExpression::commits(new_parent_ids.clone())
+                .range(&sources)
+                .roots();
+            let roots = evaluate(mut_repo, roots)?;
+            if roots.is_empty() {
+                return Ok(());
+            }
+            let moved = ResolvedRevsetExpression::commits(roots.clone()).descendants();
+            (MoveCommitsTarget::Roots(roots), moved)
+        }
+    };
+    // Not only the picked revisions: `-b` can reach down into trunk.
+    let moved = evaluate(mut_repo, moved)?;
+    for id in &moved {
+        ctx.check_mutable(mut_repo, id)?;
+    }
+    let moved: HashSet<JjCommitId> = moved.into_iter().collect();
+    if new_parent_ids
+        .iter()
+        .chain(&new_child_ids)
+        .any(|id| moved.contains(id))
+    {
+        return Err(VcsError::InvalidRebaseDestination.into());
+    }
+
+    let location = MoveCommitsLocation {
+        new_parent_ids,
+        new_child_ids,
+        target,
+    };
+    rewrite::move_commits(mut_repo, &location, &RebaseOptions::default())?;
+    Ok(())
+}
+
+/// Joins the non-empty descriptions of `commits`, in order.
+fn combine_descriptions<'a>(commits: impl Iterator<Item = &'a Commit>) -> String {
+    let combined = commits
+        .map(|c| c.description().trim())
+        .filter(|d| !d.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if combined.is_empty() {
+        String::new()
+    } else {
+        format!("{combined}\n")
+    }
+}
+
+fn evaluate(repo: &dyn Repo, expression: Arc<ResolvedRevsetExpression>) -> Result<Vec<JjCommitId>> {
+    Ok(expression
+        .evaluate(repo)?
+        .iter()
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+fn join_ids(commit_ids: &[CommitId]) -> String {
+    commit_ids
+        .iter()
+        .map(|id| id.0.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
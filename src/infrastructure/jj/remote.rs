@@ -1,36 +1,559 @@
 use super::JjAdapter;
-use anyhow::{anyhow, Result};
+use crate::domain::{
+    error::VcsError,
+    push::{BookmarkUpdate, PushPlan, PushScope, PushedBookmark, UpdateKind},
+    remote::Remote,
+};
+use anyhow::Result;
+use jj_lib::{
+    backend::CommitId as JjCommitId,
+    git::{
+        self, GitBranchPushTargets, GitFetch, GitFetchRefExpression, GitImportOptions, GitProgress,
+        GitSidebandLineTerminator, GitSubprocessCallback, GitSubprocessOptions,
+    },
+    object_id::ObjectId,
+    op_store::{RefTarget, RemoteRef},
+    ref_name::{RefNameBuf, RemoteName, RemoteNameBuf},
+    refs::{
+        classify_bookmark_push_action, BookmarkPushAction, BookmarkPushUpdate, LocalAndRemoteRef,
+    },
+    repo::Repo,
+    revset::{parse_string_expression, ResolvedRevsetExpression, RevsetDiagnostics},
+    settings::UserSettings,
+    str_util::StringExpression,
+};
+use std::collections::HashMap;
+
+/// Bookmarks that a push would update, and the ones in scope it would skip.
+struct Plan {
+    updates: Vec<(RefNameBuf, BookmarkPushUpdate)>,
+    skipped: Vec<(String, String)>,
+}
 
 impl JjAdapter {
-    pub(crate) async fn fetch_impl(&self) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let output = tokio::process::Command::new("jj")
-            .arg("git")
-            .arg("fetch")
-            .current_dir(ws_root)
-            .output()
-            .await?;
-        if output.status.success() {
-            Ok(())
+    /// Fetches from the remotes and imports what changed, like
+    /// `jj git fetch --remote <remotes> --branch <branches>`.
+    pub(crate) async fn fetch_impl(&self, remotes: &[String], branches: &[String]) -> Result<()> {
+        let requested: Vec<RemoteNameBuf> = remotes.iter().map(RemoteNameBuf::from).collect();
+        let branches = branches.to_vec();
+        let description = if requested.is_empty() {
+            "fetch from git remote".to_string()
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj fetch failed: {}", stderr.trim()))
+            format!(
+                "fetch from git remote(s) {}",
+                requested
+                    .iter()
+                    .map(|r| r.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        };
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let all_remotes = git::get_all_remote_names(mut_repo.store())?;
+            let remotes = if requested.is_empty() {
+                vec![pick_remote(&ctx.settings, "git.fetch", &all_remotes, None)?]
+            } else {
+                for remote in &requested {
+                    if !all_remotes.contains(remote) {
+                        return Err(VcsError::NoSuchRemote(remote.as_str().to_string()).into());
+                    }
+                }
+                requested
+            };
+            let branch_expr = if branches.is_empty() {
+                None
+            } else {
+                let patterns = branches
+                    .iter()
+                    .map(|branch| parse_string_expression(&mut RevsetDiagnostics::new(), branch))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Some(StringExpression::union_all(patterns))
+            };
+
+            let git_repo = git::get_git_repo(mut_repo.store())?;
+            let import_options = import_options(&ctx.settings)?;
+            let mut fetch = GitFetch::new(
+                mut_repo,
+                subprocess_options(&ctx.settings)?,
+                &import_options,
+            )?;
+            for remote in &remotes {
+                let bookmark = match &branch_expr {
+                    Some(expr) => expr.clone(),
+                    // The branches the remote's refspecs fetch, usually all.
+                    None => git::load_default_fetch_bookmarks(remote, &git_repo)?.1,
+                };
+                let refspecs = git::expand_fetch_refspecs(
+                    remote,
+                    GitFetchRefExpression {
+                        bookmark,
+                        tag: StringExpression::none(),
+                    },
+                )?;
+                fetch.fetch(remote, refspecs, &mut Quiet, None, None)?;
+            }
+            fetch.import_refs()?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn remotes_impl(&self) -> Result<Vec<Remote>> {
+        // Picks up remotes changed with `git remote` too.
+        self.reload_git_config().await?;
+        let (repo, _) = self.get_repo_and_ws().await?;
+        tokio::task::spawn_blocking(move || {
+            let git_repo = git::get_git_repo(repo.store())?;
+            let config = git_repo.config_snapshot();
+            let remotes = git::get_all_remote_names(repo.store())?
+                .into_iter()
+                .map(|name| {
+                    let get = |key: &str| {
+                        config
+                            .string(format!("remote.{}.{key}", name.as_str()).as_str())
+                            .map(|value| value.to_string())
+                    };
+                    Remote {
+                        url: get("url").unwrap_or_default(),
+                        push_url: get("pushurl"),
+                        name: name.as_str().to_string(),
+                    }
+                })
+                .collect();
+            Ok(remotes)
+        })
+        .await?
+    }
+
+    /// Adds a remote that fetches every branch, like `jj git remote add`.
+    pub(crate) async fn add_remote_impl(&self, name: &str, url: &str) -> Result<()> {
+        let name = RemoteNameBuf::from(name);
+        let url = url.to_string();
+        let description = format!("add git remote {}", name.as_str());
+        self.run_transaction(description, move |mut_repo, _| {
+            git::add_remote(
+                mut_repo,
+                &name,
+                &url,
+                None,
+                Default::default(),
+                &StringExpression::all(),
+            )?;
+            Ok(())
+        })
+        .await?;
+        self.reload_git_config().await
+    }
+
+    /// Renames a remote along with its remote bookmarks, like
+    /// `jj git remote rename`.
+    pub(crate) async fn rename_remote_impl(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let old_name = RemoteNameBuf::from(old_name);
+        let new_name = RemoteNameBuf::from(new_name);
+        let description = format!(
+            "rename git remote {} to {}",
+            old_name.as_str(),
+            new_name.as_str()
+        );
+        self.run_transaction(description, move |mut_repo, _| {
+            git::rename_remote(mut_repo, &old_name, &new_name)?;
+            Ok(())
+        })
+        .await?;
+        self.reload_git_config().await
+    }
+
+    pub(crate) async fn remove_remote_impl(&self, name: &str) -> Result<()> {
+        let name = RemoteNameBuf::from(name);
+        let description = format!("remove git remote {}", name.as_str());
+        self.run_transaction(description, move |mut_repo, _| {
+            git::remove_remote(mut_repo, &name)?;
+            Ok(())
+        })
+        .await?;
+        self.reload_git_config().await
+    }
+
+    /// Points the remote at another URL. Only Git's config changes, so like
+    /// `jj git remote set-url` this records no operation.
+    pub(crate) async fn set_remote_url_impl(&self, name: &str, url: &str) -> Result<()> {
+        let (repo, _) = self.get_repo_and_ws().await?;
+        let name = RemoteNameBuf::from(name);
+        let url = url.to_string();
+        tokio::task::spawn_blocking(move || {
+            git::set_remote_urls(repo.store(), &name, Some(&url), None)
+        })
+        .await??;
+        self.reload_git_config().await
+    }
+
+    /// The Git backend reads Git's config once when the workspace is loaded,
+    /// so remotes changed since are only seen after loading it again.
+    async fn reload_git_config(&self) -> Result<()> {
+        let settings = self.user_settings.clone();
+        let root = self.workspace_root.clone();
+        let workspace =
+            tokio::task::spawn_blocking(move || super::load_workspace(&settings, &root)).await??;
+        *self.workspace.lock().await = Some(workspace);
+        Ok(())
+    }
+
+    /// Works out what [`Self::push_impl`] would do without pushing, like
+    /// `jj git push --dry-run`. Without a remote, picks the one `jj` would.
+    pub(crate) async fn push_preview_impl(
+        &self,
+        remote: Option<String>,
+        scope: PushScope,
+    ) -> Result<PushPlan> {
+        let (repo, _) = self.get_repo_and_ws().await?;
+        let settings = self.user_settings.clone();
+        tokio::task::spawn_blocking(move || {
+            let remotes = git::get_all_remote_names(repo.store())?;
+            let remote = pick_remote(&settings, "git.push", &remotes, remote)?;
+            let change_bookmark = match &scope {
+                PushScope::Change(commit_id) => {
+                    let id = JjCommitId::try_from_hex(&commit_id.0)
+                        .ok_or_else(|| VcsError::InvalidCommitId(commit_id.0.clone()))?;
+                    Some(change_bookmark(repo.as_ref(), &id)?)
+                }
+                _ => None,
+            };
+            let plan = plan_push(repo.as_ref(), &remote, &scope, change_bookmark)?;
+            Ok(PushPlan {
+                remote: remote.as_str().to_string(),
+                remotes: remotes.iter().map(|r| r.as_str().to_string()).collect(),
+                bookmarks: repo
+                    .view()
+                    .local_bookmarks()
+                    .map(|(name, _)| name.as_str().to_string())
+                    .collect(),
+                updates: plan
+                    .updates
+                    .iter()
+                    .map(|(name, update)| to_bookmark_update(repo.as_ref(), name, update))
+                    .collect::<Result<_>>()?,
+                skipped: plan.skipped,
+            })
+        })
+        .await?
+    }
+
+    /// Pushes the bookmarks in `scope` to `remote` and reports how each one
+    /// went, like `jj git push`.
+    pub(crate) async fn push_impl(
+        &self,
+        remote: &str,
+        scope: &PushScope,
+    ) -> Result<Vec<PushedBookmark>> {
+        let remote = RemoteNameBuf::from(remote);
+        let scope = scope.clone();
+        let description = match &scope {
+            PushScope::Bookmarks(names) => format!(
+                "push bookmark {} to git remote {}",
+                names.join(", "),
+                remote.as_symbol()
+            ),
+            PushScope::Change(commit_id) => format!(
+                "push change of commit {} to git remote {}",
+                commit_id.0,
+                remote.as_symbol()
+            ),
+            PushScope::All => format!("push all bookmarks to git remote {}", remote.as_symbol()),
+            PushScope::Tracked => format!(
+                "push all tracked bookmarks to git remote {}",
+                remote.as_symbol()
+            ),
+        };
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let remotes = git::get_all_remote_names(mut_repo.store())?;
+            if !remotes.contains(&remote) {
+                return Err(VcsError::NoSuchRemote(remote.as_str().to_string()).into());
+            }
+            let change_bookmark = match &scope {
+                PushScope::Change(commit_id) => {
+                    let commit = ctx.load_commit(mut_repo, commit_id)?;
+                    let (name, target) = change_bookmark(mut_repo, commit.id())?;
+                    if mut_repo.get_local_bookmark(&name).is_absent() {
+                        mut_repo.set_local_bookmark_target(&name, target.clone());
+                    }
+                    Some((name, target))
+                }
+                _ => None,
+            };
+            let plan = plan_push(mut_repo, &remote, &scope, change_bookmark)?;
+            if plan.updates.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let options = subprocess_options(&ctx.settings)?;
+            let targets = GitBranchPushTargets {
+                branch_updates: plan.updates,
+            };
+            let stats = git::push_branches(mut_repo, options, &remote, &targets, &mut Quiet)?;
+            let error_for = |name: &RefNameBuf| {
+                let qualified = format!("refs/heads/{}", name.as_str());
+                if stats.pushed.iter().any(|r| r.as_str() == qualified) {
+                    return None;
+                }
+                let reason = |rejected: &[(jj_lib::ref_name::GitRefNameBuf, Option<String>)]| {
+                    rejected
+                        .iter()
+                        .find(|(r, _)| r.as_str() == qualified)
+                        .map(|(_, reason)| reason.clone())
+                };
+                Some(if let Some(reason) = reason(&stats.rejected) {
+                    reason.unwrap_or_else(|| {
+                        format!("{} changed since the last fetch", remote.as_symbol())
+                    })
+                } else if let Some(reason) = reason(&stats.remote_rejected) {
+                    reason.unwrap_or_else(|| "Rejected by the remote".to_string())
+                } else {
+                    "Not pushed".to_string()
+                })
+            };
+            Ok(targets
+                .branch_updates
+                .iter()
+                .map(|(name, _)| PushedBookmark {
+                    name: name.as_str().to_string(),
+                    error: error_for(name),
+                })
+                .collect())
+        })
+        .await
+    }
+}
+
+/// Reports nothing: the UI only shows how each bookmark went.
+pub(super) struct Quiet;
+
+impl GitSubprocessCallback for Quiet {
+    fn needs_progress(&self) -> bool {
+        false
+    }
+
+    fn progress(&mut self, _: &GitProgress) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn local_sideband(
+        &mut self,
+        _: &[u8],
+        _: Option<GitSidebandLineTerminator>,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn remote_sideband(
+        &mut self,
+        _: &[u8],
+        _: Option<GitSidebandLineTerminator>,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(super) fn subprocess_options(settings: &UserSettings) -> Result<GitSubprocessOptions> {
+    let mut options = GitSubprocessOptions::from_settings(settings)?;
+    // There's no terminal to ask for credentials on.
+    options
+        .environment
+        .insert("GIT_TERMINAL_PROMPT".into(), "0".into());
+    Ok(options)
+}
+
+/// Reads how fetched bookmarks are imported from `git.*` and
+/// `remotes.<name>.auto-track-bookmarks`, as `jj` does.
+fn import_options(settings: &UserSettings) -> Result<GitImportOptions> {
+    let mut remote_auto_track_bookmarks = HashMap::new();
+    for (name, remote) in settings.remote_settings()? {
+        if let Some(text) = remote.auto_track_bookmarks {
+            let expr = parse_string_expression(&mut RevsetDiagnostics::new(), &text)?;
+            remote_auto_track_bookmarks.insert(name, expr.to_matcher());
         }
     }
+    Ok(GitImportOptions {
+        auto_local_bookmark: settings.get_bool("git.auto-local-bookmark")?,
+        abandon_unreachable_commits: settings.get_bool("git.abandon-unreachable-commits")?,
+        remote_auto_track_bookmarks,
+    })
+}
 
-    pub(crate) async fn push_impl(&self, bookmark: Option<String>) -> Result<()> {
-        let (_, ws_root) = self.get_repo_and_ws().await?;
-        let mut cmd = tokio::process::Command::new("jj");
-        cmd.arg("git").arg("push");
-        if let Some(bm) = bookmark {
-            cmd.arg("-b").arg(bm);
+/// The remote to use: the requested one, else the one `setting` names
+/// (`git.push` or `git.fetch`), else `origin`, else the only remote.
+pub(super) fn pick_remote(
+    settings: &UserSettings,
+    setting: &'static str,
+    remotes: &[RemoteNameBuf],
+    requested: Option<String>,
+) -> Result<RemoteNameBuf> {
+    let remote = match requested.or_else(|| settings.get_string(setting).ok()) {
+        Some(remote) => RemoteNameBuf::from(remote),
+        None if remotes.iter().any(|r| r.as_str() == "origin") => "origin".into(),
+        None => match remotes {
+            [] => return Err(VcsError::NoRemotes.into()),
+            [only] => only.clone(),
+            _ => return Err(VcsError::NoSuchRemote("origin".to_string()).into()),
+        },
+    };
+    if !remotes.contains(&remote) {
+        return Err(VcsError::NoSuchRemote(remote.as_str().to_string()).into());
+    }
+    Ok(remote)
+}
+
+/// The bookmark `--change` pushes for a commit, and where it points: the
+/// commit, unless the bookmark already exists.
+fn change_bookmark(repo: &dyn Repo, id: &JjCommitId) -> Result<(RefNameBuf, RefTarget)> {
+    let commit = repo.store().get_commit(id)?;
+    let change_id = super::format_change_id(commit.change_id());
+    let name = RefNameBuf::from(format!("push-{}", &change_id[..12.min(change_id.len())]));
+    let target = match repo.view().get_local_bookmark(&name) {
+        target if target.is_present() => target.clone(),
+        _ => RefTarget::normal(id.clone()),
+    };
+    Ok((name, target))
+}
+
+fn plan_push(
+    repo: &dyn Repo,
+    remote: &RemoteName,
+    scope: &PushScope,
+    change_bookmark: Option<(RefNameBuf, RefTarget)>,
+) -> Result<Plan> {
+    let view = repo.view();
+    let mut candidates: Vec<(RefNameBuf, RefTarget, RemoteRef)> = Vec::new();
+    let mut skipped = Vec::new();
+    let remote_ref = |name: &RefNameBuf| {
+        view.get_remote_bookmark(name.to_remote_symbol(remote))
+            .clone()
+    };
+    match scope {
+        PushScope::Bookmarks(names) => {
+            for name in names {
+                let name = RefNameBuf::from(name.as_str());
+                let local = view.get_local_bookmark(&name).clone();
+                let remote_ref = remote_ref(&name);
+                if local.is_absent() && remote_ref.is_absent() {
+                    skipped.push((name.as_str().to_string(), "No such bookmark".to_string()));
+                    continue;
+                }
+                candidates.push((name, local, remote_ref));
+            }
         }
-        let output = cmd.current_dir(ws_root).output().await?;
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("jj push failed: {}", stderr.trim()))
+        PushScope::Change(_) => {
+            if let Some((name, target)) = change_bookmark {
+                let remote_ref = remote_ref(&name);
+                candidates.push((name, target, remote_ref));
+            }
+        }
+        PushScope::All => {
+            for (name, local) in view.local_bookmarks() {
+                let name = name.to_owned();
+                let remote_ref = remote_ref(&name);
+                candidates.push((name, local.clone(), remote_ref));
+            }
+        }
+        PushScope::Tracked => {
+            for (name, targets) in view.local_remote_bookmarks(remote) {
+                if targets.remote_ref.is_tracked() {
+                    candidates.push((
+                        name.to_owned(),
+                        targets.local_target.clone(),
+                        targets.remote_ref.clone(),
+                    ));
+                }
+            }
+        }
+    }
+    // Only bookmarks asked for by name are worth a word when up to date.
+    let explicit = matches!(scope, PushScope::Bookmarks(_) | PushScope::Change(_));
+
+    let mut updates = Vec::new();
+    for (name, local_target, remote_ref) in candidates {
+        let targets = LocalAndRemoteRef {
+            local_target: &local_target,
+            remote_ref: &remote_ref,
+        };
+        let reason = match classify_bookmark_push_action(targets) {
+            BookmarkPushAction::Update(update) => {
+                match unpushable_reason(repo, remote, update.new_target.as_ref())? {
+                    Some(reason) => reason,
+                    None => {
+                        updates.push((name, update));
+                        continue;
+                    }
+                }
+            }
+            BookmarkPushAction::AlreadyMatches if explicit => {
+                format!("Already matches {}", remote.as_symbol())
+            }
+            BookmarkPushAction::AlreadyMatches => continue,
+            BookmarkPushAction::LocalConflicted => "Conflicted locally".to_string(),
+            BookmarkPushAction::RemoteConflicted => format!(
+                "Conflicted on {}; fetch and resolve it first",
+                remote.as_symbol()
+            ),
+            BookmarkPushAction::RemoteUntracked => {
+                format!("Not tracking {}; track it first", remote.as_symbol())
+            }
+        };
+        skipped.push((name.as_str().to_string(), reason));
+    }
+    Ok(Plan { updates, skipped })
+}
+
+/// Like `jj git push`, refuses to publish commits without a description or
+/// with conflicts among the ones the remote doesn't have yet.
+fn unpushable_reason(
+    repo: &dyn Repo,
+    remote: &RemoteName,
+    new_target: Option<&JjCommitId>,
+) -> Result<Option<String>> {
+    let Some(new_target) = new_target else {
+        return Ok(None);
+    };
+    let mut remote_heads: Vec<JjCommitId> = repo
+        .view()
+        .remote_bookmarks(remote)
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids().cloned())
+        .collect();
+    remote_heads.push(repo.store().root_commit_id().clone());
+    let new_commits = ResolvedRevsetExpression::commits(remote_heads)
+        .range(&ResolvedRevsetExpression::commits(vec![new_target.clone()]));
+    for id in new_commits.evaluate(repo)?.iter() {
+        let commit = repo.store().get_commit(&id?)?;
+        let change_id = super::format_change_id(commit.change_id());
+        let change_id = &change_id[..8.min(change_id.len())];
+        if commit.description().trim().is_empty() {
+            return Ok(Some(format!(
+                "Won't push {change_id}: it has no description"
+            )));
+        }
+        if commit.tree().has_conflict() {
+            return Ok(Some(format!("Won't push {change_id}: it has conflicts")));
         }
     }
+    Ok(None)
+}
+
+fn to_bookmark_update(
+    repo: &dyn Repo,
+    name: &RefNameBuf,
+    update: &BookmarkPushUpdate,
+) -> Result<BookmarkUpdate> {
+    let kind = match (&update.old_target, &update.new_target) {
+        (None, _) => UpdateKind::Add,
+        (_, None) => UpdateKind::Delete,
+        (Some(old), Some(new)) if repo.index().is_ancestor(old, new)? => UpdateKind::MoveForward,
+        (Some(old), Some(new)) if repo.index().is_ancestor(new, old)? => UpdateKind::MoveBackward,
+        _ => UpdateKind::MoveSideways,
+    };
+    let short = |id: &Option<JjCommitId>| id.as_ref().map(|id| id.hex()[..8].to_string());
+    Ok(BookmarkUpdate {
+        name: name.as_str().to_string(),
+        kind,
+        old: short(&update.old_target),
+        new: short(&update.new_target),
+    })
 }
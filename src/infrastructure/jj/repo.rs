@@ -1,10 +1,10 @@
 use super::JjAdapter;
-use crate::domain::models::CommitId;
+use crate::domain::{error::VcsError, models::CommitId};
 use anyhow::{anyhow, Result};
 use jj_lib::{
     backend::CommitId as JjCommitId,
     object_id::ObjectId,
-    ref_name::WorkspaceName,
+    ref_name::{WorkspaceName, WorkspaceNameBuf},
     repo::{ReadonlyRepo, Repo},
 };
 use std::path::PathBuf;
@@ -14,26 +14,28 @@ impl JjAdapter {
     pub(crate) async fn validate_commit(&self, commit_id: &CommitId) -> Result<JjCommitId> {
         let (repo, _): (Arc<ReadonlyRepo>, _) = self.get_repo_and_ws().await?;
         let id = JjCommitId::try_from_hex(&commit_id.0)
-            .ok_or_else(|| anyhow!("Invalid commit ID format: {}", commit_id.0))?;
+            .ok_or_else(|| VcsError::InvalidCommitId(commit_id.0.clone()))?;
 
         if !repo.index().has_id(&id).map_err(|e| anyhow!(e))? {
-            return Err(anyhow!(
-                "Commit {} is no longer valid or has been rewritten/abandoned.",
-                commit_id.0
-            ));
+            return Err(VcsError::CommitNotFound(commit_id.0.clone()).into());
         }
         Ok(id)
     }
 
     pub(crate) async fn get_repo_and_ws(&self) -> Result<(Arc<ReadonlyRepo>, PathBuf)> {
         let ws_opt = self.workspace.lock().await;
-        let ws = ws_opt
-            .as_ref()
-            .ok_or_else(|| anyhow!("No repository found"))?;
+        let ws = ws_opt.as_ref().ok_or(VcsError::NoRepository)?;
         let repo = ws.repo_loader().load_at_head()?;
         Ok((repo, ws.workspace_root().to_path_buf()))
     }
 
+    /// The workspace Judo was launched in.
+    pub(crate) async fn workspace_name(&self) -> Result<WorkspaceNameBuf> {
+        let ws_opt = self.workspace.lock().await;
+        let ws = ws_opt.as_ref().ok_or(VcsError::NoRepository)?;
+        Ok(ws.workspace_name().to_owned())
+    }
+
     pub(crate) async fn is_valid_impl(&self) -> bool {
         self.workspace.lock().await.is_some()
     }
@@ -44,15 +46,18 @@ pub(crate) struct CommitInfo {
     pub parent_tree: Option<jj_lib::merged_tree::MergedTree>,
     pub parent_ids: Vec<CommitId>,
     pub is_working_copy: bool,
+    pub workspaces: Vec<String>,
     pub is_immutable: bool,
     pub has_conflict: bool,
     pub bookmarks: Vec<String>,
+    pub tags: Vec<String>,
 }
 
 pub(crate) fn build_commit_info(
-    repo: &ReadonlyRepo,
+    repo: &dyn Repo,
     id: &JjCommitId,
     ws_id: &WorkspaceName,
+    is_immutable: bool,
 ) -> Result<CommitInfo> {
     let commit = repo.store().get_commit(id)?;
     let mut parent_ids_domain = Vec::new();
@@ -64,8 +69,13 @@ pub(crate) fn build_commit_info(
         commit.parents().next().transpose().unwrap_or_default();
     let parent_tree = first_parent.as_ref().map(jj_lib::commit::Commit::tree);
     let is_working_copy = Some(id) == repo.view().get_wc_commit_id(ws_id);
-    // Heuristic: root commit is immutable.
-    let is_immutable = commit.parents().next().is_none();
+    let workspaces = repo
+        .view()
+        .wc_commit_ids()
+        .iter()
+        .filter(|(_, wc_id)| *wc_id == id)
+        .map(|(name, _)| name.as_str().to_string())
+        .collect();
     let has_conflict = commit.tree().has_conflict();
 
     let bookmarks = repo
@@ -74,14 +84,22 @@ pub(crate) fn build_commit_info(
         .filter(|(_, target)| target.added_ids().any(|added_id| added_id == id))
         .map(|(name, _)| name.as_str().to_string())
         .collect::<Vec<_>>();
+    let tags = repo
+        .view()
+        .local_tags()
+        .filter(|(_, target)| target.added_ids().any(|added_id| added_id == id))
+        .map(|(name, _)| name.as_str().to_string())
+        .collect::<Vec<_>>();
 
     Ok(CommitInfo {
         commit,
         parent_tree,
         parent_ids: parent_ids_domain,
         is_working_copy,
+        workspaces,
         is_immutable,
         has_conflict,
         bookmarks,
+        tags,
     })
 }
@@ -0,0 +1,317 @@
+use crate::domain::error::{RevsetError, VcsError};
+use anyhow::{anyhow, Context, Result};
+use jj_lib::{
+    backend::CommitId as JjCommitId,
+    ref_name::WorkspaceName,
+    repo::Repo,
+    repo_path::RepoPathUiConverter,
+    revset::{
+        self, RevsetAliasesMap, RevsetContainingFn, RevsetDiagnostics, RevsetExtensions,
+        RevsetParseContext, RevsetParseError, RevsetParseErrorKind, RevsetResolutionError,
+        RevsetWorkspaceContext, SymbolResolver, UserRevsetExpression,
+    },
+    settings::UserSettings,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+/// What `jj` refuses to rewrite. Overriding `immutable()` itself has no
+/// effect there, so only `immutable_heads()` is consulted.
+const IMMUTABLE_REVSET: &str = "::(immutable_heads() | root())";
+
+/// Everything needed to parse and evaluate revsets the way `jj` would for
+/// the current workspace.
+pub(crate) struct RevsetEnv<'a> {
+    pub settings: &'a UserSettings,
+    pub workspace_name: &'a WorkspaceName,
+    pub workspace_root: &'a Path,
+}
+
+impl RevsetEnv<'_> {
+    /// Evaluates `revset_str`, returning up to `limit` commit IDs in the
+    /// revset's own order (children before parents, like `jj log`).
+    pub fn evaluate(
+        &self,
+        repo: &dyn Repo,
+        revset_str: &str,
+        limit: usize,
+    ) -> Result<Vec<JjCommitId>> {
+        let extensions = RevsetExtensions::default();
+        let expression = self.parse(revset_str, &extensions)?;
+        let symbol_resolver = SymbolResolver::new(repo, extensions.symbol_resolvers());
+        let resolved = expression
+            .resolve_user_expression(repo, &symbol_resolver)
+            .map_err(|err| resolution_error(revset_str, &err))?;
+        let ids = resolved
+            .evaluate(repo)
+            .with_context(|| format!("Failed to evaluate revset \"{revset_str}\""))?
+            .iter()
+            .take(limit)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Returns a check for whether a commit is immutable, evaluating the
+    /// configured `immutable_heads()` the same way `jj` does before it
+    /// rewrites anything.
+    pub fn immutable_fn<'r>(&self, repo: &'r dyn Repo) -> Result<Box<RevsetContainingFn<'r>>> {
+        let extensions = RevsetExtensions::default();
+        let revset = self
+            .parse(IMMUTABLE_REVSET, &extensions)
+            .and_then(|expression| {
+                let symbol_resolver = SymbolResolver::new(repo, extensions.symbol_resolvers());
+                expression
+                    .resolve_user_expression(repo, &symbol_resolver)
+                    .map_err(|err| resolution_error(IMMUTABLE_REVSET, &err))
+            })
+            .and_then(|resolved| Ok(resolved.evaluate(repo)?))
+            // Not the user's filter, so keep it out of the revset error path.
+            .map_err(|err| anyhow!("Invalid `revset-aliases.immutable_heads()`: {err:#}"))?;
+        Ok(revset.containing_fn())
+    }
+
+    /// Resolves a revset (e.g. a rebase destination) to exactly one commit.
+    pub fn resolve_single(&self, repo: &dyn Repo, revset_str: &str) -> Result<JjCommitId> {
+        let revset_str = revset_str.trim();
+        match self.evaluate(repo, revset_str, 2)?.as_slice() {
+            [id] => Ok(id.clone()),
+            [] => Err(VcsError::RevisionNotFound(revset_str.to_string()).into()),
+            _ => Err(VcsError::AmbiguousRevision {
+                revset: revset_str.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    fn parse(
+        &self,
+        revset_str: &str,
+        extensions: &RevsetExtensions,
+    ) -> Result<Arc<UserRevsetExpression>> {
+        let aliases_map = load_aliases(self.settings)?;
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: self.workspace_root.to_path_buf(),
+            base: self.workspace_root.to_path_buf(),
+        };
+        let context = RevsetParseContext {
+            aliases_map: &aliases_map,
+            local_variables: HashMap::new(),
+            user_email: self.settings.user_email(),
+            date_pattern_context: chrono::Local::now().into(),
+            default_ignored_remote: Some("git".as_ref()),
+            use_glob_by_default: self
+                .settings
+                .get_bool("ui.revsets-use-glob-by-default")
+                .unwrap_or(true),
+            extensions,
+            workspace: Some(RevsetWorkspaceContext {
+                path_converter: &path_converter,
+                workspace_name: self.workspace_name,
+            }),
+        };
+        revset::parse(&mut RevsetDiagnostics::new(), revset_str, &context)
+            .map_err(|err| parse_error(revset_str, &err).into())
+    }
+}
+
+/// Collects `[revset-aliases]` from every config layer, later layers
+/// overriding earlier ones.
+fn load_aliases(settings: &UserSettings) -> Result<RevsetAliasesMap> {
+    let mut aliases_map = RevsetAliasesMap::new();
+    let config = settings.config();
+    for decl in config.table_keys("revset-aliases") {
+        let defn: String = config
+            .get(["revset-aliases", decl])
+            .with_context(|| format!("Invalid definition for revset alias \"{decl}\""))?;
+        aliases_map
+            .insert(decl, defn)
+            .with_context(|| format!("Invalid declaration for revset alias \"{decl}\""))?;
+    }
+    Ok(aliases_map)
+}
+
+fn parse_error(revset_str: &str, err: &RevsetParseError) -> VcsError {
+    let mut message = describe_parse_error(err.kind());
+    // Errors inside an alias body point at the alias call in the user's
+    // input; the underlying problem is reported by the origin.
+    if let Some(origin) = err.origin() {
+        message = format!("{message}: {}", describe_parse_error(origin.kind()));
+    }
+    VcsError::InvalidRevset(RevsetError {
+        revset: revset_str.to_string(),
+        message,
+        span: parse_error_span(revset_str, err),
+    })
+}
+
+fn describe_parse_error(kind: &RevsetParseErrorKind) -> String {
+    match kind {
+        RevsetParseErrorKind::NoSuchFunction { candidates, .. } if !candidates.is_empty() => {
+            format!("{kind} (did you mean {}?)", candidates.join(", "))
+        }
+        RevsetParseErrorKind::NotPrefixOperator { similar_op, .. }
+        | RevsetParseErrorKind::NotPostfixOperator { similar_op, .. }
+        | RevsetParseErrorKind::NotInfixOperator { similar_op, .. } => {
+            format!("{kind} (did you mean `{similar_op}`?)")
+        }
+        _ => kind.to_string(),
+    }
+}
+
+/// Recovers the character range of a parse error from its rendered form,
+/// which looks like:
+///
+/// ```text
+///  --> 1:5
+///   |
+/// 1 | foo(
+///   |     ^---
+/// ```
+///
+/// A trailing `^` marks the end of a span; a bare `^---` marks a position.
+fn parse_error_span(revset_str: &str, err: &RevsetParseError) -> Option<Range<usize>> {
+    let rendered = err.to_string();
+    let mut lines = rendered.lines();
+    let location = lines.find_map(|line| line.trim_start().strip_prefix("--> "))?;
+    let (line, col) = location.split_once(':')?;
+    let line = line.trim().parse::<usize>().ok()?.checked_sub(1)?;
+    let col = col.trim().parse::<usize>().ok()?.checked_sub(1)?;
+
+    let marker_line = lines.find(|line| line.contains('^'))?;
+    let marker = marker_line[marker_line.find('^')?..].trim_end();
+    let len = if marker.len() > 1 && marker.ends_with('^') {
+        marker.chars().count()
+    } else {
+        1
+    };
+
+    let line_offset: usize = revset_str
+        .split('\n')
+        .take(line)
+        .map(|l| l.chars().count() + 1)
+        .sum();
+    let start = line_offset + col;
+    Some(start..start + len)
+}
+
+fn resolution_error(revset_str: &str, err: &RevsetResolutionError) -> anyhow::Error {
+    let (message, symbol) = match err {
+        RevsetResolutionError::NoSuchRevision { name, candidates } => {
+            let message = if candidates.is_empty() {
+                err.to_string()
+            } else {
+                format!("{err} (did you mean {}?)", candidates.join(", "))
+            };
+            (message, Some(name.as_str()))
+        }
+        RevsetResolutionError::AmbiguousCommitIdPrefix(symbol)
+        | RevsetResolutionError::AmbiguousChangeIdPrefix(symbol)
+        | RevsetResolutionError::DivergentChangeId { symbol, .. }
+        | RevsetResolutionError::ConflictedRef { symbol, .. } => {
+            (err.to_string(), Some(symbol.as_str()))
+        }
+        _ => (err.to_string(), None),
+    };
+    let span = symbol.and_then(|symbol| symbol_span(revset_str, symbol));
+    VcsError::InvalidRevset(RevsetError {
+        revset: revset_str.to_string(),
+        message,
+        span,
+    })
+    .into()
+}
+
+/// Character range of the first occurrence of `symbol` that is a whole
+/// token, rather than part of a longer identifier like `mai` in `main`.
+fn symbol_span(revset_str: &str, symbol: &str) -> Option<Range<usize>> {
+    let (byte_start, _) = revset_str.match_indices(symbol).find(|(start, _)| {
+        let before = revset_str[..*start].chars().rev();
+        let after = revset_str[start + symbol.len()..].chars();
+        !continues_identifier(before) && !continues_identifier(after)
+    })?;
+    let start = revset_str[..byte_start].chars().count();
+    Some(start..start + symbol.chars().count())
+}
+
+/// Whether `chars`, read away from a symbol, extend it into a longer
+/// identifier. `.`, `-` and `+` only join identifier parts, so `main-` is
+/// still `main` followed by an operator.
+fn continues_identifier(mut chars: impl Iterator<Item = char>) -> bool {
+    let is_part = |c: char| c.is_alphanumeric() || matches!(c, '_' | '*' | '/');
+    match chars.next() {
+        Some(c) if is_part(c) => true,
+        Some('.' | '-' | '+') => chars.next().is_some_and(is_part),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jj_lib::{config::StackedConfig, ref_name::WorkspaceNameBuf};
+
+    fn parse_with(config: StackedConfig, revset_str: &str) -> Result<()> {
+        let settings = UserSettings::from_config(config)?;
+        let workspace_name = WorkspaceNameBuf::from("default");
+        let env = RevsetEnv {
+            settings: &settings,
+            workspace_name: &workspace_name,
+            workspace_root: Path::new("/"),
+        };
+        env.parse(revset_str, &RevsetExtensions::default())?;
+        Ok(())
+    }
+
+    fn revset_error(result: Result<()>) -> RevsetError {
+        match result.unwrap_err().downcast::<VcsError>() {
+            Ok(VcsError::InvalidRevset(err)) => err,
+            other => panic!("expected a revset error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_span() {
+        let err = revset_error(parse_with(StackedConfig::with_defaults(), "all() | foo("));
+        assert_eq!(err.span, Some(12..13));
+
+        let err = revset_error(parse_with(StackedConfig::with_defaults(), "nope() & @"));
+        assert!(err.message.contains("nope"));
+        assert_eq!(err.span, Some(0..4));
+    }
+
+    #[test]
+    fn test_resolution_error_span() {
+        let span = |revset_str: &str, name: &str| {
+            let err = RevsetResolutionError::NoSuchRevision {
+                name: name.to_string(),
+                candidates: Vec::new(),
+            };
+            match resolution_error(revset_str, &err).downcast::<VcsError>() {
+                Ok(VcsError::InvalidRevset(err)) => err.span,
+                other => panic!("expected a revset error, got {other:?}"),
+            }
+        };
+        assert_eq!(span("main | mai", "mai"), Some(7..10));
+        assert_eq!(span("foobar::bar", "bar"), Some(8..11));
+        assert_eq!(span("my-bar | bar-", "bar"), Some(9..12));
+        assert_eq!(span("main", "mai"), None);
+    }
+
+    #[test]
+    fn test_user_aliases() -> Result<()> {
+        let mut config = StackedConfig::with_defaults();
+        config.add_layer(jj_lib::config::ConfigLayer::parse(
+            jj_lib::config::ConfigSource::User,
+            "[revset-aliases]\n'wip' = 'description(\"wip\")'\n'bad' = 'foo('\n",
+        )?);
+        parse_with(config.clone(), "wip | @")?;
+
+        // Errors inside an alias underline the alias in the user's input.
+        let err = revset_error(parse_with(config, "all() | bad"));
+        assert_eq!(err.span, Some(8..11));
+        assert!(err.message.contains("bad"));
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use super::{
+    remote::{pick_remote, subprocess_options, Quiet},
+    JjAdapter,
+};
+use crate::domain::{error::VcsError, models::CommitId};
+use anyhow::{anyhow, Result};
+use jj_lib::{
+    git::{self, GitRefUpdate},
+    op_store::{RefTarget, RemoteRef, RemoteRefState},
+    ref_name::{GitRefNameBuf, RefNameBuf},
+    repo::Repo,
+};
+
+impl JjAdapter {
+    pub(crate) async fn set_tag_impl(
+        &self,
+        commit_id: &CommitId,
+        name: &str,
+        allow_move: bool,
+    ) -> Result<()> {
+        let name = parse_tag_name(name)?;
+        let commit_id = commit_id.clone();
+        let description = format!("point tag {} to commit {}", name.as_symbol(), commit_id.0);
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let commit = ctx.load_commit(mut_repo, &commit_id)?;
+            let exists = mut_repo.get_local_tag(&name).is_present();
+            if exists && !allow_move {
+                return Err(VcsError::TagExists(name.as_str().to_string()).into());
+            }
+            // Moving a tag that isn't there is more likely a typo than a
+            // request to create one.
+            if !exists && allow_move {
+                return Err(VcsError::NoSuchTag(name.as_str().to_string()).into());
+            }
+            mut_repo.set_local_tag_target(&name, RefTarget::normal(commit.id().clone()));
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn delete_tag_impl(&self, name: &str) -> Result<()> {
+        let name = parse_tag_name(name)?;
+        let description = format!("delete tag {}", name.as_symbol());
+        self.run_transaction(description, move |mut_repo, _| {
+            if mut_repo.get_local_tag(&name).is_absent() {
+                return Err(VcsError::NoSuchTag(name.as_str().to_string()).into());
+            }
+            mut_repo.set_local_tag_target(&name, RefTarget::absent());
+            Ok(())
+        })
+        .await
+    }
+
+    /// Pushes the tag to the default push remote, like
+    /// `git push <remote> refs/tags/<name>`, and records where the remote's
+    /// tag now points.
+    pub(crate) async fn push_tag_impl(&self, name: &str) -> Result<()> {
+        let name = parse_tag_name(name)?;
+        let description = format!("push tag {} to git remote", name.as_symbol());
+        self.run_transaction(description, move |mut_repo, ctx| {
+            let remotes = git::get_all_remote_names(mut_repo.store())?;
+            let remote = pick_remote(&ctx.settings, "git.push", &remotes, None)?;
+            let local = mut_repo.get_local_tag(&name);
+            let Some(new_target) = local.as_normal().cloned() else {
+                return Err(if local.is_absent() {
+                    VcsError::NoSuchTag(name.as_str().to_string()).into()
+                } else {
+                    anyhow!("Tag {} is conflicted", name.as_symbol())
+                });
+            };
+            let symbol = name.to_remote_symbol(&remote);
+            let remote_ref = mut_repo.get_remote_tag(symbol);
+            if remote_ref.target == local {
+                return Ok(());
+            }
+
+            let qualified_name = GitRefNameBuf::from(format!("refs/tags/{}", name.as_str()));
+            let update = GitRefUpdate {
+                qualified_name: qualified_name.clone(),
+                expected_current_target: remote_ref.target.as_normal().cloned(),
+                new_target: Some(new_target.clone()),
+            };
+            let options = subprocess_options(&ctx.settings)?;
+            let stats = git::push_updates(mut_repo, options, &remote, &[update], &mut Quiet)?;
+            if !stats.pushed.contains(&qualified_name) {
+                let reason = stats
+                    .rejected
+                    .iter()
+                    .chain(&stats.remote_rejected)
+                    .find(|(r, _)| *r == qualified_name)
+                    .and_then(|(_, reason)| reason.clone());
+                return Err(anyhow!(reason.unwrap_or_else(|| format!(
+                    "{} rejected tag {}",
+                    remote.as_symbol(),
+                    name.as_symbol()
+                ))));
+            }
+            mut_repo.set_remote_tag(
+                symbol,
+                RemoteRef {
+                    target: RefTarget::normal(new_target),
+                    state: RemoteRefState::Tracked,
+                },
+            );
+            Ok(())
+        })
+        .await
+    }
+}
+
+fn parse_tag_name(name: &str) -> Result<RefNameBuf> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.chars().any(char::is_whitespace) {
+        return Err(VcsError::InvalidTagName(name.to_string()).into());
+    }
+    Ok(trimmed.into())
+}
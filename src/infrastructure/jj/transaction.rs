@@ -0,0 +1,304 @@
+use super::revset::RevsetEnv;
+use super::JjAdapter;
+use crate::domain::{error::VcsError, models::CommitId};
+use anyhow::{Context, Result};
+use futures::executor::block_on;
+use jj_lib::{
+    backend::CommitId as JjCommitId,
+    commit::Commit,
+    git,
+    gitignore::GitIgnoreFile,
+    matchers::{EverythingMatcher, NothingMatcher},
+    object_id::ObjectId,
+    ref_name::WorkspaceNameBuf,
+    repo::{MutableRepo, ReadonlyRepo, Repo},
+    revset::{ResolvedRevsetExpression, RevsetContainingFn},
+    settings::UserSettings,
+    working_copy::{SnapshotOptions, WorkingCopyFreshness},
+    workspace::Workspace,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Largest untracked file that a snapshot will start tracking.
+pub(crate) const MAX_NEW_FILE_SIZE: u64 = 1024 * 1024; // 1MB
+
+/// Old and new IDs of a working-copy commit rewritten by a snapshot.
+type SnapshotRewrite = (JjCommitId, JjCommitId);
+
+/// Workspace details available to a transaction body.
+pub(crate) struct TxContext<'r> {
+    pub settings: UserSettings,
+    pub workspace_name: WorkspaceNameBuf,
+    pub workspace_root: PathBuf,
+    /// The working-copy commit rewritten by the snapshot taken just before
+    /// the transaction, as `(old, new)`. IDs the UI loaded earlier still refer
+    /// to the old commit.
+    snapshot_rewrite: Option<SnapshotRewrite>,
+    /// Whether a commit is an ancestor of `immutable_heads()` as of the
+    /// transaction's base. Commits written by the transaction itself are
+    /// mutable.
+    is_immutable: Box<RevsetContainingFn<'r>>,
+}
+
+impl<'r> TxContext<'r> {
+    fn new(
+        settings: UserSettings,
+        ws: &Workspace,
+        repo: &'r ReadonlyRepo,
+        snapshot_rewrite: Option<SnapshotRewrite>,
+    ) -> Result<Self> {
+        let workspace_name = ws.workspace_name().to_owned();
+        let workspace_root = ws.workspace_root().to_path_buf();
+        let is_immutable = RevsetEnv {
+            settings: &settings,
+            workspace_name: &workspace_name,
+            workspace_root: &workspace_root,
+        }
+        .immutable_fn(repo)?;
+        Ok(Self {
+            settings,
+            workspace_name,
+            workspace_root,
+            snapshot_rewrite,
+            is_immutable,
+        })
+    }
+
+    pub fn revset_env(&self) -> RevsetEnv<'_> {
+        RevsetEnv {
+            settings: &self.settings,
+            workspace_name: &self.workspace_name,
+            workspace_root: &self.workspace_root,
+        }
+    }
+
+    /// Loads a commit by the ID shown in the UI, following the snapshot
+    /// rewrite if the ID refers to the previous working-copy commit.
+    pub fn load_commit(&self, repo: &dyn Repo, commit_id: &CommitId) -> Result<Commit> {
+        let mut id = JjCommitId::try_from_hex(&commit_id.0)
+            .ok_or_else(|| VcsError::InvalidCommitId(commit_id.0.clone()))?;
+        if let Some((old, new)) = &self.snapshot_rewrite {
+            if *old == id {
+                id = new.clone();
+            }
+        }
+        if !repo.index().has_id(&id)? {
+            return Err(VcsError::CommitNotFound(commit_id.0.clone()).into());
+        }
+        Ok(repo.store().get_commit(&id)?)
+    }
+
+    /// Like [`Self::load_commit`], but rejects commits that `jj` wouldn't
+    /// rewrite.
+    pub fn load_mutable_commit(&self, repo: &dyn Repo, commit_id: &CommitId) -> Result<Commit> {
+        let commit = self.load_commit(repo, commit_id)?;
+        self.check_mutable(repo, commit.id())?;
+        Ok(commit)
+    }
+
+    /// Fails if `id` is the root commit or one of the immutable commits.
+    pub fn check_mutable(&self, repo: &dyn Repo, id: &JjCommitId) -> Result<()> {
+        if id == repo.store().root_commit_id() {
+            return Err(VcsError::RootCommit.into());
+        }
+        if (self.is_immutable)(id)? {
+            let hex = id.hex();
+            return Err(VcsError::ImmutableRevision(hex[..12.min(hex.len())].to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+impl JjAdapter {
+    /// Snapshots the working copy, runs `f` against a fresh transaction and
+    /// publishes it as a single operation. If the transaction moved or
+    /// rewrote the working-copy commit, the files on disk are updated to match.
+    /// Returns what `f` returned.
+    pub(crate) async fn run_transaction<F, T>(&self, description: String, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut MutableRepo, &TxContext) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut ws_guard = self.workspace.clone().lock_owned().await;
+        let settings = self.user_settings.clone();
+        tokio::task::spawn_blocking(move || {
+            let ws = ws_guard.as_mut().ok_or(VcsError::NoRepository)?;
+            let repo = ws.repo_loader().load_at_head()?;
+            let (repo, snapshot_rewrite) = snapshot_working_copy(ws, repo)?;
+            let ctx = TxContext::new(settings, ws, &repo, snapshot_rewrite)?;
+            let old_wc_commit = match repo.view().get_wc_commit_id(&ctx.workspace_name) {
+                Some(id) => Some(repo.store().get_commit(id)?),
+                None => None,
+            };
+
+            let mut tx = repo.start_transaction();
+            let value = f(tx.repo_mut(), &ctx)?;
+            tx.repo_mut().rebase_descendants()?;
+            if !tx.repo_mut().has_changes() {
+                return Ok(value);
+            }
+
+            let mut_repo = tx.repo_mut();
+            let new_wc_commit = match mut_repo.view().get_wc_commit_id(&ctx.workspace_name) {
+                Some(id) => Some(mut_repo.store().get_commit(id)?),
+                None => None,
+            };
+            let wc_changed =
+                new_wc_commit.as_ref().map(Commit::id) != old_wc_commit.as_ref().map(Commit::id);
+            let wc_to_reset = new_wc_commit.as_ref().filter(|_| wc_changed);
+            export_to_git(tx.repo_mut(), &ctx.workspace_root, wc_to_reset)?;
+
+            let new_repo = tx
+                .commit(description)
+                .context("Failed to commit transaction")?;
+
+            if let (Some(new_wc_commit), true) = (new_wc_commit, wc_changed) {
+                let old_tree = old_wc_commit.as_ref().map(Commit::tree);
+                ws.check_out(new_repo.op_id().clone(), old_tree.as_ref(), &new_wc_commit)
+                    .context("Failed to update the working copy")?;
+            }
+            Ok(value)
+        })
+        .await?
+    }
+
+    /// Runs `f` against a transaction on the current operation that is
+    /// dropped instead of committed, so nothing it does is written. Unlike
+    /// [`Self::run_transaction`], the working copy isn't snapshotted first,
+    /// since that would write an operation of its own.
+    pub(crate) async fn dry_run<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut MutableRepo, &TxContext) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let ws_guard = self.workspace.clone().lock_owned().await;
+        let settings = self.user_settings.clone();
+        tokio::task::spawn_blocking(move || {
+            let ws = ws_guard.as_ref().ok_or(VcsError::NoRepository)?;
+            let repo = ws.repo_loader().load_at_head()?;
+            let ctx = TxContext::new(settings, ws, &repo, None)?;
+            let mut tx = repo.start_transaction();
+            f(tx.repo_mut(), &ctx)
+        })
+        .await?
+    }
+
+    /// Records any on-disk changes into the working-copy commit.
+    pub(crate) async fn snapshot_working_copy(&self) -> Result<()> {
+        let mut ws_guard = self.workspace.clone().lock_owned().await;
+        tokio::task::spawn_blocking(move || {
+            let ws = ws_guard.as_mut().ok_or(VcsError::NoRepository)?;
+            let repo = ws.repo_loader().load_at_head()?;
+            snapshot_working_copy(ws, repo)?;
+            Ok(())
+        })
+        .await?
+    }
+}
+
+/// Returns the repo at the resulting operation, plus the old and new
+/// working-copy commit IDs if the snapshot rewrote it.
+fn snapshot_working_copy(
+    ws: &mut Workspace,
+    repo: Arc<ReadonlyRepo>,
+) -> Result<(Arc<ReadonlyRepo>, Option<SnapshotRewrite>)> {
+    let workspace_name = ws.workspace_name().to_owned();
+    let workspace_root = ws.workspace_root().to_path_buf();
+    let Some(wc_commit_id) = repo.view().get_wc_commit_id(&workspace_name).cloned() else {
+        return Ok((repo, None));
+    };
+    let wc_commit = repo.store().get_commit(&wc_commit_id)?;
+
+    let mut base_ignores = GitIgnoreFile::empty();
+    if let Ok(git_backend) = git::get_git_backend(repo.store()) {
+        let exclude_file = git_backend.git_repo_path().join("info").join("exclude");
+        base_ignores = base_ignores.chain_with_file("", exclude_file)?;
+    }
+
+    let mut locked_ws = ws.start_working_copy_mutation()?;
+    let repo = match WorkingCopyFreshness::check_stale(locked_ws.locked_wc(), &wc_commit, &repo)? {
+        WorkingCopyFreshness::Fresh => repo,
+        WorkingCopyFreshness::Updated(wc_operation) => repo.reload_at(&wc_operation)?,
+        WorkingCopyFreshness::WorkingCopyStale | WorkingCopyFreshness::SiblingOperation => {
+            return Err(VcsError::StaleWorkingCopy.into());
+        }
+    };
+    let wc_commit = match repo.view().get_wc_commit_id(&workspace_name) {
+        Some(id) if *id != wc_commit_id => repo.store().get_commit(id)?,
+        _ => wc_commit,
+    };
+
+    let options = SnapshotOptions {
+        base_ignores,
+        progress: None,
+        start_tracking_matcher: &EverythingMatcher,
+        force_tracking_matcher: &NothingMatcher,
+        max_new_file_size: MAX_NEW_FILE_SIZE,
+    };
+    let (new_tree, _stats) = block_on(locked_ws.locked_wc().snapshot(&options))
+        .context("Failed to snapshot the working copy")?;
+
+    let (repo, rewrite) = if new_tree.tree_ids() == wc_commit.tree_ids() {
+        (repo, None)
+    } else {
+        let mut tx = repo.start_transaction();
+        tx.set_is_snapshot(true);
+        let new_wc_commit = tx
+            .repo_mut()
+            .rewrite_commit(&wc_commit)
+            .set_tree(new_tree)
+            .write()?;
+        tx.repo_mut().rebase_descendants()?;
+        export_to_git(tx.repo_mut(), &workspace_root, None)?;
+        let repo = tx
+            .commit("snapshot working copy")
+            .context("Failed to commit working-copy snapshot")?;
+        (repo, Some((wc_commit_id, new_wc_commit.id().clone())))
+    };
+
+    locked_ws
+        .finish(repo.op_id().clone())
+        .context("Failed to save the working copy state")?;
+    Ok((repo, rewrite))
+}
+
+/// Mirrors bookmark changes into the backing Git repository and, for
+/// colocated repositories, moves Git's HEAD along with the working copy.
+fn export_to_git(
+    mut_repo: &mut MutableRepo,
+    workspace_root: &std::path::Path,
+    new_wc_commit: Option<&Commit>,
+) -> Result<()> {
+    let store = mut_repo.store().clone();
+    let Ok(git_backend) = git::get_git_backend(&store) else {
+        return Ok(());
+    };
+    let is_colocated = git_backend
+        .git_workdir()
+        .is_some_and(|workdir| same_path(workdir, workspace_root));
+    if let (Some(wc_commit), true) = (new_wc_commit, is_colocated) {
+        git::reset_head(mut_repo, wc_commit).context("Failed to update Git HEAD")?;
+    }
+    git::export_refs(mut_repo).context("Failed to export refs to Git")?;
+    Ok(())
+}
+
+fn same_path(a: &std::path::Path, b: &std::path::Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Orders commits children-first, as expected by the jj-lib rewrite helpers.
+pub(crate) fn reverse_topological(
+    repo: &dyn Repo,
+    ids: Vec<JjCommitId>,
+) -> Result<Vec<JjCommitId>> {
+    let sorted = ResolvedRevsetExpression::commits(ids)
+        .evaluate(repo)?
+        .iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(sorted)
+}
@@ -0,0 +1,212 @@
+use super::JjAdapter;
+use crate::domain::{error::VcsError, workspace::Workspace as WorkspaceInfo};
+use anyhow::{anyhow, Context, Result};
+use futures::executor::block_on;
+use jj_lib::{
+    commit::Commit,
+    local_working_copy::LocalWorkingCopyFactory,
+    object_id::ObjectId,
+    ref_name::{WorkspaceName, WorkspaceNameBuf},
+    repo::{ReadonlyRepo, Repo},
+    rewrite::merge_commit_trees,
+    settings::UserSettings,
+    working_copy::WorkingCopyFreshness,
+    workspace::Workspace,
+    workspace_store::{SimpleWorkspaceStore, WorkspaceStore},
+};
+use std::path::{Path, PathBuf};
+
+impl JjAdapter {
+    pub(crate) async fn workspaces_impl(&self) -> Result<Vec<WorkspaceInfo>> {
+        let (repo, current, repo_path) = {
+            let ws_opt = self.workspace.lock().await;
+            let ws = ws_opt.as_ref().ok_or(VcsError::NoRepository)?;
+            (
+                ws.repo_loader().load_at_head()?,
+                ws.workspace_name().to_owned(),
+                ws.repo_path().to_path_buf(),
+            )
+        };
+        let settings = self.user_settings.clone();
+        let current_root = self.workspace_root.clone();
+        tokio::task::spawn_blocking(move || {
+            let store = SimpleWorkspaceStore::load(&repo_path)?;
+            let mut workspaces = Vec::new();
+            for (name, wc_commit_id) in repo.view().wc_commit_ids() {
+                let commit = repo.store().get_commit(wc_commit_id)?;
+                let is_current = *name == current;
+                let root = match store.get_workspace_path(name)? {
+                    Some(root) => Some(root),
+                    None => is_current.then(|| current_root.clone()),
+                };
+                let change_id = super::format_change_id(commit.change_id());
+                workspaces.push(WorkspaceInfo {
+                    name: name.as_str().to_string(),
+                    is_stale: root
+                        .as_deref()
+                        .is_some_and(|root| is_stale(&settings, root, &repo, &commit)),
+                    root: root.map(|root| root.display().to_string()),
+                    working_copy_id: crate::domain::models::CommitId(commit.id().hex()),
+                    change_id_short: change_id[..8.min(change_id.len())].to_string(),
+                    summary: commit
+                        .description()
+                        .lines()
+                        .next()
+                        .unwrap_or("")
+                        .to_string(),
+                    is_current,
+                });
+            }
+            // The view keeps them sorted by name.
+            workspaces.sort_by_key(|ws| !ws.is_current);
+            Ok(workspaces)
+        })
+        .await?
+    }
+
+    pub(crate) async fn add_workspace_impl(&self, path: &str) -> Result<()> {
+        let ws_guard = self.workspace.clone().lock_owned().await;
+        let path = path.trim().to_string();
+        tokio::task::spawn_blocking(move || {
+            let ws = ws_guard.as_ref().ok_or(VcsError::NoRepository)?;
+            // Relative paths are taken from the workspace root, which is
+            // where `jj` would usually be run from.
+            let destination = ws.workspace_root().join(&path);
+            let name: WorkspaceNameBuf = destination
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| anyhow!("Cannot name a workspace after \"{path}\""))?
+                .into();
+            let repo = ws.repo_loader().load_at_head()?;
+            if repo.view().get_wc_commit_id(&name).is_some() {
+                return Err(VcsError::WorkspaceExists(name.as_str().to_string()).into());
+            }
+            if destination.exists() {
+                if destination.read_dir()?.next().is_some() {
+                    return Err(anyhow!("{path} exists and is not an empty directory"));
+                }
+            } else {
+                std::fs::create_dir_all(&destination)
+                    .with_context(|| format!("Failed to create {path}"))?;
+            }
+
+            let (mut new_ws, repo) = Workspace::init_workspace_with_existing_repo(
+                &destination,
+                ws.repo_path(),
+                &repo,
+                &LocalWorkingCopyFactory {},
+                name.clone(),
+            )?;
+
+            // Start from the same parents as the current working copy.
+            let parents = match repo.view().get_wc_commit_id(ws.workspace_name()) {
+                Some(id) => repo
+                    .store()
+                    .get_commit(id)?
+                    .parents()
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => vec![repo.store().root_commit()],
+            };
+            let mut tx = repo.start_transaction();
+            let tree = block_on(merge_commit_trees(tx.repo(), &parents))?;
+            let parent_ids = parents.iter().map(|c| c.id().clone()).collect();
+            let wc_commit = tx.repo_mut().new_commit(parent_ids, tree).write()?;
+            tx.repo_mut().edit(name.clone(), &wc_commit)?;
+            // Abandons the placeholder the workspace was initialized with.
+            tx.repo_mut().rebase_descendants()?;
+            let repo = tx
+                .commit(format!(
+                    "create initial working-copy commit in workspace {}",
+                    name.as_str()
+                ))
+                .context("Failed to commit transaction")?;
+            new_ws
+                .check_out(repo.op_id().clone(), None, &wc_commit)
+                .context("Failed to check out the new workspace")?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Stops tracking a workspace's working-copy commit, like
+    /// `jj workspace forget`. Its files are left on disk.
+    pub(crate) async fn forget_workspace_impl(&self, name: &str) -> Result<()> {
+        let repo_path = {
+            let ws_opt = self.workspace.lock().await;
+            let ws = ws_opt.as_ref().ok_or(VcsError::NoRepository)?;
+            ws.repo_path().to_path_buf()
+        };
+        let name = WorkspaceNameBuf::from(name);
+        let description = format!("forget workspace {}", name.as_str());
+        let forgotten = name.clone();
+        self.run_transaction(description, move |mut_repo, _| {
+            if mut_repo.view().get_wc_commit_id(&forgotten).is_none() {
+                return Err(VcsError::WorkspaceNotFound(forgotten.as_str().to_string()).into());
+            }
+            mut_repo.remove_wc_commit(&forgotten)?;
+            Ok(())
+        })
+        .await?;
+        SimpleWorkspaceStore::load(&repo_path)?.forget(&[name.as_ref()])?;
+        Ok(())
+    }
+
+    pub(crate) async fn update_stale_workspace_impl(&self, name: &str) -> Result<()> {
+        let mut ws_guard = self.workspace.clone().lock_owned().await;
+        let settings = self.user_settings.clone();
+        let name = WorkspaceNameBuf::from(name);
+        tokio::task::spawn_blocking(move || {
+            let current = ws_guard.as_mut().ok_or(VcsError::NoRepository)?;
+            if *current.workspace_name() == name {
+                return update_stale(current);
+            }
+            let root = workspace_root(current.repo_path(), &name)?;
+            update_stale(&mut super::load_workspace(&settings, &root)?)
+        })
+        .await?
+    }
+}
+
+fn workspace_root(repo_path: &Path, name: &WorkspaceName) -> Result<PathBuf> {
+    SimpleWorkspaceStore::load(repo_path)?
+        .get_workspace_path(name)?
+        .ok_or_else(|| VcsError::WorkspaceNotFound(name.as_str().to_string()).into())
+}
+
+/// Whether the workspace at `root` still has the files of an older version
+/// of `wc_commit` checked out.
+fn is_stale(settings: &UserSettings, root: &Path, repo: &ReadonlyRepo, wc_commit: &Commit) -> bool {
+    let Ok(ws) = super::load_workspace(settings, root) else {
+        return false;
+    };
+    let wc = ws.working_copy();
+    wc.operation_id() != repo.op_id()
+        && wc
+            .tree()
+            .is_ok_and(|tree| tree.tree_ids() != wc_commit.tree_ids())
+}
+
+/// Checks out the workspace's working-copy commit over files left behind by
+/// an operation run elsewhere, like `jj workspace update-stale`.
+fn update_stale(ws: &mut Workspace) -> Result<()> {
+    // Trees can only be compared within the store they were loaded from.
+    let repo = ws.repo_loader().load_at_head()?;
+    let name = ws.workspace_name().to_owned();
+    let wc_commit_id = repo
+        .view()
+        .get_wc_commit_id(&name)
+        .ok_or_else(|| VcsError::WorkspaceNotFound(name.as_str().to_string()))?;
+    let wc_commit = repo.store().get_commit(wc_commit_id)?;
+
+    let mut locked_ws = ws.start_working_copy_mutation()?;
+    match WorkingCopyFreshness::check_stale(locked_ws.locked_wc(), &wc_commit, &repo)? {
+        WorkingCopyFreshness::Fresh | WorkingCopyFreshness::Updated(_) => return Ok(()),
+        WorkingCopyFreshness::WorkingCopyStale | WorkingCopyFreshness::SiblingOperation => {}
+    }
+    block_on(locked_ws.locked_wc().check_out(&wc_commit))
+        .context("Failed to update the working copy")?;
+    locked_ws
+        .finish(repo.op_id().clone())
+        .context("Failed to save the working copy state")?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use clap::Parser;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -7,7 +8,12 @@ use crossterm::{
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
 
-use judo::app::{r#loop::run_loop, state::AppState};
+use judo::app::{
+    persistence,
+    r#loop::run_loop,
+    state::{AppState, ErrorSeverity, ErrorState},
+};
+use judo::cli::{self, Cli};
 use judo::domain::vcs::VcsFacade;
 use judo::infrastructure;
 
@@ -22,18 +28,54 @@ fn setup_panic_hook() {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Cli::parse();
+    let adapter = match args.repository {
+        Some(path) => infrastructure::JjAdapter::for_path(path)?,
+        None => infrastructure::JjAdapter::new()?,
+    };
+
+    // Subcommands print and exit without touching the terminal.
+    if let Some(command) = args.command {
+        if !adapter.is_valid().await {
+            bail!(
+                "There is no jj repo in \"{}\"",
+                adapter.workspace_root().display()
+            );
+        }
+        return cli::run(command, &adapter, &mut std::io::stdout().lock()).await;
+    }
+
     setup_panic_hook();
 
     // Initialize adapter to verify repo context
     // This happens BEFORE terminal setup so if it fails (e.g. corrupt config),
     // we don't leave the terminal in raw mode.
-    infrastructure::JjAdapter::check_version().await?;
-    let adapter = std::sync::Arc::new(infrastructure::JjAdapter::new()?);
+    let version_warning = infrastructure::JjAdapter::check_version().await;
     let key_config = judo::app::keymap::KeyConfig::load();
     let mut app_state = AppState::new(key_config);
+    let adapter =
+        std::sync::Arc::new(adapter.counting_lines(app_state.row_template.counts_lines()));
 
     if !adapter.is_valid().await {
         app_state.mode = judo::app::state::AppMode::NoRepo;
+    } else if let Some(path) = adapter
+        .repo_path()
+        .await
+        .and_then(|repo_path| persistence::session_path(&repo_path))
+    {
+        if let Some(session) = persistence::load_session(&path) {
+            app_state.restore_session(session);
+        }
+        app_state.session_path = Some(path);
+    }
+
+    if let Some(message) = version_warning {
+        app_state.last_error = Some(ErrorState {
+            message,
+            timestamp: chrono::Local::now(),
+            severity: ErrorSeverity::Warning,
+            suggestions: Vec::new(),
+        });
     }
 
     // Setup terminal
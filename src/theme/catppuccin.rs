@@ -29,3 +29,32 @@ pub const CATPPUCCIN_MOCHA: Palette = Palette {
     flamingo: Color::Rgb(242, 205, 205),
     rosewater: Color::Rgb(245, 224, 220),
 };
+
+pub const CATPPUCCIN_LATTE: Palette = Palette {
+    base: Color::Rgb(239, 241, 245),
+    mantle: Color::Rgb(230, 233, 239),
+    crust: Color::Rgb(220, 224, 232),
+    text: Color::Rgb(76, 79, 105),
+    subtext0: Color::Rgb(108, 111, 133),
+    subtext1: Color::Rgb(92, 95, 119),
+    surface0: Color::Rgb(204, 208, 218),
+    surface1: Color::Rgb(188, 192, 204),
+    surface2: Color::Rgb(172, 176, 190),
+    overlay0: Color::Rgb(156, 160, 176),
+    overlay1: Color::Rgb(140, 143, 161),
+    overlay2: Color::Rgb(124, 127, 147),
+    blue: Color::Rgb(30, 102, 245),
+    lavender: Color::Rgb(114, 135, 253),
+    sapphire: Color::Rgb(32, 159, 181),
+    sky: Color::Rgb(4, 165, 229),
+    teal: Color::Rgb(23, 146, 153),
+    green: Color::Rgb(64, 160, 43),
+    yellow: Color::Rgb(223, 142, 29),
+    peach: Color::Rgb(254, 100, 11),
+    maroon: Color::Rgb(230, 69, 83),
+    red: Color::Rgb(210, 15, 57),
+    mauve: Color::Rgb(136, 57, 239),
+    pink: Color::Rgb(234, 118, 203),
+    flamingo: Color::Rgb(221, 120, 120),
+    rosewater: Color::Rgb(220, 138, 120),
+};
@@ -0,0 +1,207 @@
+use super::{Palette, PaletteType, Theme};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A theme to pick from: a built-in palette or a theme file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeEntry {
+    /// What `theme` in `config.toml` calls it: the palette's name, or the
+    /// file's name without `.toml`.
+    pub name: String,
+    pub label: String,
+    pub theme: Theme,
+}
+
+/// A `themes/*.toml` file: a built-in palette to start from, colors that
+/// replace some of it, and changes to some of the styles made from it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    /// Shown in the theme selection instead of the file name.
+    name: Option<String>,
+    /// Catppuccin Mocha unless given.
+    extends: Option<PaletteType>,
+    palette: BTreeMap<String, String>,
+    styles: BTreeMap<String, StyleChange>,
+}
+
+/// Colors are palette names like `blue`, or colors like `#1e66f5` or
+/// `lightred`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StyleChange {
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: Option<bool>,
+    dim: Option<bool>,
+    italic: Option<bool>,
+    underlined: Option<bool>,
+    reversed: Option<bool>,
+}
+
+#[must_use]
+pub fn themes_dir() -> Option<PathBuf> {
+    home::home_dir().map(|mut path| {
+        path.push(".config");
+        path.push("judo");
+        path.push("themes");
+        path
+    })
+}
+
+/// The built-in themes followed by the `*.toml` files in `dir`, and what
+/// is wrong with the files that could not be used.
+#[must_use]
+pub fn load_themes(dir: Option<&Path>) -> (Vec<ThemeEntry>, Vec<String>) {
+    let mut themes: Vec<ThemeEntry> = PaletteType::all()
+        .iter()
+        .map(|palette| ThemeEntry {
+            name: palette.name().to_string(),
+            label: palette.label().to_string(),
+            theme: Theme::from_palette_type(*palette),
+        })
+        .collect();
+    let mut problems = Vec::new();
+
+    let Some(entries) = dir.and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return (themes, problems);
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let theme = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| parse_theme(&content));
+        match theme {
+            Ok((label, theme)) => themes.push(ThemeEntry {
+                label: label.unwrap_or_else(|| name.clone()),
+                name,
+                theme,
+            }),
+            Err(e) => problems.push(format!("themes/{name}.toml: {e}")),
+        }
+    }
+    (themes, problems)
+}
+
+/// The theme `content` describes, and the name it gives itself.
+fn parse_theme(content: &str) -> Result<(Option<String>, Theme), String> {
+    let file: ThemeFile = toml::from_str(content).map_err(|e| e.message().to_string())?;
+    let mut palette = file
+        .extends
+        .unwrap_or(PaletteType::CatppuccinMocha)
+        .palette()
+        .clone();
+    for (name, value) in &file.palette {
+        let color = Color::from_str(value).map_err(|_| format!("{value} is not a color"))?;
+        *palette
+            .color_mut(name)
+            .ok_or_else(|| format!("no palette color named {name}"))? = color;
+    }
+
+    let mut theme = Theme::from_palette(&palette);
+    for (name, change) in &file.styles {
+        let style = theme
+            .style_mut(name)
+            .ok_or_else(|| format!("no style named {name}"))?;
+        *style = change.apply(*style, &palette)?;
+    }
+    Ok((file.name, theme))
+}
+
+impl StyleChange {
+    fn apply(&self, mut style: Style, palette: &Palette) -> Result<Style, String> {
+        let color = |value: &str| match palette.color(value) {
+            Some(color) => Ok(color),
+            None => Color::from_str(value).map_err(|_| format!("{value} is not a color")),
+        };
+        if let Some(fg) = &self.fg {
+            style = style.fg(color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(color(bg)?);
+        }
+        for (on, modifier) in [
+            (self.bold, Modifier::BOLD),
+            (self.dim, Modifier::DIM),
+            (self.italic, Modifier::ITALIC),
+            (self.underlined, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ] {
+            style = match on {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+        Ok(style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::catppuccin::CATPPUCCIN_LATTE;
+
+    #[test]
+    fn test_load_theme_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("paper.toml"),
+            r##"
+            name = "Paper"
+            extends = "catppuccinlatte"
+
+            [palette]
+            blue = "#0000ff"
+
+            [styles]
+            diff_add = { fg = "red", bold = true }
+            border = { bg = "#102030" }
+            "##,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("broken.toml"),
+            "[styles]\nborders = { fg = \"blue\" }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a theme").unwrap();
+
+        let (themes, problems) = load_themes(Some(dir.path()));
+        assert_eq!(problems, ["themes/broken.toml: no style named borders"]);
+        let names: Vec<&str> = themes.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "catppuccinmocha",
+                "catppuccinlatte",
+                "nord",
+                "gruvbox",
+                "paper"
+            ]
+        );
+
+        let paper = &themes[4];
+        assert_eq!(paper.label, "Paper");
+        assert_eq!(paper.theme.border_focus.fg, Some(Color::Rgb(0, 0, 255)));
+        assert_eq!(paper.theme.diff_add.fg, Some(CATPPUCCIN_LATTE.red));
+        assert!(paper.theme.diff_add.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(paper.theme.border.fg, Some(CATPPUCCIN_LATTE.surface2));
+        assert_eq!(paper.theme.border.bg, Some(Color::Rgb(16, 32, 48)));
+        // Light, so it paints its own background.
+        assert_eq!(paper.theme.background.bg, Some(CATPPUCCIN_LATTE.base));
+        assert_eq!(themes[0].theme.background, Style::default());
+    }
+}
@@ -2,12 +2,14 @@ use ratatui::style::{Modifier, Style};
 use serde::{Deserialize, Serialize};
 
 pub mod catppuccin;
+pub mod file;
 pub mod glyphs;
 pub mod gruvbox;
 pub mod nord;
 pub mod palette;
 
-pub use palette::{dim_color, Palette};
+pub use file::{load_themes, themes_dir, ThemeEntry};
+pub use palette::{tint, Palette};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Theme {
@@ -25,17 +27,27 @@ pub struct Theme {
     pub change_id_immutable: Style,
     pub change_id_wc: Style,
     pub bookmark: Style,
+    pub tag: Style,
 
     pub diff_header: Style,
     pub diff_add: Style,
     pub diff_add_bg: Style,
+    pub diff_add_emphasis: Style,
     pub diff_remove: Style,
     pub diff_remove_bg: Style,
+    pub diff_remove_emphasis: Style,
     pub diff_hunk: Style,
     pub diff_context: Style,
     pub diff_modify: Style,
     pub diff_conflict: Style,
 
+    pub syntax_comment: Style,
+    pub syntax_string: Style,
+    pub syntax_keyword: Style,
+    pub syntax_function: Style,
+    pub syntax_type: Style,
+    pub syntax_constant: Style,
+
     pub author: Style,
     pub timestamp: Style,
     pub commit_id_dim: Style,
@@ -44,6 +56,7 @@ pub struct Theme {
     pub status_info: Style,
     pub status_warn: Style,
     pub status_error: Style,
+    pub input_error: Style,
 
     pub header_logo: Style,
     pub header_repo: Style,
@@ -63,12 +76,16 @@ pub struct Theme {
     pub list_selected: Style,
     pub list_item: Style,
     pub dimmed: Style,
+
+    /// Fills what the widgets leave to the terminal. Only light themes set it.
+    pub background: Style,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PaletteType {
     CatppuccinMocha,
+    CatppuccinLatte,
     Nord,
     Gruvbox,
 }
@@ -78,15 +95,38 @@ impl PaletteType {
     pub fn label(&self) -> &'static str {
         match self {
             PaletteType::CatppuccinMocha => "Catppuccin (Mocha)",
+            PaletteType::CatppuccinLatte => "Catppuccin (Latte)",
             PaletteType::Nord => "Nord",
             PaletteType::Gruvbox => "Gruvbox",
         }
     }
 
+    /// What `theme` in `config.toml` and `extends` in theme files call it.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaletteType::CatppuccinMocha => "catppuccinmocha",
+            PaletteType::CatppuccinLatte => "catppuccinlatte",
+            PaletteType::Nord => "nord",
+            PaletteType::Gruvbox => "gruvbox",
+        }
+    }
+
+    #[must_use]
+    pub fn palette(&self) -> &'static Palette {
+        match self {
+            PaletteType::CatppuccinMocha => &catppuccin::CATPPUCCIN_MOCHA,
+            PaletteType::CatppuccinLatte => &catppuccin::CATPPUCCIN_LATTE,
+            PaletteType::Nord => &nord::NORD,
+            PaletteType::Gruvbox => &gruvbox::GRUVBOX,
+        }
+    }
+
     #[must_use]
     pub fn all() -> &'static [PaletteType] {
         &[
             PaletteType::CatppuccinMocha,
+            PaletteType::CatppuccinLatte,
             PaletteType::Nord,
             PaletteType::Gruvbox,
         ]
@@ -96,11 +136,7 @@ impl PaletteType {
 impl Theme {
     #[must_use]
     pub fn from_palette_type(t: PaletteType) -> Self {
-        match t {
-            PaletteType::CatppuccinMocha => Self::from_palette(&catppuccin::CATPPUCCIN_MOCHA),
-            PaletteType::Nord => Self::from_palette(&nord::NORD),
-            PaletteType::Gruvbox => Self::from_palette(&gruvbox::GRUVBOX),
-        }
+        Self::from_palette(t.palette())
     }
 
     #[must_use]
@@ -128,20 +164,38 @@ impl Theme {
             change_id_immutable: Style::default().fg(p.overlay1).add_modifier(Modifier::BOLD),
             change_id_wc: Style::default().fg(p.blue).add_modifier(Modifier::BOLD),
             bookmark: Style::default().fg(p.green).add_modifier(Modifier::BOLD),
+            tag: Style::default().fg(p.yellow).add_modifier(Modifier::BOLD),
             author: Style::default().fg(p.peach).add_modifier(Modifier::BOLD),
             timestamp: Style::default().fg(p.subtext0).add_modifier(Modifier::DIM),
             commit_id_dim: Style::default().fg(p.surface2).add_modifier(Modifier::DIM),
 
             diff_header: Style::default().fg(p.blue).add_modifier(Modifier::BOLD),
             diff_add: Style::default().fg(p.green),
-            diff_add_bg: Style::default().fg(p.green).bg(dim_color(p.green, 0.18)),
+            diff_add_bg: Style::default().fg(p.green).bg(tint(p.green, p.base, 0.18)),
+            diff_add_emphasis: Style::default()
+                .fg(p.green)
+                .bg(tint(p.green, p.base, 0.40))
+                .add_modifier(Modifier::BOLD),
             diff_remove: Style::default().fg(p.red),
-            diff_remove_bg: Style::default().fg(p.red).bg(dim_color(p.red, 0.18)),
+            diff_remove_bg: Style::default().fg(p.red).bg(tint(p.red, p.base, 0.18)),
+            diff_remove_emphasis: Style::default()
+                .fg(p.red)
+                .bg(tint(p.red, p.base, 0.40))
+                .add_modifier(Modifier::BOLD),
             diff_hunk: Style::default().fg(p.teal),
             diff_context: Style::default().fg(p.text),
             diff_modify: Style::default().fg(p.yellow),
             diff_conflict: Style::default().fg(p.red).add_modifier(Modifier::BOLD),
 
+            syntax_comment: Style::default()
+                .fg(p.overlay1)
+                .add_modifier(Modifier::ITALIC),
+            syntax_string: Style::default().fg(p.teal),
+            syntax_keyword: Style::default().fg(p.mauve),
+            syntax_function: Style::default().fg(p.blue),
+            syntax_type: Style::default().fg(p.yellow),
+            syntax_constant: Style::default().fg(p.peach),
+
             status_ready: Style::default()
                 .bg(p.green)
                 .fg(p.crust)
@@ -158,6 +212,9 @@ impl Theme {
                 .bg(p.red)
                 .fg(p.crust)
                 .add_modifier(Modifier::BOLD),
+            input_error: Style::default()
+                .fg(p.red)
+                .add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
 
             header_logo: Style::default()
                 .bg(p.blue)
@@ -198,8 +255,75 @@ impl Theme {
                 .add_modifier(Modifier::BOLD),
             list_item: Style::default().fg(p.text),
             dimmed: Style::default().fg(p.overlay0).add_modifier(Modifier::DIM),
+
+            background: if p.is_light() {
+                Style::default().bg(p.base).fg(p.text)
+            } else {
+                Style::default()
+            },
         }
     }
+
+    /// The style a theme file calls `name`, like `diff_add` or `border_focus`.
+    pub fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "border" => &mut self.border,
+            "border_focus" => &mut self.border_focus,
+            "graph_node_wc" => &mut self.graph_node_wc,
+            "graph_node_mutable" => &mut self.graph_node_mutable,
+            "graph_node_immutable" => &mut self.graph_node_immutable,
+            "graph_node_conflict" => &mut self.graph_node_conflict,
+            "graph_line" => &mut self.graph_line,
+            "change_id_mutable" => &mut self.change_id_mutable,
+            "change_id_immutable" => &mut self.change_id_immutable,
+            "change_id_wc" => &mut self.change_id_wc,
+            "bookmark" => &mut self.bookmark,
+            "tag" => &mut self.tag,
+            "diff_header" => &mut self.diff_header,
+            "diff_add" => &mut self.diff_add,
+            "diff_add_bg" => &mut self.diff_add_bg,
+            "diff_add_emphasis" => &mut self.diff_add_emphasis,
+            "diff_remove" => &mut self.diff_remove,
+            "diff_remove_bg" => &mut self.diff_remove_bg,
+            "diff_remove_emphasis" => &mut self.diff_remove_emphasis,
+            "diff_hunk" => &mut self.diff_hunk,
+            "diff_context" => &mut self.diff_context,
+            "diff_modify" => &mut self.diff_modify,
+            "diff_conflict" => &mut self.diff_conflict,
+            "syntax_comment" => &mut self.syntax_comment,
+            "syntax_string" => &mut self.syntax_string,
+            "syntax_keyword" => &mut self.syntax_keyword,
+            "syntax_function" => &mut self.syntax_function,
+            "syntax_type" => &mut self.syntax_type,
+            "syntax_constant" => &mut self.syntax_constant,
+            "author" => &mut self.author,
+            "timestamp" => &mut self.timestamp,
+            "commit_id_dim" => &mut self.commit_id_dim,
+            "status_ready" => &mut self.status_ready,
+            "status_info" => &mut self.status_info,
+            "status_warn" => &mut self.status_warn,
+            "status_error" => &mut self.status_error,
+            "input_error" => &mut self.input_error,
+            "header_logo" => &mut self.header_logo,
+            "header_repo" => &mut self.header_repo,
+            "header_branch" => &mut self.header_branch,
+            "header_stats" => &mut self.header_stats,
+            "header_active" => &mut self.header_active,
+            "header_warn" => &mut self.header_warn,
+            "header_item" => &mut self.header_item,
+            "header" => &mut self.header,
+            "footer_segment_key" => &mut self.footer_segment_key,
+            "footer_segment_val" => &mut self.footer_segment_val,
+            "footer_group_name" => &mut self.footer_group_name,
+            "footer" => &mut self.footer,
+            "highlight" => &mut self.highlight,
+            "list_selected" => &mut self.list_selected,
+            "list_item" => &mut self.list_item,
+            "dimmed" => &mut self.dimmed,
+            "background" => &mut self.background,
+            _ => return None,
+        })
+    }
 }
 
 impl Default for Theme {
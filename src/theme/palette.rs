@@ -1,5 +1,6 @@
 use ratatui::style::Color;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Palette {
     pub base: Color,
     pub mantle: Color,
@@ -29,16 +30,68 @@ pub struct Palette {
     pub rosewater: Color,
 }
 
-/// Scale an `Rgb` color's channels by `factor` (0.0 = black, 1.0 = unchanged).
+impl Palette {
+    /// The color a theme file calls `name`, like `base` or `blue`.
+    #[must_use]
+    pub fn color(&self, name: &str) -> Option<Color> {
+        // Reads through `color_mut` so the names are listed once.
+        self.clone().color_mut(name).copied()
+    }
+
+    /// The color called `name`, for a theme file to override.
+    pub fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "base" => &mut self.base,
+            "mantle" => &mut self.mantle,
+            "crust" => &mut self.crust,
+            "text" => &mut self.text,
+            "subtext0" => &mut self.subtext0,
+            "subtext1" => &mut self.subtext1,
+            "surface0" => &mut self.surface0,
+            "surface1" => &mut self.surface1,
+            "surface2" => &mut self.surface2,
+            "overlay0" => &mut self.overlay0,
+            "overlay1" => &mut self.overlay1,
+            "overlay2" => &mut self.overlay2,
+            "blue" => &mut self.blue,
+            "lavender" => &mut self.lavender,
+            "sapphire" => &mut self.sapphire,
+            "sky" => &mut self.sky,
+            "teal" => &mut self.teal,
+            "green" => &mut self.green,
+            "yellow" => &mut self.yellow,
+            "peach" => &mut self.peach,
+            "maroon" => &mut self.maroon,
+            "red" => &mut self.red,
+            "mauve" => &mut self.mauve,
+            "pink" => &mut self.pink,
+            "flamingo" => &mut self.flamingo,
+            "rosewater" => &mut self.rosewater,
+            _ => return None,
+        })
+    }
+
+    /// Whether the background is light, so the theme paints it instead of
+    /// leaving it to the terminal.
+    #[must_use]
+    pub fn is_light(&self) -> bool {
+        match self.base {
+            Color::Rgb(r, g, b) => {
+                0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b) > 127.5
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Mix `factor` of an `Rgb` color into `base` (0.0 = base, 1.0 = unchanged).
 /// Used to derive subtle background tints from palette foreground colors.
-/// Non-Rgb `Color` variants are returned as-is (they don't appear in these palettes).
-pub fn dim_color(c: Color, factor: f32) -> Color {
-    if let Color::Rgb(r, g, b) = c {
-        Color::Rgb(
-            (f32::from(r) * factor) as u8,
-            (f32::from(g) * factor) as u8,
-            (f32::from(b) * factor) as u8,
-        )
+/// Non-Rgb `Color` variants are returned as-is.
+pub fn tint(c: Color, base: Color, factor: f32) -> Color {
+    if let (Color::Rgb(r, g, b), Color::Rgb(br, bg, bb)) = (c, base) {
+        let mix =
+            |c: u8, base: u8| (f32::from(base) + (f32::from(c) - f32::from(base)) * factor) as u8;
+        Color::Rgb(mix(r, br), mix(g, bg), mix(b, bb))
     } else {
         c
     }